@@ -0,0 +1,385 @@
+//! Local REST API.
+//!
+//! With `api.enabled` set, the daemon serves a JSON API on `api.listen` for
+//! dashboards and scripts. Every request except `GET /health` must carry the
+//! configured token as `Authorization: Bearer <token>`.
+//!
+//! Nodes are written with the same `NodeImport` bodies as the import/export
+//! format, parents included by name, and read back in that shape along with
+//! their ID and current state. Changes are stored in the database and passed to
+//! the engine as `NodeConfigUpdate`s, so they take effect straight away.
+//!
+//! Hook commands run arbitrary commands on the host, so the API can neither set
+//! nor change them; updates ignore any `hooks` in the body and keep the node's
+//! hooks as they are.
+
+use crate::cli::validate_node;
+use crate::database::Database;
+use crate::models::{
+    check_parents, resolve_node_ids, CheckSample, Node, NodeImport, NodeStatus, StatusChange,
+};
+use crate::monitoring_engine::NodeConfigUpdate;
+use anyhow::{anyhow, Result};
+use axum::extract::{Path, Query, Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::{mpsc, Arc};
+use tokio::net::TcpListener;
+use tracing::warn;
+
+/// How far back uptime and results queries look when they don't set `from`
+const DEFAULT_WINDOW: Duration = Duration::hours(24);
+
+#[derive(Clone)]
+struct ApiState {
+    db: Database,
+    config_tx: mpsc::Sender<NodeConfigUpdate>,
+    token: Arc<str>,
+}
+
+impl ApiState {
+    /// Passes a stored change on to the engine
+    fn push(&self, update: NodeConfigUpdate) {
+        if self.config_tx.send(update).is_err() {
+            warn!("The monitoring engine has stopped; the change applies once it restarts");
+        }
+    }
+}
+
+/// A node as the API returns it: its definition plus its current state
+#[derive(Debug, Serialize)]
+struct ApiNode {
+    id: i64,
+    #[serde(flatten)]
+    definition: NodeImport,
+    status: NodeStatus,
+    last_check: Option<DateTime<Utc>>,
+    response_time: Option<u64>,
+    consecutive_failures: u32,
+}
+
+/// Time window of a query, ending now and covering `DEFAULT_WINDOW` unless set
+#[derive(Debug, Deserialize)]
+struct Window {
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+}
+
+impl Window {
+    fn resolve(&self) -> Result<(DateTime<Utc>, DateTime<Utc>), ApiError> {
+        let to = self.to.unwrap_or_else(Utc::now);
+        let from = self.from.unwrap_or(to - DEFAULT_WINDOW);
+        if from >= to {
+            return Err(ApiError::bad_request("'from' must be before 'to'"));
+        }
+        Ok((from, to))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Limit {
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+struct Uptime {
+    node_id: i64,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    uptime_percentage: f64,
+}
+
+/// An error answered with its status and a JSON `{"error": ...}` body
+#[derive(Debug)]
+struct ApiError {
+    status: StatusCode,
+    message: String,
+}
+
+impl ApiError {
+    fn bad_request(message: impl fmt::Display) -> Self {
+        Self {
+            status: StatusCode::BAD_REQUEST,
+            message: message.to_string(),
+        }
+    }
+
+    fn not_found(id: i64) -> Self {
+        Self {
+            status: StatusCode::NOT_FOUND,
+            message: format!("No node with ID {}", id),
+        }
+    }
+}
+
+impl From<anyhow::Error> for ApiError {
+    fn from(e: anyhow::Error) -> Self {
+        warn!("API request failed: {:#}", e);
+        Self {
+            status: StatusCode::INTERNAL_SERVER_ERROR,
+            message: e.to_string(),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let body = Json(serde_json::json!({ "error": self.message }));
+        if self.status == StatusCode::UNAUTHORIZED {
+            (self.status, [(header::WWW_AUTHENTICATE, "Bearer")], body).into_response()
+        } else {
+            (self.status, body).into_response()
+        }
+    }
+}
+
+/// Serves the API on `listener`, storing node changes in `database` and passing
+/// them on through `config_tx`, until the task running it is dropped
+pub async fn serve(
+    listener: TcpListener,
+    database: Database,
+    config_tx: mpsc::Sender<NodeConfigUpdate>,
+    token: String,
+) -> Result<()> {
+    let state = ApiState {
+        db: database,
+        config_tx,
+        token: token.into(),
+    };
+    let app = Router::new()
+        .route("/nodes", get(list_nodes).post(create_node))
+        .route(
+            "/nodes/{id}",
+            get(get_node).put(update_node).delete(delete_node),
+        )
+        .route("/nodes/{id}/status-changes", get(status_changes))
+        .route("/nodes/{id}/uptime", get(uptime))
+        .route("/nodes/{id}/results", get(results))
+        .route_layer(middleware::from_fn_with_state(state.clone(), require_token))
+        .route("/health", get(health))
+        .with_state(state);
+    axum::serve(listener, app).await?;
+    Ok(())
+}
+
+async fn require_token(State(state): State<ApiState>, request: Request, next: Next) -> Response {
+    let authorized = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|token| tokens_match(token.trim(), &state.token));
+    if !authorized {
+        return ApiError {
+            status: StatusCode::UNAUTHORIZED,
+            message: "Missing or invalid API token".to_string(),
+        }
+        .into_response();
+    }
+    next.run(request).await
+}
+
+/// Compares tokens in a time that doesn't depend on where they differ
+fn tokens_match(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Runs `f` on the blocking thread pool, as every database call blocks
+async fn with_db<T, F>(state: &ApiState, f: F) -> Result<T, ApiError>
+where
+    T: Send + 'static,
+    F: FnOnce(&Database) -> Result<T, ApiError> + Send + 'static,
+{
+    let db = state.db.clone();
+    tokio::task::spawn_blocking(move || f(&db))
+        .await
+        .map_err(|e| ApiError::from(anyhow!(e)))?
+}
+
+fn find_node(nodes: &[Node], id: i64) -> Result<&Node, ApiError> {
+    nodes
+        .iter()
+        .find(|node| node.id == Some(id))
+        .ok_or_else(|| ApiError::not_found(id))
+}
+
+/// The stored nodes as the API returns them
+fn api_nodes(nodes: &[Node]) -> Vec<ApiNode> {
+    nodes
+        .iter()
+        .zip(NodeImport::export(nodes))
+        .filter_map(|(node, definition)| {
+            Some(ApiNode {
+                id: node.id?,
+                definition,
+                status: node.status,
+                last_check: node.last_check,
+                response_time: node.response_time,
+                consecutive_failures: node.consecutive_failures,
+            })
+        })
+        .collect()
+}
+
+fn load_node(db: &Database, id: i64) -> Result<ApiNode, ApiError> {
+    api_nodes(&db.get_all_nodes()?)
+        .into_iter()
+        .find(|node| node.id == id)
+        .ok_or_else(|| ApiError::not_found(id))
+}
+
+async fn health(State(state): State<ApiState>) -> Response {
+    match with_db(&state, |db| Ok(db.get_all_nodes().map(|_| ())?)).await {
+        Ok(()) => Json(serde_json::json!({
+            "status": "ok",
+            "version": env!("CARGO_PKG_VERSION"),
+        }))
+        .into_response(),
+        Err(e) => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(serde_json::json!({ "status": "unavailable", "error": e.message })),
+        )
+            .into_response(),
+    }
+}
+
+async fn list_nodes(State(state): State<ApiState>) -> Result<Json<Vec<ApiNode>>, ApiError> {
+    let nodes = with_db(&state, |db| Ok(api_nodes(&db.get_all_nodes()?))).await?;
+    Ok(Json(nodes))
+}
+
+async fn get_node(
+    State(state): State<ApiState>,
+    Path(id): Path<i64>,
+) -> Result<Json<ApiNode>, ApiError> {
+    Ok(Json(with_db(&state, move |db| load_node(db, id)).await?))
+}
+
+async fn create_node(
+    State(state): State<ApiState>,
+    Json(import): Json<NodeImport>,
+) -> Result<(StatusCode, Json<ApiNode>), ApiError> {
+    if !import.hooks.is_empty() {
+        return Err(ApiError::bad_request(
+            "Hook commands cannot be set through the API",
+        ));
+    }
+
+    let node = with_db(&state, move |db| {
+        let parents = resolve_node_ids(&import.parents, &db.get_all_nodes()?)
+            .map_err(ApiError::bad_request)?;
+        let mut node = Node::from(import);
+        node.parents = parents;
+        validate_node(&node).map_err(ApiError::bad_request)?;
+        node.id = Some(db.add_node(&node)?);
+        Ok(node)
+    })
+    .await?;
+    let id = node.id.unwrap_or_default();
+    state.push(NodeConfigUpdate::Add(node));
+
+    let created = with_db(&state, move |db| load_node(db, id)).await?;
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+async fn update_node(
+    State(state): State<ApiState>,
+    Path(id): Path<i64>,
+    Json(import): Json<NodeImport>,
+) -> Result<Json<ApiNode>, ApiError> {
+    let node = with_db(&state, move |db| {
+        let nodes = db.get_all_nodes()?;
+        let mut node = find_node(&nodes, id)?.clone();
+        let parents = resolve_node_ids(&import.parents, &nodes).map_err(ApiError::bad_request)?;
+
+        // Replace the definition, keeping the node's state and hooks
+        let definition = Node::from(import);
+        node.name = definition.name;
+        node.detail = definition.detail;
+        node.monitoring_interval = definition.monitoring_interval;
+        node.credential_id = definition.credential_id;
+        node.max_check_attempts = definition.max_check_attempts;
+        node.retry_interval = definition.retry_interval;
+        node.parents = parents;
+        node.group = definition.group;
+        node.tags = definition.tags;
+        validate_node(&node).map_err(ApiError::bad_request)?;
+        check_parents(&node, &nodes).map_err(ApiError::bad_request)?;
+        db.update_node(&node)?;
+        Ok(node)
+    })
+    .await?;
+    state.push(NodeConfigUpdate::Update(node));
+
+    Ok(Json(with_db(&state, move |db| load_node(db, id)).await?))
+}
+
+async fn delete_node(
+    State(state): State<ApiState>,
+    Path(id): Path<i64>,
+) -> Result<StatusCode, ApiError> {
+    with_db(&state, move |db| {
+        find_node(&db.get_all_nodes()?, id)?;
+        Ok(db.delete_node(id)?)
+    })
+    .await?;
+    state.push(NodeConfigUpdate::Delete(id));
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn status_changes(
+    State(state): State<ApiState>,
+    Path(id): Path<i64>,
+    Query(query): Query<Limit>,
+) -> Result<Json<Vec<StatusChange>>, ApiError> {
+    let changes = with_db(&state, move |db| {
+        find_node(&db.get_all_nodes()?, id)?;
+        Ok(db.get_status_changes(id, query.limit)?)
+    })
+    .await?;
+    Ok(Json(changes))
+}
+
+async fn uptime(
+    State(state): State<ApiState>,
+    Path(id): Path<i64>,
+    Query(window): Query<Window>,
+) -> Result<Json<Uptime>, ApiError> {
+    let (from, to) = window.resolve()?;
+    let uptime_percentage = with_db(&state, move |db| {
+        find_node(&db.get_all_nodes()?, id)?;
+        Ok(db.calculate_uptime_percentage(id, from, to)?)
+    })
+    .await?;
+    Ok(Json(Uptime {
+        node_id: id,
+        from,
+        to,
+        uptime_percentage,
+    }))
+}
+
+async fn results(
+    State(state): State<ApiState>,
+    Path(id): Path<i64>,
+    Query(window): Query<Window>,
+) -> Result<Json<Vec<CheckSample>>, ApiError> {
+    let (from, to) = window.resolve()?;
+    let samples = with_db(&state, move |db| {
+        find_node(&db.get_all_nodes()?, id)?;
+        Ok(db.get_check_samples(id, from, to)?)
+    })
+    .await?;
+    Ok(Json(samples))
+}
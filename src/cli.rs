@@ -0,0 +1,1771 @@
+//! Non-interactive node management for scripts and provisioning tools.
+//!
+//! Each subcommand works directly on the database, using the same `NodeImport`
+//! JSON format as the TUI's import/export screen. When a daemon is running
+//! (Unix only), changes are also sent to it over the control socket so they
+//! take effect without a restart.
+//!
+//! The `maintenance` subcommands plan maintenance windows, and the
+//! `credentials` subcommands maintain the credential store.
+
+use crate::config::{AppConfig, CredentialBackend, NodeDefaults};
+use crate::credentials::{
+    self, CredentialId, FileCredentialStore, KeyringCredentialStore, SensitiveString,
+    SharedCredentialStore, StoreStatus,
+};
+use crate::database::Database;
+use crate::hooks::hook_results;
+use crate::models::{
+    check_parents, format_duration, normalize_group, normalize_tags, resolve_node_ids, DnsProtocol,
+    DnsRecordType, HookCommands, HttpMethod, HttpOptions, JsonAssertion, MaintenanceScope,
+    MaintenanceWindow, MonitorDetail, Node, NodeImport, NodeStatus, PingThresholds, StatusRange,
+    TlsMode, UdpPayload, UdpResponseMatch, DEFAULT_DNS_PORT, DEFAULT_DNS_TIMEOUT,
+    DEFAULT_PING_FALLBACK_PORT, DEFAULT_SSH_PORT, DEFAULT_SSH_TIMEOUT, DEFAULT_UDP_TIMEOUT,
+};
+use crate::monitor;
+use crate::monitoring_engine::NodeConfigUpdate;
+use crate::paths::AppPaths;
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone, Utc};
+use clap::{Args, Subcommand};
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+/// Default number of status changes shown by `history`
+const DEFAULT_HISTORY_LIMIT: usize = 20;
+
+/// Node management subcommands
+#[derive(Debug, Subcommand)]
+#[allow(clippy::large_enum_variant)] // Parsed once per invocation
+pub enum NodeCommand {
+    /// List all nodes and their last known status
+    List {
+        /// Print the nodes as JSON instead of a table
+        #[arg(long)]
+        json: bool,
+    },
+    /// Add a node
+    Add {
+        #[command(subcommand)]
+        node: AddNode,
+    },
+    /// Change the settings of an existing node
+    Edit(EditArgs),
+    /// Remove a node together with its history
+    Remove {
+        /// Node name or ID
+        node: String,
+    },
+    /// Add the nodes from a JSON export file ("-" reads stdin)
+    Import { file: PathBuf },
+    /// Write all nodes to a JSON file ("-" writes stdout)
+    Export { file: PathBuf },
+    /// Check a node once; exits with status 1 unless it is online
+    Check {
+        /// Node name or ID
+        node: String,
+    },
+    /// Show a node's status changes, most recent first
+    History {
+        /// Node name or ID
+        node: String,
+        /// Maximum number of status changes to show
+        #[arg(short = 'n', long, default_value_t = DEFAULT_HISTORY_LIMIT)]
+        limit: usize,
+        /// Print the status changes as JSON instead of a table
+        #[arg(long)]
+        json: bool,
+    },
+    /// Plan maintenance windows, during which nodes are still checked but
+    /// their status changes are not alerted on
+    Maintenance {
+        #[command(subcommand)]
+        command: MaintenanceCommand,
+    },
+}
+
+/// Maintenance window subcommands
+#[derive(Debug, Subcommand)]
+pub enum MaintenanceCommand {
+    /// List maintenance windows and whether they are active
+    List {
+        /// Print the windows as JSON instead of a table
+        #[arg(long)]
+        json: bool,
+    },
+    /// Add a one-off or recurring maintenance window
+    Add(MaintenanceArgs),
+    /// Remove a maintenance window
+    Remove {
+        /// Window ID, as shown by `maintenance list`
+        id: i64,
+    },
+}
+
+/// Options for `maintenance add`
+#[derive(Debug, Args)]
+pub struct MaintenanceArgs {
+    /// Node name or ID the window applies to [default: every node]
+    #[arg(long)]
+    pub node: Option<String>,
+    /// Apply the window to every node with this tag instead
+    #[arg(long, conflicts_with = "node")]
+    pub tag: Option<String>,
+    /// Start as 'YYYY-MM-DD HH:MM' in local time, or RFC 3339 [default: now].
+    /// A recurring window does not start before this
+    #[arg(long, value_parser = parse_time)]
+    pub start: Option<DateTime<Utc>>,
+    /// Length of the window, or of each occurrence, in minutes
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    pub minutes: u32,
+    /// Cron expression for the start of each occurrence of a recurring
+    /// window, such as '0 2 * * sun' for 2am every Sunday (local time)
+    #[arg(long, value_name = "CRON")]
+    pub every: Option<String>,
+    /// Why the window is set up
+    #[arg(long)]
+    pub reason: Option<String>,
+}
+
+/// Parses 'YYYY-MM-DD HH:MM' in local time, or an RFC 3339 timestamp
+fn parse_time(value: &str) -> Result<DateTime<Utc>, String> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(time.with_timezone(&Utc));
+    }
+    let naive = NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M")
+        .map_err(|_| format!("'{}' is not 'YYYY-MM-DD HH:MM' or RFC 3339", value))?;
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|time| time.with_timezone(&Utc))
+        .ok_or_else(|| format!("'{}' does not exist in the local time zone", value))
+}
+
+/// Credential store subcommands
+#[derive(Debug, Subcommand)]
+pub enum CredentialCommand {
+    /// List stored credentials, without their secrets
+    List {
+        /// Print the credentials as JSON instead of a table
+        #[arg(long)]
+        json: bool,
+    },
+    /// Move the credentials in credentials.enc into the operating system
+    /// keyring and switch the config to the keyring backend
+    MigrateToKeyring,
+}
+
+/// Monitor types accepted by `add`
+#[derive(Debug, Subcommand)]
+#[allow(clippy::large_enum_variant)] // Parsed once per invocation
+pub enum AddNode {
+    /// Monitor an HTTP(S) endpoint
+    Http {
+        name: String,
+        url: String,
+        /// HTTP status code that counts as online
+        #[arg(long, default_value_t = 200)]
+        expected_status: u16,
+        /// Request timeout in seconds [default: from config]
+        #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+        timeout: Option<u64>,
+        #[command(flatten)]
+        http: HttpArgs,
+        #[command(flatten)]
+        schedule: ScheduleArgs,
+        #[command(flatten)]
+        hooks: HookArgs,
+        #[command(flatten)]
+        dependencies: DependencyArgs,
+        #[command(flatten)]
+        grouping: GroupArgs,
+    },
+    /// Monitor a host with ICMP ping, or TCP connections where ICMP is not permitted
+    Ping {
+        name: String,
+        host: String,
+        /// Number of echo requests per check
+        #[arg(long, default_value_t = 4)]
+        count: u32,
+        /// Seconds to wait for each reply
+        #[arg(long, default_value_t = 5)]
+        timeout: u64,
+        #[command(flatten)]
+        ping: PingArgs,
+        #[command(flatten)]
+        schedule: ScheduleArgs,
+        #[command(flatten)]
+        hooks: HookArgs,
+        #[command(flatten)]
+        dependencies: DependencyArgs,
+        #[command(flatten)]
+        grouping: GroupArgs,
+    },
+    /// Monitor a TCP port
+    Tcp {
+        name: String,
+        host: String,
+        port: u16,
+        /// Connection timeout in seconds
+        #[arg(long, default_value_t = 5)]
+        timeout: u64,
+        #[command(flatten)]
+        schedule: ScheduleArgs,
+        #[command(flatten)]
+        hooks: HookArgs,
+        #[command(flatten)]
+        dependencies: DependencyArgs,
+        #[command(flatten)]
+        grouping: GroupArgs,
+    },
+    /// Log in over SSH and optionally run a health check command
+    Ssh {
+        name: String,
+        host: String,
+        /// SSH port
+        #[arg(long, default_value_t = DEFAULT_SSH_PORT)]
+        port: u16,
+        /// Connection and command timeout in seconds
+        #[arg(long, default_value_t = DEFAULT_SSH_TIMEOUT)]
+        timeout: u64,
+        /// Command to run after logging in; the check fails if it exits non-zero
+        #[arg(long)]
+        command: Option<String>,
+        /// Regular expression the command's output must match
+        #[arg(long, requires = "command")]
+        expected_output: Option<String>,
+        /// SHA-256 fingerprint of the server's host key, as `ssh-keygen -l`
+        /// prints it [default: the key in ~/.ssh/known_hosts]
+        #[arg(long, value_name = "SHA256:FINGERPRINT")]
+        host_key: Option<String>,
+        /// Credential to log in with, by name or ID [default: SSH agent or ~/.ssh keys]
+        #[arg(long)]
+        credential: Option<String>,
+        #[command(flatten)]
+        schedule: ScheduleArgs,
+        #[command(flatten)]
+        hooks: HookArgs,
+        #[command(flatten)]
+        dependencies: DependencyArgs,
+        #[command(flatten)]
+        grouping: GroupArgs,
+    },
+    /// Look up a name on a specific DNS server
+    Dns {
+        name: String,
+        /// DNS server to query
+        server: String,
+        /// Name to look up
+        lookup: String,
+        /// Record type: A, AAAA, CNAME, MX, TXT or SRV
+        #[arg(long = "type", value_name = "TYPE", default_value_t = DnsRecordType::A)]
+        record_type: DnsRecordType,
+        /// Answer that must be among those returned, such as 10.0.0.5 or
+        /// '10 mail.example.com'; repeat for several [default: any answer]
+        #[arg(long = "expect", value_name = "ANSWER")]
+        expected: Vec<String>,
+        /// DNS server port
+        #[arg(long, default_value_t = DEFAULT_DNS_PORT)]
+        port: u16,
+        /// Query over udp or tcp; UDP queries are retried over TCP when the
+        /// answer is truncated
+        #[arg(long, default_value_t = DnsProtocol::Udp)]
+        protocol: DnsProtocol,
+        /// Query timeout in seconds
+        #[arg(long, default_value_t = DEFAULT_DNS_TIMEOUT)]
+        timeout: u64,
+        #[command(flatten)]
+        schedule: ScheduleArgs,
+        #[command(flatten)]
+        hooks: HookArgs,
+        #[command(flatten)]
+        dependencies: DependencyArgs,
+        #[command(flatten)]
+        grouping: GroupArgs,
+    },
+    /// Send a datagram to a UDP service and check the reply
+    Udp {
+        name: String,
+        host: String,
+        port: u16,
+        /// Datagram to send, as text or as hex:<digits> [default: empty]
+        #[arg(long)]
+        payload: Option<UdpPayload>,
+        /// Regular expression the reply must match, or hex:<digits> it must
+        /// start with [default: any reply]
+        #[arg(long = "expect", value_name = "PATTERN")]
+        expected_response: Option<UdpResponseMatch>,
+        /// Count the service as up when it doesn't reply, unless the host
+        /// reports the port unreachable
+        #[arg(long)]
+        no_reply_is_up: bool,
+        /// Seconds to wait for the reply
+        #[arg(long, default_value_t = DEFAULT_UDP_TIMEOUT)]
+        timeout: u64,
+        #[command(flatten)]
+        schedule: ScheduleArgs,
+        #[command(flatten)]
+        hooks: HookArgs,
+        #[command(flatten)]
+        dependencies: DependencyArgs,
+        #[command(flatten)]
+        grouping: GroupArgs,
+    },
+}
+
+/// Check scheduling options shared by every monitor type; unset options use
+/// the defaults from the config file
+#[derive(Debug, Args)]
+pub struct ScheduleArgs {
+    /// Seconds between checks [default: from config]
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    pub interval: Option<u64>,
+    /// Consecutive failures before the node is confirmed offline [default: from config]
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    pub max_check_attempts: Option<u32>,
+    /// Seconds between retries while the node is degraded [default: from config]
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    pub retry_interval: Option<u64>,
+}
+
+/// Commands run when the node changes status, shared by every `add` type and
+/// `edit`. When editing, an empty value removes a hook.
+#[derive(Debug, Args)]
+pub struct HookArgs {
+    /// Shell command to run when the node is confirmed offline
+    #[arg(long, value_name = "COMMAND")]
+    pub on_down: Option<String>,
+    /// Shell command to run when the node starts failing checks
+    #[arg(long, value_name = "COMMAND")]
+    pub on_degraded: Option<String>,
+    /// Shell command to run when the node is back online
+    #[arg(long, value_name = "COMMAND")]
+    pub on_recovery: Option<String>,
+}
+
+impl HookArgs {
+    /// Applies the given commands to `hooks`, leaving the rest unchanged
+    fn apply(self, hooks: &mut HookCommands) {
+        if let Some(command) = self.on_down {
+            hooks.on_down = optional(command);
+        }
+        if let Some(command) = self.on_degraded {
+            hooks.on_degraded = optional(command);
+        }
+        if let Some(command) = self.on_recovery {
+            hooks.on_recovery = optional(command);
+        }
+    }
+}
+
+/// Nodes a new node depends on, shared by every `add` type
+#[derive(Debug, Args)]
+pub struct DependencyArgs {
+    /// Node this node depends on, by name or ID, such as the switch in front
+    /// of it; repeat for several. While a parent is down, this node is
+    /// unreachable rather than offline
+    #[arg(long = "parent", value_name = "NODE")]
+    pub parents: Vec<String>,
+}
+
+/// Group and tags of a node, shared by every `add` type and `edit`. When
+/// editing, an empty group removes it and the tags given replace the node's.
+#[derive(Debug, Args)]
+pub struct GroupArgs {
+    /// Group the node is listed under, such as a site or customer
+    #[arg(long)]
+    pub group: Option<String>,
+    /// Label for the node, such as prod; repeat or separate with commas for several
+    #[arg(long = "tag", value_name = "TAG")]
+    pub tags: Vec<String>,
+}
+
+impl GroupArgs {
+    /// Applies the given group and tags to `node`, leaving the rest unchanged
+    fn apply(self, node: &mut Node) {
+        if let Some(group) = self.group {
+            node.group = normalize_group(&group);
+        }
+        if !self.tags.is_empty() {
+            node.tags = normalize_tags(&self.tags);
+        }
+    }
+}
+
+/// Request and response options of HTTP nodes, shared by `add http` and
+/// `edit`. When editing, an empty value removes an option.
+#[derive(Debug, Args)]
+pub struct HttpArgs {
+    /// Request method [default: GET]
+    #[arg(long)]
+    pub method: Option<HttpMethod>,
+    /// Request header as 'Name: value'; repeat for several. Replaces the
+    /// node's existing headers
+    #[arg(long = "header", value_name = "HEADER")]
+    pub headers: Vec<String>,
+    /// Request body
+    #[arg(long)]
+    pub body: Option<String>,
+    /// Status codes that count as online instead of just the expected
+    /// status, such as 200-299,304
+    #[arg(long, value_name = "RANGES")]
+    pub accept_status: Option<String>,
+    /// Redirects to follow; with 0 the redirect response itself is checked [default: 10]
+    #[arg(long)]
+    pub max_redirects: Option<u32>,
+    /// Text the response body must contain
+    #[arg(long)]
+    pub contains: Option<String>,
+    /// Text the response body must not contain
+    #[arg(long)]
+    pub not_contains: Option<String>,
+    /// Regular expression the response body must match
+    #[arg(long)]
+    pub matches: Option<String>,
+    /// Regular expression the response body must not match
+    #[arg(long)]
+    pub not_matches: Option<String>,
+    /// JSON pointer into the response body, such as /status, whose value
+    /// must equal --json-value
+    #[arg(long)]
+    pub json_pointer: Option<String>,
+    /// Value expected at --json-pointer; read as JSON if it parses, otherwise
+    /// as a string
+    #[arg(long)]
+    pub json_value: Option<String>,
+    /// Largest response body accepted, in bytes; 0 removes the limit
+    #[arg(long)]
+    pub max_body_size: Option<u64>,
+    /// How HTTPS certificates are checked: verify, accept-invalid or
+    /// sha256:<fingerprint> [default: verify]
+    #[arg(long, value_name = "MODE")]
+    pub tls: Option<TlsMode>,
+    /// PEM file of extra root certificates to trust when verifying
+    #[arg(long, value_name = "PATH")]
+    pub ca_bundle: Option<String>,
+    /// Degrade the node once its certificate expires within this many days;
+    /// 0 turns the warning off [default: 14]
+    #[arg(long, value_name = "DAYS")]
+    pub cert_warning_days: Option<u32>,
+    /// Take the node offline once its certificate expires within this many
+    /// days [default: 3]
+    #[arg(long, value_name = "DAYS")]
+    pub cert_critical_days: Option<u32>,
+}
+
+impl HttpArgs {
+    /// Which options were given, by flag name
+    fn given(&self) -> [(&'static str, bool); 16] {
+        [
+            ("method", self.method.is_some()),
+            ("header", !self.headers.is_empty()),
+            ("body", self.body.is_some()),
+            ("accept-status", self.accept_status.is_some()),
+            ("max-redirects", self.max_redirects.is_some()),
+            ("contains", self.contains.is_some()),
+            ("not-contains", self.not_contains.is_some()),
+            ("matches", self.matches.is_some()),
+            ("not-matches", self.not_matches.is_some()),
+            ("json-pointer", self.json_pointer.is_some()),
+            ("json-value", self.json_value.is_some()),
+            ("max-body-size", self.max_body_size.is_some()),
+            ("tls", self.tls.is_some()),
+            ("ca-bundle", self.ca_bundle.is_some()),
+            ("cert-warning-days", self.cert_warning_days.is_some()),
+            ("cert-critical-days", self.cert_critical_days.is_some()),
+        ]
+    }
+
+    /// Applies the given options to `options`, leaving the rest unchanged
+    fn apply(self, options: &mut HttpOptions) -> Result<()> {
+        if let Some(method) = self.method {
+            options.method = method;
+        }
+        if !self.headers.is_empty() {
+            options.headers = self
+                .headers
+                .iter()
+                .filter(|header| !header.is_empty())
+                .map(|header| header.parse())
+                .collect::<Result<_>>()?;
+        }
+        if let Some(body) = self.body {
+            options.body = optional(body);
+        }
+        if let Some(ranges) = &self.accept_status {
+            options.accepted_statuses = StatusRange::parse_list(ranges)?;
+        }
+        if let Some(max_redirects) = self.max_redirects {
+            options.max_redirects = max_redirects;
+        }
+
+        let assertions = &mut options.assertions;
+        if let Some(text) = self.contains {
+            assertions.contains = optional(text);
+        }
+        if let Some(text) = self.not_contains {
+            assertions.not_contains = optional(text);
+        }
+        if let Some(pattern) = self.matches {
+            assertions.matches = optional(pattern);
+        }
+        if let Some(pattern) = self.not_matches {
+            assertions.not_matches = optional(pattern);
+        }
+        if let Some(max) = self.max_body_size {
+            assertions.max_body_bytes = Some(max).filter(|&max| max > 0);
+        }
+
+        let value = self
+            .json_value
+            .map(|value| serde_json::from_str(&value).unwrap_or(serde_json::Value::String(value)));
+        match (self.json_pointer, value, &mut assertions.json) {
+            (Some(pointer), _, json) if pointer.is_empty() => *json = None,
+            (Some(pointer), Some(value), json) => *json = Some(JsonAssertion { pointer, value }),
+            (Some(pointer), None, Some(json)) => json.pointer = pointer,
+            (None, Some(value), Some(json)) => json.value = value,
+            (Some(_), None, None) => return Err(anyhow!("--json-pointer needs a --json-value")),
+            (None, Some(_), None) => return Err(anyhow!("--json-value needs a --json-pointer")),
+            (None, None, _) => {}
+        }
+
+        let tls = &mut options.tls;
+        if let Some(mode) = self.tls {
+            tls.mode = mode;
+        }
+        if let Some(path) = self.ca_bundle {
+            tls.ca_bundle = optional(path).map(PathBuf::from);
+        }
+        if let Some(days) = self.cert_warning_days {
+            tls.expiry_warning_days = days;
+        }
+        if let Some(days) = self.cert_critical_days {
+            tls.expiry_critical_days = days;
+        }
+        Ok(())
+    }
+}
+
+/// Packet loss and latency thresholds and the TCP fallback of ping nodes,
+/// shared by `add ping` and `edit`. A threshold of 0 turns it off.
+#[derive(Debug, Args)]
+pub struct PingArgs {
+    /// Degrade the node once this percentage of echo requests is lost
+    #[arg(long, value_name = "PERCENT")]
+    pub loss_warning: Option<u32>,
+    /// Take the node offline once this percentage of echo requests is lost
+    #[arg(long, value_name = "PERCENT")]
+    pub loss_critical: Option<u32>,
+    /// Degrade the node once the mean round trip takes this many milliseconds
+    #[arg(long, value_name = "MS")]
+    pub latency_warning: Option<u64>,
+    /// Take the node offline once the mean round trip takes this many milliseconds
+    #[arg(long, value_name = "MS")]
+    pub latency_critical: Option<u64>,
+    /// TCP port to time connections to when ICMP sockets are not permitted
+    /// [default for new nodes: 80]
+    #[arg(long, value_name = "PORT", value_parser = clap::value_parser!(u16).range(1..))]
+    pub fallback_port: Option<u16>,
+}
+
+impl PingArgs {
+    /// Which options were given, by flag name
+    fn given(&self) -> [(&'static str, bool); 5] {
+        [
+            ("loss-warning", self.loss_warning.is_some()),
+            ("loss-critical", self.loss_critical.is_some()),
+            ("latency-warning", self.latency_warning.is_some()),
+            ("latency-critical", self.latency_critical.is_some()),
+            ("fallback-port", self.fallback_port.is_some()),
+        ]
+    }
+
+    /// Applies the given options to a ping node, leaving the rest unchanged
+    fn apply(self, thresholds: &mut PingThresholds, fallback_port: &mut u16) {
+        if let Some(port) = self.fallback_port {
+            *fallback_port = port;
+        }
+        if let Some(percent) = self.loss_warning {
+            thresholds.loss_warning_percent = percent;
+        }
+        if let Some(percent) = self.loss_critical {
+            thresholds.loss_critical_percent = percent;
+        }
+        if let Some(ms) = self.latency_warning {
+            thresholds.latency_warning_ms = ms;
+        }
+        if let Some(ms) = self.latency_critical {
+            thresholds.latency_critical_ms = ms;
+        }
+    }
+}
+
+/// An empty string clears an optional setting
+fn optional(value: String) -> Option<String> {
+    Some(value).filter(|v| !v.is_empty())
+}
+
+/// Options for `edit`; anything not given is left unchanged
+#[derive(Debug, Args)]
+pub struct EditArgs {
+    /// Node name or ID
+    pub node: String,
+    /// New name
+    #[arg(long)]
+    pub name: Option<String>,
+    /// Seconds between checks
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    pub interval: Option<u64>,
+    /// Consecutive failures before the node is confirmed offline
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    pub max_check_attempts: Option<u32>,
+    /// Seconds between retries while the node is degraded
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    pub retry_interval: Option<u64>,
+    /// URL (HTTP nodes)
+    #[arg(long)]
+    pub url: Option<String>,
+    /// Expected status code (HTTP nodes)
+    #[arg(long)]
+    pub expected_status: Option<u16>,
+    /// Host name or address (ping, TCP, SSH and UDP nodes)
+    #[arg(long)]
+    pub host: Option<String>,
+    /// Port (TCP, SSH, DNS and UDP nodes)
+    #[arg(long)]
+    pub port: Option<u16>,
+    /// Echo requests per check (ping nodes)
+    #[arg(long)]
+    pub count: Option<u32>,
+    /// Timeout in seconds
+    #[arg(long)]
+    pub timeout: Option<u64>,
+    /// Command to run after logging in; an empty string removes it (SSH nodes)
+    #[arg(long)]
+    pub command: Option<String>,
+    /// Regular expression the command's output must match; an empty string
+    /// removes it (SSH nodes)
+    #[arg(long)]
+    pub expected_output: Option<String>,
+    /// Fingerprint the server's host key must have; an empty string checks
+    /// it against ~/.ssh/known_hosts again (SSH nodes)
+    #[arg(long, value_name = "SHA256:FINGERPRINT")]
+    pub host_key: Option<String>,
+    /// DNS server to query (DNS nodes)
+    #[arg(long)]
+    pub server: Option<String>,
+    /// Name to look up (DNS nodes)
+    #[arg(long)]
+    pub lookup: Option<String>,
+    /// Record type to look up (DNS nodes)
+    #[arg(long = "type", value_name = "TYPE")]
+    pub record_type: Option<DnsRecordType>,
+    /// Answer that must be returned; repeat for several. Replaces the node's
+    /// expected answers, and an empty string removes them (DNS nodes). For
+    /// UDP nodes, the reply pattern or hex:<digits> prefix, given once
+    #[arg(long = "expect", value_name = "ANSWER")]
+    pub expected: Vec<String>,
+    /// Query over udp or tcp (DNS nodes)
+    #[arg(long)]
+    pub protocol: Option<DnsProtocol>,
+    /// Datagram to send, as text or as hex:<digits> (UDP nodes)
+    #[arg(long)]
+    pub payload: Option<UdpPayload>,
+    /// Count the service as up when it doesn't reply, unless the host reports
+    /// the port unreachable (UDP nodes)
+    #[arg(long, conflicts_with = "require_reply")]
+    pub no_reply_is_up: bool,
+    /// Count the service as down when it doesn't reply (UDP nodes)
+    #[arg(long)]
+    pub require_reply: bool,
+    /// Credential, by name or ID (not HTTP, DNS or UDP nodes)
+    #[arg(long, conflicts_with = "no_credential")]
+    pub credential: Option<String>,
+    /// Remove the node's credential
+    #[arg(long)]
+    pub no_credential: bool,
+    /// Node this node depends on, by name or ID; repeat for several. Replaces
+    /// the node's parents
+    #[arg(long = "parent", value_name = "NODE", conflicts_with = "no_parents")]
+    pub parents: Vec<String>,
+    /// Remove the node's parents
+    #[arg(long)]
+    pub no_parents: bool,
+    #[command(flatten)]
+    pub grouping: GroupArgs,
+    /// Remove the node's tags
+    #[arg(long, conflicts_with = "tags")]
+    pub no_tags: bool,
+    #[command(flatten)]
+    pub hooks: HookArgs,
+    #[command(flatten)]
+    pub http: HttpArgs,
+    #[command(flatten)]
+    pub ping: PingArgs,
+}
+
+/// Runs a node management command, writing its output to `out`.
+///
+/// New nodes take any setting not given on the command line from `defaults`.
+/// `credentials` resolves credential names and is used by `check` to log in
+/// to SSH nodes. Returns the process exit code; only `check` reports failure
+/// this way; every other problem is returned as an error.
+pub fn run(
+    database: &Database,
+    defaults: &NodeDefaults,
+    credentials: &SharedCredentialStore,
+    command: NodeCommand,
+    out: &mut dyn Write,
+) -> Result<ExitCode> {
+    match command {
+        NodeCommand::List { json } => list_nodes(database, json, out)?,
+        NodeCommand::Add { node } => add_node(database, defaults, credentials, node, out)?,
+        NodeCommand::Edit(args) => edit_node(database, credentials, args, out)?,
+        NodeCommand::Remove { node } => remove_node(database, &node, out)?,
+        NodeCommand::Import { file } => import_nodes(database, &file, out)?,
+        NodeCommand::Export { file } => export_nodes(database, &file, out)?,
+        NodeCommand::Check { node } => return check_node(database, credentials, &node, out),
+        NodeCommand::History { node, limit, json } => {
+            show_history(database, &node, limit, json, out)?
+        }
+        NodeCommand::Maintenance { command } => run_maintenance(database, command, out)?,
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Runs a credential store command, writing its output to `out`.
+///
+/// `credentials` is the store selected by the config. Migrating opens the
+/// encrypted file store with `master_password` instead, and updates `config`
+/// and saves it to `paths.config` when the credential backend changes.
+pub fn run_credentials(
+    paths: &AppPaths,
+    config: &mut AppConfig,
+    master_password: Option<SensitiveString>,
+    credentials: &SharedCredentialStore,
+    command: CredentialCommand,
+    out: &mut dyn Write,
+) -> Result<ExitCode> {
+    match command {
+        CredentialCommand::List { json } => list_credentials(credentials, json, out)?,
+        CredentialCommand::MigrateToKeyring => {
+            migrate_to_keyring(paths, config, master_password, out)?
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn list_credentials(
+    credentials: &SharedCredentialStore,
+    json: bool,
+    out: &mut dyn Write,
+) -> Result<()> {
+    let store = credentials.read();
+    let mut summaries = unlocked(&store)?.list_credentials()?;
+    summaries.sort_by(|a, b| a.name.cmp(&b.name));
+
+    if json {
+        writeln!(out, "{}", serde_json::to_string_pretty(&summaries)?)?;
+        return Ok(());
+    }
+    if summaries.is_empty() {
+        writeln!(out, "No credentials stored")?;
+        return Ok(());
+    }
+
+    let header = ["ID", "NAME", "TYPE", "USERNAME"];
+    let rows: Vec<[String; 4]> = summaries
+        .into_iter()
+        .map(|c| {
+            [
+                c.id,
+                c.name,
+                c.credential_type,
+                c.username.unwrap_or_else(|| "-".to_string()),
+            ]
+        })
+        .collect();
+    write_table(out, &header, &rows)
+}
+
+/// Returns the opened credential store, or explains how to unlock it
+fn unlocked(
+    store: &Option<Box<dyn credentials::CredentialStore>>,
+) -> Result<&dyn credentials::CredentialStore> {
+    store.as_deref().ok_or_else(|| {
+        anyhow!(
+            "The credential store is locked; set {} or pass --master-password-file",
+            credentials::ENV_MASTER_PASSWORD
+        )
+    })
+}
+
+/// Finds a credential by exact name, falling back to its ID
+fn find_credential(credentials: &SharedCredentialStore, key: &str) -> Result<CredentialId> {
+    credentials::find_credential_id(unlocked(&credentials.read())?, key)
+}
+
+fn migrate_to_keyring(
+    paths: &AppPaths,
+    config: &mut AppConfig,
+    master_password: Option<SensitiveString>,
+    out: &mut dyn Write,
+) -> Result<()> {
+    let moved = match FileCredentialStore::status(&paths.credentials)? {
+        StoreStatus::Uninitialized => 0,
+        StoreStatus::Legacy | StoreStatus::Protected => {
+            let password = master_password.ok_or_else(|| {
+                anyhow!(
+                    "The master password is needed to read {}; set {} or pass --master-password-file",
+                    paths.credentials.display(),
+                    credentials::ENV_MASTER_PASSWORD
+                )
+            })?;
+            let mut source =
+                FileCredentialStore::with_path(password.as_str().to_string(), &paths.credentials)
+                    .context("Failed to unlock the credential store")?;
+            let mut target = KeyringCredentialStore::with_path(&paths.keyring_metadata)?;
+            credentials::migrate_to_keyring(&mut source, &mut target)?
+        }
+    };
+    writeln!(out, "Moved {} credential(s) to the keyring", moved)?;
+
+    if config.credentials.backend != CredentialBackend::Keyring {
+        config.credentials.backend = CredentialBackend::Keyring;
+        config
+            .save_to(&paths.config)
+            .with_context(|| format!("Failed to save config file {}", paths.config.display()))?;
+        writeln!(out, "Switched credentials.backend to keyring")?;
+    }
+    Ok(())
+}
+
+/// Finds a node by exact name, falling back to its numeric ID
+pub fn find_node(database: &Database, key: &str) -> Result<Node> {
+    let nodes = database.get_all_nodes()?;
+
+    let mut by_name = nodes.iter().filter(|n| n.name == key);
+    if let Some(node) = by_name.next() {
+        if let Some(other) = by_name.next() {
+            return Err(anyhow!(
+                "Several nodes are named '{}' (IDs {}, {}, ...); use the node ID instead",
+                key,
+                node.id.unwrap_or_default(),
+                other.id.unwrap_or_default()
+            ));
+        }
+        return Ok(node.clone());
+    }
+
+    key.parse::<i64>()
+        .ok()
+        .and_then(|id| nodes.iter().find(|n| n.id == Some(id)))
+        .cloned()
+        .ok_or_else(|| anyhow!("No node named '{}'", key))
+}
+
+fn list_nodes(database: &Database, json: bool, out: &mut dyn Write) -> Result<()> {
+    let nodes = database.get_all_nodes()?;
+    if json {
+        writeln!(out, "{}", serde_json::to_string_pretty(&nodes)?)?;
+        return Ok(());
+    }
+
+    let header = [
+        "ID",
+        "NAME",
+        "TYPE",
+        "TARGET",
+        "STATUS",
+        "LAST CHECK",
+        "RESPONSE",
+        "PARENTS",
+        "GROUP",
+        "TAGS",
+    ];
+    let rows: Vec<[String; 10]> = nodes
+        .iter()
+        .map(|node| {
+            [
+                node.id.map(|id| id.to_string()).unwrap_or_default(),
+                node.name.clone(),
+                node.detail.to_string(),
+                node.detail.get_connection_target(),
+                node.status.to_string(),
+                node.last_check
+                    .map(format_timestamp)
+                    .unwrap_or_else(|| "Never".to_string()),
+                node.response_time
+                    .map(|ms| format!("{}ms", ms))
+                    .unwrap_or_else(|| "-".to_string()),
+                parent_names(node, &nodes).unwrap_or_else(|| "-".to_string()),
+                node.group.clone().unwrap_or_else(|| "-".to_string()),
+                if node.tags.is_empty() {
+                    "-".to_string()
+                } else {
+                    node.tags.join(", ")
+                },
+            ]
+        })
+        .collect();
+
+    write_table(out, &header, &rows)
+}
+
+/// Names of the parents of `node`, separated by commas
+fn parent_names(node: &Node, nodes: &[Node]) -> Option<String> {
+    let names: Vec<&str> = node
+        .parents
+        .iter()
+        .filter_map(|&id| nodes.iter().find(|n| n.id == Some(id)))
+        .map(|parent| parent.name.as_str())
+        .collect();
+    (!names.is_empty()).then(|| names.join(", "))
+}
+
+fn add_node(
+    database: &Database,
+    defaults: &NodeDefaults,
+    credentials: &SharedCredentialStore,
+    add: AddNode,
+    out: &mut dyn Write,
+) -> Result<()> {
+    let mut credential = None;
+    let (name, detail, schedule, hooks, dependencies, grouping) = match add {
+        AddNode::Http {
+            name,
+            url,
+            expected_status,
+            timeout,
+            http,
+            schedule,
+            hooks,
+            dependencies,
+            grouping,
+        } => {
+            let mut options = HttpOptions::default();
+            http.apply(&mut options)?;
+            (
+                name,
+                MonitorDetail::Http {
+                    url,
+                    expected_status,
+                    timeout: timeout.unwrap_or(defaults.http_timeout),
+                    options,
+                },
+                schedule,
+                hooks,
+                dependencies,
+                grouping,
+            )
+        }
+        AddNode::Ping {
+            name,
+            host,
+            count,
+            timeout,
+            ping,
+            schedule,
+            hooks,
+            dependencies,
+            grouping,
+        } => {
+            let mut thresholds = PingThresholds::default();
+            let mut fallback_port = DEFAULT_PING_FALLBACK_PORT;
+            ping.apply(&mut thresholds, &mut fallback_port);
+            (
+                name,
+                MonitorDetail::Ping {
+                    host,
+                    count,
+                    timeout,
+                    fallback_port,
+                    thresholds,
+                },
+                schedule,
+                hooks,
+                dependencies,
+                grouping,
+            )
+        }
+        AddNode::Tcp {
+            name,
+            host,
+            port,
+            timeout,
+            schedule,
+            hooks,
+            dependencies,
+            grouping,
+        } => (
+            name,
+            MonitorDetail::Tcp {
+                host,
+                port,
+                timeout,
+            },
+            schedule,
+            hooks,
+            dependencies,
+            grouping,
+        ),
+        AddNode::Ssh {
+            name,
+            host,
+            port,
+            timeout,
+            command,
+            expected_output,
+            host_key,
+            credential: credential_key,
+            schedule,
+            hooks,
+            dependencies,
+            grouping,
+        } => {
+            credential = credential_key
+                .map(|key| find_credential(credentials, &key))
+                .transpose()?;
+            (
+                name,
+                MonitorDetail::Ssh {
+                    host,
+                    port,
+                    timeout,
+                    command,
+                    expected_output,
+                    host_key,
+                },
+                schedule,
+                hooks,
+                dependencies,
+                grouping,
+            )
+        }
+        AddNode::Dns {
+            name,
+            server,
+            lookup,
+            record_type,
+            expected,
+            port,
+            protocol,
+            timeout,
+            schedule,
+            hooks,
+            dependencies,
+            grouping,
+        } => (
+            name,
+            MonitorDetail::Dns {
+                server,
+                port,
+                name: lookup,
+                record_type,
+                expected,
+                protocol,
+                timeout,
+            },
+            schedule,
+            hooks,
+            dependencies,
+            grouping,
+        ),
+        AddNode::Udp {
+            name,
+            host,
+            port,
+            payload,
+            expected_response,
+            no_reply_is_up,
+            timeout,
+            schedule,
+            hooks,
+            dependencies,
+            grouping,
+        } => (
+            name,
+            MonitorDetail::Udp {
+                host,
+                port,
+                payload: payload.unwrap_or_default(),
+                expected_response,
+                no_reply_is_up,
+                timeout,
+            },
+            schedule,
+            hooks,
+            dependencies,
+            grouping,
+        ),
+    };
+
+    let mut node = Node::from(NodeImport {
+        name,
+        detail,
+        monitoring_interval: schedule.interval.unwrap_or(defaults.monitoring_interval),
+        credential_id: credential,
+        max_check_attempts: schedule
+            .max_check_attempts
+            .unwrap_or(defaults.max_check_attempts),
+        retry_interval: schedule.retry_interval.unwrap_or(defaults.retry_interval),
+        hooks: HookCommands::default(),
+        parents: Vec::new(),
+        group: None,
+        tags: Vec::new(),
+    });
+    hooks.apply(&mut node.hooks);
+    grouping.apply(&mut node);
+    if !dependencies.parents.is_empty() {
+        node.parents = resolve_node_ids(&dependencies.parents, &database.get_all_nodes()?)?;
+    }
+    validate_node(&node)?;
+
+    let id = database.add_node(&node)?;
+    node.id = Some(id);
+    writeln!(out, "Added node '{}' (ID {})", node.name, id)?;
+    notify_daemon(database, NodeConfigUpdate::Add(node));
+    Ok(())
+}
+
+fn edit_node(
+    database: &Database,
+    credentials: &SharedCredentialStore,
+    args: EditArgs,
+    out: &mut dyn Write,
+) -> Result<()> {
+    let mut node = find_node(database, &args.node)?;
+
+    if let Some(name) = args.name {
+        node.name = name;
+    }
+    if let Some(interval) = args.interval {
+        node.monitoring_interval = interval;
+    }
+    if let Some(max_check_attempts) = args.max_check_attempts {
+        node.max_check_attempts = max_check_attempts;
+    }
+    if let Some(retry_interval) = args.retry_interval {
+        node.retry_interval = retry_interval;
+    }
+    args.hooks.apply(&mut node.hooks);
+    if args.no_parents {
+        node.parents.clear();
+    }
+    if !args.parents.is_empty() {
+        let nodes = database.get_all_nodes()?;
+        node.parents = resolve_node_ids(&args.parents, &nodes)?;
+        check_parents(&node, &nodes)?;
+    }
+    if args.no_tags {
+        node.tags.clear();
+    }
+    args.grouping.apply(&mut node);
+
+    // Options that only some monitor types have
+    let http_given = args.http.given();
+    let ping_given = args.ping.given();
+    let given = [
+        ("url", args.url.is_some()),
+        ("expected-status", args.expected_status.is_some()),
+        ("host", args.host.is_some()),
+        ("port", args.port.is_some()),
+        ("count", args.count.is_some()),
+        ("command", args.command.is_some()),
+        ("expected-output", args.expected_output.is_some()),
+        ("host-key", args.host_key.is_some()),
+        ("server", args.server.is_some()),
+        ("lookup", args.lookup.is_some()),
+        ("type", args.record_type.is_some()),
+        ("expect", !args.expected.is_empty()),
+        ("protocol", args.protocol.is_some()),
+        ("payload", args.payload.is_some()),
+        ("no-reply-is-up", args.no_reply_is_up),
+        ("require-reply", args.require_reply),
+        ("credential", args.credential.is_some()),
+    ];
+    let http_options: Vec<&str> = http_given.iter().map(|(option, _)| *option).collect();
+    let ping_options: Vec<&str> = ping_given.iter().map(|(option, _)| *option).collect();
+    let applicable: &[&str] = match node.detail {
+        MonitorDetail::Http { .. } => &[&["url", "expected-status"], &http_options[..]].concat(),
+        MonitorDetail::Ping { .. } => {
+            &[&["host", "count", "credential"], &ping_options[..]].concat()
+        }
+        MonitorDetail::Tcp { .. } => &["host", "port", "credential"],
+        MonitorDetail::Ssh { .. } => &[
+            "host",
+            "port",
+            "command",
+            "expected-output",
+            "host-key",
+            "credential",
+        ],
+        MonitorDetail::Dns { .. } => &["server", "port", "lookup", "type", "expect", "protocol"],
+        MonitorDetail::Udp { .. } => &[
+            "host",
+            "port",
+            "payload",
+            "expect",
+            "no-reply-is-up",
+            "require-reply",
+        ],
+    };
+    if let Some((option, _)) = given
+        .iter()
+        .chain(&http_given)
+        .chain(&ping_given)
+        .find(|(option, is_given)| *is_given && !applicable.contains(option))
+    {
+        return Err(anyhow!(
+            "--{} does not apply to {} nodes",
+            option,
+            node.detail
+        ));
+    }
+
+    if args.no_credential {
+        node.credential_id = None;
+    }
+    if let Some(key) = &args.credential {
+        node.credential_id = Some(find_credential(credentials, key)?);
+    }
+
+    match &mut node.detail {
+        MonitorDetail::Http {
+            url,
+            expected_status,
+            timeout,
+            options,
+        } => {
+            args.http.apply(options)?;
+            if let Some(new_url) = args.url {
+                *url = new_url;
+            }
+            if let Some(status) = args.expected_status {
+                *expected_status = status;
+            }
+            if let Some(new_timeout) = args.timeout {
+                *timeout = new_timeout;
+            }
+        }
+        MonitorDetail::Ping {
+            host,
+            count,
+            timeout,
+            fallback_port,
+            thresholds,
+        } => {
+            args.ping.apply(thresholds, fallback_port);
+            if let Some(new_host) = args.host {
+                *host = new_host;
+            }
+            if let Some(new_count) = args.count {
+                *count = new_count;
+            }
+            if let Some(new_timeout) = args.timeout {
+                *timeout = new_timeout;
+            }
+        }
+        MonitorDetail::Tcp {
+            host,
+            port,
+            timeout,
+        } => {
+            if let Some(new_host) = args.host {
+                *host = new_host;
+            }
+            if let Some(new_port) = args.port {
+                *port = new_port;
+            }
+            if let Some(new_timeout) = args.timeout {
+                *timeout = new_timeout;
+            }
+        }
+        MonitorDetail::Ssh {
+            host,
+            port,
+            timeout,
+            command,
+            expected_output,
+            host_key,
+        } => {
+            if let Some(new_host) = args.host {
+                *host = new_host;
+            }
+            if let Some(new_port) = args.port {
+                *port = new_port;
+            }
+            if let Some(new_timeout) = args.timeout {
+                *timeout = new_timeout;
+            }
+            if let Some(new_command) = args.command {
+                *command = optional(new_command);
+            }
+            if let Some(new_expected_output) = args.expected_output {
+                *expected_output = optional(new_expected_output);
+            }
+            if let Some(new_host_key) = args.host_key {
+                *host_key = optional(new_host_key);
+            }
+        }
+        MonitorDetail::Dns {
+            server,
+            port,
+            name,
+            record_type,
+            expected,
+            protocol,
+            timeout,
+        } => {
+            if let Some(new_server) = args.server {
+                *server = new_server;
+            }
+            if let Some(new_port) = args.port {
+                *port = new_port;
+            }
+            if let Some(new_name) = args.lookup {
+                *name = new_name;
+            }
+            if let Some(new_record_type) = args.record_type {
+                *record_type = new_record_type;
+            }
+            if !args.expected.is_empty() {
+                *expected = args
+                    .expected
+                    .into_iter()
+                    .filter(|answer| !answer.is_empty())
+                    .collect();
+            }
+            if let Some(new_protocol) = args.protocol {
+                *protocol = new_protocol;
+            }
+            if let Some(new_timeout) = args.timeout {
+                *timeout = new_timeout;
+            }
+        }
+        MonitorDetail::Udp {
+            host,
+            port,
+            payload,
+            expected_response,
+            no_reply_is_up,
+            timeout,
+        } => {
+            if let Some(new_host) = args.host {
+                *host = new_host;
+            }
+            if let Some(new_port) = args.port {
+                *port = new_port;
+            }
+            if let Some(new_payload) = args.payload {
+                *payload = new_payload;
+            }
+            match &args.expected[..] {
+                [] => {}
+                [expected] => {
+                    *expected_response =
+                        optional(expected.clone()).map(|e| e.parse()).transpose()?
+                }
+                _ => return Err(anyhow!("A UDP node expects a single reply pattern")),
+            }
+            if args.no_reply_is_up {
+                *no_reply_is_up = true;
+            }
+            if args.require_reply {
+                *no_reply_is_up = false;
+            }
+            if let Some(new_timeout) = args.timeout {
+                *timeout = new_timeout;
+            }
+        }
+    }
+    validate_node(&node)?;
+
+    database.update_node(&node)?;
+    writeln!(out, "Updated node '{}'", node.name)?;
+    notify_daemon(database, NodeConfigUpdate::Update(node));
+    Ok(())
+}
+
+fn remove_node(database: &Database, key: &str, out: &mut dyn Write) -> Result<()> {
+    let node = find_node(database, key)?;
+    let id = node
+        .id
+        .ok_or_else(|| anyhow!("Node '{}' has no ID", node.name))?;
+
+    database.delete_node(id)?;
+    writeln!(out, "Removed node '{}'", node.name)?;
+    notify_daemon(database, NodeConfigUpdate::Delete(id));
+    Ok(())
+}
+
+fn import_nodes(database: &Database, file: &Path, out: &mut dyn Write) -> Result<()> {
+    let data = if file == Path::new("-") {
+        let mut data = String::new();
+        io::stdin().read_to_string(&mut data)?;
+        data
+    } else {
+        fs::read_to_string(file)
+            .with_context(|| format!("Failed to read import file {}", file.display()))?
+    };
+    let imports: Vec<NodeImport> =
+        serde_json::from_str(&data).context("Failed to parse import file")?;
+
+    // Validate everything up front so a bad entry doesn't leave a partial import
+    let existing = database.get_all_nodes()?;
+    for import in &imports {
+        let node = Node::from(import.clone());
+        validate_node(&node).with_context(|| format!("Invalid node '{}'", node.name))?;
+        if let Some(parent) = import.parents.iter().find(|parent| {
+            !imports.iter().any(|other| &other.name == *parent)
+                && !existing.iter().any(|other| &other.name == *parent)
+        }) {
+            return Err(anyhow!(
+                "Invalid node '{}': no node named '{}' to depend on",
+                import.name,
+                parent
+            ));
+        }
+    }
+
+    // Look for dependency cycles among the imported nodes, which stand in with
+    // negative IDs until they are stored
+    let mut planned: Vec<Node> = imports
+        .iter()
+        .enumerate()
+        .map(|(i, import)| Node {
+            id: Some(-(i as i64) - 1),
+            ..Node::from(import.clone())
+        })
+        .collect();
+    for (i, import) in imports.iter().enumerate() {
+        planned[i].parents = import
+            .parents
+            .iter()
+            .map(|parent| {
+                resolve_node_ids(std::slice::from_ref(parent), &planned)
+                    .or_else(|_| resolve_node_ids(std::slice::from_ref(parent), &existing))
+                    .map(|ids| ids[0])
+            })
+            .collect::<Result<_>>()
+            .with_context(|| format!("Invalid node '{}'", import.name))?;
+    }
+    let all: Vec<Node> = existing.iter().chain(&planned).cloned().collect();
+    for node in &planned {
+        check_parents(node, &all).with_context(|| format!("Invalid node '{}'", node.name))?;
+    }
+
+    let mut added = Vec::new();
+    for import in imports {
+        let parents = import.parents.clone();
+        let mut node = Node::from(import);
+        let id = database
+            .add_node(&node)
+            .with_context(|| format!("Failed to add node '{}'", node.name))?;
+        node.id = Some(id);
+        writeln!(out, "Added node '{}' (ID {})", node.name, id)?;
+        added.push((node, parents));
+    }
+
+    // Parents are named, so they are resolved once every node is stored,
+    // preferring imported nodes over existing ones of the same name
+    let imported: Vec<Node> = added.iter().map(|(node, _)| node.clone()).collect();
+    for (mut node, parents) in added {
+        if !parents.is_empty() {
+            node.parents = parents
+                .iter()
+                .map(|parent| {
+                    resolve_node_ids(std::slice::from_ref(parent), &imported)
+                        .or_else(|_| resolve_node_ids(std::slice::from_ref(parent), &existing))
+                        .map(|ids| ids[0])
+                })
+                .collect::<Result<_>>()
+                .with_context(|| format!("Failed to set the parents of '{}'", node.name))?;
+            database
+                .update_node(&node)
+                .with_context(|| format!("Failed to set the parents of '{}'", node.name))?;
+        }
+        notify_daemon(database, NodeConfigUpdate::Add(node));
+    }
+    Ok(())
+}
+
+fn export_nodes(database: &Database, file: &Path, out: &mut dyn Write) -> Result<()> {
+    let nodes = database.get_all_nodes()?;
+    let exports = NodeImport::export(&nodes);
+    let data = serde_json::to_string_pretty(&exports)?;
+
+    if file == Path::new("-") {
+        writeln!(out, "{}", data)?;
+    } else {
+        fs::write(file, data)
+            .with_context(|| format!("Failed to write export file {}", file.display()))?;
+        writeln!(
+            out,
+            "Exported {} nodes to {}",
+            exports.len(),
+            file.display()
+        )?;
+    }
+    Ok(())
+}
+
+fn check_node(
+    database: &Database,
+    credentials: &SharedCredentialStore,
+    key: &str,
+    out: &mut dyn Write,
+) -> Result<ExitCode> {
+    let node = find_node(database, key)?;
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+    let result = runtime.block_on(monitor::check_node_with_credentials(&node, credentials))?;
+
+    writeln!(
+        out,
+        "{}: {} ({})",
+        node.name,
+        result.status,
+        result
+            .response_time
+            .map(|ms| format!("{}ms", ms))
+            .unwrap_or_else(|| "-".to_string())
+    )?;
+    if let Some(details) = &result.details {
+        writeln!(out, "{}", details)?;
+    }
+
+    Ok(if result.status == NodeStatus::Online {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
+
+fn show_history(
+    database: &Database,
+    key: &str,
+    limit: usize,
+    json: bool,
+    out: &mut dyn Write,
+) -> Result<()> {
+    let node = find_node(database, key)?;
+    let id = node
+        .id
+        .ok_or_else(|| anyhow!("Node '{}' has no ID", node.name))?;
+    let changes = database.get_status_changes(id, Some(limit))?;
+
+    if json {
+        writeln!(out, "{}", serde_json::to_string_pretty(&changes)?)?;
+        return Ok(());
+    }
+
+    if changes.is_empty() {
+        writeln!(out, "No status changes recorded for '{}'", node.name)?;
+        return Ok(());
+    }
+
+    let runs = database.get_notification_deliveries(id, None)?;
+    let header = ["CHANGED AT", "FROM", "TO", "PREVIOUS DURATION", "HOOKS"];
+    let rows: Vec<[String; 5]> = changes
+        .iter()
+        .map(|change| {
+            [
+                format_timestamp(change.changed_at),
+                change.from_status.to_string(),
+                if change.in_maintenance {
+                    format!("{} (maintenance)", change.to_status)
+                } else {
+                    change.to_status.to_string()
+                },
+                change
+                    .duration_ms
+                    .map(format_duration)
+                    .unwrap_or_else(|| "-".to_string()),
+                change
+                    .id
+                    .and_then(|id| hook_results(&runs, id))
+                    .unwrap_or_else(|| "-".to_string()),
+            ]
+        })
+        .collect();
+
+    write_table(out, &header, &rows)
+}
+
+fn run_maintenance(
+    database: &Database,
+    command: MaintenanceCommand,
+    out: &mut dyn Write,
+) -> Result<()> {
+    match command {
+        MaintenanceCommand::List { json } => list_maintenance(database, json, out),
+        MaintenanceCommand::Add(args) => {
+            let (scope, nodes) = match (&args.node, &args.tag) {
+                (Some(key), _) => {
+                    let node = find_node(database, key)?;
+                    let id = node
+                        .id
+                        .ok_or_else(|| anyhow!("Node '{}' has no ID", node.name))?;
+                    (MaintenanceScope::Node(id), format!("'{}'", node.name))
+                }
+                (None, Some(tag)) => match normalize_tags(&[tag]).as_slice() {
+                    [tag] => (
+                        MaintenanceScope::Tag(tag.clone()),
+                        format!("nodes tagged '{}'", tag),
+                    ),
+                    _ => return Err(anyhow!("'{}' is not a single tag", tag)),
+                },
+                (None, None) => (MaintenanceScope::All, "every node".to_string()),
+            };
+            let window = MaintenanceWindow {
+                id: None,
+                scope,
+                starts_at: args.start.unwrap_or_else(Utc::now),
+                duration_mins: args.minutes,
+                recurrence: args.every.and_then(optional),
+                reason: args.reason.and_then(optional),
+            };
+            window.validate()?;
+            let id = database.add_maintenance_window(&window)?;
+            writeln!(out, "Added maintenance window {} for {}", id, nodes)?;
+            Ok(())
+        }
+        MaintenanceCommand::Remove { id } => {
+            if !database.delete_maintenance_window(id)? {
+                return Err(anyhow!("No maintenance window with ID {}", id));
+            }
+            writeln!(out, "Removed maintenance window {}", id)?;
+            Ok(())
+        }
+    }
+}
+
+fn list_maintenance(database: &Database, json: bool, out: &mut dyn Write) -> Result<()> {
+    let windows = database.get_maintenance_windows()?;
+    if json {
+        writeln!(out, "{}", serde_json::to_string_pretty(&windows)?)?;
+        return Ok(());
+    }
+    if windows.is_empty() {
+        writeln!(out, "No maintenance windows")?;
+        return Ok(());
+    }
+
+    let nodes = database.get_all_nodes()?;
+    let now = Utc::now();
+    let header = ["ID", "NODES", "SCHEDULE", "DURATION", "STATE", "REASON"];
+    let rows: Vec<[String; 6]> = windows
+        .into_iter()
+        .map(|window| {
+            let scope = match &window.scope {
+                MaintenanceScope::All => "all".to_string(),
+                MaintenanceScope::Node(id) => nodes
+                    .iter()
+                    .find(|node| node.id == Some(*id))
+                    .map_or_else(|| id.to_string(), |node| node.name.clone()),
+                MaintenanceScope::Tag(tag) => format!("tag:{}", tag),
+            };
+            let schedule = match &window.recurrence {
+                Some(cron) => format!("{} from {}", cron, format_timestamp(window.starts_at)),
+                None => format!("once at {}", format_timestamp(window.starts_at)),
+            };
+            let state = if let Some(end) = window.active_until(now) {
+                format!("active until {}", format_timestamp(end))
+            } else if let Some(&(start, _)) = window
+                .occurrences(now, now + chrono::Duration::days(366))
+                .first()
+            {
+                format!("next at {}", format_timestamp(start))
+            } else {
+                "ended".to_string()
+            };
+            [
+                window.id.unwrap_or_default().to_string(),
+                scope,
+                schedule,
+                format_duration(i64::from(window.duration_mins) * 60_000),
+                state,
+                window.reason.unwrap_or_else(|| "-".to_string()),
+            ]
+        })
+        .collect();
+    write_table(out, &header, &rows)
+}
+
+/// Rejects node definitions the TUI form would not produce
+pub fn validate_node(node: &Node) -> Result<()> {
+    if node.name.trim().is_empty() {
+        return Err(anyhow!("Node name cannot be empty"));
+    }
+    if node.monitoring_interval == 0 {
+        return Err(anyhow!("Monitoring interval must be at least 1 second"));
+    }
+    let target_missing = match &node.detail {
+        MonitorDetail::Http { url, .. } => url.trim().is_empty(),
+        MonitorDetail::Ping { host, .. }
+        | MonitorDetail::Tcp { host, .. }
+        | MonitorDetail::Ssh { host, .. } => host.trim().is_empty(),
+        MonitorDetail::Dns { server, .. } => server.trim().is_empty(),
+        MonitorDetail::Udp { host, .. } => host.trim().is_empty(),
+    };
+    if target_missing {
+        return Err(anyhow!("Node '{}' has no target", node.name));
+    }
+    node.detail.validate()
+}
+
+/// Tells a running daemon about a node change, if there is one
+#[cfg(unix)]
+fn notify_daemon(database: &Database, update: NodeConfigUpdate) {
+    let socket = crate::ipc::socket_path(database.path());
+    if !socket.exists() {
+        return;
+    }
+    if let Err(e) = crate::ipc::send_command(&socket, &update) {
+        eprintln!(
+            "Warning: the change was saved but the running daemon could not be notified ({}); \
+             restart the daemon to pick it up",
+            e
+        );
+    }
+}
+
+#[cfg(not(unix))]
+fn notify_daemon(_database: &Database, _update: NodeConfigUpdate) {}
+
+fn format_timestamp(timestamp: DateTime<Utc>) -> String {
+    timestamp
+        .with_timezone(&Local)
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string()
+}
+
+/// Writes left-aligned columns separated by two spaces
+fn write_table<const N: usize>(
+    out: &mut dyn Write,
+    header: &[&str; N],
+    rows: &[[String; N]],
+) -> Result<()> {
+    let mut widths = header.map(|h| h.chars().count());
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let header: Vec<String> = header.iter().map(|h| h.to_string()).collect();
+    for row in std::iter::once(header.as_slice()).chain(rows.iter().map(|r| r.as_slice())) {
+        let line: Vec<String> = row
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect();
+        writeln!(out, "{}", line.join("  ").trim_end())?;
+    }
+    Ok(())
+}
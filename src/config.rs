@@ -1,52 +1,855 @@
-use anyhow::Result;
-use directories::ProjectDirs;
+use crate::models::{
+    HookCommands, HttpHeader, RetentionPolicy, DEFAULT_HOUR_ROLLUP_RETENTION_DAYS,
+    DEFAULT_HTTP_TIMEOUT, DEFAULT_MAX_CHECK_ATTEMPTS, DEFAULT_MINUTE_ROLLUP_RETENTION_DAYS,
+    DEFAULT_RAW_RETENTION_DAYS, DEFAULT_RETRY_INTERVAL,
+};
+use crate::monitoring_engine::{
+    EngineConfig, DEFAULT_CHECK_DEADLINE_SECS, DEFAULT_MAX_CONCURRENT_CHECKS,
+    DEFAULT_MAX_START_JITTER_SECS,
+};
+use crate::paths::AppPaths;
+use anyhow::{anyhow, Result};
+use lettre::message::Mailbox;
 use serde::{Deserialize, Serialize};
+use std::fmt;
 use std::fs;
-use std::path::PathBuf;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Default monitoring interval in seconds for new nodes
+pub const DEFAULT_MONITORING_INTERVAL: u64 = 5;
+
+/// Default TUI refresh rate in milliseconds
+pub const DEFAULT_REFRESH_RATE_MS: u64 = 100;
+
+/// Allowed range for the TUI refresh rate in milliseconds
+const REFRESH_RATE_RANGE_MS: std::ops::RangeInclusive<u64> = 10..=2000;
+
+/// Default number of attempts to deliver a notification to each channel
+pub const DEFAULT_NOTIFICATION_MAX_ATTEMPTS: u32 = 5;
+
+/// Default delay in seconds before the first notification retry
+pub const DEFAULT_NOTIFICATION_RETRY_BACKOFF_SECS: u64 = 5;
+
+/// Default time in seconds to collect status changes into one email
+pub const DEFAULT_EMAIL_BATCH_WINDOW_SECS: u64 = 10;
+
+/// Default seconds a hook command may run before it is killed
+pub const DEFAULT_HOOK_TIMEOUT_SECS: u64 = 30;
+
+/// Default address of the Prometheus metrics endpoint
+pub const DEFAULT_METRICS_LISTEN: &str = "127.0.0.1:9469";
+
+/// Default address of the REST API
+pub const DEFAULT_API_LISTEN: &str = "127.0.0.1:9470";
 
 /// Application configuration
-#[allow(dead_code)]
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct AppConfig {}
+///
+/// Every section falls back to its defaults when missing from the file, so a
+/// config written by an older version keeps loading.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(default)]
+pub struct AppConfig {
+    /// Settings applied to newly created nodes
+    pub defaults: NodeDefaults,
+    /// Monitoring engine tuning
+    pub engine: EngineSettings,
+    /// TUI preferences
+    pub ui: UiSettings,
+    /// Which status changes produce notifications
+    pub notifications: NotificationSettings,
+    /// Commands run on status changes of every node
+    pub hooks: HookSettings,
+    /// Prometheus metrics endpoint
+    pub metrics: MetricsSettings,
+    /// REST API served by the daemon
+    pub api: ApiSettings,
+    /// Where SSH credentials are kept
+    pub credentials: CredentialSettings,
+}
+
+/// Settings applied to newly created nodes
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct NodeDefaults {
+    /// Seconds between checks
+    pub monitoring_interval: u64,
+    /// Consecutive failures before a node is confirmed offline
+    pub max_check_attempts: u32,
+    /// Seconds between retries while a node is degraded
+    pub retry_interval: u64,
+    /// HTTP request timeout in seconds
+    pub http_timeout: u64,
+}
+
+impl Default for NodeDefaults {
+    fn default() -> Self {
+        Self {
+            monitoring_interval: DEFAULT_MONITORING_INTERVAL,
+            max_check_attempts: DEFAULT_MAX_CHECK_ATTEMPTS,
+            retry_interval: DEFAULT_RETRY_INTERVAL,
+            http_timeout: DEFAULT_HTTP_TIMEOUT,
+        }
+    }
+}
+
+/// Monitoring engine tuning
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct EngineSettings {
+    /// Maximum number of checks running at once
+    pub max_concurrent_checks: usize,
+    /// Seconds a single check may run before it counts as failed
+    pub check_deadline_secs: u64,
+    /// Upper bound in seconds of the random delay before each node's first check
+    pub max_start_jitter_secs: u64,
+    /// Days to keep raw check samples
+    pub raw_retention_days: u32,
+    /// Days to keep per-minute rollups
+    pub minute_rollup_retention_days: u32,
+    /// Days to keep per-hour rollups
+    pub hour_rollup_retention_days: u32,
+}
+
+impl Default for EngineSettings {
+    fn default() -> Self {
+        Self {
+            max_concurrent_checks: DEFAULT_MAX_CONCURRENT_CHECKS,
+            check_deadline_secs: DEFAULT_CHECK_DEADLINE_SECS,
+            max_start_jitter_secs: DEFAULT_MAX_START_JITTER_SECS,
+            raw_retention_days: DEFAULT_RAW_RETENTION_DAYS,
+            minute_rollup_retention_days: DEFAULT_MINUTE_ROLLUP_RETENTION_DAYS,
+            hour_rollup_retention_days: DEFAULT_HOUR_ROLLUP_RETENTION_DAYS,
+        }
+    }
+}
+
+impl EngineSettings {
+    /// Builds the runtime engine configuration from these settings
+    pub fn engine_config(&self) -> EngineConfig {
+        EngineConfig {
+            max_concurrent_checks: self.max_concurrent_checks,
+            check_deadline: Duration::from_secs(self.check_deadline_secs),
+            max_start_jitter: Duration::from_secs(self.max_start_jitter_secs),
+            retention: RetentionPolicy {
+                raw_days: self.raw_retention_days,
+                minute_rollup_days: self.minute_rollup_retention_days,
+                hour_rollup_days: self.hour_rollup_retention_days,
+            },
+            notifications: NotificationSettings::default(),
+            hooks: HookSettings::default(),
+            metrics: MetricsSettings::default(),
+        }
+    }
+}
+
+/// TUI color scheme
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Theme {
+    /// Light text on a dark terminal background
+    #[default]
+    Dark,
+    /// Dark text on a light terminal background
+    Light,
+}
+
+impl fmt::Display for Theme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Theme::Dark => write!(f, "dark"),
+            Theme::Light => write!(f, "light"),
+        }
+    }
+}
+
+impl FromStr for Theme {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "dark" => Ok(Theme::Dark),
+            "light" => Ok(Theme::Light),
+            other => Err(anyhow!(
+                "unknown theme '{}' (expected dark or light)",
+                other
+            )),
+        }
+    }
+}
+
+/// Columns of the TUI node table
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeColumn {
+    Name,
+    Target,
+    Type,
+    Status,
+    Latency,
+    Uptime,
+    LastCheck,
+    CertExpiry,
+}
+
+impl NodeColumn {
+    /// Every column, in the default display order
+    pub const ALL: [NodeColumn; 8] = [
+        NodeColumn::Name,
+        NodeColumn::Target,
+        NodeColumn::Type,
+        NodeColumn::Status,
+        NodeColumn::Latency,
+        NodeColumn::Uptime,
+        NodeColumn::LastCheck,
+        NodeColumn::CertExpiry,
+    ];
+
+    /// Header shown above the column
+    pub fn title(&self) -> &'static str {
+        match self {
+            NodeColumn::Name => "Name",
+            NodeColumn::Target => "Target",
+            NodeColumn::Type => "Type",
+            NodeColumn::Status => "Status",
+            NodeColumn::Latency => "Latency",
+            NodeColumn::Uptime => "Uptime/Downtime",
+            NodeColumn::LastCheck => "Last Check",
+            NodeColumn::CertExpiry => "Cert Expiry",
+        }
+    }
+
+    /// Relative width of the column
+    pub fn width(&self) -> u16 {
+        match self {
+            NodeColumn::Name => 16,
+            NodeColumn::Target => 20,
+            NodeColumn::Type => 11,
+            NodeColumn::Status => 12,
+            NodeColumn::Latency => 10,
+            NodeColumn::Uptime => 16,
+            NodeColumn::LastCheck => 18,
+            NodeColumn::CertExpiry => 12,
+        }
+    }
+}
+
+impl fmt::Display for NodeColumn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            NodeColumn::Name => "name",
+            NodeColumn::Target => "target",
+            NodeColumn::Type => "type",
+            NodeColumn::Status => "status",
+            NodeColumn::Latency => "latency",
+            NodeColumn::Uptime => "uptime",
+            NodeColumn::LastCheck => "last_check",
+            NodeColumn::CertExpiry => "cert_expiry",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for NodeColumn {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        NodeColumn::ALL
+            .into_iter()
+            .find(|column| column.to_string().eq_ignore_ascii_case(s))
+            .ok_or_else(|| anyhow!("unknown column '{}'", s))
+    }
+}
+
+/// TUI preferences
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct UiSettings {
+    /// Color scheme
+    pub theme: Theme,
+    /// Milliseconds between screen refreshes
+    pub refresh_rate_ms: u64,
+    /// Node table columns, in display order
+    pub columns: Vec<NodeColumn>,
+}
+
+impl Default for UiSettings {
+    fn default() -> Self {
+        Self {
+            theme: Theme::default(),
+            refresh_rate_ms: DEFAULT_REFRESH_RATE_MS,
+            columns: NodeColumn::ALL.to_vec(),
+        }
+    }
+}
+
+/// Which status changes produce notifications
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct NotificationSettings {
+    /// Master switch for all notifications
+    pub enabled: bool,
+    /// Notify when a node is confirmed offline
+    pub on_offline: bool,
+    /// Notify when a node starts failing checks
+    pub on_degraded: bool,
+    /// Notify when a node comes back online
+    pub on_recovery: bool,
+    /// Webhooks that receive every notification
+    pub webhooks: Vec<WebhookSettings>,
+    /// Email sent through an SMTP server, when set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<EmailSettings>,
+    /// Attempts to deliver a notification to each channel before giving up
+    pub max_attempts: u32,
+    /// Seconds before the first retry; the delay doubles with each retry after that
+    pub retry_backoff_secs: u64,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            on_offline: true,
+            on_degraded: false,
+            on_recovery: true,
+            webhooks: Vec::new(),
+            email: None,
+            max_attempts: DEFAULT_NOTIFICATION_MAX_ATTEMPTS,
+            retry_backoff_secs: DEFAULT_NOTIFICATION_RETRY_BACKOFF_SECS,
+        }
+    }
+}
+
+/// A URL that receives each notification as a JSON POST
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WebhookSettings {
+    pub url: String,
+    /// Extra request headers, such as an authorization token
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub headers: Vec<HttpHeader>,
+}
+
+impl WebhookSettings {
+    /// A webhook posting to `url` without extra headers
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            headers: Vec::new(),
+        }
+    }
+}
+
+/// How the connection to an SMTP server is secured
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SmtpSecurity {
+    /// Upgrade a plain connection with STARTTLS, failing if the server doesn't offer it
+    #[default]
+    Starttls,
+    /// TLS from the start of the connection
+    Tls,
+    /// No encryption, for relays on a trusted network
+    None,
+}
+
+impl SmtpSecurity {
+    /// Port SMTP servers usually listen on for this kind of connection
+    pub fn default_port(&self) -> u16 {
+        match self {
+            SmtpSecurity::Starttls => 587,
+            SmtpSecurity::Tls => 465,
+            SmtpSecurity::None => 25,
+        }
+    }
+}
+
+/// Email notifications sent through an SMTP server
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EmailSettings {
+    /// SMTP server host name
+    pub host: String,
+    /// SMTP server port, by default the usual one for `security`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    #[serde(default)]
+    pub security: SmtpSecurity,
+    /// Name or ID of the password credential to log in with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credential: Option<String>,
+    /// Sender address, such as `net-monitor <alerts@example.com>`
+    pub from: String,
+    /// Recipient addresses
+    pub to: Vec<String>,
+    /// Seconds to wait for further status changes to send in the same email
+    #[serde(default = "default_email_batch_window_secs")]
+    pub batch_window_secs: u64,
+}
+
+fn default_email_batch_window_secs() -> u64 {
+    DEFAULT_EMAIL_BATCH_WINDOW_SECS
+}
+
+impl EmailSettings {
+    /// Port of the SMTP server
+    pub fn port(&self) -> u16 {
+        self.port.unwrap_or(self.security.default_port())
+    }
+}
+
+/// Commands run on status changes of every node, and how long any hook may run
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct HookSettings {
+    #[serde(flatten)]
+    pub commands: HookCommands,
+    /// Seconds a hook may run before it is killed
+    pub timeout_secs: u64,
+}
+
+impl Default for HookSettings {
+    fn default() -> Self {
+        Self {
+            commands: HookCommands::default(),
+            timeout_secs: DEFAULT_HOOK_TIMEOUT_SECS,
+        }
+    }
+}
+
+/// Prometheus metrics endpoint, served while monitoring runs
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct MetricsSettings {
+    /// Serve `/metrics`
+    pub enabled: bool,
+    /// Address and port to listen on; use `0.0.0.0` to accept remote scrapes
+    pub listen: SocketAddr,
+}
+
+impl Default for MetricsSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            listen: DEFAULT_METRICS_LISTEN
+                .parse()
+                .expect("default metrics address is valid"),
+        }
+    }
+}
+
+/// REST API served by the daemon
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ApiSettings {
+    /// Serve the API while the daemon runs
+    pub enabled: bool,
+    /// Address and port to listen on
+    pub listen: SocketAddr,
+    /// Bearer token every request except `/health` must carry
+    pub token: Option<String>,
+}
+
+impl Default for ApiSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            listen: DEFAULT_API_LISTEN
+                .parse()
+                .expect("default API address is valid"),
+            token: None,
+        }
+    }
+}
+
+impl ApiSettings {
+    /// The configured token, unless it is blank
+    pub fn token(&self) -> Option<&str> {
+        self.token
+            .as_deref()
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+    }
+}
+
+/// Storage backend for SSH credentials
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CredentialBackend {
+    /// Encrypted file protected by a master password
+    #[default]
+    File,
+    /// Operating system keyring, with only metadata on disk
+    Keyring,
+}
+
+impl fmt::Display for CredentialBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CredentialBackend::File => write!(f, "file"),
+            CredentialBackend::Keyring => write!(f, "keyring"),
+        }
+    }
+}
+
+impl FromStr for CredentialBackend {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "file" => Ok(CredentialBackend::File),
+            "keyring" => Ok(CredentialBackend::Keyring),
+            other => Err(anyhow!(
+                "unknown credential backend '{}' (expected file or keyring)",
+                other
+            )),
+        }
+    }
+}
+
+/// Where SSH credentials are kept
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(default)]
+pub struct CredentialSettings {
+    /// Credential storage backend
+    pub backend: CredentialBackend,
+}
+
+/// Names of the settings editable through `get_setting`/`set_setting`, in display order
+pub const SETTING_KEYS: &[&str] = &[
+    "defaults.monitoring_interval",
+    "defaults.max_check_attempts",
+    "defaults.retry_interval",
+    "defaults.http_timeout",
+    "engine.max_concurrent_checks",
+    "engine.check_deadline_secs",
+    "engine.max_start_jitter_secs",
+    "engine.raw_retention_days",
+    "engine.minute_rollup_retention_days",
+    "engine.hour_rollup_retention_days",
+    "ui.theme",
+    "ui.refresh_rate_ms",
+    "ui.columns",
+    "notifications.enabled",
+    "notifications.on_offline",
+    "notifications.on_degraded",
+    "notifications.on_recovery",
+    "notifications.webhooks",
+    "notifications.max_attempts",
+    "notifications.retry_backoff_secs",
+    "hooks.on_down",
+    "hooks.on_degraded",
+    "hooks.on_recovery",
+    "hooks.timeout_secs",
+    "metrics.enabled",
+    "metrics.listen",
+    "api.enabled",
+    "api.listen",
+    "credentials.backend",
+];
 
-#[allow(dead_code)]
 impl AppConfig {
     /// Load configuration from file, or create default if it doesn't exist
+    #[allow(dead_code)] // The binary passes an explicit path; kept for library users
     pub fn load() -> Result<Self> {
-        let config_path = Self::get_config_path()?;
+        Self::load_from(&Self::get_config_path()?)
+    }
 
+    /// Load configuration from `config_path`, creating a default file if it doesn't exist
+    pub fn load_from(config_path: &Path) -> Result<Self> {
         if config_path.exists() {
-            let contents = fs::read_to_string(&config_path)?;
+            let contents = fs::read_to_string(config_path)?;
             let config: AppConfig = serde_json::from_str(&contents)?;
+            config.validate()?;
             Ok(config)
         } else {
             // Create default config
             let config = Self::default();
-            config.save()?;
+            config.save_to(config_path)?;
             Ok(config)
         }
     }
 
     /// Save configuration to file
+    #[allow(dead_code)] // The binary passes an explicit path; kept for library users
     pub fn save(&self) -> Result<()> {
-        let config_path = Self::get_config_path()?;
+        self.save_to(&Self::get_config_path()?)
+    }
 
+    /// Save configuration to `config_path`
+    pub fn save_to(&self, config_path: &Path) -> Result<()> {
         // Ensure parent directory exists
         if let Some(parent) = config_path.parent() {
             fs::create_dir_all(parent)?;
         }
 
         let contents = serde_json::to_string_pretty(self)?;
-        fs::write(&config_path, contents)?;
+        fs::write(config_path, contents)?;
         Ok(())
     }
 
+    /// Builds the runtime engine configuration, including where notifications
+    /// go, which hooks run and where metrics are served
+    pub fn engine_config(&self) -> EngineConfig {
+        EngineConfig {
+            notifications: self.notifications.clone(),
+            hooks: self.hooks.clone(),
+            metrics: self.metrics.clone(),
+            ..self.engine.engine_config()
+        }
+    }
+
     /// Get the path to the configuration file
     fn get_config_path() -> Result<PathBuf> {
-        let proj_dirs = ProjectDirs::from("com", "casey", "net-monitor")
-            .ok_or_else(|| anyhow::anyhow!("Could not determine config directory"))?;
+        Ok(AppPaths::from_env()?.config)
+    }
 
-        let config_dir = proj_dirs.config_dir();
-        Ok(config_dir.join("config.json"))
+    /// Checks that every setting is within its allowed range
+    pub fn validate(&self) -> Result<()> {
+        fn at_least_one(key: &str, value: u64) -> Result<()> {
+            if value == 0 {
+                return Err(anyhow!("{} must be at least 1", key));
+            }
+            Ok(())
+        }
+
+        at_least_one(
+            "defaults.monitoring_interval",
+            self.defaults.monitoring_interval,
+        )?;
+        at_least_one(
+            "defaults.max_check_attempts",
+            self.defaults.max_check_attempts.into(),
+        )?;
+        at_least_one("defaults.retry_interval", self.defaults.retry_interval)?;
+        at_least_one("defaults.http_timeout", self.defaults.http_timeout)?;
+        at_least_one(
+            "engine.max_concurrent_checks",
+            self.engine.max_concurrent_checks as u64,
+        )?;
+        at_least_one(
+            "engine.check_deadline_secs",
+            self.engine.check_deadline_secs,
+        )?;
+        at_least_one(
+            "engine.raw_retention_days",
+            self.engine.raw_retention_days.into(),
+        )?;
+        at_least_one(
+            "engine.minute_rollup_retention_days",
+            self.engine.minute_rollup_retention_days.into(),
+        )?;
+        at_least_one(
+            "engine.hour_rollup_retention_days",
+            self.engine.hour_rollup_retention_days.into(),
+        )?;
+        at_least_one(
+            "notifications.max_attempts",
+            self.notifications.max_attempts.into(),
+        )?;
+        at_least_one("hooks.timeout_secs", self.hooks.timeout_secs)?;
+
+        if !REFRESH_RATE_RANGE_MS.contains(&self.ui.refresh_rate_ms) {
+            return Err(anyhow!(
+                "ui.refresh_rate_ms must be between {} and {}",
+                REFRESH_RATE_RANGE_MS.start(),
+                REFRESH_RATE_RANGE_MS.end()
+            ));
+        }
+        if self.ui.columns.is_empty() {
+            return Err(anyhow!("ui.columns must list at least one column"));
+        }
+        for (i, column) in self.ui.columns.iter().enumerate() {
+            if self.ui.columns[..i].contains(column) {
+                return Err(anyhow!("ui.columns lists '{}' more than once", column));
+            }
+        }
+
+        if let Some(email) = &self.notifications.email {
+            if email.host.trim().is_empty() {
+                return Err(anyhow!("notifications.email.host cannot be empty"));
+            }
+            if email.to.is_empty() {
+                return Err(anyhow!(
+                    "notifications.email.to must list at least one address"
+                ));
+            }
+            for (key, address) in std::iter::once(("from", &email.from))
+                .chain(email.to.iter().map(|address| ("to", address)))
+            {
+                address.parse::<Mailbox>().map_err(|e| {
+                    anyhow!(
+                        "notifications.email.{} has an invalid address '{}': {}",
+                        key,
+                        address,
+                        e
+                    )
+                })?;
+            }
+        }
+
+        for webhook in &self.notifications.webhooks {
+            let url = reqwest::Url::parse(&webhook.url).map_err(|e| {
+                anyhow!(
+                    "notifications.webhooks has an invalid URL '{}': {}",
+                    webhook.url,
+                    e
+                )
+            })?;
+            if !matches!(url.scheme(), "http" | "https") {
+                return Err(anyhow!(
+                    "notifications.webhooks URL '{}' must start with http:// or https://",
+                    webhook.url
+                ));
+            }
+        }
+
+        if self.api.enabled && self.api.token().is_none() {
+            return Err(anyhow!(
+                "api.token must be set in config.json to enable the API"
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Returns the current value of the setting named `key` (see `SETTING_KEYS`)
+    pub fn get_setting(&self, key: &str) -> Option<String> {
+        let value = match key {
+            "defaults.monitoring_interval" => self.defaults.monitoring_interval.to_string(),
+            "defaults.max_check_attempts" => self.defaults.max_check_attempts.to_string(),
+            "defaults.retry_interval" => self.defaults.retry_interval.to_string(),
+            "defaults.http_timeout" => self.defaults.http_timeout.to_string(),
+            "engine.max_concurrent_checks" => self.engine.max_concurrent_checks.to_string(),
+            "engine.check_deadline_secs" => self.engine.check_deadline_secs.to_string(),
+            "engine.max_start_jitter_secs" => self.engine.max_start_jitter_secs.to_string(),
+            "engine.raw_retention_days" => self.engine.raw_retention_days.to_string(),
+            "engine.minute_rollup_retention_days" => {
+                self.engine.minute_rollup_retention_days.to_string()
+            }
+            "engine.hour_rollup_retention_days" => {
+                self.engine.hour_rollup_retention_days.to_string()
+            }
+            "ui.theme" => self.ui.theme.to_string(),
+            "ui.refresh_rate_ms" => self.ui.refresh_rate_ms.to_string(),
+            "ui.columns" => self
+                .ui
+                .columns
+                .iter()
+                .map(|c| c.to_string())
+                .collect::<Vec<_>>()
+                .join(","),
+            "notifications.enabled" => self.notifications.enabled.to_string(),
+            "notifications.on_offline" => self.notifications.on_offline.to_string(),
+            "notifications.on_degraded" => self.notifications.on_degraded.to_string(),
+            "notifications.on_recovery" => self.notifications.on_recovery.to_string(),
+            "notifications.webhooks" => self
+                .notifications
+                .webhooks
+                .iter()
+                .map(|webhook| webhook.url.as_str())
+                .collect::<Vec<_>>()
+                .join(","),
+            "notifications.max_attempts" => self.notifications.max_attempts.to_string(),
+            "notifications.retry_backoff_secs" => self.notifications.retry_backoff_secs.to_string(),
+            "hooks.on_down" => self.hooks.commands.on_down.clone().unwrap_or_default(),
+            "hooks.on_degraded" => self.hooks.commands.on_degraded.clone().unwrap_or_default(),
+            "hooks.on_recovery" => self.hooks.commands.on_recovery.clone().unwrap_or_default(),
+            "hooks.timeout_secs" => self.hooks.timeout_secs.to_string(),
+            "metrics.enabled" => self.metrics.enabled.to_string(),
+            "metrics.listen" => self.metrics.listen.to_string(),
+            "api.enabled" => self.api.enabled.to_string(),
+            "api.listen" => self.api.listen.to_string(),
+            "credentials.backend" => self.credentials.backend.to_string(),
+            _ => return None,
+        };
+        Some(value)
+    }
+
+    /// Parses `value` into the setting named `key` (see `SETTING_KEYS`).
+    ///
+    /// Only the format is checked here; call `validate` once all changes are made.
+    pub fn set_setting(&mut self, key: &str, value: &str) -> Result<()> {
+        fn parse<T: FromStr>(key: &str, value: &str) -> Result<T>
+        where
+            T::Err: fmt::Display,
+        {
+            value
+                .trim()
+                .parse()
+                .map_err(|e| anyhow!("Invalid value '{}' for {}: {}", value, key, e))
+        }
+
+        // An empty command removes the hook
+        let command = || Some(value.trim().to_string()).filter(|c| !c.is_empty());
+
+        match key {
+            "defaults.monitoring_interval" => {
+                self.defaults.monitoring_interval = parse(key, value)?
+            }
+            "defaults.max_check_attempts" => self.defaults.max_check_attempts = parse(key, value)?,
+            "defaults.retry_interval" => self.defaults.retry_interval = parse(key, value)?,
+            "defaults.http_timeout" => self.defaults.http_timeout = parse(key, value)?,
+            "engine.max_concurrent_checks" => {
+                self.engine.max_concurrent_checks = parse(key, value)?
+            }
+            "engine.check_deadline_secs" => self.engine.check_deadline_secs = parse(key, value)?,
+            "engine.max_start_jitter_secs" => {
+                self.engine.max_start_jitter_secs = parse(key, value)?
+            }
+            "engine.raw_retention_days" => self.engine.raw_retention_days = parse(key, value)?,
+            "engine.minute_rollup_retention_days" => {
+                self.engine.minute_rollup_retention_days = parse(key, value)?
+            }
+            "engine.hour_rollup_retention_days" => {
+                self.engine.hour_rollup_retention_days = parse(key, value)?
+            }
+            "ui.theme" => self.ui.theme = parse(key, value)?,
+            "ui.refresh_rate_ms" => self.ui.refresh_rate_ms = parse(key, value)?,
+            "ui.columns" => {
+                self.ui.columns = value
+                    .split(',')
+                    .filter(|c| !c.trim().is_empty())
+                    .map(|c| parse(key, c))
+                    .collect::<Result<_>>()?
+            }
+            "notifications.enabled" => self.notifications.enabled = parse(key, value)?,
+            "notifications.on_offline" => self.notifications.on_offline = parse(key, value)?,
+            "notifications.on_degraded" => self.notifications.on_degraded = parse(key, value)?,
+            "notifications.on_recovery" => self.notifications.on_recovery = parse(key, value)?,
+            "notifications.webhooks" => {
+                // Only URLs are edited here, so keep the headers of webhooks that stay
+                let existing = std::mem::take(&mut self.notifications.webhooks);
+                self.notifications.webhooks = value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|url| !url.is_empty())
+                    .map(|url| {
+                        existing
+                            .iter()
+                            .find(|webhook| webhook.url == url)
+                            .cloned()
+                            .unwrap_or_else(|| WebhookSettings::new(url))
+                    })
+                    .collect()
+            }
+            "notifications.max_attempts" => self.notifications.max_attempts = parse(key, value)?,
+            "notifications.retry_backoff_secs" => {
+                self.notifications.retry_backoff_secs = parse(key, value)?
+            }
+            "hooks.on_down" => self.hooks.commands.on_down = command(),
+            "hooks.on_degraded" => self.hooks.commands.on_degraded = command(),
+            "hooks.on_recovery" => self.hooks.commands.on_recovery = command(),
+            "hooks.timeout_secs" => self.hooks.timeout_secs = parse(key, value)?,
+            "metrics.enabled" => self.metrics.enabled = parse(key, value)?,
+            "metrics.listen" => self.metrics.listen = parse(key, value)?,
+            "api.enabled" => self.api.enabled = parse(key, value)?,
+            "api.listen" => self.api.listen = parse(key, value)?,
+            "credentials.backend" => self.credentials.backend = parse(key, value)?,
+            _ => return Err(anyhow!("Unknown setting '{}'", key)),
+        }
+        Ok(())
     }
 }
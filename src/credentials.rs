@@ -1,10 +1,19 @@
 //! Credential management for SSH connections.
 //!
 //! This module provides secure storage and management of SSH credentials.
+//! Two backends are available:
+//!
+//! - `FileCredentialStore` encrypts credentials with a key derived from a
+//!   user-supplied master password, which the TUI prompts for and headless use
+//!   reads from the environment or a password file (see `read_master_password`).
+//! - `KeyringCredentialStore` keeps passwords, key data and passphrases in the
+//!   operating system keyring and only non-secret metadata on disk.
+//!
 //! **Note:** Credentials are only supported for SSH-based connections (SSH, Ping, TCP).
 //! HTTP/HTTPS targets do not support credential-based authentication and will open
 //! in the default web browser without any credential handling.
 
+use crate::paths::AppPaths;
 use aes_gcm::{
     aead::{Aead, AeadCore, KeyInit, OsRng as AeadOsRng},
     Aes256Gcm, Key, Nonce,
@@ -12,16 +21,79 @@ use aes_gcm::{
 use anyhow::{anyhow, Result};
 use argon2::password_hash::{rand_core::OsRng, SaltString};
 use argon2::{Argon2, PasswordHasher};
-use directories::ProjectDirs;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
 use tracing::info;
-use zeroize::{Zeroize, ZeroizeOnDrop};
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
 
 /// Unique identifier for stored credentials
 pub type CredentialId = String;
 
+/// Environment variable holding the credential store master password
+pub const ENV_MASTER_PASSWORD: &str = "NET_MONITOR_MASTER_PASSWORD";
+
+/// Environment variable naming a file that contains the master password
+pub const ENV_MASTER_PASSWORD_FILE: &str = "NET_MONITOR_MASTER_PASSWORD_FILE";
+
+/// Keyring service name under which credential secrets are stored
+pub const KEYRING_SERVICE: &str = "net-monitor";
+
+/// Password that credential stores were encrypted with before master passwords
+/// were introduced. Such stores are re-encrypted the first time they are opened
+/// with a real master password.
+const LEGACY_MASTER_PASSWORD: &str = "default_password";
+
+/// Returned when the credential store cannot be decrypted with the supplied master password
+#[derive(Debug)]
+pub struct IncorrectMasterPassword;
+
+impl fmt::Display for IncorrectMasterPassword {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Incorrect master password for the credential store")
+    }
+}
+
+impl std::error::Error for IncorrectMasterPassword {}
+
+/// Reads the master password for headless use.
+///
+/// `password_file` takes precedence over the `NET_MONITOR_MASTER_PASSWORD`
+/// environment variable. A single trailing newline is stripped from the file,
+/// so it can be written with `echo`. Returns `None` when neither is set.
+pub fn read_master_password(password_file: Option<&Path>) -> Result<Option<SensitiveString>> {
+    if let Some(path) = password_file {
+        let contents = Zeroizing::new(std::fs::read_to_string(path).map_err(|e| {
+            anyhow!(
+                "Failed to read master password file {}: {}",
+                path.display(),
+                e
+            )
+        })?);
+        let password = contents
+            .strip_suffix('\n')
+            .map(|p| p.strip_suffix('\r').unwrap_or(p))
+            .unwrap_or(&contents);
+        validate_master_password(password)?;
+        return Ok(Some(SensitiveString::from(password)));
+    }
+
+    match std::env::var(ENV_MASTER_PASSWORD) {
+        Ok(value) if !value.is_empty() => Ok(Some(SensitiveString::new(value))),
+        _ => Ok(None),
+    }
+}
+
+/// Checks that `password` is acceptable as a new master password
+pub fn validate_master_password(password: &str) -> Result<()> {
+    if password.is_empty() {
+        return Err(anyhow!("Master password cannot be empty"));
+    }
+    Ok(())
+}
+
 /// Sensitive data that should be securely cleared from memory
 #[derive(Clone, Serialize, Deserialize, Zeroize, ZeroizeOnDrop)]
 pub struct SensitiveString(String);
@@ -55,6 +127,12 @@ impl From<&str> for SensitiveString {
     }
 }
 
+impl fmt::Debug for SensitiveString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SensitiveString(***)")
+    }
+}
+
 /// Different types of SSH credentials
 #[derive(Clone, Serialize, Deserialize)]
 pub enum SshCredential {
@@ -194,6 +272,84 @@ pub trait CredentialStore: Send + Sync {
     fn mark_credential_used(&mut self, id: &CredentialId) -> Result<()>;
 }
 
+/// A credential store shared between the TUI or daemon and the monitoring
+/// engine, which needs credentials for checks that log in to their target.
+///
+/// Empty while the store is locked. Clones refer to the same store, so a store
+/// unlocked after monitoring has started is picked up by the next check.
+#[derive(Clone, Default)]
+pub struct SharedCredentialStore(Arc<RwLock<Option<Box<dyn CredentialStore>>>>);
+
+impl SharedCredentialStore {
+    /// Replaces the shared store; `None` locks it again
+    pub fn set(&self, store: Option<Box<dyn CredentialStore>>) {
+        *self.write() = store;
+    }
+
+    /// Whether a store has been opened
+    pub fn is_unlocked(&self) -> bool {
+        self.read().is_some()
+    }
+
+    pub fn read(&self) -> RwLockReadGuard<'_, Option<Box<dyn CredentialStore>>> {
+        self.0
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub fn write(&self) -> RwLockWriteGuard<'_, Option<Box<dyn CredentialStore>>> {
+        self.0
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Looks up credential `id`, failing if the store is locked or doesn't have it
+    pub fn get(&self, id: &CredentialId) -> Result<SshCredential> {
+        let store = self.read();
+        let store = store
+            .as_ref()
+            .ok_or_else(|| anyhow!("Credential store is locked"))?;
+        store
+            .get_credential(id)?
+            .map(|stored| stored.credential.clone())
+            .ok_or_else(|| anyhow!("Credential {} not found", id))
+    }
+
+    /// Looks up a credential by exact name or ID, failing if the store is locked
+    pub fn find(&self, key: &str) -> Result<SshCredential> {
+        let id = {
+            let store = self.read();
+            let store = store
+                .as_deref()
+                .ok_or_else(|| anyhow!("Credential store is locked"))?;
+            find_credential_id(store, key)?
+        };
+        self.get(&id)
+    }
+}
+
+/// Finds a credential by exact name, falling back to its ID
+pub fn find_credential_id(store: &dyn CredentialStore, key: &str) -> Result<CredentialId> {
+    let summaries = store.list_credentials()?;
+
+    let mut by_name = summaries.iter().filter(|c| c.name == key);
+    if let Some(credential) = by_name.next() {
+        if by_name.next().is_some() {
+            return Err(anyhow!(
+                "Several credentials are named '{}'; use the credential ID instead",
+                key
+            ));
+        }
+        return Ok(credential.id.clone());
+    }
+
+    summaries
+        .iter()
+        .find(|c| c.id == key)
+        .map(|c| c.id.clone())
+        .ok_or_else(|| anyhow!("No credential named '{}'", key))
+}
+
 /// Summary of a credential without sensitive data
 #[derive(Clone, Serialize, Deserialize)]
 pub struct CredentialSummary {
@@ -227,6 +383,18 @@ impl From<&StoredCredential> for CredentialSummary {
     }
 }
 
+/// How the credential store file on disk is protected
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StoreStatus {
+    /// Nothing has been saved yet, so a master password still has to be chosen
+    Uninitialized,
+    /// Encrypted with the built-in password used by older versions; a master
+    /// password has to be chosen
+    Legacy,
+    /// Encrypted with a master password
+    Protected,
+}
+
 /// File-based credential store with encryption
 pub struct FileCredentialStore {
     storage_path: PathBuf,
@@ -235,13 +403,24 @@ pub struct FileCredentialStore {
 }
 
 impl FileCredentialStore {
-    /// Create a new file-based credential store
+    /// Create a new file-based credential store at the default location
+    #[allow(dead_code)] // The binary passes an explicit path; kept for library users
     pub fn new(master_password: String) -> Result<Self> {
-        let storage_path = Self::get_storage_path()?;
-        std::fs::create_dir_all(storage_path.parent().unwrap())?;
+        Self::with_path(master_password, &Self::get_storage_path()?)
+    }
+
+    /// Create a file-based credential store backed by `storage_path`.
+    ///
+    /// Fails with `IncorrectMasterPassword` if an existing store cannot be
+    /// decrypted with `master_password`. A legacy store is re-encrypted with
+    /// `master_password` instead.
+    pub fn with_path(master_password: String, storage_path: &Path) -> Result<Self> {
+        if let Some(parent) = storage_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
 
         let mut store = Self {
-            storage_path,
+            storage_path: storage_path.to_path_buf(),
             master_password: SensitiveString::new(master_password),
             credentials: HashMap::new(),
         };
@@ -256,11 +435,42 @@ impl FileCredentialStore {
         Ok(store)
     }
 
+    /// Reports how the store at `storage_path` is protected, without needing the password
+    pub fn status(storage_path: &Path) -> Result<StoreStatus> {
+        if !storage_path.exists() {
+            return Ok(StoreStatus::Uninitialized);
+        }
+
+        let encrypted_data = std::fs::read(storage_path)?;
+        if encrypted_data.is_empty() {
+            Ok(StoreStatus::Uninitialized)
+        } else if decrypt_data(LEGACY_MASTER_PASSWORD, &encrypted_data).is_ok() {
+            Ok(StoreStatus::Legacy)
+        } else {
+            Ok(StoreStatus::Protected)
+        }
+    }
+
+    /// Re-encrypts the store with `new_password` and saves it
+    pub fn change_master_password(&mut self, new_password: String) -> Result<()> {
+        validate_master_password(&new_password)?;
+
+        let old_password = std::mem::replace(
+            &mut self.master_password,
+            SensitiveString::new(new_password),
+        );
+        if let Err(e) = self.save_credentials() {
+            self.master_password = old_password;
+            return Err(e);
+        }
+
+        info!("Credential store re-encrypted with a new master password");
+        Ok(())
+    }
+
     /// Get the default storage path for credentials
     fn get_storage_path() -> Result<PathBuf> {
-        let project_dirs = ProjectDirs::from("com", "casey", "net-monitor")
-            .ok_or_else(|| anyhow!("Could not find project directories"))?;
-        Ok(project_dirs.data_dir().join("credentials.enc"))
+        Ok(AppPaths::from_env()?.credentials)
     }
 
     /// Load credentials from encrypted file
@@ -270,112 +480,130 @@ impl FileCredentialStore {
             return Ok(());
         }
 
-        let decrypted_data = self.decrypt_data(&encrypted_data)?;
+        let mut migrated = false;
+        let decrypted_data = match decrypt_data(self.master_password.as_str(), &encrypted_data) {
+            Ok(data) => data,
+            Err(e) if e.is::<IncorrectMasterPassword>() => {
+                // Stores written before master passwords existed take on this one
+                let data = decrypt_data(LEGACY_MASTER_PASSWORD, &encrypted_data).map_err(|_| e)?;
+                migrated = true;
+                data
+            }
+            Err(e) => return Err(e),
+        };
         let credentials: HashMap<CredentialId, StoredCredential> =
             serde_json::from_slice(&decrypted_data)?;
 
         self.credentials = credentials;
+        if migrated {
+            self.save_credentials()?;
+            info!("Re-encrypted legacy credential store with the master password");
+        }
         info!("Loaded {} credentials from storage", self.credentials.len());
         Ok(())
     }
 
-    /// Save credentials to encrypted file
+    /// Save credentials to encrypted file.
+    ///
+    /// The file is replaced atomically so that an interrupted write never
+    /// leaves a store that no password can decrypt.
     fn save_credentials(&self) -> Result<()> {
         let json_data = serde_json::to_vec(&self.credentials)?;
-        let encrypted_data = self.encrypt_data(&json_data)?;
-        std::fs::write(&self.storage_path, encrypted_data)?;
+        let encrypted_data = encrypt_data(self.master_password.as_str(), &json_data)?;
+        let temp_path = self.storage_path.with_extension("enc.tmp");
+        std::fs::write(&temp_path, encrypted_data)?;
+        std::fs::rename(&temp_path, &self.storage_path)?;
         Ok(())
     }
+}
 
-    /// Encrypt data using AES-256-GCM with password-derived key
-    fn encrypt_data(&self, data: &[u8]) -> Result<Vec<u8>> {
-        // Derive key from password
-        let salt = SaltString::generate(&mut OsRng);
-        let argon2 = Argon2::default();
-        let password_hash = argon2
-            .hash_password(self.master_password.as_str().as_bytes(), &salt)
-            .map_err(|e| anyhow!("Failed to hash password: {}", e))?;
-
-        // Extract first 32 bytes for AES-256 key
-        let hash = password_hash.hash.unwrap();
-        let key_bytes = hash.as_bytes();
-        let key = Key::<Aes256Gcm>::from_slice(&key_bytes[..32]);
-        let cipher = Aes256Gcm::new(key);
-
-        // Generate random nonce
-        let nonce = Aes256Gcm::generate_nonce(&mut AeadOsRng);
-
-        // Encrypt the data
-        let ciphertext = cipher
-            .encrypt(&nonce, data)
-            .map_err(|e| anyhow!("Encryption failed: {}", e))?;
+/// Generate a unique credential ID for a store currently holding `count` credentials
+fn generate_credential_id(count: usize) -> CredentialId {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    let mut hasher = DefaultHasher::new();
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_nanos()
+        .hash(&mut hasher);
+    count.hash(&mut hasher);
+    format!("cred_{:016x}", hasher.finish())
+}
 
-        // Prepend salt and nonce to ciphertext
-        let mut result = Vec::new();
-        result.extend_from_slice(salt.as_str().as_bytes());
-        result.push(0); // Separator
-        result.extend_from_slice(&nonce);
-        result.extend_from_slice(&ciphertext);
+/// Encrypt data using AES-256-GCM with password-derived key
+fn encrypt_data(master_password: &str, data: &[u8]) -> Result<Vec<u8>> {
+    // Derive key from password
+    let salt = SaltString::generate(&mut OsRng);
+    let argon2 = Argon2::default();
+    let password_hash = argon2
+        .hash_password(master_password.as_bytes(), &salt)
+        .map_err(|e| anyhow!("Failed to hash password: {}", e))?;
+
+    // Extract first 32 bytes for AES-256 key
+    let hash = password_hash.hash.unwrap();
+    let key_bytes = hash.as_bytes();
+    let key = Key::<Aes256Gcm>::from_slice(&key_bytes[..32]);
+    let cipher = Aes256Gcm::new(key);
+
+    // Generate random nonce
+    let nonce = Aes256Gcm::generate_nonce(&mut AeadOsRng);
+
+    // Encrypt the data
+    let ciphertext = cipher
+        .encrypt(&nonce, data)
+        .map_err(|e| anyhow!("Encryption failed: {}", e))?;
+
+    // Prepend salt and nonce to ciphertext
+    let mut result = Vec::new();
+    result.extend_from_slice(salt.as_str().as_bytes());
+    result.push(0); // Separator
+    result.extend_from_slice(&nonce);
+    result.extend_from_slice(&ciphertext);
+
+    Ok(result)
+}
 
-        Ok(result)
+/// Decrypt data using AES-256-GCM with password-derived key
+fn decrypt_data(master_password: &str, encrypted_data: &[u8]) -> Result<Vec<u8>> {
+    // Find separator between salt and nonce+ciphertext
+    let separator_pos = encrypted_data
+        .iter()
+        .position(|&b| b == 0)
+        .ok_or_else(|| anyhow!("Invalid encrypted data format"))?;
+
+    let salt_bytes = &encrypted_data[..separator_pos];
+    let salt_str = std::str::from_utf8(salt_bytes).map_err(|_| anyhow!("Invalid salt format"))?;
+    let salt = SaltString::from_b64(salt_str).map_err(|_| anyhow!("Invalid salt encoding"))?;
+
+    // Derive key from password and salt
+    let argon2 = Argon2::default();
+    let password_hash = argon2
+        .hash_password(master_password.as_bytes(), &salt)
+        .map_err(|e| anyhow!("Failed to hash password: {}", e))?;
+
+    let hash = password_hash.hash.unwrap();
+    let key_bytes = hash.as_bytes();
+    let key = Key::<Aes256Gcm>::from_slice(&key_bytes[..32]);
+    let cipher = Aes256Gcm::new(key);
+
+    // Extract nonce and ciphertext
+    let nonce_and_ciphertext = &encrypted_data[separator_pos + 1..];
+    if nonce_and_ciphertext.len() < 12 {
+        return Err(anyhow!("Invalid encrypted data: too short"));
     }
 
-    /// Decrypt data using AES-256-GCM with password-derived key
-    fn decrypt_data(&self, encrypted_data: &[u8]) -> Result<Vec<u8>> {
-        // Find separator between salt and nonce+ciphertext
-        let separator_pos = encrypted_data
-            .iter()
-            .position(|&b| b == 0)
-            .ok_or_else(|| anyhow!("Invalid encrypted data format"))?;
-
-        let salt_bytes = &encrypted_data[..separator_pos];
-        let salt_str =
-            std::str::from_utf8(salt_bytes).map_err(|_| anyhow!("Invalid salt format"))?;
-        let salt = SaltString::from_b64(salt_str).map_err(|_| anyhow!("Invalid salt encoding"))?;
+    let nonce = Nonce::from_slice(&nonce_and_ciphertext[..12]);
+    let ciphertext = &nonce_and_ciphertext[12..];
 
-        // Derive key from password and salt
-        let argon2 = Argon2::default();
-        let password_hash = argon2
-            .hash_password(self.master_password.as_str().as_bytes(), &salt)
-            .map_err(|e| anyhow!("Failed to hash password: {}", e))?;
+    // Decrypt the data; authentication fails when the key is wrong
+    let plaintext = cipher
+        .decrypt(nonce, ciphertext)
+        .map_err(|_| IncorrectMasterPassword)?;
 
-        let hash = password_hash.hash.unwrap();
-        let key_bytes = hash.as_bytes();
-        let key = Key::<Aes256Gcm>::from_slice(&key_bytes[..32]);
-        let cipher = Aes256Gcm::new(key);
-
-        // Extract nonce and ciphertext
-        let nonce_and_ciphertext = &encrypted_data[separator_pos + 1..];
-        if nonce_and_ciphertext.len() < 12 {
-            return Err(anyhow!("Invalid encrypted data: too short"));
-        }
-
-        let nonce = Nonce::from_slice(&nonce_and_ciphertext[..12]);
-        let ciphertext = &nonce_and_ciphertext[12..];
-
-        // Decrypt the data
-        let plaintext = cipher
-            .decrypt(nonce, ciphertext)
-            .map_err(|e| anyhow!("Decryption failed: {}", e))?;
-
-        Ok(plaintext)
-    }
-
-    /// Generate a unique credential ID
-    fn generate_credential_id(&self) -> CredentialId {
-        use std::collections::hash_map::DefaultHasher;
-        use std::hash::{Hash, Hasher};
-        use std::time::{SystemTime, UNIX_EPOCH};
-
-        let mut hasher = DefaultHasher::new();
-        SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_nanos()
-            .hash(&mut hasher);
-        self.credentials.len().hash(&mut hasher);
-        format!("cred_{:016x}", hasher.finish())
-    }
+    Ok(plaintext)
 }
 
 impl CredentialStore for FileCredentialStore {
@@ -385,7 +613,7 @@ impl CredentialStore for FileCredentialStore {
         description: Option<String>,
         credential: SshCredential,
     ) -> Result<CredentialId> {
-        let id = self.generate_credential_id();
+        let id = generate_credential_id(self.credentials.len());
         let stored_credential = StoredCredential {
             id: id.clone(),
             name,
@@ -455,6 +683,324 @@ impl CredentialStore for FileCredentialStore {
     }
 }
 
+/// Secret fields of an `SshCredential`, kept in the keyring as JSON
+#[derive(Default, Serialize, Deserialize, Zeroize, ZeroizeOnDrop)]
+struct CredentialSecrets {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    password: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    private_key_data: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    passphrase: Option<String>,
+}
+
+impl SshCredential {
+    /// Whether any part of this credential has to be kept in the keyring
+    fn has_secrets(&self) -> bool {
+        !matches!(
+            self,
+            SshCredential::Default
+                | SshCredential::Key {
+                    passphrase: None,
+                    ..
+                }
+        )
+    }
+
+    /// Moves the secret fields out of this credential, leaving empty placeholders
+    fn take_secrets(&mut self) -> CredentialSecrets {
+        fn take(value: &mut SensitiveString) -> String {
+            std::mem::take(&mut value.0)
+        }
+
+        let mut secrets = CredentialSecrets::default();
+        match self {
+            SshCredential::Default => {}
+            SshCredential::Password { password, .. } => secrets.password = Some(take(password)),
+            SshCredential::Key { passphrase, .. } => {
+                secrets.passphrase = passphrase.as_mut().map(take)
+            }
+            SshCredential::KeyData {
+                private_key_data,
+                passphrase,
+                ..
+            } => {
+                secrets.private_key_data = Some(take(private_key_data));
+                secrets.passphrase = passphrase.as_mut().map(take);
+            }
+        }
+        secrets
+    }
+
+    /// Fills the placeholders left by `take_secrets` from `secrets`
+    fn restore_secrets(&mut self, secrets: &mut CredentialSecrets) {
+        fn fill(target: &mut SensitiveString, value: &mut Option<String>) {
+            if let Some(value) = value.take() {
+                *target = SensitiveString::new(value);
+            }
+        }
+
+        match self {
+            SshCredential::Default => {}
+            SshCredential::Password { password, .. } => fill(password, &mut secrets.password),
+            SshCredential::Key { passphrase, .. } => {
+                if let Some(passphrase) = passphrase {
+                    fill(passphrase, &mut secrets.passphrase);
+                }
+            }
+            SshCredential::KeyData {
+                private_key_data,
+                passphrase,
+                ..
+            } => {
+                fill(private_key_data, &mut secrets.private_key_data);
+                if let Some(passphrase) = passphrase {
+                    fill(passphrase, &mut secrets.passphrase);
+                }
+            }
+        }
+    }
+}
+
+/// Credential store that keeps secrets in the operating system keyring
+/// (Secret Service on Linux, Keychain on macOS, Credential Manager on Windows).
+///
+/// Names, descriptions, usernames and key paths are saved to a JSON metadata
+/// file with the secret fields left empty. Each credential's password, key data
+/// and passphrase are stored together as one keyring item under
+/// `KEYRING_SERVICE`, named after the credential ID. No master password is needed.
+pub struct KeyringCredentialStore {
+    metadata_path: PathBuf,
+    credentials: HashMap<CredentialId, StoredCredential>,
+    entries: HashMap<CredentialId, keyring::Entry>,
+}
+
+impl KeyringCredentialStore {
+    /// Opens the keyring-backed store whose metadata is kept at `metadata_path`
+    pub fn with_path(metadata_path: &Path) -> Result<Self> {
+        if let Some(parent) = metadata_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let credentials: HashMap<CredentialId, StoredCredential> = if metadata_path.exists() {
+            serde_json::from_slice(&std::fs::read(metadata_path)?).map_err(|e| {
+                anyhow!(
+                    "Failed to parse credential metadata {}: {}",
+                    metadata_path.display(),
+                    e
+                )
+            })?
+        } else {
+            HashMap::new()
+        };
+
+        // Entries are created once and reused, so every lookup of a credential
+        // goes through the same keyring item
+        let mut entries = HashMap::new();
+        for (id, stored) in &credentials {
+            if stored.credential.has_secrets() {
+                entries.insert(id.clone(), keyring_entry(id)?);
+            }
+        }
+
+        info!(
+            "Loaded {} keyring credentials from {:?}",
+            credentials.len(),
+            metadata_path
+        );
+        Ok(Self {
+            metadata_path: metadata_path.to_path_buf(),
+            credentials,
+            entries,
+        })
+    }
+
+    /// Adds `stored` as-is, keeping its ID and timestamps.
+    ///
+    /// Used when moving credentials from another store, so that nodes keep
+    /// referring to them.
+    pub fn import_credential(&mut self, stored: StoredCredential) -> Result<()> {
+        if self.credentials.contains_key(&stored.id) {
+            return Err(anyhow!("Credential with ID {} already exists", stored.id));
+        }
+        self.insert(stored)
+    }
+
+    /// Writes the secrets of `stored` to the keyring and its metadata to disk,
+    /// replacing any credential with the same ID
+    fn insert(&mut self, mut stored: StoredCredential) -> Result<()> {
+        let id = stored.id.clone();
+        let secrets = stored.credential.take_secrets();
+
+        if stored.credential.has_secrets() {
+            let entry = match self.entries.remove(&id) {
+                Some(entry) => entry,
+                None => keyring_entry(&id)?,
+            };
+            let secret = Zeroizing::new(serde_json::to_string(&secrets)?);
+            entry
+                .set_password(&secret)
+                .map_err(|e| anyhow!("Failed to save credential {} to the keyring: {}", id, e))?;
+            self.entries.insert(id.clone(), entry);
+        } else if let Some(entry) = self.entries.remove(&id) {
+            delete_keyring_entry(&id, &entry)?;
+        }
+
+        self.credentials.insert(id, stored);
+        self.save_metadata()
+    }
+
+    /// Save the metadata file, replacing it atomically
+    fn save_metadata(&self) -> Result<()> {
+        let json_data = serde_json::to_vec_pretty(&self.credentials)?;
+        let temp_path = self.metadata_path.with_extension("json.tmp");
+        std::fs::write(&temp_path, json_data)?;
+        std::fs::rename(&temp_path, &self.metadata_path)?;
+        Ok(())
+    }
+}
+
+/// Keyring item holding the secrets of credential `id`
+fn keyring_entry(id: &str) -> Result<keyring::Entry> {
+    keyring::Entry::new(KEYRING_SERVICE, id)
+        .map_err(|e| anyhow!("Failed to access the keyring for credential {}: {}", id, e))
+}
+
+/// Removes the keyring item of credential `id`; an item that is already gone is fine
+fn delete_keyring_entry(id: &str, entry: &keyring::Entry) -> Result<()> {
+    match entry.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(anyhow!(
+            "Failed to remove credential {} from the keyring: {}",
+            id,
+            e
+        )),
+    }
+}
+
+impl CredentialStore for KeyringCredentialStore {
+    fn store_credential(
+        &mut self,
+        name: String,
+        description: Option<String>,
+        credential: SshCredential,
+    ) -> Result<CredentialId> {
+        let id = generate_credential_id(self.credentials.len());
+        self.insert(StoredCredential {
+            id: id.clone(),
+            name,
+            description,
+            credential,
+            created_at: chrono::Utc::now(),
+            last_used: None,
+        })?;
+
+        info!("Stored credential with ID {} in the keyring", id);
+        Ok(id)
+    }
+
+    fn get_credential(&self, id: &CredentialId) -> Result<Option<StoredCredential>> {
+        let Some(stored) = self.credentials.get(id) else {
+            return Ok(None);
+        };
+
+        let mut stored = stored.clone();
+        if let Some(entry) = self.entries.get(id) {
+            let secret = Zeroizing::new(entry.get_password().map_err(|e| match e {
+                keyring::Error::NoEntry => {
+                    anyhow!("Secret for credential {} is missing from the keyring", id)
+                }
+                e => anyhow!("Failed to read credential {} from the keyring: {}", id, e),
+            })?);
+            let mut secrets: CredentialSecrets = serde_json::from_str(&secret)?;
+            stored.credential.restore_secrets(&mut secrets);
+        }
+        Ok(Some(stored))
+    }
+
+    fn list_credentials(&self) -> Result<Vec<CredentialSummary>> {
+        Ok(self
+            .credentials
+            .values()
+            .map(CredentialSummary::from)
+            .collect())
+    }
+
+    fn update_credential(
+        &mut self,
+        id: &CredentialId,
+        name: String,
+        description: Option<String>,
+        credential: SshCredential,
+    ) -> Result<()> {
+        let Some(existing) = self.credentials.get(id) else {
+            return Err(anyhow!("Credential with ID {} not found", id));
+        };
+
+        let updated = StoredCredential {
+            id: id.clone(),
+            name,
+            description,
+            credential,
+            created_at: existing.created_at,
+            last_used: existing.last_used,
+        };
+        self.insert(updated)?;
+        info!("Updated credential with ID {} in the keyring", id);
+        Ok(())
+    }
+
+    fn delete_credential(&mut self, id: &CredentialId) -> Result<()> {
+        if !self.credentials.contains_key(id) {
+            return Err(anyhow!("Credential with ID {} not found", id));
+        }
+
+        if let Some(entry) = self.entries.get(id) {
+            delete_keyring_entry(id, entry)?;
+        }
+        self.entries.remove(id);
+        self.credentials.remove(id);
+        self.save_metadata()?;
+        info!("Deleted credential with ID {} from the keyring", id);
+        Ok(())
+    }
+
+    fn mark_credential_used(&mut self, id: &CredentialId) -> Result<()> {
+        if let Some(stored_credential) = self.credentials.get_mut(id) {
+            stored_credential.last_used = Some(chrono::Utc::now());
+            self.save_metadata()
+        } else {
+            Err(anyhow!("Credential with ID {} not found", id))
+        }
+    }
+}
+
+/// Moves every credential from `source` into the keyring store `target`.
+///
+/// IDs are kept, so nodes stay linked to their credentials. Each credential is
+/// deleted from `source` as soon as it is in the keyring, which makes an
+/// interrupted migration safe to run again. Returns how many were moved.
+pub fn migrate_to_keyring(
+    source: &mut dyn CredentialStore,
+    target: &mut KeyringCredentialStore,
+) -> Result<usize> {
+    let mut moved = 0;
+    for summary in source.list_credentials()? {
+        // Already moved by an earlier run that stopped before deleting it here
+        if !target.credentials.contains_key(&summary.id) {
+            let Some(stored) = source.get_credential(&summary.id)? else {
+                continue;
+            };
+            target.import_credential(stored)?;
+        }
+        source.delete_credential(&summary.id)?;
+        moved += 1;
+    }
+
+    info!("Moved {} credential(s) to the keyring", moved);
+    Ok(moved)
+}
+
 /// SSH key utilities
 pub mod ssh_keys {
     use super::*;
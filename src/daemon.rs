@@ -0,0 +1,228 @@
+//! Headless monitoring mode.
+//!
+//! Runs the monitoring engine without a TUI until the process is asked to stop
+//! (SIGINT/SIGTERM on Unix, Ctrl+C on Windows). A PID file next to the database
+//! doubles as a lock, so only one daemon monitors a given database at a time.
+//!
+//! On Unix the daemon also listens on a control socket (see `ipc`) so that TUIs
+//! can attach to it instead of running their own engine.
+//!
+//! When `api.enabled` is set the daemon serves the REST API as well (see `api`).
+
+use crate::config::ApiSettings;
+use crate::credentials::SharedCredentialStore;
+use crate::database::Database;
+use crate::monitoring_engine::{self, EngineConfig};
+use anyhow::{anyhow, Context, Result};
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::mpsc;
+use std::thread;
+use tracing::{debug, error, info};
+
+/// An exclusively locked file containing the daemon's process ID.
+///
+/// The lock is held for as long as this value is alive and is released by the
+/// operating system if the process dies, so a stale file never blocks a restart.
+pub struct PidFile {
+    // Keeps the lock alive; it is released when the file handle closes
+    file: File,
+    path: PathBuf,
+}
+
+impl PidFile {
+    /// Creates and locks the PID file at `path`.
+    ///
+    /// Fails if another process already holds the lock.
+    pub fn acquire(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)
+            .with_context(|| format!("Failed to open PID file {}", path.display()))?;
+
+        match file.try_lock() {
+            Ok(()) => {}
+            Err(TryLockError::WouldBlock) => {
+                let mut contents = String::new();
+                let _ = file.read_to_string(&mut contents);
+                let owner = match contents.trim() {
+                    "" => String::new(),
+                    pid => format!(" (PID {})", pid),
+                };
+                return Err(anyhow!(
+                    "Another net-monitor daemon{} is already running (lock file {})",
+                    owner,
+                    path.display()
+                ));
+            }
+            Err(TryLockError::Error(e)) => {
+                return Err(e)
+                    .with_context(|| format!("Failed to lock PID file {}", path.display()));
+            }
+        }
+
+        file.set_len(0)?;
+        writeln!(file, "{}", process::id())?;
+        file.sync_all()?;
+
+        Ok(Self {
+            file,
+            path: path.to_path_buf(),
+        })
+    }
+
+    /// Returns the location of the PID file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for PidFile {
+    /// Empties the file rather than removing it: another daemon may already be
+    /// waiting on this file's lock, and unlinking it would let a third one lock
+    /// a fresh file at the same path alongside it.
+    fn drop(&mut self) {
+        let _ = self.file.set_len(0);
+    }
+}
+
+/// Returns the PID file location used for the database at `db_path`
+pub fn pid_file_path(db_path: &Path) -> PathBuf {
+    db_path.with_extension("pid")
+}
+
+/// Runs the monitoring engine with `config`, and the REST API when `api` enables
+/// it, until a shutdown signal is received.
+///
+/// `credentials` holds the opened credential store: the keyring store, or the
+/// encrypted file store when a master password was supplied. SSH checks log
+/// in with it.
+pub fn run(
+    database: Database,
+    config: EngineConfig,
+    api: ApiSettings,
+    credentials: SharedCredentialStore,
+) -> Result<()> {
+    let pid_file = PidFile::acquire(&pid_file_path(database.path()))?;
+    info!(
+        "Daemon started (PID {}, lock file {})",
+        process::id(),
+        pid_file.path().display()
+    );
+
+    let nodes = database.get_all_nodes()?;
+    info!("Monitoring {} node(s)", nodes.len());
+    match &*credentials.read() {
+        Some(store) => info!(
+            "Credential store unlocked ({} credential(s))",
+            store.list_credentials()?.len()
+        ),
+        None => info!("No master password supplied; the credential store stays locked"),
+    }
+
+    #[cfg(unix)]
+    let socket_path = crate::ipc::socket_path(database.path());
+
+    let api_database = database.clone();
+    let (update_tx, update_rx) = mpsc::channel();
+    let handle = monitoring_engine::start_monitoring_with_config(
+        database,
+        nodes.clone(),
+        update_tx,
+        config,
+        credentials,
+    );
+
+    #[cfg(unix)]
+    let server = crate::ipc::MonitorServer::bind(&socket_path, nodes, handle.config_tx.clone())?;
+    // Changes made through the API go by way of the control socket, so that
+    // attached TUIs hear about them too
+    #[cfg(unix)]
+    let api_config_tx = server.command_sender();
+    #[cfg(not(unix))]
+    let api_config_tx = handle.config_tx.clone();
+
+    // The engine stops if nobody receives its updates, so keep draining them
+    // (and relaying them to attached TUIs). The channel closes once the engine
+    // thread has exited.
+    let (engine_done_tx, engine_done_rx) = tokio::sync::oneshot::channel();
+    let drain = thread::spawn(move || {
+        for node in update_rx {
+            debug!("{} is {}", node.name, node.status);
+            #[cfg(unix)]
+            server.broadcast(&node);
+        }
+        let _ = engine_done_tx.send(());
+    });
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+
+    let result = runtime.block_on(async {
+        if api.enabled {
+            let token = api
+                .token()
+                .ok_or_else(|| anyhow!("api.token must be set in config.json to enable the API"))?
+                .to_string();
+            let listener = tokio::net::TcpListener::bind(api.listen)
+                .await
+                .with_context(|| format!("Failed to listen for API requests on {}", api.listen))?;
+            info!("Serving the API at http://{}", api.listen);
+            tokio::spawn(async move {
+                if let Err(e) =
+                    crate::api::serve(listener, api_database, api_config_tx, token).await
+                {
+                    error!("API server stopped: {}", e);
+                }
+            });
+        }
+
+        tokio::select! {
+            signal = wait_for_shutdown_signal() => {
+                info!("Received {}, stopping monitoring", signal?);
+                Ok(())
+            }
+            _ = engine_done_rx => Err(anyhow!("Monitoring engine exited unexpectedly")),
+        }
+    });
+
+    let _ = handle.stop_tx.send(());
+    if drain.join().is_err() {
+        error!("Update drain thread panicked");
+    }
+
+    info!("Daemon stopped");
+    result
+}
+
+/// Waits for SIGINT or SIGTERM and returns the name of the signal received
+#[cfg(unix)]
+async fn wait_for_shutdown_signal() -> Result<&'static str> {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut terminate = signal(SignalKind::terminate())?;
+    tokio::select! {
+        result = tokio::signal::ctrl_c() => {
+            result?;
+            Ok("SIGINT")
+        }
+        _ = terminate.recv() => Ok("SIGTERM"),
+    }
+}
+
+/// Waits for Ctrl+C and returns the name of the signal received
+#[cfg(not(unix))]
+async fn wait_for_shutdown_signal() -> Result<&'static str> {
+    tokio::signal::ctrl_c().await?;
+    Ok("Ctrl+C")
+}
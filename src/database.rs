@@ -1,10 +1,20 @@
-use crate::models::{MonitorDetail, MonitoringResult, Node, NodeStatus, StatusChange};
-use anyhow::Result;
+use crate::models::{
+    check_dependencies, CheckSample, CheckStats, HookCommands, HttpOptions, MaintenanceScope,
+    MaintenanceWindow, MonitorDetail, MonitoringResult, Node, NodeStatus, NotificationDelivery,
+    RetentionPolicy, RollupBucket, RollupResolution, StatusChange, StatusRange, TlsOptions,
+    DEFAULT_HTTP_TIMEOUT, DEFAULT_PING_FALLBACK_PORT,
+};
+use anyhow::{anyhow, Result};
 use chrono::{DateTime, Utc};
-use rusqlite::{params, Connection, Row};
+use rusqlite::{params, Connection, OptionalExtension, Row, Transaction};
+use std::collections::{BTreeMap, HashMap};
 use std::path::{Path, PathBuf};
 use tracing::info;
 
+/// Buckets are only rolled up once they ended at least this long ago, so that
+/// checks completing right at a bucket boundary are not missed.
+const ROLLUP_GRACE_SECS: i64 = 60;
+
 /// Database manager for handling SQLite operations
 #[derive(Clone)]
 pub struct Database {
@@ -20,6 +30,11 @@ impl Database {
         Ok(db)
     }
 
+    /// Returns the path of the database file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
     /// Creates a new connection for this database
     fn get_connection(&self) -> Result<Connection> {
         Ok(Connection::open(&self.path)?)
@@ -76,7 +91,91 @@ impl Database {
             [],
         )?;
 
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS notification_deliveries (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                status_change_id INTEGER,
+                node_id INTEGER NOT NULL,
+                channel TEXT NOT NULL,
+                attempt INTEGER NOT NULL,
+                attempted_at TEXT NOT NULL,
+                success INTEGER NOT NULL,
+                response_status INTEGER,
+                error TEXT,
+                FOREIGN KEY (status_change_id) REFERENCES status_changes (id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS maintenance_windows (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                node_id INTEGER,
+                tag TEXT,
+                starts_at TEXT NOT NULL,
+                duration_mins INTEGER NOT NULL,
+                recurrence TEXT,
+                reason TEXT,
+                FOREIGN KEY (node_id) REFERENCES nodes (id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        // Each node's parents, in the order they were given
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS node_dependencies (
+                node_id INTEGER NOT NULL,
+                parent_id INTEGER NOT NULL,
+                position INTEGER NOT NULL,
+                PRIMARY KEY (node_id, parent_id),
+                FOREIGN KEY (node_id) REFERENCES nodes (id) ON DELETE CASCADE,
+                FOREIGN KEY (parent_id) REFERENCES nodes (id) ON DELETE CASCADE
+            ) WITHOUT ROWID",
+            [],
+        )?;
+
+        // Compact time series of every check: epoch-millisecond timestamps, no text columns
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS check_samples (
+                node_id INTEGER NOT NULL,
+                checked_at INTEGER NOT NULL,
+                success INTEGER NOT NULL,
+                response_time INTEGER,
+                PRIMARY KEY (node_id, checked_at)
+            ) WITHOUT ROWID",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS check_rollups (
+                resolution TEXT NOT NULL,
+                node_id INTEGER NOT NULL,
+                bucket_start INTEGER NOT NULL,
+                sample_count INTEGER NOT NULL,
+                success_count INTEGER NOT NULL,
+                min_response_time INTEGER,
+                avg_response_time REAL,
+                max_response_time INTEGER,
+                p95_response_time INTEGER,
+                PRIMARY KEY (resolution, node_id, bucket_start)
+            ) WITHOUT ROWID",
+            [],
+        )?;
+
+        // Tracks how far each rollup resolution has been computed
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS rollup_state (
+                resolution TEXT PRIMARY KEY,
+                rolled_up_to INTEGER NOT NULL
+            )",
+            [],
+        )?;
+
         // Create indexes for efficient queries
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_check_samples_checked_at ON check_samples(checked_at)",
+            [],
+        )?;
         conn.execute(
             "CREATE INDEX IF NOT EXISTS idx_status_changes_node_id ON status_changes(node_id)",
             [],
@@ -85,6 +184,11 @@ impl Database {
             "CREATE INDEX IF NOT EXISTS idx_status_changes_changed_at ON status_changes(changed_at)",
             [],
         )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_notification_deliveries_node_id
+             ON notification_deliveries(node_id)",
+            [],
+        )?;
 
         // Add credential_id column to existing nodes table if it doesn't exist
         self.migrate_credential_column(&conn)?;
@@ -101,6 +205,43 @@ impl Database {
         // Add retry tracking columns
         self.migrate_retry_columns(&conn)?;
 
+        // Add per-node HTTP timeout column
+        self.migrate_http_timeout_column(&conn)?;
+
+        // Add SSH check columns
+        self.migrate_ssh_columns(&conn)?;
+
+        // Add HTTP request and response check columns
+        self.migrate_http_options_columns(&conn)?;
+
+        // Add TLS policy and certificate expiry columns
+        self.migrate_tls_columns(&conn)?;
+
+        // Migrate to add the DNS check columns
+        self.migrate_dns_columns(&conn)?;
+
+        // Add ping threshold, fallback and statistics columns
+        self.migrate_ping_columns(&conn)?;
+
+        // Add the UDP check columns
+        self.migrate_udp_columns(&conn)?;
+
+        // Add the per-node hook command columns
+        self.migrate_hook_columns(&conn)?;
+
+        // Add the group and tag columns
+        self.add_missing_columns(&conn, "nodes", &[("node_group", "TEXT"), ("tags", "TEXT")])?;
+
+        // Flag status changes that happened during maintenance
+        self.add_missing_columns(
+            &conn,
+            "status_changes",
+            &[("in_maintenance", "INTEGER NOT NULL DEFAULT 0")],
+        )?;
+
+        // Let maintenance windows cover every node with a tag
+        self.add_missing_columns(&conn, "maintenance_windows", &[("tag", "TEXT")])?;
+
         Ok(())
     }
 
@@ -269,6 +410,165 @@ impl Database {
         Ok(())
     }
 
+    /// Migrate to add http_timeout column if it doesn't exist
+    fn migrate_http_timeout_column(&self, conn: &Connection) -> Result<()> {
+        let mut stmt = conn.prepare("PRAGMA table_info(nodes)")?;
+        let column_exists = stmt
+            .query_map([], |row| {
+                let column_name: String = row.get(1)?;
+                Ok(column_name)
+            })?
+            .any(|name| name.unwrap_or_default() == "http_timeout");
+
+        if !column_exists {
+            conn.execute("ALTER TABLE nodes ADD COLUMN http_timeout INTEGER", [])?;
+            // Existing HTTP nodes keep the timeout that used to be hardcoded
+            conn.execute(
+                "UPDATE nodes SET http_timeout = ?1 WHERE monitor_type = 'http'",
+                [DEFAULT_HTTP_TIMEOUT],
+            )?;
+            info!("Added http_timeout column to nodes table");
+        }
+
+        Ok(())
+    }
+
+    /// Migrate to add the SSH check columns if they don't exist
+    fn migrate_ssh_columns(&self, conn: &Connection) -> Result<()> {
+        self.add_missing_columns(
+            conn,
+            "nodes",
+            &[
+                ("ssh_host", "TEXT"),
+                ("ssh_port", "INTEGER"),
+                ("ssh_timeout", "INTEGER"),
+                ("ssh_command", "TEXT"),
+                ("ssh_expected_output", "TEXT"),
+                ("ssh_host_key", "TEXT"),
+            ],
+        )
+    }
+
+    /// Migrate to add the HTTP method, header, body, status, redirect and
+    /// assertion columns if they don't exist. Existing nodes keep the defaults.
+    fn migrate_http_options_columns(&self, conn: &Connection) -> Result<()> {
+        self.add_missing_columns(
+            conn,
+            "nodes",
+            &[
+                ("http_method", "TEXT"),
+                ("http_headers", "TEXT"),
+                ("http_body", "TEXT"),
+                ("http_accepted_statuses", "TEXT"),
+                ("http_max_redirects", "INTEGER"),
+                ("http_assertions", "TEXT"),
+            ],
+        )
+    }
+
+    /// Migrate to add the TLS policy columns and the latest certificate expiry.
+    ///
+    /// Earlier versions accepted any certificate, so existing nodes keep doing
+    /// so; new nodes always store their mode explicitly.
+    fn migrate_tls_columns(&self, conn: &Connection) -> Result<()> {
+        self.add_missing_columns(
+            conn,
+            "nodes",
+            &[
+                ("http_tls_mode", "TEXT DEFAULT 'accept-invalid'"),
+                ("http_ca_bundle", "TEXT"),
+                ("http_cert_warning_days", "INTEGER"),
+                ("http_cert_critical_days", "INTEGER"),
+                ("cert_expires_at", "TEXT"),
+            ],
+        )
+    }
+
+    /// Migrate to add the DNS check columns if they don't exist
+    fn migrate_dns_columns(&self, conn: &Connection) -> Result<()> {
+        self.add_missing_columns(
+            conn,
+            "nodes",
+            &[
+                ("dns_server", "TEXT"),
+                ("dns_port", "INTEGER"),
+                ("dns_name", "TEXT"),
+                ("dns_record_type", "TEXT"),
+                ("dns_expected", "TEXT"),
+                ("dns_protocol", "TEXT"),
+                ("dns_timeout", "INTEGER"),
+            ],
+        )
+    }
+
+    /// Migrate to add the ping threshold, fallback and statistics columns if they don't exist
+    fn migrate_ping_columns(&self, conn: &Connection) -> Result<()> {
+        self.add_missing_columns(
+            conn,
+            "nodes",
+            &[
+                ("ping_thresholds", "TEXT"),
+                ("ping_stats", "TEXT"),
+                ("ping_fallback_port", "INTEGER"),
+            ],
+        )?;
+        self.add_missing_columns(conn, "monitoring_results", &[("ping_stats", "TEXT")])
+    }
+
+    /// Migrate to add the UDP check columns if they don't exist
+    fn migrate_udp_columns(&self, conn: &Connection) -> Result<()> {
+        self.add_missing_columns(
+            conn,
+            "nodes",
+            &[
+                ("udp_host", "TEXT"),
+                ("udp_port", "INTEGER"),
+                ("udp_payload", "TEXT"),
+                ("udp_expected_response", "TEXT"),
+                ("udp_no_reply_is_up", "INTEGER"),
+                ("udp_timeout", "INTEGER"),
+            ],
+        )
+    }
+
+    /// Migrate to add the hook command columns if they don't exist
+    fn migrate_hook_columns(&self, conn: &Connection) -> Result<()> {
+        self.add_missing_columns(
+            conn,
+            "nodes",
+            &[
+                ("hook_on_down", "TEXT"),
+                ("hook_on_degraded", "TEXT"),
+                ("hook_on_recovery", "TEXT"),
+            ],
+        )
+    }
+
+    /// Adds each `(column, sql_type)` that `table` doesn't have yet
+    fn add_missing_columns(
+        &self,
+        conn: &Connection,
+        table: &str,
+        columns: &[(&str, &str)],
+    ) -> Result<()> {
+        let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+        let existing_columns: Vec<String> = stmt
+            .query_map([], |row| row.get::<_, String>(1))?
+            .collect::<std::result::Result<_, _>>()?;
+
+        for &(column, sql_type) in columns {
+            if !existing_columns.iter().any(|c| c == column) {
+                conn.execute(
+                    &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, sql_type),
+                    [],
+                )?;
+                info!("Added {} column to {} table", column, table);
+            }
+        }
+
+        Ok(())
+    }
+
     /// Adds a new node to the database
     pub fn add_node(&self, node: &Node) -> Result<i64> {
         // Validate: HTTP nodes cannot have credentials (SSH-only feature)
@@ -276,22 +576,21 @@ impl Database {
             && node.credential_id.is_some()
         {
             return Err(anyhow::anyhow!(
-                "HTTP/HTTPS targets do not support credentials. Credentials are only supported for SSH-based connections (Ping, TCP)."
+                "HTTP/HTTPS targets do not support credentials. Credentials are only supported for SSH-based connections (SSH, Ping, TCP)."
             ));
         }
 
         let conn = self.get_connection()?;
-        let (
-            monitor_type,
-            http_url,
-            http_expected_status,
-            ping_host,
-            ping_count,
-            ping_timeout,
-            tcp_host,
-            tcp_port,
-            tcp_timeout,
-        ) = node.detail.to_db_params();
+        self.check_parents(&conn, node)?;
+        let columns = node.detail.to_columns()?;
+        let ping_stats = node
+            .ping_stats
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()?;
+        let tags = (!node.tags.is_empty())
+            .then(|| serde_json::to_string(&node.tags))
+            .transpose()?;
 
         let status_str = node.status.to_string();
 
@@ -300,31 +599,83 @@ impl Database {
                 name, monitor_type, status, last_check, response_time, monitoring_interval,
                 credential_id, http_url, http_expected_status, ping_host, ping_count, ping_timeout,
                 tcp_host, tcp_port, tcp_timeout, display_order,
-                consecutive_failures, max_check_attempts, retry_interval
+                consecutive_failures, max_check_attempts, retry_interval, http_timeout,
+                ssh_host, ssh_port, ssh_timeout, ssh_command, ssh_expected_output,
+                http_method, http_headers, http_body, http_accepted_statuses, http_max_redirects,
+                http_assertions, http_tls_mode, http_ca_bundle, http_cert_warning_days,
+                http_cert_critical_days, cert_expires_at, dns_server, dns_port, dns_name,
+                dns_record_type, dns_expected, dns_protocol, dns_timeout, ping_thresholds,
+                ping_stats, ping_fallback_port, udp_host, udp_port, udp_payload,
+                udp_expected_response, udp_no_reply_is_up, udp_timeout, hook_on_down,
+                hook_on_degraded, hook_on_recovery, node_group, tags, ssh_host_key
             ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15,
-                (SELECT COALESCE(MAX(display_order), -1) + 1 FROM nodes), ?16, ?17, ?18)",
+                (SELECT COALESCE(MAX(display_order), -1) + 1 FROM nodes), ?16, ?17, ?18, ?19,
+                ?20, ?21, ?22, ?23, ?24, ?25, ?26, ?27, ?28, ?29, ?30, ?31, ?32, ?33, ?34, ?35,
+                ?36, ?37, ?38, ?39, ?40, ?41, ?42, ?43, ?44, ?45,
+                ?46, ?47, ?48, ?49, ?50, ?51, ?52, ?53, ?54, ?55, ?56, ?57)",
             params![
                 node.name,
-                monitor_type,
+                columns.monitor_type,
                 status_str,
                 node.last_check.map(|dt| dt.to_rfc3339()),
                 node.response_time,
                 node.monitoring_interval,
                 node.credential_id,
-                http_url,
-                http_expected_status,
-                ping_host,
-                ping_count,
-                ping_timeout,
-                tcp_host,
-                tcp_port,
-                tcp_timeout,
+                columns.http_url,
+                columns.http_expected_status,
+                columns.ping_host,
+                columns.ping_count,
+                columns.ping_timeout,
+                columns.tcp_host,
+                columns.tcp_port,
+                columns.tcp_timeout,
                 node.consecutive_failures,
                 node.max_check_attempts,
                 node.retry_interval,
+                columns.http_timeout,
+                columns.ssh_host,
+                columns.ssh_port,
+                columns.ssh_timeout,
+                columns.ssh_command,
+                columns.ssh_expected_output,
+                columns.http_method,
+                columns.http_headers,
+                columns.http_body,
+                columns.http_accepted_statuses,
+                columns.http_max_redirects,
+                columns.http_assertions,
+                columns.http_tls_mode,
+                columns.http_ca_bundle,
+                columns.http_cert_warning_days,
+                columns.http_cert_critical_days,
+                node.cert_expires_at.map(|dt| dt.to_rfc3339()),
+                columns.dns_server,
+                columns.dns_port,
+                columns.dns_name,
+                columns.dns_record_type,
+                columns.dns_expected,
+                columns.dns_protocol,
+                columns.dns_timeout,
+                columns.ping_thresholds,
+                ping_stats,
+                columns.ping_fallback_port,
+                columns.udp_host,
+                columns.udp_port,
+                columns.udp_payload,
+                columns.udp_expected_response,
+                columns.udp_no_reply_is_up,
+                columns.udp_timeout,
+                node.hooks.on_down,
+                node.hooks.on_degraded,
+                node.hooks.on_recovery,
+                node.group,
+                tags,
+                columns.ssh_host_key,
             ],
         )?;
-        Ok(conn.last_insert_rowid())
+        let id = conn.last_insert_rowid();
+        self.write_parents(&conn, id, &node.parents)?;
+        Ok(id)
     }
 
     /// Updates an existing node in the database
@@ -334,22 +685,21 @@ impl Database {
             && node.credential_id.is_some()
         {
             return Err(anyhow::anyhow!(
-                "HTTP/HTTPS targets do not support credentials. Credentials are only supported for SSH-based connections (Ping, TCP)."
+                "HTTP/HTTPS targets do not support credentials. Credentials are only supported for SSH-based connections (SSH, Ping, TCP)."
             ));
         }
 
         let conn = self.get_connection()?;
-        let (
-            monitor_type,
-            http_url,
-            http_expected_status,
-            ping_host,
-            ping_count,
-            ping_timeout,
-            tcp_host,
-            tcp_port,
-            tcp_timeout,
-        ) = node.detail.to_db_params();
+        self.check_parents(&conn, node)?;
+        let columns = node.detail.to_columns()?;
+        let ping_stats = node
+            .ping_stats
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()?;
+        let tags = (!node.tags.is_empty())
+            .then(|| serde_json::to_string(&node.tags))
+            .transpose()?;
 
         let status_str = node.status.to_string();
 
@@ -359,30 +709,126 @@ impl Database {
                 monitoring_interval = ?6, credential_id = ?7, http_url = ?8, http_expected_status = ?9,
                 ping_host = ?10, ping_count = ?11, ping_timeout = ?12,
                 tcp_host = ?13, tcp_port = ?14, tcp_timeout = ?15,
-                consecutive_failures = ?16, max_check_attempts = ?17, retry_interval = ?18
-            WHERE id = ?19",
+                consecutive_failures = ?16, max_check_attempts = ?17, retry_interval = ?18,
+                http_timeout = ?19, ssh_host = ?20, ssh_port = ?21, ssh_timeout = ?22,
+                ssh_command = ?23, ssh_expected_output = ?24,
+                http_method = ?25, http_headers = ?26, http_body = ?27,
+                http_accepted_statuses = ?28, http_max_redirects = ?29, http_assertions = ?30,
+                http_tls_mode = ?31, http_ca_bundle = ?32, http_cert_warning_days = ?33,
+                http_cert_critical_days = ?34, cert_expires_at = ?35,
+                dns_server = ?36, dns_port = ?37, dns_name = ?38, dns_record_type = ?39,
+                dns_expected = ?40, dns_protocol = ?41, dns_timeout = ?42,
+                ping_thresholds = ?43, ping_stats = ?44, ping_fallback_port = ?45,
+                udp_host = ?46, udp_port = ?47, udp_payload = ?48, udp_expected_response = ?49,
+                udp_no_reply_is_up = ?50, udp_timeout = ?51,
+                hook_on_down = ?52, hook_on_degraded = ?53, hook_on_recovery = ?54,
+                node_group = ?55, tags = ?56, ssh_host_key = ?57
+            WHERE id = ?58",
             params![
                 node.name,
-                monitor_type,
+                columns.monitor_type,
                 status_str,
                 node.last_check.map(|dt| dt.to_rfc3339()),
                 node.response_time,
                 node.monitoring_interval,
                 node.credential_id,
-                http_url,
-                http_expected_status,
-                ping_host,
-                ping_count,
-                ping_timeout,
-                tcp_host,
-                tcp_port,
-                tcp_timeout,
+                columns.http_url,
+                columns.http_expected_status,
+                columns.ping_host,
+                columns.ping_count,
+                columns.ping_timeout,
+                columns.tcp_host,
+                columns.tcp_port,
+                columns.tcp_timeout,
                 node.consecutive_failures,
                 node.max_check_attempts,
                 node.retry_interval,
+                columns.http_timeout,
+                columns.ssh_host,
+                columns.ssh_port,
+                columns.ssh_timeout,
+                columns.ssh_command,
+                columns.ssh_expected_output,
+                columns.http_method,
+                columns.http_headers,
+                columns.http_body,
+                columns.http_accepted_statuses,
+                columns.http_max_redirects,
+                columns.http_assertions,
+                columns.http_tls_mode,
+                columns.http_ca_bundle,
+                columns.http_cert_warning_days,
+                columns.http_cert_critical_days,
+                node.cert_expires_at.map(|dt| dt.to_rfc3339()),
+                columns.dns_server,
+                columns.dns_port,
+                columns.dns_name,
+                columns.dns_record_type,
+                columns.dns_expected,
+                columns.dns_protocol,
+                columns.dns_timeout,
+                columns.ping_thresholds,
+                ping_stats,
+                columns.ping_fallback_port,
+                columns.udp_host,
+                columns.udp_port,
+                columns.udp_payload,
+                columns.udp_expected_response,
+                columns.udp_no_reply_is_up,
+                columns.udp_timeout,
+                node.hooks.on_down,
+                node.hooks.on_degraded,
+                node.hooks.on_recovery,
+                node.group,
+                tags,
+                columns.ssh_host_key,
                 node.id,
             ],
         )?;
+        if let Some(id) = node.id {
+            self.write_parents(&conn, id, &node.parents)?;
+        }
+        Ok(())
+    }
+
+    /// Checks that every parent of `node` exists, and that none of them is
+    /// the node itself or depends on it
+    fn check_parents(&self, conn: &Connection, node: &Node) -> Result<()> {
+        if node.parents.is_empty() {
+            return Ok(());
+        }
+
+        let mut stmt = conn.prepare("SELECT id, name FROM nodes")?;
+        let names: HashMap<i64, String> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<std::result::Result<_, _>>()?;
+        let mut stmt = conn.prepare("SELECT node_id, parent_id FROM node_dependencies")?;
+        let mut edges: HashMap<i64, Vec<i64>> = HashMap::new();
+        for edge in stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get(1)?)))? {
+            let (child, parent) = edge?;
+            edges.entry(child).or_default().push(parent);
+        }
+
+        if let Some(&parent) = node.parents.iter().find(|id| !names.contains_key(id)) {
+            return Err(anyhow!("No node with ID {} to depend on", parent));
+        }
+        check_dependencies(
+            node,
+            |id| names.get(&id).map(String::as_str),
+            |id| edges.get(&id).map_or(&[][..], Vec::as_slice),
+        )
+    }
+
+    /// Replaces the parents of node `node_id`
+    fn write_parents(&self, conn: &Connection, node_id: i64, parents: &[i64]) -> Result<()> {
+        conn.execute("DELETE FROM node_dependencies WHERE node_id = ?", [node_id])?;
+        let mut stmt = conn.prepare(
+            "INSERT OR IGNORE INTO node_dependencies (node_id, parent_id, position)
+             VALUES (?1, ?2, ?3)",
+        )?;
+        for (position, parent) in parents.iter().enumerate() {
+            stmt.execute(params![node_id, parent, position as i64])?;
+        }
         Ok(())
     }
 
@@ -392,13 +838,35 @@ impl Database {
         let mut stmt = conn.prepare(
             "SELECT id, name, monitor_type, status, last_check, response_time, monitoring_interval,
                     credential_id, http_url, http_expected_status, ping_host, ping_count, ping_timeout,
-                    tcp_host, tcp_port, tcp_timeout, consecutive_failures, max_check_attempts, retry_interval
+                    tcp_host, tcp_port, tcp_timeout, consecutive_failures, max_check_attempts, retry_interval,
+                    http_timeout, ssh_host, ssh_port, ssh_timeout, ssh_command, ssh_expected_output,
+                    http_method, http_headers, http_body, http_accepted_statuses, http_max_redirects,
+                    http_assertions, http_tls_mode, http_ca_bundle, http_cert_warning_days,
+                    http_cert_critical_days, cert_expires_at, dns_server, dns_port, dns_name,
+                    dns_record_type, dns_expected, dns_protocol, dns_timeout, ping_thresholds,
+                    ping_stats, ping_fallback_port, udp_host, udp_port, udp_payload,
+                    udp_expected_response, udp_no_reply_is_up, udp_timeout, hook_on_down,
+                    hook_on_degraded, hook_on_recovery, node_group, tags, ssh_host_key
              FROM nodes ORDER BY display_order, name",
         )?;
-        let nodes = stmt.query_map([], |row| self.row_to_node(row))?;
-        nodes
-            .collect::<std::result::Result<Vec<_>, _>>()
-            .map_err(Into::into)
+        let mut nodes = stmt
+            .query_map([], |row| self.row_to_node(row))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT node_id, parent_id FROM node_dependencies ORDER BY node_id, position",
+        )?;
+        let mut parents: HashMap<i64, Vec<i64>> = HashMap::new();
+        for edge in stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get(1)?)))? {
+            let (child, parent) = edge?;
+            parents.entry(child).or_default().push(parent);
+        }
+        for node in &mut nodes {
+            if let Some(node_parents) = node.id.and_then(|id| parents.remove(&id)) {
+                node.parents = node_parents;
+            }
+        }
+        Ok(nodes)
     }
 
     /// Updates display_order for multiple nodes atomically
@@ -419,6 +887,13 @@ impl Database {
     pub fn delete_node(&self, id: i64) -> Result<()> {
         let conn = self.get_connection()?;
         conn.execute("DELETE FROM nodes WHERE id = ?", [id])?;
+        conn.execute("DELETE FROM check_samples WHERE node_id = ?", [id])?;
+        conn.execute("DELETE FROM check_rollups WHERE node_id = ?", [id])?;
+        conn.execute("DELETE FROM maintenance_windows WHERE node_id = ?", [id])?;
+        conn.execute(
+            "DELETE FROM node_dependencies WHERE node_id = ?1 OR parent_id = ?1",
+            [id],
+        )?;
         Ok(())
     }
 
@@ -426,15 +901,22 @@ impl Database {
     pub fn add_monitoring_result(&self, result: &MonitoringResult) -> Result<i64> {
         let conn = self.get_connection()?;
         let status_str = result.status.to_string();
+        let ping_stats = result
+            .ping_stats
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()?;
         conn.execute(
-            "INSERT INTO monitoring_results (node_id, timestamp, status, response_time, details)
-             VALUES (?, ?, ?, ?, ?)",
+            "INSERT INTO monitoring_results (node_id, timestamp, status, response_time, details,
+                ping_stats)
+             VALUES (?, ?, ?, ?, ?, ?)",
             params![
                 result.node_id,
                 result.timestamp.to_rfc3339(),
                 status_str,
                 result.response_time,
                 result.details,
+                ping_stats,
             ],
         )?;
         Ok(conn.last_insert_rowid())
@@ -444,7 +926,7 @@ impl Database {
     pub fn get_latest_monitoring_result(&self, node_id: i64) -> Result<Option<MonitoringResult>> {
         let conn = self.get_connection()?;
         let mut stmt = conn.prepare(
-            "SELECT id, node_id, timestamp, status, response_time, details
+            "SELECT id, node_id, timestamp, status, response_time, details, ping_stats
              FROM monitoring_results
              WHERE node_id = ?
              ORDER BY timestamp DESC
@@ -466,6 +948,10 @@ impl Database {
                 status: status_str.parse().unwrap_or(NodeStatus::Offline),
                 response_time: row.get("response_time")?,
                 details: row.get("details")?,
+                cert_expires_at: None,
+                ping_stats: parse_column(row, "ping_stats", |text| {
+                    Ok(serde_json::from_str(text)?)
+                })?,
             })
         })?;
 
@@ -480,14 +966,16 @@ impl Database {
     pub fn add_status_change(&self, change: &StatusChange) -> Result<i64> {
         let conn = self.get_connection()?;
         conn.execute(
-            "INSERT INTO status_changes (node_id, from_status, to_status, changed_at, duration_ms)
-             VALUES (?, ?, ?, ?, ?)",
+            "INSERT INTO status_changes (node_id, from_status, to_status, changed_at, duration_ms,
+                in_maintenance)
+             VALUES (?, ?, ?, ?, ?, ?)",
             params![
                 change.node_id,
                 change.from_status.to_string(),
                 change.to_status.to_string(),
                 change.changed_at.to_rfc3339(),
                 change.duration_ms,
+                change.in_maintenance,
             ],
         )?;
         Ok(conn.last_insert_rowid())
@@ -502,7 +990,7 @@ impl Database {
         let conn = self.get_connection()?;
         let query = if let Some(limit) = limit {
             format!(
-                "SELECT id, node_id, from_status, to_status, changed_at, duration_ms
+                "SELECT id, node_id, from_status, to_status, changed_at, duration_ms, in_maintenance
                  FROM status_changes
                  WHERE node_id = ?
                  ORDER BY changed_at DESC
@@ -510,7 +998,7 @@ impl Database {
                 limit
             )
         } else {
-            "SELECT id, node_id, from_status, to_status, changed_at, duration_ms
+            "SELECT id, node_id, from_status, to_status, changed_at, duration_ms, in_maintenance
              FROM status_changes
              WHERE node_id = ?
              ORDER BY changed_at DESC"
@@ -524,11 +1012,70 @@ impl Database {
             .map_err(Into::into)
     }
 
+    /// Records an attempt to deliver a notification
+    pub fn add_notification_delivery(&self, delivery: &NotificationDelivery) -> Result<i64> {
+        let conn = self.get_connection()?;
+        conn.execute(
+            "INSERT INTO notification_deliveries (status_change_id, node_id, channel, attempt,
+                attempted_at, success, response_status, error)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            params![
+                delivery.status_change_id,
+                delivery.node_id,
+                delivery.channel,
+                delivery.attempt,
+                delivery.attempted_at.to_rfc3339(),
+                delivery.success,
+                delivery.response_status,
+                delivery.error,
+            ],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// Retrieves notification delivery attempts for a node, most recent first
+    pub fn get_notification_deliveries(
+        &self,
+        node_id: i64,
+        limit: Option<usize>,
+    ) -> Result<Vec<NotificationDelivery>> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, status_change_id, node_id, channel, attempt, attempted_at, success,
+                response_status, error
+             FROM notification_deliveries
+             WHERE node_id = ?1
+             ORDER BY attempted_at DESC, id DESC
+             LIMIT ?2",
+        )?;
+
+        let limit = limit.map_or(-1, |limit| limit as i64);
+        let deliveries = stmt.query_map(params![node_id, limit], |row| {
+            let attempted_at: String = row.get("attempted_at")?;
+            Ok(NotificationDelivery {
+                id: row.get("id")?,
+                status_change_id: row.get("status_change_id")?,
+                node_id: row.get("node_id")?,
+                channel: row.get("channel")?,
+                attempt: row.get("attempt")?,
+                attempted_at: DateTime::parse_from_rfc3339(&attempted_at)
+                    .map(|dt| dt.with_timezone(&Utc))
+                    .map_err(|_| rusqlite::Error::InvalidQuery)?,
+                success: row.get("success")?,
+                response_status: row.get("response_status")?,
+                error: row.get("error")?,
+            })
+        })?;
+        deliveries
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(Into::into)
+    }
+
     /// Gets the most recent status change for a node
     pub fn get_latest_status_change(&self, node_id: i64) -> Result<Option<StatusChange>> {
         let conn = self.get_connection()?;
         let mut stmt = conn.prepare(
-            "SELECT id, node_id, from_status, to_status, changed_at, duration_ms
+            "SELECT id, node_id, from_status, to_status, changed_at, duration_ms, in_maintenance
              FROM status_changes
              WHERE node_id = ?
              ORDER BY changed_at DESC
@@ -593,11 +1140,60 @@ impl Database {
     /// This provides a more realistic representation for newly added nodes:
     /// - No status changes = 100% uptime (assumed online)
     /// - With outages = 100% - (offline_time / total_period * 100%)
+    ///
+    /// Offline time inside the node's maintenance windows is not counted, and
+    /// neither is time spent unreachable behind a parent that was down.
     pub fn calculate_uptime_percentage(
         &self,
         node_id: i64,
         start_time: DateTime<Utc>,
         end_time: DateTime<Utc>,
+    ) -> Result<f64> {
+        let offline_percentage =
+            self.percentage_in_status(node_id, NodeStatus::Offline, start_time, end_time)?;
+        Ok((100.0 - offline_percentage).clamp(0.0, 100.0))
+    }
+
+    /// Uptime percentage of a group of nodes over a time period: the average
+    /// of `calculate_uptime_percentage` over `node_ids`, so every node weighs
+    /// the same. A group without nodes has 100% uptime.
+    pub fn calculate_group_uptime_percentage(
+        &self,
+        node_ids: &[i64],
+        start_time: DateTime<Utc>,
+        end_time: DateTime<Utc>,
+    ) -> Result<f64> {
+        if node_ids.is_empty() {
+            return Ok(100.0);
+        }
+        let mut total = 0.0;
+        for &node_id in node_ids {
+            total += self.calculate_uptime_percentage(node_id, start_time, end_time)?;
+        }
+        Ok(total / node_ids.len() as f64)
+    }
+
+    /// Percentage (0.0 - 100.0) of a time period that the node was unreachable
+    /// because a node it depends on was down. As with uptime, time inside the
+    /// node's maintenance windows is not counted.
+    pub fn calculate_unreachable_percentage(
+        &self,
+        node_id: i64,
+        start_time: DateTime<Utc>,
+        end_time: DateTime<Utc>,
+    ) -> Result<f64> {
+        self.percentage_in_status(node_id, NodeStatus::Unreachable, start_time, end_time)
+    }
+
+    /// Percentage of a time period that the node spent in `status` outside its
+    /// maintenance windows. A node without status changes is taken to have
+    /// been online throughout.
+    fn percentage_in_status(
+        &self,
+        node_id: i64,
+        status: NodeStatus,
+        start_time: DateTime<Utc>,
+        end_time: DateTime<Utc>,
     ) -> Result<f64> {
         // Validate time window
         if start_time >= end_time {
@@ -638,16 +1234,38 @@ impl Database {
                     to_status: to_status.parse().unwrap_or(NodeStatus::Offline),
                     changed_at,
                     duration_ms: None,
+                    in_maintenance: false,
                 })
             })?
             .collect::<std::result::Result<Vec<_>, _>>()?;
 
-        // If no status changes at all, assume 100% uptime (node is online)
+        // With no status changes at all, the node is assumed to have been online
         if changes.is_empty() {
-            return Ok(100.0);
+            return Ok(0.0);
         }
 
-        let mut offline_duration = 0i64;
+        let maintenance = merge_periods(
+            self.get_maintenance_windows_for_node(node_id)?
+                .iter()
+                .flat_map(|window| window.occurrences(start_time, end_time))
+                .collect(),
+        );
+        let counted_ms = |from: DateTime<Utc>, to: DateTime<Utc>| {
+            let excluded: i64 = maintenance
+                .iter()
+                .map(|&(start, end)| {
+                    let (start, end) = (start.max(from), end.min(to));
+                    if start < end {
+                        StatusChange::calculate_duration(start, end)
+                    } else {
+                        0
+                    }
+                })
+                .sum();
+            StatusChange::calculate_duration(from, to) - excluded
+        };
+
+        let mut status_duration = 0i64;
         let mut current_status = NodeStatus::Online; // Default assumption
         let mut current_time = start_time;
 
@@ -672,8 +1290,8 @@ impl Database {
                 let period_start = current_time.max(start_time);
                 let period_end = change.changed_at.min(end_time);
 
-                if period_start < period_end && current_status == NodeStatus::Offline {
-                    offline_duration += StatusChange::calculate_duration(period_start, period_end);
+                if period_start < period_end && current_status == status {
+                    status_duration += counted_ms(period_start, period_end);
                 }
 
                 current_time = change.changed_at;
@@ -686,14 +1304,290 @@ impl Database {
         }
 
         // Handle the remaining time from the last change to end_time
-        if current_time < end_time && current_status == NodeStatus::Offline {
-            offline_duration += StatusChange::calculate_duration(current_time, end_time);
+        if current_time < end_time && current_status == status {
+            status_duration += counted_ms(current_time, end_time);
+        }
+
+        Ok((status_duration as f64 / total_duration as f64) * 100.0)
+    }
+
+    /// Adds a maintenance window, returning its ID
+    pub fn add_maintenance_window(&self, window: &MaintenanceWindow) -> Result<i64> {
+        let conn = self.get_connection()?;
+        let (node_id, tag) = match &window.scope {
+            MaintenanceScope::All => (None, None),
+            MaintenanceScope::Node(id) => (Some(*id), None),
+            MaintenanceScope::Tag(tag) => (None, Some(tag)),
+        };
+        conn.execute(
+            "INSERT INTO maintenance_windows (node_id, tag, starts_at, duration_mins,
+                recurrence, reason)
+             VALUES (?, ?, ?, ?, ?, ?)",
+            params![
+                node_id,
+                tag,
+                window.starts_at.to_rfc3339(),
+                window.duration_mins,
+                window.recurrence,
+                window.reason,
+            ],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// Retrieves every maintenance window, earliest start first
+    pub fn get_maintenance_windows(&self) -> Result<Vec<MaintenanceWindow>> {
+        self.query_maintenance_windows("", params![])
+    }
+
+    /// Retrieves the maintenance windows that apply to a node, by its ID or one
+    /// of its tags, earliest start first
+    pub fn get_maintenance_windows_for_node(&self, node_id: i64) -> Result<Vec<MaintenanceWindow>> {
+        let conn = self.get_connection()?;
+        let tags: Vec<String> = conn
+            .query_row("SELECT tags FROM nodes WHERE id = ?", [node_id], |row| {
+                parse_column(row, "tags", |text| Ok(serde_json::from_str(text)?))
+            })
+            .optional()?
+            .flatten()
+            .unwrap_or_default();
+        let windows = self
+            .query_maintenance_windows("WHERE node_id IS NULL OR node_id = ?", params![node_id])?;
+        Ok(windows
+            .into_iter()
+            .filter(|window| window.scope.applies_to(node_id, &tags))
+            .collect())
+    }
+
+    fn query_maintenance_windows(
+        &self,
+        filter: &str,
+        params: &[&dyn rusqlite::ToSql],
+    ) -> Result<Vec<MaintenanceWindow>> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT id, node_id, tag, starts_at, duration_mins, recurrence, reason
+             FROM maintenance_windows
+             {}
+             ORDER BY starts_at, id",
+            filter
+        ))?;
+        let windows = stmt.query_map(params, |row| {
+            let starts_at: String = row.get("starts_at")?;
+            Ok(MaintenanceWindow {
+                id: row.get("id")?,
+                scope: match (row.get("node_id")?, row.get("tag")?) {
+                    (Some(node_id), _) => MaintenanceScope::Node(node_id),
+                    (None, Some(tag)) => MaintenanceScope::Tag(tag),
+                    (None, None) => MaintenanceScope::All,
+                },
+                starts_at: DateTime::parse_from_rfc3339(&starts_at)
+                    .map(|dt| dt.with_timezone(&Utc))
+                    .map_err(|_| rusqlite::Error::InvalidQuery)?,
+                duration_mins: row.get("duration_mins")?,
+                recurrence: row.get("recurrence")?,
+                reason: row.get("reason")?,
+            })
+        })?;
+        windows
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(Into::into)
+    }
+
+    /// Deletes a maintenance window, returning whether it existed
+    pub fn delete_maintenance_window(&self, id: i64) -> Result<bool> {
+        let conn = self.get_connection()?;
+        Ok(conn.execute("DELETE FROM maintenance_windows WHERE id = ?", [id])? > 0)
+    }
+
+    /// Whether a maintenance window covers the node at `at`
+    pub fn in_maintenance(&self, node_id: i64, at: DateTime<Utc>) -> Result<bool> {
+        Ok(self
+            .get_maintenance_windows_for_node(node_id)?
+            .iter()
+            .any(|window| window.active_until(at).is_some()))
+    }
+
+    /// Records a single check outcome in the compact time series
+    pub fn add_check_sample(&self, sample: &CheckSample) -> Result<()> {
+        let conn = self.get_connection()?;
+        conn.execute(
+            "INSERT OR REPLACE INTO check_samples (node_id, checked_at, success, response_time)
+             VALUES (?, ?, ?, ?)",
+            params![
+                sample.node_id,
+                sample.checked_at.timestamp_millis(),
+                sample.success,
+                sample.response_time,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Retrieves raw check samples for a node within `[start_time, end_time)`, oldest first
+    pub fn get_check_samples(
+        &self,
+        node_id: i64,
+        start_time: DateTime<Utc>,
+        end_time: DateTime<Utc>,
+    ) -> Result<Vec<CheckSample>> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT node_id, checked_at, success, response_time
+             FROM check_samples
+             WHERE node_id = ? AND checked_at >= ? AND checked_at < ?
+             ORDER BY checked_at ASC",
+        )?;
+
+        let samples = stmt.query_map(
+            params![
+                node_id,
+                start_time.timestamp_millis(),
+                end_time.timestamp_millis()
+            ],
+            row_to_check_sample,
+        )?;
+        samples
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(Into::into)
+    }
+
+    /// Retrieves rollup buckets for a node whose start lies within `[start_time, end_time)`,
+    /// oldest first
+    #[allow(dead_code)] // Used by reporting consumers of the rollup tables
+    pub fn get_rollups(
+        &self,
+        node_id: i64,
+        resolution: RollupResolution,
+        start_time: DateTime<Utc>,
+        end_time: DateTime<Utc>,
+    ) -> Result<Vec<RollupBucket>> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT node_id, bucket_start, sample_count, success_count, min_response_time,
+                    avg_response_time, max_response_time, p95_response_time
+             FROM check_rollups
+             WHERE resolution = ? AND node_id = ? AND bucket_start >= ? AND bucket_start < ?
+             ORDER BY bucket_start ASC",
+        )?;
+
+        let buckets = stmt.query_map(
+            params![
+                resolution.to_string(),
+                node_id,
+                start_time.timestamp_millis(),
+                end_time.timestamp_millis()
+            ],
+            |row| {
+                let bucket_start: i64 = row.get("bucket_start")?;
+                Ok(RollupBucket {
+                    node_id: row.get("node_id")?,
+                    resolution,
+                    bucket_start: DateTime::from_timestamp_millis(bucket_start)
+                        .ok_or(rusqlite::Error::InvalidQuery)?,
+                    stats: CheckStats {
+                        sample_count: row.get("sample_count")?,
+                        success_count: row.get("success_count")?,
+                        min_response_time: row.get("min_response_time")?,
+                        avg_response_time: row.get("avg_response_time")?,
+                        max_response_time: row.get("max_response_time")?,
+                        p95_response_time: row.get("p95_response_time")?,
+                    },
+                })
+            },
+        )?;
+        buckets
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(Into::into)
+    }
+
+    /// Computes rollups for every bucket that has closed since the last refresh
+    ///
+    /// Each resolution keeps its own watermark in `rollup_state`, so buckets are
+    /// rolled up exactly once even if the monitor was not running for a while.
+    /// Returns the number of rollup rows written.
+    pub fn refresh_rollups(&self, now: DateTime<Utc>) -> Result<usize> {
+        let mut conn = self.get_connection()?;
+        let tx = conn.transaction()?;
+        let cutoff = now.timestamp_millis() - ROLLUP_GRACE_SECS * 1000;
+        let mut written = 0;
+
+        for resolution in RollupResolution::ALL {
+            let end = resolution.bucket_start(cutoff);
+            let watermark: Option<i64> = tx
+                .query_row(
+                    "SELECT rolled_up_to FROM rollup_state WHERE resolution = ?",
+                    [resolution.to_string()],
+                    |row| row.get(0),
+                )
+                .optional()?;
+
+            let start = match watermark {
+                Some(watermark) => watermark,
+                None => {
+                    let first: Option<i64> =
+                        tx.query_row("SELECT MIN(checked_at) FROM check_samples", [], |row| {
+                            row.get(0)
+                        })?;
+                    match first {
+                        Some(first) => resolution.bucket_start(first),
+                        None => continue,
+                    }
+                }
+            };
+
+            if start >= end {
+                continue;
+            }
+
+            written += write_rollups(&tx, resolution, start, end)?;
+
+            tx.execute(
+                "INSERT OR REPLACE INTO rollup_state (resolution, rolled_up_to) VALUES (?, ?)",
+                params![resolution.to_string(), end],
+            )?;
         }
 
-        // Calculate uptime as 100% minus the percentage of time spent offline
-        let offline_percentage = (offline_duration as f64 / total_duration as f64) * 100.0;
-        let uptime_percentage = 100.0 - offline_percentage;
-        Ok(uptime_percentage.clamp(0.0, 100.0))
+        tx.commit()?;
+        Ok(written)
+    }
+
+    /// Deletes history that has aged out of the retention policy
+    ///
+    /// Raw samples are only removed once every rollup resolution has covered
+    /// them, so pruning never loses data that has not been summarised yet.
+    /// Daily rollups are never pruned. Returns the number of rows deleted.
+    pub fn prune_history(&self, policy: &RetentionPolicy, now: DateTime<Utc>) -> Result<usize> {
+        let conn = self.get_connection()?;
+        let day_millis = RollupResolution::Day.bucket_millis();
+        let now_ms = now.timestamp_millis();
+        let mut deleted = 0;
+
+        let rolled_up_to: Option<i64> = conn.query_row(
+            "SELECT CASE WHEN COUNT(*) = ? THEN MIN(rolled_up_to) END FROM rollup_state",
+            [RollupResolution::ALL.len() as i64],
+            |row| row.get(0),
+        )?;
+
+        if let Some(rolled_up_to) = rolled_up_to {
+            let raw_cutoff = (now_ms - policy.raw_days as i64 * day_millis).min(rolled_up_to);
+            deleted += conn.execute(
+                "DELETE FROM check_samples WHERE checked_at < ?",
+                [raw_cutoff],
+            )?;
+        }
+
+        for (resolution, days) in [
+            (RollupResolution::Minute, policy.minute_rollup_days),
+            (RollupResolution::Hour, policy.hour_rollup_days),
+        ] {
+            deleted += conn.execute(
+                "DELETE FROM check_rollups WHERE resolution = ? AND bucket_start < ?",
+                params![resolution.to_string(), now_ms - days as i64 * day_millis],
+            )?;
+        }
+
+        Ok(deleted)
     }
 
     /// Converts a database row to a Node struct
@@ -705,6 +1599,10 @@ impl Database {
         let last_check = last_check_str
             .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
             .map(|dt| dt.with_timezone(&Utc));
+        let cert_expires_at = row
+            .get::<_, Option<String>>("cert_expires_at")?
+            .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
+            .map(|dt| dt.with_timezone(&Utc));
 
         Ok(Node {
             id: row.get("id")?,
@@ -718,6 +1616,18 @@ impl Database {
             consecutive_failures: row.get("consecutive_failures").unwrap_or(0),
             max_check_attempts: row.get("max_check_attempts").unwrap_or(3),
             retry_interval: row.get("retry_interval").unwrap_or(15),
+            cert_expires_at,
+            ping_stats: parse_column(row, "ping_stats", |text| Ok(serde_json::from_str(text)?))?,
+            hooks: HookCommands {
+                on_down: row.get("hook_on_down")?,
+                on_degraded: row.get("hook_on_degraded")?,
+                on_recovery: row.get("hook_on_recovery")?,
+            },
+            // Filled in from node_dependencies by the caller
+            parents: Vec::new(),
+            group: row.get("node_group")?,
+            tags: parse_column(row, "tags", |text| Ok(serde_json::from_str(text)?))?
+                .unwrap_or_default(),
         })
     }
 
@@ -741,70 +1651,256 @@ impl Database {
             to_status: to_status.parse().unwrap_or(NodeStatus::Offline),
             changed_at,
             duration_ms: row.get("duration_ms")?,
+            in_maintenance: row.get("in_maintenance")?,
         })
     }
 }
 
-type DbParams = (
-    &'static str,   // monitor_type
-    Option<String>, // http_url
-    Option<u16>,    // http_expected_status
-    Option<String>, // ping_host
-    Option<u32>,    // ping_count
-    Option<u64>,    // ping_timeout
-    Option<String>, // tcp_host
-    Option<u16>,    // tcp_port
-    Option<u64>,    // tcp_timeout
-);
+/// Sorts `periods` and joins the ones that overlap, so no time is counted twice
+fn merge_periods(
+    mut periods: Vec<(DateTime<Utc>, DateTime<Utc>)>,
+) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+    periods.sort();
+    let mut merged: Vec<(DateTime<Utc>, DateTime<Utc>)> = Vec::with_capacity(periods.len());
+    for (start, end) in periods {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+/// Rolls up all samples in `[start, end)` at the given resolution, replacing
+/// any existing rollup rows for the same buckets
+fn write_rollups(
+    tx: &Transaction,
+    resolution: RollupResolution,
+    start: i64,
+    end: i64,
+) -> Result<usize> {
+    let mut stmt = tx.prepare(
+        "SELECT node_id, checked_at, success, response_time
+         FROM check_samples
+         WHERE checked_at >= ? AND checked_at < ?
+         ORDER BY node_id, checked_at",
+    )?;
+
+    let mut buckets: BTreeMap<(i64, i64), Vec<CheckSample>> = BTreeMap::new();
+    for sample in stmt.query_map([start, end], row_to_check_sample)? {
+        let sample = sample?;
+        let bucket_start = resolution.bucket_start(sample.checked_at.timestamp_millis());
+        buckets
+            .entry((sample.node_id, bucket_start))
+            .or_default()
+            .push(sample);
+    }
+
+    for ((node_id, bucket_start), samples) in &buckets {
+        let stats = CheckStats::from_samples(samples);
+        tx.execute(
+            "INSERT OR REPLACE INTO check_rollups (resolution, node_id, bucket_start,
+                sample_count, success_count, min_response_time, avg_response_time,
+                max_response_time, p95_response_time)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            params![
+                resolution.to_string(),
+                node_id,
+                bucket_start,
+                stats.sample_count,
+                stats.success_count,
+                stats.min_response_time,
+                stats.avg_response_time,
+                stats.max_response_time,
+                stats.p95_response_time,
+            ],
+        )?;
+    }
+
+    Ok(buckets.len())
+}
+
+/// Converts a `check_samples` row to a CheckSample struct
+fn row_to_check_sample(row: &Row) -> std::result::Result<CheckSample, rusqlite::Error> {
+    let checked_at: i64 = row.get("checked_at")?;
+    Ok(CheckSample {
+        node_id: row.get("node_id")?,
+        checked_at: DateTime::from_timestamp_millis(checked_at)
+            .ok_or(rusqlite::Error::InvalidQuery)?,
+        success: row.get("success")?,
+        response_time: row.get("response_time")?,
+    })
+}
+
+/// Type-specific `nodes` columns; those not used by a monitor type stay NULL
+#[derive(Default)]
+struct DetailColumns {
+    monitor_type: &'static str,
+    http_url: Option<String>,
+    http_expected_status: Option<u16>,
+    http_timeout: Option<u64>,
+    http_method: Option<String>,
+    /// JSON array of `Name: value` strings
+    http_headers: Option<String>,
+    http_body: Option<String>,
+    http_accepted_statuses: Option<String>,
+    http_max_redirects: Option<u32>,
+    /// JSON object; NULL when there are no assertions
+    http_assertions: Option<String>,
+    http_tls_mode: Option<String>,
+    http_ca_bundle: Option<String>,
+    http_cert_warning_days: Option<u32>,
+    http_cert_critical_days: Option<u32>,
+    ping_host: Option<String>,
+    ping_count: Option<u32>,
+    ping_timeout: Option<u64>,
+    ping_fallback_port: Option<u16>,
+    /// JSON object; NULL when no threshold is set
+    ping_thresholds: Option<String>,
+    tcp_host: Option<String>,
+    tcp_port: Option<u16>,
+    tcp_timeout: Option<u64>,
+    ssh_host: Option<String>,
+    ssh_port: Option<u16>,
+    ssh_timeout: Option<u64>,
+    ssh_command: Option<String>,
+    ssh_expected_output: Option<String>,
+    ssh_host_key: Option<String>,
+    dns_server: Option<String>,
+    dns_port: Option<u16>,
+    dns_name: Option<String>,
+    dns_record_type: Option<String>,
+    /// JSON array of answers
+    dns_expected: Option<String>,
+    dns_protocol: Option<String>,
+    dns_timeout: Option<u64>,
+    udp_host: Option<String>,
+    udp_port: Option<u16>,
+    udp_payload: Option<String>,
+    udp_expected_response: Option<String>,
+    udp_no_reply_is_up: Option<bool>,
+    udp_timeout: Option<u64>,
+}
 
 impl MonitorDetail {
-    fn to_db_params(&self) -> DbParams {
-        match self {
+    fn to_columns(&self) -> Result<DetailColumns> {
+        let columns = match self {
             MonitorDetail::Http {
                 url,
                 expected_status,
-            } => (
-                "http",
-                Some(url.clone()),
-                Some(*expected_status),
-                None,
-                None,
-                None,
-                None,
-                None,
-                None,
-            ),
+                timeout,
+                options,
+            } => DetailColumns {
+                monitor_type: "http",
+                http_url: Some(url.clone()),
+                http_expected_status: Some(*expected_status),
+                http_timeout: Some(*timeout),
+                http_method: Some(options.method.to_string()),
+                http_headers: Some(serde_json::to_string(&options.headers)?),
+                http_body: options.body.clone(),
+                http_accepted_statuses: Some(StatusRange::format_list(&options.accepted_statuses))
+                    .filter(|ranges| !ranges.is_empty()),
+                http_max_redirects: Some(options.max_redirects),
+                http_assertions: if options.assertions.is_empty() {
+                    None
+                } else {
+                    Some(serde_json::to_string(&options.assertions)?)
+                },
+                http_tls_mode: Some(options.tls.mode.to_string()),
+                http_ca_bundle: options
+                    .tls
+                    .ca_bundle
+                    .as_ref()
+                    .map(|path| path.to_string_lossy().into_owned()),
+                http_cert_warning_days: Some(options.tls.expiry_warning_days),
+                http_cert_critical_days: Some(options.tls.expiry_critical_days),
+                ..Default::default()
+            },
             MonitorDetail::Ping {
                 host,
                 count,
                 timeout,
-            } => (
-                "ping",
-                None,
-                None,
-                Some(host.clone()),
-                Some(*count),
-                Some(*timeout),
-                None,
-                None,
-                None,
-            ),
+                fallback_port,
+                thresholds,
+            } => DetailColumns {
+                monitor_type: "ping",
+                ping_host: Some(host.clone()),
+                ping_count: Some(*count),
+                ping_timeout: Some(*timeout),
+                ping_fallback_port: Some(*fallback_port),
+                ping_thresholds: if thresholds.is_default() {
+                    None
+                } else {
+                    Some(serde_json::to_string(thresholds)?)
+                },
+                ..Default::default()
+            },
             MonitorDetail::Tcp {
                 host,
                 port,
                 timeout,
-            } => (
-                "tcp",
-                None,
-                None,
-                None,
-                None,
-                None,
-                Some(host.clone()),
-                Some(*port),
-                Some(*timeout),
-            ),
-        }
+            } => DetailColumns {
+                monitor_type: "tcp",
+                tcp_host: Some(host.clone()),
+                tcp_port: Some(*port),
+                tcp_timeout: Some(*timeout),
+                ..Default::default()
+            },
+            MonitorDetail::Ssh {
+                host,
+                port,
+                timeout,
+                command,
+                expected_output,
+                host_key,
+            } => DetailColumns {
+                monitor_type: "ssh",
+                ssh_host: Some(host.clone()),
+                ssh_port: Some(*port),
+                ssh_timeout: Some(*timeout),
+                ssh_command: command.clone(),
+                ssh_expected_output: expected_output.clone(),
+                ssh_host_key: host_key.clone(),
+                ..Default::default()
+            },
+            MonitorDetail::Dns {
+                server,
+                port,
+                name,
+                record_type,
+                expected,
+                protocol,
+                timeout,
+            } => DetailColumns {
+                monitor_type: "dns",
+                dns_server: Some(server.clone()),
+                dns_port: Some(*port),
+                dns_name: Some(name.clone()),
+                dns_record_type: Some(record_type.to_string()),
+                dns_expected: Some(serde_json::to_string(expected)?),
+                dns_protocol: Some(protocol.to_string()),
+                dns_timeout: Some(*timeout),
+                ..Default::default()
+            },
+            MonitorDetail::Udp {
+                host,
+                port,
+                payload,
+                expected_response,
+                no_reply_is_up,
+                timeout,
+            } => DetailColumns {
+                monitor_type: "udp",
+                udp_host: Some(host.clone()),
+                udp_port: Some(*port),
+                udp_payload: Some(payload.to_string()),
+                udp_expected_response: expected_response.as_ref().map(ToString::to_string),
+                udp_no_reply_is_up: Some(*no_reply_is_up),
+                udp_timeout: Some(*timeout),
+                ..Default::default()
+            },
+        };
+        Ok(columns)
     }
 
     fn from_row(row: &Row) -> std::result::Result<MonitorDetail, rusqlite::Error> {
@@ -813,17 +1909,56 @@ impl MonitorDetail {
             "http" => Ok(MonitorDetail::Http {
                 url: row.get("http_url")?,
                 expected_status: row.get("http_expected_status")?,
+                timeout: row
+                    .get::<_, Option<u64>>("http_timeout")?
+                    .unwrap_or(DEFAULT_HTTP_TIMEOUT),
+                options: http_options_from_row(row)?,
             }),
             "ping" => Ok(MonitorDetail::Ping {
                 host: row.get("ping_host")?,
                 count: row.get("ping_count")?,
                 timeout: row.get("ping_timeout")?,
+                fallback_port: row
+                    .get::<_, Option<u16>>("ping_fallback_port")?
+                    .unwrap_or(DEFAULT_PING_FALLBACK_PORT),
+                thresholds: parse_column(row, "ping_thresholds", |text| {
+                    Ok(serde_json::from_str(text)?)
+                })?
+                .unwrap_or_default(),
             }),
             "tcp" => Ok(MonitorDetail::Tcp {
                 host: row.get("tcp_host")?,
                 port: row.get("tcp_port")?,
                 timeout: row.get("tcp_timeout")?,
             }),
+            "ssh" => Ok(MonitorDetail::Ssh {
+                host: row.get("ssh_host")?,
+                port: row.get("ssh_port")?,
+                timeout: row.get("ssh_timeout")?,
+                command: row.get("ssh_command")?,
+                expected_output: row.get("ssh_expected_output")?,
+                host_key: row.get("ssh_host_key")?,
+            }),
+            "dns" => Ok(MonitorDetail::Dns {
+                server: row.get("dns_server")?,
+                port: row.get("dns_port")?,
+                name: row.get("dns_name")?,
+                record_type: parse_column(row, "dns_record_type", str::parse)?.unwrap_or_default(),
+                expected: parse_column(row, "dns_expected", |text| {
+                    Ok(serde_json::from_str(text)?)
+                })?
+                .unwrap_or_default(),
+                protocol: parse_column(row, "dns_protocol", str::parse)?.unwrap_or_default(),
+                timeout: row.get("dns_timeout")?,
+            }),
+            "udp" => Ok(MonitorDetail::Udp {
+                host: row.get("udp_host")?,
+                port: row.get("udp_port")?,
+                payload: parse_column(row, "udp_payload", str::parse)?.unwrap_or_default(),
+                expected_response: parse_column(row, "udp_expected_response", str::parse)?,
+                no_reply_is_up: row.get("udp_no_reply_is_up")?,
+                timeout: row.get("udp_timeout")?,
+            }),
             _ => Err(rusqlite::Error::InvalidColumnType(
                 0,
                 "monitor_type".to_string(),
@@ -833,6 +1968,56 @@ impl MonitorDetail {
     }
 }
 
+/// Reads the HTTP options columns; NULLs, as left by older versions, give the defaults
+fn http_options_from_row(row: &Row) -> std::result::Result<HttpOptions, rusqlite::Error> {
+    let defaults = HttpOptions::default();
+    Ok(HttpOptions {
+        method: parse_column(row, "http_method", str::parse)?.unwrap_or(defaults.method),
+        headers: parse_column(row, "http_headers", |text| Ok(serde_json::from_str(text)?))?
+            .unwrap_or_default(),
+        body: row.get("http_body")?,
+        accepted_statuses: parse_column(row, "http_accepted_statuses", StatusRange::parse_list)?
+            .unwrap_or_default(),
+        max_redirects: row
+            .get::<_, Option<u32>>("http_max_redirects")?
+            .unwrap_or(defaults.max_redirects),
+        assertions: parse_column(row, "http_assertions", |text| {
+            Ok(serde_json::from_str(text)?)
+        })?
+        .unwrap_or_default(),
+        tls: TlsOptions {
+            mode: parse_column(row, "http_tls_mode", str::parse)?.unwrap_or(defaults.tls.mode),
+            ca_bundle: row
+                .get::<_, Option<String>>("http_ca_bundle")?
+                .map(PathBuf::from),
+            expiry_warning_days: row
+                .get::<_, Option<u32>>("http_cert_warning_days")?
+                .unwrap_or(defaults.tls.expiry_warning_days),
+            expiry_critical_days: row
+                .get::<_, Option<u32>>("http_cert_critical_days")?
+                .unwrap_or(defaults.tls.expiry_critical_days),
+        },
+    })
+}
+
+/// Parses a text column with `parse`, giving `None` for NULL
+fn parse_column<T>(
+    row: &Row,
+    column: &str,
+    parse: impl FnOnce(&str) -> anyhow::Result<T>,
+) -> std::result::Result<Option<T>, rusqlite::Error> {
+    let Some(text) = row.get::<_, Option<String>>(column)? else {
+        return Ok(None);
+    };
+    parse(&text).map(Some).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(
+            row.as_ref().column_index(column).unwrap_or(0),
+            rusqlite::types::Type::Text,
+            e.into(),
+        )
+    })
+}
+
 impl std::str::FromStr for NodeStatus {
     type Err = anyhow::Error;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -840,6 +2025,7 @@ impl std::str::FromStr for NodeStatus {
             "Online" => Ok(NodeStatus::Online),
             "Offline" => Ok(NodeStatus::Offline),
             "Degraded" => Ok(NodeStatus::Degraded),
+            "Unreachable" => Ok(NodeStatus::Unreachable),
             _ => Ok(NodeStatus::Offline), // Default to Offline for unknown strings
         }
     }
@@ -0,0 +1,281 @@
+//! Local commands run when a node changes status.
+//!
+//! Hooks come from two places: the `hooks` section of the config, which
+//! applies to every node, and the node's own hooks. When both have a command
+//! for a change, both run. Each command runs through the system shell with the
+//! details of the change in `NET_MONITOR_*` environment variables, is killed
+//! once it outlives the hook timeout, and has its output written to the log.
+//!
+//! Hooks run in their own tasks so that a slow command never holds up checks,
+//! and are not retried, since running a command twice may not be harmless.
+//! Every run is recorded in the database alongside notification deliveries,
+//! with a channel of `hook:<command>`.
+
+use crate::config::HookSettings;
+use crate::database::Database;
+use crate::models::{HookCommands, Node, NodeStatus, NotificationDelivery, StatusChange};
+use chrono::Utc;
+use std::process::{Output, Stdio};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::process::Command;
+use tracing::{info, warn};
+
+/// Prefix of the channel recorded for hook runs
+pub const HOOK_CHANNEL_PREFIX: &str = "hook:";
+
+/// Kind of status change a hook runs for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookEvent {
+    /// The node was confirmed offline
+    Down,
+    /// The node started failing checks, or passed them with a warning
+    Degraded,
+    /// The node is back online
+    Recovery,
+}
+
+impl HookEvent {
+    /// Returns the event of `change` that `commands` has a command for.
+    ///
+    /// As with notifications, a recovery only runs its hook when the change
+    /// that left the node offline or degraded ran one from the same commands.
+    pub fn for_change(commands: &HookCommands, change: &StatusChange) -> Option<Self> {
+        let event = if change.is_recovery() {
+            let outage = match change.from_status {
+                NodeStatus::Offline => HookEvent::Down,
+                _ => HookEvent::Degraded,
+            };
+            outage.command(commands)?;
+            HookEvent::Recovery
+        } else if change.is_degradation() {
+            match change.to_status {
+                NodeStatus::Offline => HookEvent::Down,
+                _ => HookEvent::Degraded,
+            }
+        } else {
+            return None;
+        };
+        event.command(commands).map(|_| event)
+    }
+
+    /// The command `commands` runs for this event, if any
+    pub fn command(self, commands: &HookCommands) -> Option<&str> {
+        match self {
+            HookEvent::Down => commands.on_down.as_deref(),
+            HookEvent::Degraded => commands.on_degraded.as_deref(),
+            HookEvent::Recovery => commands.on_recovery.as_deref(),
+        }
+    }
+
+    /// Value of `NET_MONITOR_EVENT`
+    pub fn name(self) -> &'static str {
+        match self {
+            HookEvent::Down => "down",
+            HookEvent::Degraded => "degraded",
+            HookEvent::Recovery => "recovery",
+        }
+    }
+}
+
+/// Environment variables describing `change` of `node` to a hook.
+/// `last_error` is the details of the failed check behind the change, or
+/// for a recovery, of the check that took the node down.
+pub fn hook_environment(
+    event: HookEvent,
+    node: &Node,
+    change: &StatusChange,
+    last_error: Option<&str>,
+) -> Vec<(&'static str, String)> {
+    let mut env = vec![
+        ("NET_MONITOR_EVENT", event.name().to_string()),
+        ("NET_MONITOR_NODE_ID", change.node_id.to_string()),
+        ("NET_MONITOR_NODE_NAME", node.name.clone()),
+        ("NET_MONITOR_NODE_TYPE", node.detail.to_string()),
+        ("NET_MONITOR_TARGET", node.detail.get_connection_target()),
+        ("NET_MONITOR_FROM_STATUS", change.from_status.to_string()),
+        ("NET_MONITOR_TO_STATUS", change.to_status.to_string()),
+        ("NET_MONITOR_CHANGED_AT", change.changed_at.to_rfc3339()),
+        (
+            "NET_MONITOR_FAILED_CHECKS",
+            node.consecutive_failures.to_string(),
+        ),
+    ];
+    if let Some(duration_ms) = change.duration_ms {
+        env.push(("NET_MONITOR_DURATION_MS", duration_ms.to_string()));
+    }
+    if let Some(error) = last_error {
+        env.push(("NET_MONITOR_LAST_ERROR", error.to_string()));
+    }
+    env
+}
+
+/// Summarises the hook runs for `status_change_id` in the order they finished,
+/// such as `ok, exit 1`. `runs` is listed most recent first, as the database
+/// returns deliveries, and may hold other deliveries, which are skipped.
+pub fn hook_results(runs: &[NotificationDelivery], status_change_id: i64) -> Option<String> {
+    let results: Vec<String> = runs
+        .iter()
+        .rev()
+        .filter(|run| {
+            run.status_change_id == Some(status_change_id)
+                && run.channel.starts_with(HOOK_CHANNEL_PREFIX)
+        })
+        .map(|run| match (run.success, run.response_status, &run.error) {
+            (true, _, _) => "ok".to_string(),
+            (false, Some(code), _) => format!("exit {}", code),
+            // Keep "Timed out after 30s" or "Failed to start" without the cause
+            (false, None, Some(error)) => error.split(':').next().unwrap_or(error).to_string(),
+            (false, None, None) => "failed".to_string(),
+        })
+        .collect();
+    (!results.is_empty()).then(|| results.join(", "))
+}
+
+/// Runs the global and per-node hooks for status changes
+#[derive(Clone)]
+pub struct HookRunner {
+    settings: Arc<HookSettings>,
+    db: Database,
+}
+
+impl HookRunner {
+    /// Creates a runner for the global hooks and timeout in `settings`,
+    /// recording each run in `db`
+    pub fn new(settings: &HookSettings, db: Database) -> Self {
+        Self {
+            settings: Arc::new(settings.clone()),
+            db,
+        }
+    }
+
+    /// Starts every hook for `change` of `node` on the current tokio runtime.
+    /// See `hook_environment` for `last_error`.
+    pub fn run(&self, node: &Node, change: &StatusChange, last_error: Option<&str>) {
+        for commands in [&self.settings.commands, &node.hooks] {
+            let Some(event) = HookEvent::for_change(commands, change) else {
+                continue;
+            };
+            let Some(command) = event.command(commands) else {
+                continue;
+            };
+            info!(
+                "Running {} hook for {} ({})",
+                event.name(),
+                node.name,
+                change.description()
+            );
+            let runner = self.clone();
+            let command = command.to_string();
+            let env = hook_environment(event, node, change, last_error);
+            let (node_id, status_change_id) = (change.node_id, change.id);
+            tokio::spawn(async move {
+                runner
+                    .run_hook(&command, &env, node_id, status_change_id)
+                    .await
+            });
+        }
+    }
+
+    /// Runs `command` with `env` until it exits or the timeout passes, logging
+    /// its output and recording the run. Returns whether it exited successfully.
+    pub async fn run_hook(
+        &self,
+        command: &str,
+        env: &[(&'static str, String)],
+        node_id: i64,
+        status_change_id: Option<i64>,
+    ) -> bool {
+        let timeout = Duration::from_secs(self.settings.timeout_secs);
+        let outcome = run_command(command, env, timeout).await;
+
+        let (success, exit_code, error) = match &outcome {
+            Ok(output) if output.status.success() => (true, output.status.code(), None),
+            Ok(output) => {
+                let mut error = match output.status.code() {
+                    Some(code) => format!("Exited with status {}", code),
+                    None => format!("Ended by {}", output.status),
+                };
+                // The last line of stderr usually says what went wrong
+                if let Some(line) = String::from_utf8_lossy(&output.stderr)
+                    .lines()
+                    .rev()
+                    .find(|line| !line.trim().is_empty())
+                {
+                    error.push_str(": ");
+                    error.push_str(line.trim());
+                }
+                (false, output.status.code(), Some(error))
+            }
+            Err(error) => (false, None, Some(error.clone())),
+        };
+
+        if let Ok(output) = &outcome {
+            for (stream, bytes) in [("stdout", &output.stdout), ("stderr", &output.stderr)] {
+                let text = String::from_utf8_lossy(bytes);
+                let text = text.trim();
+                if text.is_empty() {
+                    continue;
+                }
+                if success {
+                    info!("Hook '{}' {}: {}", command, stream, text);
+                } else {
+                    warn!("Hook '{}' {}: {}", command, stream, text);
+                }
+            }
+        }
+        match &error {
+            None => info!("Hook '{}' finished", command),
+            Some(error) => warn!("Hook '{}' failed: {}", command, error),
+        }
+
+        let run = NotificationDelivery {
+            id: None,
+            status_change_id,
+            node_id,
+            channel: format!("{}{}", HOOK_CHANNEL_PREFIX, command),
+            attempt: 1,
+            attempted_at: Utc::now(),
+            success,
+            response_status: exit_code.and_then(|code| u16::try_from(code).ok()),
+            error,
+        };
+        if let Err(e) = self.db.add_notification_delivery(&run) {
+            warn!("Failed to record hook run: {}", e);
+        }
+        success
+    }
+}
+
+/// Runs `command` through the shell, killing it once `timeout` passes
+async fn run_command(
+    command: &str,
+    env: &[(&'static str, String)],
+    timeout: Duration,
+) -> Result<Output, String> {
+    let mut shell = if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.arg("/C");
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+    let child = shell
+        .arg(command)
+        .envs(env.iter().map(|(name, value)| (name, value)))
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| format!("Failed to start: {}", e))?;
+
+    // Dropping the child when the timeout passes kills it
+    match tokio::time::timeout(timeout, child.wait_with_output()).await {
+        Ok(Ok(output)) => Ok(output),
+        Ok(Err(e)) => Err(format!("Failed to wait for the command: {}", e)),
+        Err(_) => Err(format!("Timed out after {}s", timeout.as_secs())),
+    }
+}
@@ -0,0 +1,298 @@
+//! ICMP echo requests over whichever socket the process may open.
+//!
+//! Raw ICMP sockets need root or `CAP_NET_RAW`. Linux also hands out ICMP
+//! datagram sockets to users whose group is within
+//! `net.ipv4.ping_group_range`, and macOS hands them out to everyone, so
+//! those are tried first. When neither kind is permitted, ping checks probe
+//! a TCP port instead.
+
+use socket2::{Domain, Protocol, SockAddr, Socket, Type};
+use std::fmt;
+use std::io::{self, Read};
+use std::net::{IpAddr, SocketAddr};
+use std::time::{Duration, Instant};
+
+const ECHO_REQUEST_V4: u8 = 8;
+const ECHO_REPLY_V4: u8 = 0;
+const ECHO_REQUEST_V6: u8 = 128;
+const ECHO_REPLY_V6: u8 = 129;
+
+/// Type, code, checksum, identifier and sequence number
+const HEADER_LEN: usize = 8;
+
+/// Random bytes sent with every request of a socket, which replies echo back
+const TOKEN_LEN: usize = 16;
+
+/// Kind of socket echo requests go out on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IcmpSocketKind {
+    /// Unprivileged datagram socket
+    Datagram,
+    /// Raw socket
+    Raw,
+}
+
+/// Why no ICMP socket could be opened
+#[derive(Debug)]
+pub struct IcmpUnavailable {
+    datagram: io::Error,
+    raw: io::Error,
+}
+
+impl fmt::Display for IcmpUnavailable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ICMP is unavailable (datagram socket: {}", self.datagram)?;
+        if let Some(range) = ping_group_range() {
+            write!(f, ", net.ipv4.ping_group_range is '{}'", range)?;
+        }
+        write!(f, "; raw socket: {})", self.raw)
+    }
+}
+
+impl std::error::Error for IcmpUnavailable {}
+
+/// Groups allowed to open ICMP datagram sockets, where the system has the setting
+fn ping_group_range() -> Option<String> {
+    let range = std::fs::read_to_string("/proc/sys/net/ipv4/ping_group_range").ok()?;
+    Some(range.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+/// A socket sending echo requests to one address
+pub struct IcmpSocket {
+    socket: Socket,
+    kind: IcmpSocketKind,
+    target: SockAddr,
+    ipv6: bool,
+    ident: u16,
+    token: [u8; TOKEN_LEN],
+}
+
+impl IcmpSocket {
+    /// Opens a socket for pinging `addr`, preferring an unprivileged datagram
+    /// socket over a raw one
+    pub fn open(addr: IpAddr) -> Result<Self, IcmpUnavailable> {
+        let (domain, protocol) = match addr {
+            IpAddr::V4(_) => (Domain::IPV4, Protocol::ICMPV4),
+            IpAddr::V6(_) => (Domain::IPV6, Protocol::ICMPV6),
+        };
+        let (socket, kind) = match Socket::new(domain, Type::DGRAM, Some(protocol)) {
+            Ok(socket) => (socket, IcmpSocketKind::Datagram),
+            Err(datagram) => match Socket::new(domain, Type::RAW, Some(protocol)) {
+                Ok(socket) => (socket, IcmpSocketKind::Raw),
+                Err(raw) => return Err(IcmpUnavailable { datagram, raw }),
+            },
+        };
+
+        Ok(Self {
+            socket,
+            kind,
+            target: SocketAddr::new(addr, 0).into(),
+            ipv6: addr.is_ipv6(),
+            ident: rand::random(),
+            token: rand::random(),
+        })
+    }
+
+    pub fn kind(&self) -> IcmpSocketKind {
+        self.kind
+    }
+
+    /// Sends `count` echo requests `interval` apart, as ping(8) does, and
+    /// returns the round trip time of each, or `None` where no reply came
+    /// back within `timeout`. Replies are read while later requests go out,
+    /// so the whole exchange takes at most `(count - 1) * interval + timeout`.
+    /// Blocks the calling thread.
+    pub fn ping(
+        &self,
+        count: u16,
+        interval: Duration,
+        timeout: Duration,
+    ) -> io::Result<Vec<Option<Duration>>> {
+        let count = usize::from(count);
+        let started = Instant::now();
+        let due = |seq: usize| started + interval * seq as u32;
+        let mut sent_at: Vec<Instant> = Vec::with_capacity(count);
+        let mut round_trips = vec![None; count];
+        let mut buffer = [0; 1500];
+
+        loop {
+            while sent_at.len() < count && Instant::now() >= due(sent_at.len()) {
+                let request = self.request(sent_at.len() as u16);
+                self.socket.send_to(&request, &self.target)?;
+                sent_at.push(Instant::now());
+            }
+
+            // Listen until the next request is due, or else until the last
+            // unanswered one gives up
+            let wake = if sent_at.len() < count {
+                due(sent_at.len())
+            } else {
+                match sent_at
+                    .iter()
+                    .zip(&round_trips)
+                    .filter(|(_, round_trip)| round_trip.is_none())
+                    .map(|(sent, _)| *sent + timeout)
+                    .max()
+                {
+                    Some(wake) => wake,
+                    None => break,
+                }
+            };
+            let Some(remaining) = wake
+                .checked_duration_since(Instant::now())
+                .filter(|remaining| !remaining.is_zero())
+            else {
+                if sent_at.len() < count {
+                    continue;
+                }
+                break;
+            };
+            self.socket.set_read_timeout(Some(remaining))?;
+            let len = match (&self.socket).read(&mut buffer) {
+                Ok(len) => len,
+                Err(e)
+                    if matches!(
+                        e.kind(),
+                        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                    ) =>
+                {
+                    continue
+                }
+                Err(e) => return Err(e),
+            };
+            let Some(seq) = self.reply_seq(&buffer[..len]).map(usize::from) else {
+                continue;
+            };
+            if let (Some(sent), Some(round_trip @ None)) =
+                (sent_at.get(seq), round_trips.get_mut(seq))
+            {
+                let elapsed = sent.elapsed();
+                if elapsed <= timeout {
+                    *round_trip = Some(elapsed);
+                }
+            }
+        }
+        Ok(round_trips)
+    }
+
+    fn request(&self, seq: u16) -> Vec<u8> {
+        let echo_request = if self.ipv6 {
+            ECHO_REQUEST_V6
+        } else {
+            ECHO_REQUEST_V4
+        };
+        let mut packet = vec![echo_request, 0, 0, 0];
+        packet.extend_from_slice(&self.ident.to_be_bytes());
+        packet.extend_from_slice(&seq.to_be_bytes());
+        packet.extend_from_slice(&self.token);
+        // The kernel fills in the ICMPv6 checksum, which covers the IPv6 addresses
+        if !self.ipv6 {
+            let checksum = checksum(&packet);
+            packet[2..4].copy_from_slice(&checksum.to_be_bytes());
+        }
+        packet
+    }
+
+    /// The sequence number of the request `packet` answers, if it is a reply to
+    /// this socket. Linux datagram sockets swap in their own identifier, so
+    /// only raw sockets, which see every ICMP packet on the host, check it.
+    fn reply_seq(&self, packet: &[u8]) -> Option<u16> {
+        // IPv4 raw sockets, and datagram sockets on macOS, include the IP header
+        let packet = match packet.first() {
+            Some(first) if !self.ipv6 && first >> 4 == 4 => packet
+                .get(usize::from(first & 0x0f) * 4..)
+                .unwrap_or_default(),
+            _ => packet,
+        };
+        if packet.len() < HEADER_LEN + TOKEN_LEN {
+            return None;
+        }
+
+        let echo_reply = if self.ipv6 {
+            ECHO_REPLY_V6
+        } else {
+            ECHO_REPLY_V4
+        };
+        let is_reply = packet[0] == echo_reply
+            && (self.kind == IcmpSocketKind::Datagram || packet[4..6] == self.ident.to_be_bytes())
+            && packet[HEADER_LEN..HEADER_LEN + TOKEN_LEN] == self.token;
+        is_reply.then(|| u16::from_be_bytes([packet[6], packet[7]]))
+    }
+}
+
+/// The Internet checksum of RFC 1071
+fn checksum(data: &[u8]) -> u16 {
+    let mut sum: u32 = data
+        .chunks(2)
+        .map(|pair| {
+            u32::from(u16::from_be_bytes([
+                pair[0],
+                pair.get(1).copied().unwrap_or(0),
+            ]))
+        })
+        .sum();
+    while sum >> 16 != 0 {
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+    !(sum as u16)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checksum() {
+        // Echo request with identifier 1 and sequence number 1, as RFC 1071 sums it
+        let mut packet = [8, 0, 0, 0, 0, 1, 0, 1, b'a', b'b', b'c'];
+        let sum = checksum(&packet);
+        assert_eq!(sum, 0x339b);
+        packet[2..4].copy_from_slice(&sum.to_be_bytes());
+        assert_eq!(checksum(&packet), 0);
+    }
+
+    /// A socket that never sends anything, for checking how replies are matched
+    fn unsent(kind: IcmpSocketKind) -> IcmpSocket {
+        IcmpSocket {
+            socket: Socket::new(Domain::IPV4, Type::DGRAM, None).unwrap(),
+            kind,
+            target: SocketAddr::from(([192, 0, 2, 1], 0)).into(),
+            ipv6: false,
+            ident: 0x1234,
+            token: [7; TOKEN_LEN],
+        }
+    }
+
+    /// Turns a request into the reply a host would send back
+    fn reply_to(mut request: Vec<u8>) -> Vec<u8> {
+        request[0] = ECHO_REPLY_V4;
+        request
+    }
+
+    #[test]
+    fn test_reply_seq() {
+        let raw = unsent(IcmpSocketKind::Raw);
+        let reply = reply_to(raw.request(5));
+        assert_eq!(raw.reply_seq(&reply), Some(5));
+        assert_eq!(raw.reply_seq(&reply_to(raw.request(6))), Some(6));
+        assert_eq!(raw.reply_seq(&raw.request(5)), None);
+        assert_eq!(raw.reply_seq(&reply[..HEADER_LEN]), None);
+
+        // Raw sockets receive the IP header too
+        let mut with_header = vec![0x45; 1];
+        with_header.extend_from_slice(&[0; 19]);
+        with_header.extend_from_slice(&reply);
+        assert_eq!(raw.reply_seq(&with_header), Some(5));
+
+        // Another process pinging the same host
+        let mut other = reply.clone();
+        other[4..6].copy_from_slice(&0x4321u16.to_be_bytes());
+        assert_eq!(raw.reply_seq(&other), None);
+
+        // Datagram sockets swap in their own identifier, but the token still matches
+        assert_eq!(unsent(IcmpSocketKind::Datagram).reply_seq(&other), Some(5));
+        let mut stranger = other;
+        stranger[HEADER_LEN] = 0;
+        assert_eq!(unsent(IcmpSocketKind::Datagram).reply_seq(&stranger), None);
+    }
+}
@@ -0,0 +1,366 @@
+//! Local control socket between the daemon and attached TUIs.
+//!
+//! Messages are newline-delimited JSON over a Unix socket next to the database.
+//! When a client connects the daemon sends a `Snapshot` of every node, followed
+//! by a `NodeUpdate` each time the engine finishes a check. Clients send
+//! `NodeConfigUpdate` commands, which the daemon forwards to its engine. Every
+//! command, whether from a client, the command line or the API, is also passed
+//! on to all clients as a `NodeUpdate` or `NodeRemoved`, so they stay in step.
+//!
+//! The client side is exposed as an ordinary `MonitoringHandle`, so callers use
+//! the same code path whether the engine runs in-process or in a daemon.
+
+use crate::models::Node;
+use crate::monitoring_engine::{MonitoringHandle, NodeConfigUpdate};
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::Shutdown;
+use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// How long the daemon waits on a stalled client before dropping it
+const CLIENT_WRITE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// How long a client waits for the initial snapshot
+const SNAPSHOT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Messages sent from the daemon to attached clients
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type")]
+#[allow(clippy::large_enum_variant)] // Serialized as soon as it is built
+pub enum DaemonMessage {
+    /// Current state of every monitored node, sent once on connect
+    Snapshot { nodes: Vec<Node> },
+    /// A node after its latest check, or as it was just added or edited
+    NodeUpdate { node: Node },
+    /// A node that was deleted
+    NodeRemoved { node_id: i64 },
+}
+
+/// Returns the control socket location used for the database at `db_path`
+pub fn socket_path(db_path: &Path) -> PathBuf {
+    db_path.with_extension("sock")
+}
+
+/// Daemon side of the control socket
+pub struct MonitorServer {
+    state: Arc<Mutex<ServerState>>,
+    config_tx: mpsc::Sender<NodeConfigUpdate>,
+    path: PathBuf,
+}
+
+struct ServerState {
+    nodes: Vec<Node>,
+    clients: Vec<UnixStream>,
+}
+
+impl MonitorServer {
+    /// Binds the control socket at `path` and starts accepting clients.
+    ///
+    /// `nodes` seeds the snapshot sent to new clients; commands received from
+    /// clients are forwarded to `config_tx`. Any existing file at `path` is
+    /// replaced, so callers must hold the daemon lock first.
+    pub fn bind(
+        path: &Path,
+        nodes: Vec<Node>,
+        config_tx: mpsc::Sender<NodeConfigUpdate>,
+    ) -> Result<Self> {
+        let _ = fs::remove_file(path);
+        let listener = bind_private(path)
+            .with_context(|| format!("Failed to bind control socket {}", path.display()))?;
+        info!("Listening for TUI clients on {}", path.display());
+
+        let state = Arc::new(Mutex::new(ServerState {
+            nodes,
+            clients: Vec::new(),
+        }));
+
+        let accept_state = Arc::clone(&state);
+        let accept_config_tx = config_tx.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                match stream {
+                    Ok(stream) => {
+                        if let Err(e) = register_client(&accept_state, &accept_config_tx, stream) {
+                            warn!("Failed to register TUI client: {}", e);
+                        }
+                    }
+                    Err(e) => warn!("Failed to accept TUI client: {}", e),
+                }
+            }
+        });
+
+        Ok(Self {
+            state,
+            config_tx,
+            path: path.to_path_buf(),
+        })
+    }
+
+    /// Records the latest state of `node` and sends it to every attached client.
+    ///
+    /// Clients that cannot keep up are disconnected.
+    pub fn broadcast(&self, node: &Node) {
+        let mut state = lock(&self.state);
+        // A check that finished after its node was deleted is no news
+        let Some(existing) = state.nodes.iter_mut().find(|n| n.id == node.id) else {
+            return;
+        };
+        *existing = node.clone();
+        send_to_clients(
+            &mut state,
+            &DaemonMessage::NodeUpdate { node: node.clone() },
+        );
+    }
+
+    /// Returns a sender for commands that don't come through the socket, such
+    /// as those of the API. They reach the engine and the clients just like the
+    /// commands of clients do.
+    pub fn command_sender(&self) -> mpsc::Sender<NodeConfigUpdate> {
+        let (command_tx, command_rx) = mpsc::channel();
+        let state = Arc::clone(&self.state);
+        let config_tx = self.config_tx.clone();
+        thread::spawn(move || {
+            for update in command_rx {
+                if !relay_command(&state, &config_tx, update) {
+                    break;
+                }
+            }
+        });
+        command_tx
+    }
+}
+
+impl Drop for MonitorServer {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Binds a socket at `path` that only its owner can connect to, as node commands
+/// are accepted from anyone who can.
+///
+/// The socket is bound inside a new owner-only directory and made owner-only
+/// itself before it is moved to `path`, so there is no moment at which others
+/// could connect.
+fn bind_private(path: &Path) -> io::Result<UnixListener> {
+    let staging = path.with_extension(format!("sock.{}", process::id()));
+    let _ = fs::remove_dir_all(&staging);
+    fs::DirBuilder::new().mode(0o700).create(&staging)?;
+    let staged = staging.join("socket");
+    let result = UnixListener::bind(&staged).and_then(|listener| {
+        fs::set_permissions(&staged, fs::Permissions::from_mode(0o600))?;
+        fs::rename(&staged, path)?;
+        Ok(listener)
+    });
+    let _ = fs::remove_dir_all(&staging);
+    result
+}
+
+/// Sends the snapshot to a new client and starts reading its commands
+fn register_client(
+    state: &Arc<Mutex<ServerState>>,
+    config_tx: &mpsc::Sender<NodeConfigUpdate>,
+    mut stream: UnixStream,
+) -> Result<()> {
+    stream.set_write_timeout(Some(CLIENT_WRITE_TIMEOUT))?;
+    let reader = stream.try_clone()?;
+
+    {
+        let mut state = lock(state);
+        let snapshot = DaemonMessage::Snapshot {
+            nodes: state.nodes.clone(),
+        };
+        write_message(&mut stream, &snapshot)?;
+        state.clients.push(stream);
+    }
+    info!("TUI client attached");
+
+    let state = Arc::clone(state);
+    let config_tx = config_tx.clone();
+    thread::spawn(move || {
+        for line in BufReader::new(reader).lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            match serde_json::from_str::<NodeConfigUpdate>(&line) {
+                Ok(update) => {
+                    if !relay_command(&state, &config_tx, update) {
+                        break;
+                    }
+                }
+                Err(e) => warn!("Ignoring malformed command from TUI client: {}", e),
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// Applies a command to the snapshot, tells every client about it and forwards
+/// it to the engine. Returns false once the engine has stopped listening.
+fn relay_command(
+    state: &Mutex<ServerState>,
+    config_tx: &mpsc::Sender<NodeConfigUpdate>,
+    update: NodeConfigUpdate,
+) -> bool {
+    {
+        let mut state = lock(state);
+        apply_to_snapshot(&mut state.nodes, &update);
+        let message = match &update {
+            NodeConfigUpdate::Add(node) | NodeConfigUpdate::Update(node) => {
+                DaemonMessage::NodeUpdate { node: node.clone() }
+            }
+            NodeConfigUpdate::Delete(node_id) => DaemonMessage::NodeRemoved { node_id: *node_id },
+        };
+        send_to_clients(&mut state, &message);
+    }
+    config_tx.send(update).is_ok()
+}
+
+/// Sends `message` to every attached client, disconnecting those that cannot
+/// keep up
+fn send_to_clients(state: &mut ServerState, message: &DaemonMessage) {
+    state
+        .clients
+        .retain_mut(|client| match write_message(client, message) {
+            Ok(()) => true,
+            Err(e) => {
+                info!("TUI client disconnected: {}", e);
+                false
+            }
+        });
+}
+
+/// Attaches to the daemon listening on `path`.
+///
+/// Returns a handle whose `config_tx` sends commands to the daemon and whose
+/// `stop_tx` detaches (the daemon keeps monitoring), together with the node
+/// snapshot received on connect. Node updates are delivered to `update_tx`,
+/// and the IDs of deleted nodes to `removed_tx`.
+pub fn attach(
+    path: &Path,
+    update_tx: mpsc::Sender<Node>,
+    removed_tx: mpsc::Sender<i64>,
+) -> Result<(MonitoringHandle, Vec<Node>)> {
+    let stream = UnixStream::connect(path)
+        .with_context(|| format!("No monitor daemon listening on {}", path.display()))?;
+
+    stream.set_read_timeout(Some(SNAPSHOT_TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut line = String::new();
+    reader
+        .read_line(&mut line)
+        .context("Daemon did not send a node snapshot")?;
+    stream.set_read_timeout(None)?;
+
+    let nodes = match serde_json::from_str(&line)? {
+        DaemonMessage::Snapshot { nodes } => nodes,
+        DaemonMessage::NodeUpdate { .. } | DaemonMessage::NodeRemoved { .. } => {
+            return Err(anyhow!("Daemon sent an update before the node snapshot"))
+        }
+    };
+
+    thread::spawn(move || {
+        for line in reader.lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            match serde_json::from_str::<DaemonMessage>(&line) {
+                Ok(DaemonMessage::NodeUpdate { node }) => {
+                    if update_tx.send(node).is_err() {
+                        break;
+                    }
+                }
+                Ok(DaemonMessage::NodeRemoved { node_id }) => {
+                    if removed_tx.send(node_id).is_err() {
+                        break;
+                    }
+                }
+                Ok(DaemonMessage::Snapshot { .. }) => {}
+                Err(e) => warn!("Ignoring malformed message from daemon: {}", e),
+            }
+        }
+        info!("Detached from monitor daemon");
+    });
+
+    let (config_tx, config_rx) = mpsc::channel::<NodeConfigUpdate>();
+    let mut writer = stream.try_clone()?;
+    thread::spawn(move || {
+        for update in config_rx {
+            if let Err(e) = write_message(&mut writer, &update) {
+                warn!("Failed to send command to daemon: {}", e);
+                break;
+            }
+        }
+    });
+
+    // Stopping (or dropping the handle) closes the connection, which also ends
+    // the reader thread. The daemon itself keeps running.
+    let (stop_tx, stop_rx) = mpsc::channel::<()>();
+    thread::spawn(move || {
+        let _ = stop_rx.recv();
+        let _ = stream.shutdown(Shutdown::Both);
+    });
+
+    info!("Attached to monitor daemon at {}", path.display());
+    Ok((MonitoringHandle { stop_tx, config_tx }, nodes))
+}
+
+/// Sends a single command to the daemon listening on `path` and disconnects.
+///
+/// Used by the command-line interface so that nodes changed in the database
+/// are picked up by a running daemon without restarting it.
+pub fn send_command(path: &Path, update: &NodeConfigUpdate) -> Result<()> {
+    let mut stream = UnixStream::connect(path)
+        .with_context(|| format!("No monitor daemon listening on {}", path.display()))?;
+
+    // Wait for the snapshot so the daemon has registered us before we hang up
+    stream.set_read_timeout(Some(SNAPSHOT_TIMEOUT))?;
+    let mut line = String::new();
+    BufReader::new(stream.try_clone()?)
+        .read_line(&mut line)
+        .context("Daemon did not send a node snapshot")?;
+
+    write_message(&mut stream, update)?;
+    stream.shutdown(Shutdown::Write)?;
+    Ok(())
+}
+
+/// Keeps the daemon's snapshot in step with commands from clients
+fn apply_to_snapshot(nodes: &mut Vec<Node>, update: &NodeConfigUpdate) {
+    match update {
+        NodeConfigUpdate::Add(node) | NodeConfigUpdate::Update(node) => {
+            upsert_node(nodes, node.clone())
+        }
+        NodeConfigUpdate::Delete(node_id) => nodes.retain(|n| n.id != Some(*node_id)),
+    }
+}
+
+fn upsert_node(nodes: &mut Vec<Node>, node: Node) {
+    match nodes.iter_mut().find(|n| n.id == node.id) {
+        Some(existing) => *existing = node,
+        None => nodes.push(node),
+    }
+}
+
+fn write_message<T: Serialize>(stream: &mut UnixStream, message: &T) -> Result<()> {
+    let mut line = serde_json::to_string(message)?;
+    line.push('\n');
+    stream.write_all(line.as_bytes())?;
+    Ok(())
+}
+
+fn lock(state: &Mutex<ServerState>) -> std::sync::MutexGuard<'_, ServerState> {
+    state
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
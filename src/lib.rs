@@ -1,8 +1,18 @@
+pub mod api;
+pub mod cli;
 pub mod config;
 pub mod connection;
 pub mod credentials;
+pub mod daemon;
 pub mod database;
+pub mod hooks;
+pub mod icmp;
+#[cfg(unix)]
+pub mod ipc;
+pub mod metrics;
 pub mod models;
 pub mod monitor;
 pub mod monitoring_engine;
+pub mod notifications;
+pub mod paths;
 pub mod tui;
@@ -1,62 +1,205 @@
+mod api;
+mod cli;
 mod config;
 mod connection;
 mod credentials;
+mod daemon;
 mod database;
+mod hooks;
+mod icmp;
+#[cfg(unix)]
+mod ipc;
+mod metrics;
 mod models;
 mod monitor;
 mod monitoring_engine;
+mod notifications;
+mod paths;
 mod tui;
 
+use crate::config::AppConfig;
+use crate::config::CredentialBackend;
+use crate::credentials::{
+    CredentialStore, FileCredentialStore, KeyringCredentialStore, SensitiveString,
+    SharedCredentialStore,
+};
 use crate::database::Database;
-use crate::tui::NetworkMonitorTui;
-use anyhow::Result;
-use directories::ProjectDirs;
+use crate::paths::{AppPaths, PathOverrides};
+use crate::tui::{NetworkMonitorTui, TuiOptions};
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+use std::process::ExitCode;
 use tracing_subscriber::{fmt, prelude::*, EnvFilter};
 
+/// A network monitoring tool with a TUI interface.
+///
+/// Run without a subcommand to start the TUI.
+#[derive(Parser)]
+#[command(name = "net-monitor", version, about)]
+struct Cli {
+    /// Directory for the database, credential store and log file
+    #[arg(long, global = true, env = paths::ENV_DATA_DIR, value_name = "DIR")]
+    data_dir: Option<PathBuf>,
+
+    /// Database file [default: <data-dir>/network_monitor.db]
+    #[arg(long, global = true, env = paths::ENV_DB, value_name = "FILE")]
+    db: Option<PathBuf>,
+
+    /// Configuration file [default: config.json in the platform config directory, or in --data-dir when given]
+    #[arg(long, global = true, env = paths::ENV_CONFIG, value_name = "FILE")]
+    config: Option<PathBuf>,
+
+    /// File containing the credential store master password. The password can
+    /// also be passed in the NET_MONITOR_MASTER_PASSWORD environment variable.
+    #[arg(long, global = true, env = credentials::ENV_MASTER_PASSWORD_FILE, value_name = "FILE")]
+    master_password_file: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+#[allow(clippy::large_enum_variant)] // Parsed once at startup
+enum Command {
+    /// Run the monitoring engine without the TUI until SIGINT/SIGTERM
+    Daemon,
+    /// Manage the credential store
+    Credentials {
+        #[command(subcommand)]
+        command: cli::CredentialCommand,
+    },
+    #[command(flatten)]
+    Nodes(cli::NodeCommand),
+}
+
 /// Main entry point for the network monitor application
-fn main() -> Result<()> {
+fn main() -> Result<ExitCode> {
+    let cli = Cli::parse();
+
+    let paths = AppPaths::resolve(PathOverrides {
+        data_dir: cli.data_dir,
+        database: cli.db,
+        config: cli.config,
+    })?;
+
     // Setup logging - guard must be kept alive for the lifetime of the application
-    let _guard = if let Some(proj_dirs) = project_dirs() {
-        let log_dir = proj_dirs.data_dir();
+    if let Some(log_dir) = paths.log_file.parent() {
         std::fs::create_dir_all(log_dir)?;
-        let log_file = log_dir.join("net-monitor.log");
-
-        // Open file in append mode to keep all logs in one file
-        let file = std::fs::OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(log_file)?;
-
-        let (non_blocking, guard) = tracing_appender::non_blocking(file);
-
-        tracing_subscriber::registry()
-            .with(
-                fmt::layer()
-                    .with_writer(non_blocking)
-                    .with_ansi(false) // Disable ANSI color codes in log file
-                    .with_target(false), // Cleaner format without module paths
-            )
-            .with(EnvFilter::from_default_env().add_directive("net_monitor=info".parse()?))
-            .init();
+    }
 
-        Some(guard)
-    } else {
-        tracing_subscriber::fmt::init();
-        None
-    };
+    // Open file in append mode to keep all logs in one file
+    let file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&paths.log_file)?;
+
+    let (non_blocking, _guard) = tracing_appender::non_blocking(file);
+
+    tracing_subscriber::registry()
+        .with(
+            fmt::layer()
+                .with_writer(non_blocking)
+                .with_ansi(false) // Disable ANSI color codes in log file
+                .with_target(false), // Cleaner format without module paths
+        )
+        .with(EnvFilter::from_default_env().add_directive("net_monitor=info".parse()?))
+        .init();
+
+    // Load (or create) the config file up front so a broken file is reported at startup
+    let mut config = AppConfig::load_from(&paths.config)
+        .with_context(|| format!("Failed to load config file {}", paths.config.display()))?;
 
     // Setup database
-    let db_path = project_dirs()
-        .expect("Could not find project directories")
-        .data_dir()
-        .join("network_monitor.db");
-    let database = Database::new(&db_path)?;
-
-    // Create and run the TUI application
-    let mut app = NetworkMonitorTui::new(database)?;
-    app.run()
+    if let Some(db_dir) = paths.database.parent() {
+        std::fs::create_dir_all(db_dir)?;
+    }
+    let database = Database::new(&paths.database)?;
+
+    let master_password = credentials::read_master_password(cli.master_password_file.as_deref())?;
+
+    match cli.command {
+        Some(Command::Daemon) => {
+            // Unlock before starting so a wrong password stops the daemon straight away
+            let credentials = open_credentials(&paths, &config, master_password.as_ref())?;
+            daemon::run(
+                database,
+                config.engine_config(),
+                config.api.clone(),
+                credentials,
+            )
+            .map(|()| ExitCode::SUCCESS)
+        }
+        Some(Command::Credentials { command }) => {
+            let credentials = open_credentials(&paths, &config, master_password.as_ref())?;
+            cli::run_credentials(
+                &paths,
+                &mut config,
+                master_password,
+                &credentials,
+                command,
+                &mut std::io::stdout(),
+            )
+        }
+        Some(Command::Nodes(command)) => {
+            let credentials = open_credentials(&paths, &config, master_password.as_ref())?;
+            cli::run(
+                &database,
+                &config.defaults,
+                &credentials,
+                command,
+                &mut std::io::stdout(),
+            )
+        }
+        None => {
+            // If a daemon is monitoring this database, attach to it rather than
+            // running a second engine against the same nodes
+            let mut options = TuiOptions {
+                credentials_path: Some(paths.credentials),
+                keyring_metadata_path: Some(paths.keyring_metadata),
+                master_password,
+                config,
+                config_path: Some(paths.config),
+                ..TuiOptions::default()
+            };
+            #[cfg(unix)]
+            {
+                let socket = ipc::socket_path(database.path());
+                if socket.exists() {
+                    options.attach_socket = Some(socket);
+                }
+            }
+
+            // Create and run the TUI application
+            let mut app = NetworkMonitorTui::with_options(database, options)?;
+            app.run()?;
+            Ok(ExitCode::SUCCESS)
+        }
+    }
 }
 
-fn project_dirs() -> Option<ProjectDirs> {
-    ProjectDirs::from("com", "casey", "net-monitor")
+/// Opens the credential store selected by the config.
+///
+/// The encrypted file store stays locked unless a master password was supplied.
+fn open_credentials(
+    paths: &AppPaths,
+    config: &AppConfig,
+    master_password: Option<&SensitiveString>,
+) -> Result<SharedCredentialStore> {
+    let store: Option<Box<dyn CredentialStore>> = match config.credentials.backend {
+        CredentialBackend::File => master_password
+            .map(|password| {
+                FileCredentialStore::with_path(password.as_str().to_string(), &paths.credentials)
+                    .context("Failed to unlock the credential store")
+            })
+            .transpose()?
+            .map(|store| Box::new(store) as Box<dyn CredentialStore>),
+        CredentialBackend::Keyring => Some(Box::new(
+            KeyringCredentialStore::with_path(&paths.keyring_metadata)
+                .context("Failed to open the keyring credential store")?,
+        )),
+    };
+    let credentials = SharedCredentialStore::default();
+    credentials.set(store);
+    Ok(credentials)
 }
@@ -0,0 +1,281 @@
+//! Prometheus metrics endpoint.
+//!
+//! The monitoring engine records every check, every status change and its own
+//! health in an `EngineMetrics`. When `metrics.enabled` is set in the config,
+//! the engine also serves them at `/metrics` on `metrics.listen`, in the
+//! Prometheus text exposition format, for as long as it runs.
+//!
+//! Node metrics are labelled with the node's ID, name, type, target and tags
+//! (comma separated). Counters start from zero whenever the engine starts, which
+//! Prometheus treats as a counter reset.
+
+use crate::models::{Node, NodeStatus, StatusChange};
+use anyhow::Result;
+use axum::http::header;
+use axum::routing::get;
+use axum::Router;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+use tokio::net::TcpListener;
+
+/// Content type of the Prometheus text exposition format
+const TEXT_FORMAT: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// How a single check turned out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CheckResult {
+    /// The check passed
+    Success,
+    /// The check passed with a warning, such as a certificate close to expiry
+    Warning,
+    /// The check failed
+    Failure,
+    /// The check could not be run at all
+    Error,
+}
+
+impl CheckResult {
+    const ALL: [CheckResult; 4] = [
+        CheckResult::Success,
+        CheckResult::Warning,
+        CheckResult::Failure,
+        CheckResult::Error,
+    ];
+
+    /// Value of the `result` label
+    pub fn label(self) -> &'static str {
+        match self {
+            CheckResult::Success => "success",
+            CheckResult::Warning => "warning",
+            CheckResult::Failure => "failure",
+            CheckResult::Error => "error",
+        }
+    }
+
+    /// Result of a check that reported `status`
+    pub fn from_status(status: NodeStatus) -> Self {
+        match status {
+            NodeStatus::Online => CheckResult::Success,
+            NodeStatus::Degraded => CheckResult::Warning,
+            NodeStatus::Offline | NodeStatus::Unreachable => CheckResult::Failure,
+        }
+    }
+}
+
+/// Reads a gauge's value off a node, if it has one
+type NodeGauge = fn(&Node) -> Option<f64>;
+
+const STATUSES: [NodeStatus; 4] = [
+    NodeStatus::Online,
+    NodeStatus::Degraded,
+    NodeStatus::Offline,
+    NodeStatus::Unreachable,
+];
+
+/// Metrics of a running monitoring engine, shared with the endpoint serving them
+#[derive(Debug, Default)]
+pub struct EngineMetrics {
+    state: Mutex<MetricsState>,
+}
+
+#[derive(Debug, Default)]
+struct MetricsState {
+    /// The monitored nodes, as of their latest check
+    nodes: Vec<Node>,
+    checks: HashMap<(i64, CheckResult), u64>,
+    status_changes: HashMap<(i64, NodeStatus), u64>,
+    loop_lag: Duration,
+    checks_in_flight: usize,
+}
+
+impl EngineMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replaces the monitored nodes, dropping the counters of nodes no longer among them
+    pub fn set_nodes(&self, nodes: &[Node]) {
+        let mut state = self.lock();
+        state.nodes = nodes.to_vec();
+        let ids: Vec<i64> = nodes.iter().filter_map(|n| n.id).collect();
+        state.checks.retain(|(id, _), _| ids.contains(id));
+        state.status_changes.retain(|(id, _), _| ids.contains(id));
+    }
+
+    /// Counts a check of `node`, which is as it stands after the check
+    pub fn record_check(&self, node: &Node, result: CheckResult) {
+        let Some(node_id) = node.id else {
+            return;
+        };
+        let mut state = self.lock();
+        match state.nodes.iter_mut().find(|n| n.id == Some(node_id)) {
+            Some(existing) => *existing = node.clone(),
+            None => state.nodes.push(node.clone()),
+        }
+        *state.checks.entry((node_id, result)).or_default() += 1;
+    }
+
+    /// Counts a confirmed status change
+    pub fn record_status_change(&self, change: &StatusChange) {
+        *self
+            .lock()
+            .status_changes
+            .entry((change.node_id, change.to_status))
+            .or_default() += 1;
+    }
+
+    /// Records how late the scheduler's latest tick ran, and how many checks
+    /// are running or waiting to run
+    pub fn set_engine_health(&self, loop_lag: Duration, checks_in_flight: usize) {
+        let mut state = self.lock();
+        state.loop_lag = loop_lag;
+        state.checks_in_flight = checks_in_flight;
+    }
+
+    /// The metrics in the Prometheus text exposition format
+    pub fn render(&self) -> String {
+        let state = self.lock();
+        let nodes: Vec<(i64, &Node, String)> = state
+            .nodes
+            .iter()
+            .filter_map(|node| Some((node.id?, node, node_labels(node))))
+            .collect();
+        let mut out = String::new();
+
+        let gauges: [(&str, &str, NodeGauge); 4] = [
+            (
+                "net_monitor_node_up",
+                "Whether the node is online (1) or not (0)",
+                |node| Some(f64::from(u8::from(node.status == NodeStatus::Online))),
+            ),
+            (
+                "net_monitor_node_degraded",
+                "Whether the node is degraded (1) or not (0)",
+                |node| Some(f64::from(u8::from(node.status == NodeStatus::Degraded))),
+            ),
+            (
+                "net_monitor_node_response_time_seconds",
+                "Response time of the node's latest check",
+                |node| node.response_time.map(|ms| ms as f64 / 1000.0),
+            ),
+            (
+                "net_monitor_node_consecutive_failures",
+                "Checks in a row the node has failed",
+                |node| Some(f64::from(node.consecutive_failures)),
+            ),
+        ];
+        for (name, help, value) in gauges {
+            write_header(&mut out, name, help, "gauge");
+            for (_, node, labels) in &nodes {
+                if let Some(value) = value(node) {
+                    let _ = writeln!(out, "{}{{{}}} {}", name, labels, value);
+                }
+            }
+        }
+
+        let name = "net_monitor_node_checks_total";
+        write_header(&mut out, name, "Checks of the node by result", "counter");
+        for (id, _, labels) in &nodes {
+            for result in CheckResult::ALL {
+                let count = state.checks.get(&(*id, result)).copied().unwrap_or(0);
+                let _ = writeln!(
+                    out,
+                    "{}{{{},result=\"{}\"}} {}",
+                    name,
+                    labels,
+                    result.label(),
+                    count
+                );
+            }
+        }
+
+        let name = "net_monitor_node_status_changes_total";
+        write_header(
+            &mut out,
+            name,
+            "Confirmed status changes of the node by the status changed to",
+            "counter",
+        );
+        for (id, _, labels) in &nodes {
+            for status in STATUSES {
+                let count = state
+                    .status_changes
+                    .get(&(*id, status))
+                    .copied()
+                    .unwrap_or(0);
+                let _ = writeln!(
+                    out,
+                    "{}{{{},to=\"{}\"}} {}",
+                    name,
+                    labels,
+                    status.to_string().to_lowercase(),
+                    count
+                );
+            }
+        }
+
+        let name = "net_monitor_engine_loop_lag_seconds";
+        write_header(
+            &mut out,
+            name,
+            "How late the scheduler's latest tick ran",
+            "gauge",
+        );
+        let _ = writeln!(out, "{} {}", name, state.loop_lag.as_secs_f64());
+        let name = "net_monitor_engine_checks_in_flight";
+        write_header(
+            &mut out,
+            name,
+            "Checks running or waiting for a free slot",
+            "gauge",
+        );
+        let _ = writeln!(out, "{} {}", name, state.checks_in_flight);
+        let name = "net_monitor_engine_nodes";
+        write_header(&mut out, name, "Nodes being monitored", "gauge");
+        let _ = writeln!(out, "{} {}", name, nodes.len());
+
+        out
+    }
+
+    fn lock(&self) -> MutexGuard<'_, MetricsState> {
+        // The state stays consistent even if a holder panicked
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+fn write_header(out: &mut String, name: &str, help: &str, kind: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+/// Labels identifying `node` on each of its metrics
+fn node_labels(node: &Node) -> String {
+    format!(
+        "id=\"{}\",node=\"{}\",type=\"{}\",target=\"{}\",tags=\"{}\"",
+        node.id.unwrap_or(0),
+        escape_label(&node.name),
+        escape_label(&node.detail.to_string()),
+        escape_label(&node.detail.get_connection_target()),
+        escape_label(&node.tags.join(","))
+    )
+}
+
+/// Escapes a label value as the text format requires
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Serves `metrics` at `/metrics` on `listener` until the task running it is dropped
+pub async fn serve(listener: TcpListener, metrics: Arc<EngineMetrics>) -> Result<()> {
+    let app = Router::new().route(
+        "/metrics",
+        get(move || async move { ([(header::CONTENT_TYPE, TEXT_FORMAT)], metrics.render()) }),
+    );
+    axum::serve(listener, app).await?;
+    Ok(())
+}
@@ -1,27 +1,104 @@
 use crate::connection::ConnectionType;
 use crate::credentials::CredentialId;
-use chrono::{DateTime, Utc};
+use anyhow::{anyhow, Result};
+use base64::prelude::{Engine, BASE64_STANDARD_NO_PAD};
+use chrono::{DateTime, Local, Utc};
+use croner::Cron;
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
 
 /// Represents the details for each monitoring type
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(tag = "type")]
+#[allow(clippy::large_enum_variant)] // Most nodes are HTTP, so boxing would not save anything
 pub enum MonitorDetail {
     Http {
         url: String,
         expected_status: u16,
+        /// Request timeout in seconds
+        #[serde(default = "default_http_timeout")]
+        timeout: u64,
+        /// Method, headers, body, redirects and response checks
+        #[serde(flatten)]
+        options: HttpOptions,
     },
     Ping {
+        /// Host name or address to ping
         host: String,
+        /// Echo requests sent per check
         count: u32,
+        /// Seconds to wait for each reply
         timeout: u64,
+        /// Port probed over TCP instead when ICMP sockets are not permitted
+        #[serde(default = "default_ping_fallback_port")]
+        fallback_port: u16,
+        /// Packet loss and latency at which the node is degraded or offline
+        #[serde(flatten, skip_serializing_if = "PingThresholds::is_default")]
+        thresholds: PingThresholds,
     },
     Tcp {
         host: String,
         port: u16,
         timeout: u64,
     },
+    /// Logs in over SSH with the node's credential and optionally runs a command
+    Ssh {
+        host: String,
+        port: u16,
+        /// Connection and command timeout in seconds
+        timeout: u64,
+        /// Command to run after logging in; the check fails if it exits non-zero
+        #[serde(default)]
+        command: Option<String>,
+        /// Regular expression the command's output must match
+        #[serde(default)]
+        expected_output: Option<String>,
+        /// SHA-256 fingerprint the server's host key must have, as `ssh-keygen -l`
+        /// prints it; without one, the key must be in `~/.ssh/known_hosts`
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        host_key: Option<String>,
+    },
+    /// Queries a DNS server directly and compares its answers
+    Dns {
+        /// Server to query, as an address or host name
+        server: String,
+        #[serde(default = "default_dns_port")]
+        port: u16,
+        /// Name to look up
+        name: String,
+        record_type: DnsRecordType,
+        /// Answers that must all be among those returned, such as `10.0.0.5`
+        /// or `10 mail.example.com`; with none, any answer passes
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        expected: Vec<String>,
+        #[serde(default)]
+        protocol: DnsProtocol,
+        /// Query timeout in seconds
+        #[serde(default = "default_dns_timeout")]
+        timeout: u64,
+    },
+    /// Sends a datagram to a UDP service and checks the reply
+    Udp {
+        host: String,
+        port: u16,
+        /// Datagram to send; empty sends an empty datagram
+        #[serde(default, skip_serializing_if = "UdpPayload::is_empty")]
+        payload: UdpPayload,
+        /// What the reply must look like; with none, any reply passes
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        expected_response: Option<UdpResponseMatch>,
+        /// Count a service that stays silent as up, unless the host answers
+        /// with an ICMP port unreachable
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        no_reply_is_up: bool,
+        /// Seconds to wait for the reply
+        #[serde(default = "default_udp_timeout")]
+        timeout: u64,
+    },
 }
 
 impl MonitorDetail {
@@ -30,16 +107,92 @@ impl MonitorDetail {
         match self {
             MonitorDetail::Http { url, .. } => url.clone(),
             MonitorDetail::Ping { host, .. } => host.clone(),
-            MonitorDetail::Tcp { host, port, .. } => format!("{}:{}", host, port),
+            MonitorDetail::Tcp { host, port, .. }
+            | MonitorDetail::Ssh { host, port, .. }
+            | MonitorDetail::Udp { host, port, .. } => format!("{}:{}", host, port),
+            MonitorDetail::Dns {
+                server, port, name, ..
+            } => format!("{} @{}:{}", name, server, port),
         }
     }
 
+    /// Checks the settings that the types alone don't guarantee, such as
+    /// that patterns compile
+    pub fn validate(&self) -> Result<()> {
+        if let MonitorDetail::Http { options, .. } = self {
+            options.assertions.validate()?;
+            options.tls.validate()?;
+        }
+        if let MonitorDetail::Ping {
+            count,
+            fallback_port,
+            thresholds,
+            ..
+        } = self
+        {
+            if *count == 0 {
+                return Err(anyhow!(
+                    "A ping check needs to send at least one echo request"
+                ));
+            }
+            if *fallback_port == 0 {
+                return Err(anyhow!("The TCP fallback port of a ping check cannot be 0"));
+            }
+            thresholds.validate()?;
+        }
+        if let MonitorDetail::Ssh {
+            command,
+            expected_output: Some(pattern),
+            ..
+        } = self
+        {
+            if command.is_none() {
+                return Err(anyhow!("An expected output needs a command to run"));
+            }
+            regex::Regex::new(pattern)
+                .map_err(|e| anyhow!("Invalid expected output pattern: {}", e))?;
+        }
+        if let MonitorDetail::Ssh {
+            host_key: Some(fingerprint),
+            ..
+        } = self
+        {
+            parse_host_key_fingerprint(fingerprint)?;
+        }
+        if let MonitorDetail::Dns {
+            name,
+            record_type,
+            expected,
+            ..
+        } = self
+        {
+            if name.trim().is_empty() {
+                return Err(anyhow!("A DNS check needs a name to look up"));
+            }
+            for answer in expected {
+                record_type.validate_answer(answer)?;
+            }
+        }
+        if let MonitorDetail::Udp {
+            expected_response: Some(UdpResponseMatch::Pattern(pattern)),
+            ..
+        } = self
+        {
+            regex::Regex::new(pattern)
+                .map_err(|e| anyhow!("Invalid expected response pattern: {}", e))?;
+        }
+        Ok(())
+    }
+
     /// Get the appropriate connection type for this monitor
     pub fn get_connection_type(&self) -> ConnectionType {
         match self {
             MonitorDetail::Http { .. } => ConnectionType::Http,
             MonitorDetail::Ping { .. } => ConnectionType::Ping,
             MonitorDetail::Tcp { .. } => ConnectionType::Tcp,
+            MonitorDetail::Ssh { .. } => ConnectionType::Ssh,
+            MonitorDetail::Dns { .. } => ConnectionType::Dns,
+            MonitorDetail::Udp { .. } => ConnectionType::Udp,
         }
     }
 }
@@ -50,12 +203,904 @@ impl fmt::Display for MonitorDetail {
             MonitorDetail::Http { .. } => write!(f, "HTTP"),
             MonitorDetail::Ping { .. } => write!(f, "Ping"),
             MonitorDetail::Tcp { .. } => write!(f, "TCP"),
+            MonitorDetail::Ssh { .. } => write!(f, "SSH"),
+            MonitorDetail::Dns { .. } => write!(f, "DNS"),
+            MonitorDetail::Udp { .. } => write!(f, "UDP"),
         }
     }
 }
 
-/// Represents the current status of a node
+/// Request method of an HTTP check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum HttpMethod {
+    #[default]
+    Get,
+    Head,
+    Post,
+    Put,
+    Patch,
+    Delete,
+    Options,
+}
+
+impl HttpMethod {
+    /// Every method, in the order the TUI cycles through them
+    pub const ALL: [HttpMethod; 7] = [
+        HttpMethod::Get,
+        HttpMethod::Head,
+        HttpMethod::Post,
+        HttpMethod::Put,
+        HttpMethod::Patch,
+        HttpMethod::Delete,
+        HttpMethod::Options,
+    ];
+}
+
+impl fmt::Display for HttpMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            HttpMethod::Get => "GET",
+            HttpMethod::Head => "HEAD",
+            HttpMethod::Post => "POST",
+            HttpMethod::Put => "PUT",
+            HttpMethod::Patch => "PATCH",
+            HttpMethod::Delete => "DELETE",
+            HttpMethod::Options => "OPTIONS",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for HttpMethod {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        HttpMethod::ALL
+            .into_iter()
+            .find(|method| method.to_string().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| anyhow!("Unknown HTTP method '{}'", s))
+    }
+}
+
+/// Record type looked up by a DNS check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum DnsRecordType {
+    #[default]
+    A,
+    Aaaa,
+    Cname,
+    Mx,
+    Txt,
+    Srv,
+}
+
+impl DnsRecordType {
+    /// Every record type, in the order the TUI lists them
+    pub const ALL: [DnsRecordType; 6] = [
+        DnsRecordType::A,
+        DnsRecordType::Aaaa,
+        DnsRecordType::Cname,
+        DnsRecordType::Mx,
+        DnsRecordType::Txt,
+        DnsRecordType::Srv,
+    ];
+
+    /// Writes an answer in the form answers are compared in: addresses in
+    /// their shortest form, and names in lower case without the trailing dot.
+    /// TXT data is compared exactly.
+    pub fn normalize_answer(&self, answer: &str) -> String {
+        let answer = answer.trim();
+        match self {
+            DnsRecordType::A | DnsRecordType::Aaaa => answer
+                .parse::<IpAddr>()
+                .map(|ip| ip.to_string())
+                .unwrap_or_else(|_| answer.to_string()),
+            DnsRecordType::Txt => answer.to_string(),
+            DnsRecordType::Cname | DnsRecordType::Mx | DnsRecordType::Srv => answer
+                .split_whitespace()
+                .map(|part| part.trim_end_matches('.').to_lowercase())
+                .collect::<Vec<_>>()
+                .join(" "),
+        }
+    }
+
+    /// Checks that an expected answer is written the way answers of this type are
+    fn validate_answer(&self, answer: &str) -> Result<()> {
+        let parts: Vec<&str> = answer.split_whitespace().collect();
+        let numbers_then_name = |numbers: usize| {
+            parts.len() == numbers + 1 && parts[..numbers].iter().all(|n| n.parse::<u16>().is_ok())
+        };
+        let valid = match self {
+            DnsRecordType::A => answer.trim().parse::<Ipv4Addr>().is_ok(),
+            DnsRecordType::Aaaa => answer.trim().parse::<Ipv6Addr>().is_ok(),
+            DnsRecordType::Cname => parts.len() == 1,
+            DnsRecordType::Mx => numbers_then_name(1),
+            DnsRecordType::Srv => numbers_then_name(3),
+            DnsRecordType::Txt => true,
+        };
+        if valid {
+            return Ok(());
+        }
+        let form = match self {
+            DnsRecordType::A => "an IPv4 address",
+            DnsRecordType::Aaaa => "an IPv6 address",
+            DnsRecordType::Cname => "a host name",
+            DnsRecordType::Mx => "'<preference> <host>'",
+            DnsRecordType::Srv => "'<priority> <weight> <port> <host>'",
+            DnsRecordType::Txt => unreachable!("any TXT data is valid"),
+        };
+        Err(anyhow!(
+            "Invalid expected {} answer '{}' (expected {})",
+            self,
+            answer,
+            form
+        ))
+    }
+}
+
+impl fmt::Display for DnsRecordType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            DnsRecordType::A => "A",
+            DnsRecordType::Aaaa => "AAAA",
+            DnsRecordType::Cname => "CNAME",
+            DnsRecordType::Mx => "MX",
+            DnsRecordType::Txt => "TXT",
+            DnsRecordType::Srv => "SRV",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for DnsRecordType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        DnsRecordType::ALL
+            .into_iter()
+            .find(|record_type| record_type.to_string().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| anyhow!("Unknown DNS record type '{}'", s))
+    }
+}
+
+/// Transport a DNS check queries over
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DnsProtocol {
+    /// UDP, retried over TCP when the answer is truncated
+    #[default]
+    Udp,
+    Tcp,
+}
+
+impl fmt::Display for DnsProtocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DnsProtocol::Udp => write!(f, "udp"),
+            DnsProtocol::Tcp => write!(f, "tcp"),
+        }
+    }
+}
+
+impl FromStr for DnsProtocol {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "udp" => Ok(DnsProtocol::Udp),
+            "tcp" => Ok(DnsProtocol::Tcp),
+            _ => Err(anyhow!(
+                "Unknown DNS protocol '{}' (expected udp or tcp)",
+                s
+            )),
+        }
+    }
+}
+
+/// Datagram sent by a UDP check, written as text or as `hex:` followed by
+/// hex digits
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum UdpPayload {
+    Text(String),
+    Hex(Vec<u8>),
+}
+
+impl UdpPayload {
+    pub fn as_bytes(&self) -> &[u8] {
+        match self {
+            UdpPayload::Text(text) => text.as_bytes(),
+            UdpPayload::Hex(bytes) => bytes,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.as_bytes().is_empty()
+    }
+}
+
+impl Default for UdpPayload {
+    fn default() -> Self {
+        UdpPayload::Text(String::new())
+    }
+}
+
+impl fmt::Display for UdpPayload {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UdpPayload::Text(text) => f.write_str(text),
+            UdpPayload::Hex(bytes) => write!(f, "hex:{}", format_hex(bytes)),
+        }
+    }
+}
+
+impl FromStr for UdpPayload {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.strip_prefix("hex:") {
+            Some(digits) => parse_hex(digits).map(UdpPayload::Hex),
+            None => Ok(UdpPayload::Text(s.to_string())),
+        }
+    }
+}
+
+impl TryFrom<String> for UdpPayload {
+    type Error = anyhow::Error;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl From<UdpPayload> for String {
+    fn from(payload: UdpPayload) -> Self {
+        payload.to_string()
+    }
+}
+
+/// The reply a UDP check expects, written as a regular expression matched
+/// against the reply as text, or as `hex:` followed by the hex digits the
+/// reply must start with
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum UdpResponseMatch {
+    Pattern(String),
+    HexPrefix(Vec<u8>),
+}
+
+impl UdpResponseMatch {
+    /// Whether `reply` matches; a pattern that doesn't compile matches nothing
+    pub fn matches(&self, reply: &[u8]) -> bool {
+        match self {
+            UdpResponseMatch::Pattern(pattern) => regex::Regex::new(pattern)
+                .is_ok_and(|regex| regex.is_match(&String::from_utf8_lossy(reply))),
+            UdpResponseMatch::HexPrefix(prefix) => reply.starts_with(prefix),
+        }
+    }
+}
+
+impl fmt::Display for UdpResponseMatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UdpResponseMatch::Pattern(pattern) => f.write_str(pattern),
+            UdpResponseMatch::HexPrefix(prefix) => write!(f, "hex:{}", format_hex(prefix)),
+        }
+    }
+}
+
+impl FromStr for UdpResponseMatch {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.strip_prefix("hex:") {
+            Some(digits) => parse_hex(digits).map(UdpResponseMatch::HexPrefix),
+            None => Ok(UdpResponseMatch::Pattern(s.to_string())),
+        }
+    }
+}
+
+impl TryFrom<String> for UdpResponseMatch {
+    type Error = anyhow::Error;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl From<UdpResponseMatch> for String {
+    fn from(expected: UdpResponseMatch) -> Self {
+        expected.to_string()
+    }
+}
+
+/// Writes bytes as lowercase hex digits, such as `ff00`
+pub fn format_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Reads pairs of hex digits, ignoring whitespace, such as `ff 00 1a`
+fn parse_hex(text: &str) -> Result<Vec<u8>> {
+    let digits: String = text.split_whitespace().collect();
+    if !digits.len().is_multiple_of(2) || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(anyhow!(
+            "Invalid hex '{}' (expected pairs of hex digits)",
+            text.trim()
+        ));
+    }
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).map_err(Into::into))
+        .collect()
+}
+
+/// A request header, written as `Name: value`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct HttpHeader {
+    pub name: String,
+    pub value: String,
+}
+
+impl fmt::Display for HttpHeader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.name, self.value)
+    }
+}
+
+impl FromStr for HttpHeader {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (name, value) = s
+            .split_once(':')
+            .ok_or_else(|| anyhow!("Invalid header '{}' (expected 'Name: value')", s))?;
+        let (name, value) = (name.trim(), value.trim());
+        reqwest::header::HeaderName::from_bytes(name.as_bytes())
+            .map_err(|_| anyhow!("Invalid header name '{}'", name))?;
+        reqwest::header::HeaderValue::from_str(value)
+            .map_err(|_| anyhow!("Invalid value for header '{}'", name))?;
+        Ok(Self {
+            name: name.to_string(),
+            value: value.to_string(),
+        })
+    }
+}
+
+impl TryFrom<String> for HttpHeader {
+    type Error = anyhow::Error;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl From<HttpHeader> for String {
+    fn from(header: HttpHeader) -> Self {
+        header.to_string()
+    }
+}
+
+/// Inclusive range of HTTP status codes, written as `200-299`, or `204` for a
+/// single code
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct StatusRange {
+    pub start: u16,
+    pub end: u16,
+}
+
+impl StatusRange {
+    pub fn contains(&self, status: u16) -> bool {
+        (self.start..=self.end).contains(&status)
+    }
+
+    /// Parses a comma-separated list of ranges, such as `200-299,304`
+    pub fn parse_list(s: &str) -> Result<Vec<StatusRange>> {
+        s.split(',')
+            .map(str::trim)
+            .filter(|part| !part.is_empty())
+            .map(str::parse)
+            .collect()
+    }
+
+    /// Formats ranges the way `parse_list` reads them
+    pub fn format_list(ranges: &[StatusRange]) -> String {
+        ranges
+            .iter()
+            .map(StatusRange::to_string)
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+impl fmt::Display for StatusRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.start == self.end {
+            write!(f, "{}", self.start)
+        } else {
+            write!(f, "{}-{}", self.start, self.end)
+        }
+    }
+}
+
+impl FromStr for StatusRange {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || anyhow!("Invalid status range '{}' (expected e.g. 200-299)", s);
+        let (start, end) = s.trim().split_once('-').unwrap_or((s, s));
+        let start: u16 = start.trim().parse().map_err(|_| invalid())?;
+        let end: u16 = end.trim().parse().map_err(|_| invalid())?;
+        if !(100..=599).contains(&start) || !(100..=599).contains(&end) || start > end {
+            return Err(invalid());
+        }
+        Ok(Self { start, end })
+    }
+}
+
+impl TryFrom<String> for StatusRange {
+    type Error = anyhow::Error;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl From<StatusRange> for String {
+    fn from(range: StatusRange) -> Self {
+        range.to_string()
+    }
+}
+
+/// Value that a JSON pointer (RFC 6901) into the response body must have
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JsonAssertion {
+    pub pointer: String,
+    pub value: serde_json::Value,
+}
+
+/// Checks on an HTTP response body; checks that aren't set are skipped
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ResponseAssertions {
+    /// Text the body must contain
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contains: Option<String>,
+    /// Text the body must not contain
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub not_contains: Option<String>,
+    /// Regular expression the body must match
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub matches: Option<String>,
+    /// Regular expression the body must not match
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub not_matches: Option<String>,
+    /// Value the JSON body must have at a pointer
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub json: Option<JsonAssertion>,
+    /// Largest body accepted, in bytes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_body_bytes: Option<u64>,
+}
+
+impl ResponseAssertions {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    fn validate(&self) -> Result<()> {
+        for pattern in [&self.matches, &self.not_matches].into_iter().flatten() {
+            regex::Regex::new(pattern)
+                .map_err(|e| anyhow!("Invalid response body pattern: {}", e))?;
+        }
+        if let Some(json) = &self.json {
+            if !json.pointer.is_empty() && !json.pointer.starts_with('/') {
+                return Err(anyhow!(
+                    "Invalid JSON pointer '{}' (it must start with '/')",
+                    json.pointer
+                ));
+            }
+        }
+        if self.max_body_bytes == Some(0) {
+            return Err(anyhow!("The maximum body size must be at least 1 byte"));
+        }
+        Ok(())
+    }
+}
+
+/// Request and response settings of an HTTP check beyond its URL, expected
+/// status and timeout
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HttpOptions {
+    pub method: HttpMethod,
+    /// Extra request headers, sent in order
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub headers: Vec<HttpHeader>,
+    /// Request body
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    /// Status codes accepted instead of just the expected status
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub accepted_statuses: Vec<StatusRange>,
+    /// Redirects followed before the check fails; with 0 the redirect
+    /// response itself is checked
+    pub max_redirects: u32,
+    #[serde(skip_serializing_if = "ResponseAssertions::is_empty")]
+    pub assertions: ResponseAssertions,
+    /// Certificate checks for HTTPS
+    #[serde(skip_serializing_if = "TlsOptions::is_default")]
+    pub tls: TlsOptions,
+}
+
+impl Default for HttpOptions {
+    fn default() -> Self {
+        Self {
+            method: HttpMethod::default(),
+            headers: Vec::new(),
+            body: None,
+            accepted_statuses: Vec::new(),
+            max_redirects: DEFAULT_HTTP_MAX_REDIRECTS,
+            assertions: ResponseAssertions::default(),
+            tls: TlsOptions::default(),
+        }
+    }
+}
+
+impl HttpOptions {
+    /// Whether `status` passes: any of the accepted ranges if there are some,
+    /// otherwise only `expected_status`
+    pub fn accepts(&self, expected_status: u16, status: u16) -> bool {
+        if self.accepted_statuses.is_empty() {
+            status == expected_status
+        } else {
+            self.accepted_statuses.iter().any(|r| r.contains(status))
+        }
+    }
+}
+
+/// How the certificate of an HTTPS endpoint is checked, written as
+/// `verify`, `accept-invalid` or `sha256:<fingerprint>`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum TlsMode {
+    /// The certificate must chain to a trusted root and match the host name
+    #[default]
+    Verify,
+    /// Any certificate is accepted, such as the self-signed ones of internal
+    /// appliances, and its expiry is only reported
+    AcceptInvalid,
+    /// Only the certificate with this SHA-256 fingerprint is accepted, whoever
+    /// signed it
+    Pinned([u8; 32]),
+}
+
+impl fmt::Display for TlsMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TlsMode::Verify => write!(f, "verify"),
+            TlsMode::AcceptInvalid => write!(f, "accept-invalid"),
+            TlsMode::Pinned(fingerprint) => {
+                write!(f, "sha256:{}", format_fingerprint(fingerprint))
+            }
+        }
+    }
+}
+
+impl FromStr for TlsMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("verify") {
+            return Ok(TlsMode::Verify);
+        }
+        if s.eq_ignore_ascii_case("accept-invalid") {
+            return Ok(TlsMode::AcceptInvalid);
+        }
+        let Some(fingerprint) = s
+            .get(..7)
+            .filter(|prefix| prefix.eq_ignore_ascii_case("sha256:"))
+        else {
+            return Err(anyhow!(
+                "Unknown TLS mode '{}' (expected verify, accept-invalid or sha256:<fingerprint>)",
+                s
+            ));
+        };
+
+        // Accept both the plain hex digest and openssl's colon-separated form
+        let hex: String = s[fingerprint.len()..]
+            .chars()
+            .filter(|c| *c != ':')
+            .collect();
+        let invalid = || anyhow!("Invalid SHA-256 fingerprint '{}'", &s[7..]);
+        if hex.len() != 64 || !hex.is_ascii() {
+            return Err(invalid());
+        }
+        let mut bytes = [0; 32];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).map_err(|_| invalid())?;
+        }
+        Ok(TlsMode::Pinned(bytes))
+    }
+}
+
+impl TryFrom<String> for TlsMode {
+    type Error = anyhow::Error;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl From<TlsMode> for String {
+    fn from(mode: TlsMode) -> Self {
+        mode.to_string()
+    }
+}
+
+/// Formats a duration as its two most significant units, e.g. "2h 5m"
+pub fn format_duration(duration_ms: i64) -> String {
+    let seconds = duration_ms / 1000;
+    let minutes = seconds / 60;
+    let hours = minutes / 60;
+    let days = hours / 24;
+
+    if days > 0 {
+        format!("{}d {}h", days, hours % 24)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes % 60)
+    } else if minutes > 0 {
+        format!("{}m {}s", minutes, seconds % 60)
+    } else {
+        format!("{}s", seconds)
+    }
+}
+
+/// Formats a certificate fingerprint the way `openssl x509 -fingerprint` does
+pub fn format_fingerprint(fingerprint: &[u8]) -> String {
+    fingerprint
+        .iter()
+        .map(|byte| format!("{:02X}", byte))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+/// Formats an SSH host key fingerprint the way `ssh-keygen -l` does, such as
+/// `SHA256:uNiVztksCsDhcc0u9e8BujQXVUpKZIDTMczCvj3tD2s`
+pub fn format_host_key_fingerprint(fingerprint: &[u8]) -> String {
+    format!("SHA256:{}", BASE64_STANDARD_NO_PAD.encode(fingerprint))
+}
+
+/// Parses an SSH host key fingerprint as `ssh-keygen -l` prints it
+pub fn parse_host_key_fingerprint(s: &str) -> Result<[u8; 32]> {
+    let invalid = || {
+        anyhow!(
+            "Invalid host key fingerprint '{}' (expected SHA256:<base64>, as ssh-keygen -l prints it)",
+            s
+        )
+    };
+    let encoded = s.trim().strip_prefix("SHA256:").ok_or_else(invalid)?;
+    BASE64_STANDARD_NO_PAD
+        .decode(encoded.trim_end_matches('='))
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(invalid)
+}
+
+/// Certificate settings of an HTTP check, used when the URL is HTTPS
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TlsOptions {
+    pub mode: TlsMode,
+    /// PEM file of extra root certificates trusted besides the system's, for
+    /// internal certificate authorities
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ca_bundle: Option<PathBuf>,
+    /// The node is degraded once its certificate expires within this many
+    /// days; 0 turns the warning off
+    pub expiry_warning_days: u32,
+    /// The node is offline once its certificate expires within this many
+    /// days; with 0 only an expired certificate fails
+    pub expiry_critical_days: u32,
+}
+
+impl Default for TlsOptions {
+    fn default() -> Self {
+        Self {
+            mode: TlsMode::default(),
+            ca_bundle: None,
+            expiry_warning_days: DEFAULT_CERT_WARNING_DAYS,
+            expiry_critical_days: DEFAULT_CERT_CRITICAL_DAYS,
+        }
+    }
+}
+
+impl TlsOptions {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.ca_bundle.is_some() && self.mode != TlsMode::Verify {
+            return Err(anyhow!(
+                "A CA bundle only applies when certificates are verified"
+            ));
+        }
+        if self.expiry_warning_days != 0 && self.expiry_critical_days > self.expiry_warning_days {
+            return Err(anyhow!(
+                "The certificate expiry warning ({} days) must not be shorter than the critical threshold ({} days)",
+                self.expiry_warning_days,
+                self.expiry_critical_days
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Packet loss and latency limits of a ping check. Latency is the mean
+/// round trip time of the replies; a threshold of 0 is turned off.
+///
+/// Without any thresholds a node is online as long as one reply comes back.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PingThresholds {
+    /// The node is degraded once this percentage of echo requests is lost
+    pub loss_warning_percent: u32,
+    /// The node is offline once this percentage of echo requests is lost
+    pub loss_critical_percent: u32,
+    /// The node is degraded once the mean round trip takes this many milliseconds
+    pub latency_warning_ms: u64,
+    /// The node is offline once the mean round trip takes this many milliseconds
+    pub latency_critical_ms: u64,
+}
+
+impl PingThresholds {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    fn validate(&self) -> Result<()> {
+        for (name, percent) in [
+            ("warning", self.loss_warning_percent),
+            ("critical", self.loss_critical_percent),
+        ] {
+            if percent > 100 {
+                return Err(anyhow!(
+                    "The packet loss {} threshold must be a percentage from 0 to 100",
+                    name
+                ));
+            }
+        }
+        if self.loss_warning_percent != 0
+            && self.loss_critical_percent != 0
+            && self.loss_warning_percent > self.loss_critical_percent
+        {
+            return Err(anyhow!(
+                "The packet loss warning ({}%) must not be above the critical threshold ({}%)",
+                self.loss_warning_percent,
+                self.loss_critical_percent
+            ));
+        }
+        if self.latency_warning_ms != 0
+            && self.latency_critical_ms != 0
+            && self.latency_warning_ms > self.latency_critical_ms
+        {
+            return Err(anyhow!(
+                "The latency warning ({}ms) must not be above the critical threshold ({}ms)",
+                self.latency_warning_ms,
+                self.latency_critical_ms
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// How a ping check reached its host
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PingMethod {
+    /// Echo requests over an unprivileged ICMP datagram socket
+    Icmp,
+    /// Echo requests over a raw ICMP socket, which needs privileges. Stats
+    /// recorded before the method was tracked all came from raw sockets.
+    #[default]
+    RawIcmp,
+    /// TCP connections to the given port, used when ICMP is not permitted
+    Tcp(u16),
+}
+
+impl fmt::Display for PingMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PingMethod::Icmp => write!(f, "ICMP"),
+            PingMethod::RawIcmp => write!(f, "raw ICMP"),
+            PingMethod::Tcp(port) => write!(f, "TCP port {}", port),
+        }
+    }
+}
+
+/// Outcome of the probes sent by one ping check. Round trip times are in
+/// milliseconds and only cover the replies that came back.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PingStats {
+    /// How the probes were sent
+    #[serde(default)]
+    pub method: PingMethod,
+    /// Probes sent
+    pub transmitted: u32,
+    /// Replies received
+    pub received: u32,
+    pub min_rtt: Option<f64>,
+    pub avg_rtt: Option<f64>,
+    pub max_rtt: Option<f64>,
+    /// Mean difference between consecutive round trips, as RFC 3550 measures
+    /// jitter; needs at least two replies
+    pub jitter: Option<f64>,
+}
+
+impl PingStats {
+    /// Computes the statistics of `transmitted` probes sent by `method` whose
+    /// replies took `round_trips`, in the order they were sent
+    pub fn from_round_trips(
+        method: PingMethod,
+        transmitted: u32,
+        round_trips: &[Duration],
+    ) -> Self {
+        let millis: Vec<f64> = round_trips
+            .iter()
+            .map(|rtt| rtt.as_secs_f64() * 1000.0)
+            .collect();
+        let count = millis.len() as f64;
+        let jitter = (millis.len() > 1)
+            .then(|| millis.windows(2).map(|w| (w[1] - w[0]).abs()).sum::<f64>() / (count - 1.0));
+        Self {
+            method,
+            transmitted,
+            received: millis.len() as u32,
+            min_rtt: millis.iter().copied().reduce(f64::min),
+            avg_rtt: (!millis.is_empty()).then(|| millis.iter().sum::<f64>() / count),
+            max_rtt: millis.iter().copied().reduce(f64::max),
+            jitter,
+        }
+    }
+
+    /// Percentage of probes that got no reply
+    pub fn loss_percent(&self) -> f64 {
+        if self.transmitted == 0 {
+            return 0.0;
+        }
+        100.0 * f64::from(self.transmitted.saturating_sub(self.received))
+            / f64::from(self.transmitted)
+    }
+}
+
+impl fmt::Display for PingStats {
+    /// Formats the statistics like the summary of ping(8), e.g.
+    /// "4/4 replies over ICMP, 0% loss, rtt min/avg/max 1.2/1.4/1.9 ms, jitter 0.3 ms"
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}/{} replies over {}, {:.0}% loss",
+            self.received,
+            self.transmitted,
+            self.method,
+            self.loss_percent()
+        )?;
+        if let (Some(min), Some(avg), Some(max)) = (self.min_rtt, self.avg_rtt, self.max_rtt) {
+            write!(f, ", rtt min/avg/max {:.1}/{:.1}/{:.1} ms", min, avg, max)?;
+        }
+        if let Some(jitter) = self.jitter {
+            write!(f, ", jitter {:.1} ms", jitter)?;
+        }
+        Ok(())
+    }
+}
+
+/// Represents the current status of a node
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum NodeStatus {
     /// Node is online and responding
     Online,
@@ -63,6 +1108,9 @@ pub enum NodeStatus {
     Offline,
     /// Node is failing checks but not yet confirmed down (soft state)
     Degraded,
+    /// Node is failing checks while a node it depends on is down, so the
+    /// failures are not counted as an outage of its own
+    Unreachable,
 }
 
 impl fmt::Display for NodeStatus {
@@ -71,16 +1119,88 @@ impl fmt::Display for NodeStatus {
             NodeStatus::Online => write!(f, "Online"),
             NodeStatus::Offline => write!(f, "Offline"),
             NodeStatus::Degraded => write!(f, "Degraded"),
+            NodeStatus::Unreachable => write!(f, "Unreachable"),
         }
     }
 }
 
+/// Default HTTP request timeout in seconds
+pub const DEFAULT_HTTP_TIMEOUT: u64 = 30;
+
+fn default_http_timeout() -> u64 {
+    DEFAULT_HTTP_TIMEOUT
+}
+
+/// Default number of redirects an HTTP check follows
+pub const DEFAULT_HTTP_MAX_REDIRECTS: u32 = 10;
+
+/// Default days before certificate expiry at which an HTTPS node is degraded
+pub const DEFAULT_CERT_WARNING_DAYS: u32 = 14;
+
+/// Default days before certificate expiry at which an HTTPS node goes offline
+pub const DEFAULT_CERT_CRITICAL_DAYS: u32 = 3;
+
+/// Default port for SSH checks
+pub const DEFAULT_SSH_PORT: u16 = 22;
+
+/// Default SSH check timeout in seconds
+pub const DEFAULT_SSH_TIMEOUT: u64 = 10;
+
+/// Default port for DNS checks
+pub const DEFAULT_DNS_PORT: u16 = 53;
+
+fn default_dns_port() -> u16 {
+    DEFAULT_DNS_PORT
+}
+
+/// Default DNS query timeout in seconds
+pub const DEFAULT_DNS_TIMEOUT: u64 = 5;
+
+fn default_dns_timeout() -> u64 {
+    DEFAULT_DNS_TIMEOUT
+}
+
+/// Default port probed by ping checks that cannot send ICMP
+pub const DEFAULT_PING_FALLBACK_PORT: u16 = 80;
+
+fn default_ping_fallback_port() -> u16 {
+    DEFAULT_PING_FALLBACK_PORT
+}
+
+/// Default seconds a UDP check waits for the reply
+pub const DEFAULT_UDP_TIMEOUT: u64 = 5;
+
+fn default_udp_timeout() -> u64 {
+    DEFAULT_UDP_TIMEOUT
+}
+
 /// Default number of consecutive failures before confirming offline
 pub const DEFAULT_MAX_CHECK_ATTEMPTS: u32 = 3;
 
 /// Default retry interval in seconds when in degraded state
 pub const DEFAULT_RETRY_INTERVAL: u64 = 15;
 
+/// Commands run when a node changes status, either for every node (from the
+/// config) or for a single node
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct HookCommands {
+    /// Run when the node is confirmed offline
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_down: Option<String>,
+    /// Run when the node starts failing checks or passes them with a warning
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_degraded: Option<String>,
+    /// Run when the node is back online
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_recovery: Option<String>,
+}
+
+impl HookCommands {
+    pub fn is_empty(&self) -> bool {
+        self.on_down.is_none() && self.on_degraded.is_none() && self.on_recovery.is_none()
+    }
+}
+
 /// Represents a network node to be monitored
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Node {
@@ -109,6 +1229,265 @@ pub struct Node {
     /// Retry interval in seconds when in degraded state (shorter than monitoring_interval)
     #[serde(default = "default_retry_interval")]
     pub retry_interval: u64,
+    /// Expiry of the TLS certificate seen by the latest check, if it used TLS
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cert_expires_at: Option<DateTime<Utc>>,
+    /// Packet loss and round trip times of the latest ping check
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ping_stats: Option<PingStats>,
+    /// Commands run when this node changes status, besides the global ones
+    #[serde(default, skip_serializing_if = "HookCommands::is_empty")]
+    pub hooks: HookCommands,
+    /// IDs of the nodes this node depends on, such as the switch in front of
+    /// it. While one of them is down, this node is unreachable, not offline.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parents: Vec<i64>,
+    /// Group the node is listed under, such as a site or customer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    /// Free-form labels, such as `prod` or `homelab`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+/// Splits `tags` on commas and whitespace, dropping empty tags and repeats
+/// that differ only in case
+pub fn normalize_tags<S: AsRef<str>>(tags: &[S]) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags
+        .iter()
+        .flat_map(|t| t.as_ref().split(|c: char| c == ',' || c.is_whitespace()))
+        .filter(|t| !t.is_empty())
+    {
+        if !normalized.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+            normalized.push(tag.to_string());
+        }
+    }
+    normalized
+}
+
+/// Trims a group name, treating a blank one as no group
+pub fn normalize_group(group: &str) -> Option<String> {
+    Some(group.trim().to_string()).filter(|g| !g.is_empty())
+}
+
+/// A search over nodes, such as `web status:offline,degraded tag:prod`.
+/// Plain words match part of a node's name, target, type or tags. Terms with
+/// a key narrow the search by `status:`, `type:`, `tag:` or `group:`, and
+/// match any of their comma separated values; status and type values may be
+/// abbreviated. Double quotes keep spaces in a word, as in `group:"Head Office"`.
+/// A node must match every term, and an empty filter matches every node.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NodeFilter {
+    terms: Vec<FilterTerm>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum FilterTerm {
+    Text(String),
+    Status(Vec<String>),
+    Type(Vec<String>),
+    Tag(Vec<String>),
+    Group(Vec<String>),
+}
+
+impl NodeFilter {
+    pub fn parse(query: &str) -> Self {
+        let terms = filter_words(query)
+            .into_iter()
+            .filter_map(|word| {
+                let word = word.to_lowercase();
+                let Some((key, value)) = word.split_once(':') else {
+                    return Some(FilterTerm::Text(word));
+                };
+                let term: fn(Vec<String>) -> FilterTerm = match key {
+                    "status" => FilterTerm::Status,
+                    "type" => FilterTerm::Type,
+                    "tag" => FilterTerm::Tag,
+                    "group" => FilterTerm::Group,
+                    // Such as a URL
+                    _ => return Some(FilterTerm::Text(word)),
+                };
+                let values: Vec<String> = value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|v| !v.is_empty())
+                    .map(String::from)
+                    .collect();
+                // A key still being typed doesn't hide every node
+                (!values.is_empty()).then(|| term(values))
+            })
+            .collect();
+        Self { terms }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    pub fn matches(&self, node: &Node) -> bool {
+        let node_type = node.detail.to_string().to_lowercase();
+        let tags: Vec<String> = node.tags.iter().map(|t| t.to_lowercase()).collect();
+        self.terms.iter().all(|term| match term {
+            FilterTerm::Text(text) => {
+                node.name.to_lowercase().contains(text)
+                    || node
+                        .detail
+                        .get_connection_target()
+                        .to_lowercase()
+                        .contains(text)
+                    || node_type.contains(text)
+                    || tags.iter().any(|t| t.contains(text))
+            }
+            FilterTerm::Status(values) => values.iter().any(|v| status_matches(v, node.status)),
+            FilterTerm::Type(values) => values.iter().any(|v| node_type.starts_with(v.as_str())),
+            FilterTerm::Tag(values) => values.iter().any(|v| tags.contains(v)),
+            FilterTerm::Group(values) => node
+                .group
+                .as_ref()
+                .is_some_and(|g| values.iter().any(|v| g.to_lowercase() == *v)),
+        })
+    }
+}
+
+/// Splits a filter query on whitespace outside double quotes, dropping the quotes
+fn filter_words(query: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut quoted = false;
+    for c in query.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !word.is_empty() {
+                    words.push(std::mem::take(&mut word));
+                }
+            }
+            c => word.push(c),
+        }
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+/// Whether the (lowercase) status filter `value` names `status` or the start
+/// of it; `up` and `down` stand for online and offline
+fn status_matches(value: &str, status: NodeStatus) -> bool {
+    let value = match value {
+        "up" => "online",
+        "down" => "offline",
+        value => value,
+    };
+    status.to_string().to_lowercase().starts_with(value)
+}
+
+/// Orders `nodes` as a dependency tree, returning each node's index in
+/// `nodes` and its depth. Every child follows its first parent, and nodes
+/// with the same parent keep their order in `nodes`.
+pub fn dependency_order(nodes: &[Node]) -> Vec<(usize, usize)> {
+    let index_of = |id: i64| nodes.iter().position(|n| n.id == Some(id));
+    let first_parent: Vec<Option<usize>> = nodes
+        .iter()
+        .enumerate()
+        .map(|(i, node)| {
+            node.parents
+                .iter()
+                .filter_map(|&parent| index_of(parent))
+                .find(|&parent| parent != i)
+        })
+        .collect();
+
+    let mut order = Vec::with_capacity(nodes.len());
+    let mut visited = vec![false; nodes.len()];
+    // Nodes caught in a cycle have no root; they are listed at the top level
+    let roots = (0..nodes.len())
+        .filter(|&i| first_parent[i].is_none())
+        .chain(0..nodes.len());
+    for root in roots {
+        let mut stack = vec![(root, 0)];
+        while let Some((i, depth)) = stack.pop() {
+            if visited[i] {
+                continue;
+            }
+            visited[i] = true;
+            order.push((i, depth));
+            let children = (0..nodes.len()).filter(|&child| first_parent[child] == Some(i));
+            stack.extend(children.rev().map(|child| (child, depth + 1)));
+        }
+    }
+    order
+}
+
+/// Looks up the IDs of the nodes in `keys` among `nodes`, by exact name or
+/// failing that, by ID
+pub fn resolve_node_ids(keys: &[String], nodes: &[Node]) -> Result<Vec<i64>> {
+    keys.iter()
+        .map(|key| {
+            let mut by_name = nodes.iter().filter(|n| &n.name == key);
+            match (by_name.next(), by_name.next()) {
+                (Some(_), Some(_)) => Err(anyhow!(
+                    "Several nodes are named '{}'; use the node ID instead",
+                    key
+                )),
+                (Some(node), None) => node.id.ok_or_else(|| anyhow!("'{}' is not saved", key)),
+                (None, _) => key
+                    .parse::<i64>()
+                    .ok()
+                    .filter(|id| nodes.iter().any(|n| n.id == Some(*id)))
+                    .ok_or_else(|| anyhow!("No node named '{}'", key)),
+            }
+        })
+        .collect()
+}
+
+/// Checks that `node` may depend on its parents among `nodes`: a node can't
+/// be its own parent, nor depend on a node that depends on it in turn, as
+/// neither could ever be confirmed offline
+pub fn check_parents(node: &Node, nodes: &[Node]) -> Result<()> {
+    let find = |id: i64| nodes.iter().find(|n| n.id == Some(id));
+    check_dependencies(
+        node,
+        |id| find(id).map(|n| n.name.as_str()),
+        |id| find(id).map_or(&[][..], |n| &n.parents[..]),
+    )
+}
+
+/// `check_parents` over nodes kept elsewhere, where `name_of` and `parents_of`
+/// look up the name and parents of each node by ID
+pub fn check_dependencies<'a>(
+    node: &Node,
+    name_of: impl Fn(i64) -> Option<&'a str>,
+    parents_of: impl Fn(i64) -> &'a [i64],
+) -> Result<()> {
+    // Nothing can depend on a node that isn't stored yet
+    let Some(id) = node.id else {
+        return Ok(());
+    };
+    if node.parents.contains(&id) {
+        return Err(anyhow!("'{}' can't depend on itself", node.name));
+    }
+
+    for &parent in &node.parents {
+        // Walk up from the parent; reaching the node would close a loop
+        let mut ancestors = vec![parent];
+        let mut seen = Vec::new();
+        while let Some(ancestor) = ancestors.pop() {
+            if ancestor == id {
+                return Err(anyhow!(
+                    "'{}' can't depend on '{}', which already depends on it",
+                    node.name,
+                    name_of(parent).unwrap_or_default()
+                ));
+            }
+            if !seen.contains(&ancestor) {
+                seen.push(ancestor);
+                ancestors.extend(parents_of(ancestor));
+            }
+        }
+    }
+    Ok(())
 }
 
 fn default_max_check_attempts() -> u32 {
@@ -134,6 +1513,12 @@ pub struct MonitoringResult {
     pub response_time: Option<u64>,
     /// Additional details about the check
     pub details: Option<String>,
+    /// Expiry of the TLS certificate the check saw; not kept in the history
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cert_expires_at: Option<DateTime<Utc>>,
+    /// Packet loss and round trip times, for ping checks
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ping_stats: Option<PingStats>,
 }
 
 /// Represents a node for import/export operations
@@ -153,6 +1538,81 @@ pub struct NodeImport {
     /// Retry interval in seconds when in degraded state
     #[serde(default = "default_retry_interval")]
     pub retry_interval: u64,
+    /// Commands run when the node changes status
+    #[serde(default, skip_serializing_if = "HookCommands::is_empty")]
+    pub hooks: HookCommands,
+    /// Names of the nodes this node depends on
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parents: Vec<String>,
+    /// Group the node is listed under
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    /// Free-form labels
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+impl NodeImport {
+    /// Converts `nodes` for export, naming each node's parents
+    pub fn export(nodes: &[Node]) -> Vec<Self> {
+        nodes
+            .iter()
+            .map(|node| Self {
+                parents: node
+                    .parents
+                    .iter()
+                    .filter_map(|&id| nodes.iter().find(|n| n.id == Some(id)))
+                    .map(|parent| parent.name.clone())
+                    .collect(),
+                ..Self::from(node)
+            })
+            .collect()
+    }
+}
+
+impl From<&Node> for NodeImport {
+    /// Converts a single node, without its parents, which can only be named
+    /// with the other nodes at hand (see `NodeImport::export`)
+    fn from(node: &Node) -> Self {
+        Self {
+            name: node.name.clone(),
+            detail: node.detail.clone(),
+            monitoring_interval: node.monitoring_interval,
+            credential_id: node.credential_id.clone(),
+            max_check_attempts: node.max_check_attempts,
+            retry_interval: node.retry_interval,
+            hooks: node.hooks.clone(),
+            parents: Vec::new(),
+            group: node.group.clone(),
+            tags: node.tags.clone(),
+        }
+    }
+}
+
+impl From<NodeImport> for Node {
+    /// Creates a new, not yet stored node from an imported definition. Its
+    /// parents are left to be resolved once the nodes they name are stored.
+    fn from(import: NodeImport) -> Self {
+        Self {
+            id: None,
+            name: import.name,
+            detail: import.detail,
+            status: NodeStatus::Offline,
+            last_check: None,
+            response_time: None,
+            monitoring_interval: import.monitoring_interval,
+            credential_id: import.credential_id,
+            consecutive_failures: 0,
+            max_check_attempts: import.max_check_attempts,
+            retry_interval: import.retry_interval,
+            cert_expires_at: None,
+            ping_stats: None,
+            hooks: import.hooks,
+            parents: Vec::new(),
+            group: import.group.as_deref().and_then(normalize_group),
+            tags: normalize_tags(&import.tags),
+        }
+    }
 }
 
 /// Represents a status change event for a node
@@ -170,6 +1630,10 @@ pub struct StatusChange {
     pub changed_at: DateTime<Utc>,
     /// Duration in milliseconds spent in the previous status (None for first status)
     pub duration_ms: Option<i64>,
+    /// Whether the change happened during a maintenance window, in which case
+    /// it was not alerted on
+    #[serde(default)]
+    pub in_maintenance: bool,
 }
 
 impl StatusChange {
@@ -179,18 +1643,17 @@ impl StatusChange {
     }
 
     /// Check if this is a transition to/from an error state
-    #[allow(dead_code)] // Future feature: status change analysis
     pub fn is_degradation(&self) -> bool {
         matches!(
             (self.from_status, self.to_status),
             (NodeStatus::Online, NodeStatus::Offline)
                 | (NodeStatus::Online, NodeStatus::Degraded)
                 | (NodeStatus::Degraded, NodeStatus::Offline)
+                | (NodeStatus::Unreachable, NodeStatus::Offline)
         )
     }
 
     /// Check if this is a recovery to a healthy state
-    #[allow(dead_code)] // Future feature: status change analysis
     pub fn is_recovery(&self) -> bool {
         matches!(
             (self.from_status, self.to_status),
@@ -199,8 +1662,317 @@ impl StatusChange {
     }
 
     /// Get a human-readable description of the status change
-    #[allow(dead_code)] // Future feature: status change descriptions
     pub fn description(&self) -> String {
         format!("{} → {}", self.from_status, self.to_status)
     }
 }
+
+/// Upper bound on the occurrences of a recurring maintenance window looked at
+/// in one query, so that a window repeating every minute stays cheap
+const MAX_MAINTENANCE_OCCURRENCES: usize = 10_000;
+
+/// Nodes a maintenance window applies to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MaintenanceScope {
+    /// Every node
+    All,
+    /// A single node, by ID
+    Node(i64),
+    /// Every node carrying the tag, matched without regard to case
+    Tag(String),
+}
+
+impl MaintenanceScope {
+    /// Whether the node with `node_id` and `tags` is covered
+    pub fn applies_to(&self, node_id: i64, tags: &[String]) -> bool {
+        match self {
+            MaintenanceScope::All => true,
+            MaintenanceScope::Node(id) => *id == node_id,
+            MaintenanceScope::Tag(tag) => tags.iter().any(|t| t.eq_ignore_ascii_case(tag)),
+        }
+    }
+}
+
+/// A period of planned work during which nodes are still checked, but their
+/// status changes are flagged and not alerted on, and their outages do not
+/// count against uptime
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MaintenanceWindow {
+    /// Unique identifier for the window
+    pub id: Option<i64>,
+    /// Nodes the window applies to
+    pub scope: MaintenanceScope,
+    /// Start of a one-off window, or the earliest time a recurring window
+    /// can start
+    pub starts_at: DateTime<Utc>,
+    /// Length of the window, or of each occurrence, in minutes
+    pub duration_mins: u32,
+    /// Cron expression (minute, hour, day of month, month, day of week, in
+    /// local time) giving the start of each occurrence of a recurring window
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recurrence: Option<String>,
+    /// Why the window was set up
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl MaintenanceWindow {
+    /// Checks that the window has a length and a valid schedule
+    pub fn validate(&self) -> Result<()> {
+        if self.duration_mins == 0 {
+            return Err(anyhow!("A maintenance window must last at least a minute"));
+        }
+        self.schedule()?;
+        Ok(())
+    }
+
+    /// Parsed `recurrence`, or None for a one-off window
+    fn schedule(&self) -> Result<Option<Cron>> {
+        self.recurrence
+            .as_deref()
+            .map(|expr| {
+                Cron::new(expr)
+                    .parse()
+                    .map_err(|e| anyhow!("Invalid maintenance schedule '{}': {}", expr, e))
+            })
+            .transpose()
+    }
+
+    fn duration(&self) -> chrono::Duration {
+        chrono::Duration::minutes(i64::from(self.duration_mins))
+    }
+
+    /// Start and end of each occurrence of the window that overlaps
+    /// `[from, to)`, in order. A window with an invalid schedule has none.
+    pub fn occurrences(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+        let duration = self.duration();
+        let schedule = match self.schedule() {
+            Ok(Some(schedule)) => schedule,
+            Ok(None) => {
+                let end = self.starts_at + duration;
+                return if self.starts_at < to && end > from {
+                    vec![(self.starts_at, end)]
+                } else {
+                    Vec::new()
+                };
+            }
+            Err(_) => return Vec::new(),
+        };
+
+        // Occurrences that started up to one duration before `from` still overlap it
+        let mut cursor = (from - duration).max(self.starts_at).with_timezone(&Local);
+        let mut inclusive = true;
+        let mut occurrences = Vec::new();
+        while occurrences.len() < MAX_MAINTENANCE_OCCURRENCES {
+            let Ok(start) = schedule.find_next_occurrence(&cursor, inclusive) else {
+                break;
+            };
+            let start_utc = start.with_timezone(&Utc);
+            if start_utc >= to {
+                break;
+            }
+            if start_utc + duration > from {
+                occurrences.push((start_utc, start_utc + duration));
+            }
+            cursor = start;
+            inclusive = false;
+        }
+        occurrences
+    }
+
+    /// End of the occurrence of the window covering `at`, if it is active then
+    pub fn active_until(&self, at: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.occurrences(at, at + chrono::Duration::milliseconds(1))
+            .first()
+            .map(|&(_, end)| end)
+    }
+}
+
+/// One attempt at delivering a status change notification to a channel
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NotificationDelivery {
+    /// Unique identifier for the attempt
+    pub id: Option<i64>,
+    /// Status change the notification announced
+    pub status_change_id: Option<i64>,
+    /// ID of the node that changed status
+    pub node_id: i64,
+    /// Where the notification was sent, such as `webhook:<host>` or
+    /// `mailto:<addresses>`
+    pub channel: String,
+    /// 1 for the first attempt, counting up with each retry
+    pub attempt: u32,
+    /// When the attempt finished
+    pub attempted_at: DateTime<Utc>,
+    /// Whether the channel accepted the notification
+    pub success: bool,
+    /// HTTP status of the response, when one came back
+    pub response_status: Option<u16>,
+    /// Why the attempt failed
+    pub error: Option<String>,
+}
+
+/// A single check outcome stored in the compact time series
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CheckSample {
+    /// ID of the node that was checked
+    pub node_id: i64,
+    /// When the check completed
+    pub checked_at: DateTime<Utc>,
+    /// Whether the check itself succeeded (before soft/hard state evaluation)
+    pub success: bool,
+    /// Response time in milliseconds
+    pub response_time: Option<u64>,
+}
+
+/// Granularity of a rollup bucket
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RollupResolution {
+    Minute,
+    Hour,
+    Day,
+}
+
+impl RollupResolution {
+    /// All resolutions, finest first
+    pub const ALL: [RollupResolution; 3] = [
+        RollupResolution::Minute,
+        RollupResolution::Hour,
+        RollupResolution::Day,
+    ];
+
+    /// Length of one bucket in milliseconds
+    pub fn bucket_millis(&self) -> i64 {
+        match self {
+            RollupResolution::Minute => 60 * 1000,
+            RollupResolution::Hour => 60 * 60 * 1000,
+            RollupResolution::Day => 24 * 60 * 60 * 1000,
+        }
+    }
+
+    /// Start of the bucket containing `timestamp_ms` (Unix epoch milliseconds)
+    pub fn bucket_start(&self, timestamp_ms: i64) -> i64 {
+        timestamp_ms - timestamp_ms.rem_euclid(self.bucket_millis())
+    }
+}
+
+impl fmt::Display for RollupResolution {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RollupResolution::Minute => write!(f, "minute"),
+            RollupResolution::Hour => write!(f, "hour"),
+            RollupResolution::Day => write!(f, "day"),
+        }
+    }
+}
+
+/// Aggregate statistics over a set of check samples
+///
+/// Latency figures only include successful checks, since the response time of
+/// a failed check is the time it took to fail rather than a real latency.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CheckStats {
+    /// Number of checks
+    pub sample_count: u64,
+    /// Number of successful checks
+    pub success_count: u64,
+    /// Fastest successful response time in milliseconds
+    pub min_response_time: Option<u64>,
+    /// Mean successful response time in milliseconds
+    pub avg_response_time: Option<f64>,
+    /// Slowest successful response time in milliseconds
+    pub max_response_time: Option<u64>,
+    /// 95th percentile successful response time in milliseconds (nearest rank)
+    pub p95_response_time: Option<u64>,
+}
+
+impl CheckStats {
+    /// Computes statistics for the given samples
+    pub fn from_samples(samples: &[CheckSample]) -> Self {
+        let mut latencies: Vec<u64> = samples
+            .iter()
+            .filter(|s| s.success)
+            .filter_map(|s| s.response_time)
+            .collect();
+        latencies.sort_unstable();
+
+        let (min, avg, max, p95) = if latencies.is_empty() {
+            (None, None, None, None)
+        } else {
+            let sum: u64 = latencies.iter().sum();
+            let rank = ((latencies.len() as f64) * 0.95).ceil() as usize;
+            (
+                latencies.first().copied(),
+                Some(sum as f64 / latencies.len() as f64),
+                latencies.last().copied(),
+                latencies.get(rank.saturating_sub(1)).copied(),
+            )
+        };
+
+        Self {
+            sample_count: samples.len() as u64,
+            success_count: samples.iter().filter(|s| s.success).count() as u64,
+            min_response_time: min,
+            avg_response_time: avg,
+            max_response_time: max,
+            p95_response_time: p95,
+        }
+    }
+
+    /// Fraction of successful checks (0.0 - 1.0), or None if there were no checks
+    pub fn success_ratio(&self) -> Option<f64> {
+        if self.sample_count == 0 {
+            None
+        } else {
+            Some(self.success_count as f64 / self.sample_count as f64)
+        }
+    }
+}
+
+/// Rolled-up statistics for one node over one time bucket
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RollupBucket {
+    /// ID of the node
+    pub node_id: i64,
+    /// Bucket granularity
+    pub resolution: RollupResolution,
+    /// Start of the bucket
+    pub bucket_start: DateTime<Utc>,
+    /// Statistics for the samples in the bucket
+    pub stats: CheckStats,
+}
+
+/// Default number of days raw check samples are kept
+pub const DEFAULT_RAW_RETENTION_DAYS: u32 = 7;
+
+/// Default number of days per-minute rollups are kept
+pub const DEFAULT_MINUTE_ROLLUP_RETENTION_DAYS: u32 = 30;
+
+/// Default number of days per-hour rollups are kept
+pub const DEFAULT_HOUR_ROLLUP_RETENTION_DAYS: u32 = 365;
+
+/// How long check history is kept. Daily rollups are kept indefinitely.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RetentionPolicy {
+    /// Days to keep raw check samples
+    pub raw_days: u32,
+    /// Days to keep per-minute rollups
+    pub minute_rollup_days: u32,
+    /// Days to keep per-hour rollups
+    pub hour_rollup_days: u32,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self {
+            raw_days: DEFAULT_RAW_RETENTION_DAYS,
+            minute_rollup_days: DEFAULT_MINUTE_ROLLUP_RETENTION_DAYS,
+            hour_rollup_days: DEFAULT_HOUR_ROLLUP_RETENTION_DAYS,
+        }
+    }
+}
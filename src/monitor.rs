@@ -1,39 +1,150 @@
-use crate::models::{MonitorDetail, MonitoringResult, Node, NodeStatus};
+use crate::credentials::{SharedCredentialStore, SshCredential};
+use crate::icmp::{IcmpSocket, IcmpSocketKind};
+use crate::models::{
+    format_fingerprint, format_hex, format_host_key_fingerprint, parse_host_key_fingerprint,
+    DnsProtocol, DnsRecordType, HttpOptions, MonitorDetail, MonitoringResult, Node, NodeStatus,
+    PingMethod, PingStats, PingThresholds, ResponseAssertions, StatusRange, TlsMode, TlsOptions,
+    UdpPayload, UdpResponseMatch,
+};
 use anyhow::{anyhow, Context, Result};
-use chrono::Utc;
-use std::net::{TcpStream, ToSocketAddrs};
-use std::time::Duration;
-use tracing::info;
+use chrono::{DateTime, Utc};
+use hickory_proto::op::{Message, MessageType, OpCode, Query, ResponseCode};
+use hickory_proto::rr::{Name, RData, RecordType};
+use regex::Regex;
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::client::WebPkiServerVerifier;
+use rustls::crypto::WebPkiSupportedAlgorithms;
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{DigitallySignedStruct, SignatureScheme};
+use sha2::{Digest, Sha256};
+use std::io::{self, Read};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, ToSocketAddrs};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{lookup_host, TcpStream, UdpSocket};
+use tracing::{info, warn};
 
+/// Checks `node` without access to stored credentials; SSH nodes that name a
+/// credential fail, others log in with the system defaults
+#[allow(dead_code)] // The binary always checks with its credential store
 pub async fn check_node(node: &Node) -> Result<MonitoringResult> {
+    check_node_with_credentials(node, &SharedCredentialStore::default()).await
+}
+
+/// Checks `node`, looking up the credential of SSH nodes in `credentials`
+pub async fn check_node_with_credentials(
+    node: &Node,
+    credentials: &SharedCredentialStore,
+) -> Result<MonitoringResult> {
     info!("Checking node: {}", node.name);
-    let start_time = std::time::Instant::now();
+    let start_time = Instant::now();
 
     let check_result = match &node.detail {
         MonitorDetail::Http {
             url,
             expected_status,
-        } => check_http(url, *expected_status).await,
+            timeout,
+            options,
+        } => check_http(url, *expected_status, *timeout, options).await,
         MonitorDetail::Ping {
             host,
-            count: _,
+            count,
+            timeout,
+            fallback_port,
+            thresholds,
+        } => check_ping(host, *count, *timeout, *fallback_port, thresholds).await,
+        MonitorDetail::Tcp {
+            host,
+            port,
+            timeout,
+        } => check_tcp(host, *port, *timeout)
+            .await
+            .map(CheckOutput::from),
+        MonitorDetail::Ssh {
+            host,
+            port,
             timeout,
+            command,
+            expected_output,
+            host_key,
         } => {
-            // The `ping` crate doesn't support a `count` parameter in this function signature
-            check_ping(host, *timeout).await
+            let check = SshCheck {
+                host: host.clone(),
+                port: *port,
+                timeout: *timeout,
+                command: command.clone(),
+                expected_output: expected_output.clone(),
+                host_key: host_key.clone(),
+            };
+            let credential_id = node.credential_id.clone();
+            let credentials = credentials.clone();
+            // ssh2 is blocking, and so is reading secrets from the OS keyring
+            tokio::task::spawn_blocking(move || {
+                let credential = match &credential_id {
+                    Some(id) => credentials.get(id)?,
+                    None => SshCredential::Default,
+                };
+                check.run(&credential)
+            })
+            .await
+            .context("SSH check task failed")?
+            .map(CheckOutput::from)
         }
-        MonitorDetail::Tcp {
+        MonitorDetail::Dns {
+            server,
+            port,
+            name,
+            record_type,
+            expected,
+            protocol,
+            timeout,
+        } => {
+            let query = DnsCheck {
+                server,
+                port: *port,
+                name,
+                record_type: *record_type,
+                protocol: *protocol,
+                timeout: *timeout,
+            };
+            query.run(expected).await.map(CheckOutput::from)
+        }
+        MonitorDetail::Udp {
             host,
             port,
+            payload,
+            expected_response,
+            no_reply_is_up,
             timeout,
-        } => check_tcp(host, *port, *timeout).await,
+        } => check_udp(
+            host,
+            *port,
+            payload,
+            expected_response.as_ref(),
+            *no_reply_is_up,
+            *timeout,
+        )
+        .await
+        .map(CheckOutput::from),
     };
-    let response_time = start_time.elapsed().as_millis() as u64;
+    let mut response_time = start_time.elapsed().as_millis() as u64;
 
-    let (status, details) = match check_result {
-        Ok(details) => (NodeStatus::Online, Some(details)),
-        Err(e) => (NodeStatus::Offline, Some(e.to_string())),
+    let (status, details, cert_expires_at, ping_stats) = match check_result {
+        Ok(output) => (
+            output.status,
+            Some(output.details),
+            output.cert_expires_at,
+            output.ping_stats,
+        ),
+        Err(e) => (NodeStatus::Offline, Some(e.to_string()), None, None),
     };
+    // A ping check spends most of its time waiting between probes
+    if let Some(avg_rtt) = ping_stats.as_ref().and_then(|stats| stats.avg_rtt) {
+        response_time = avg_rtt.round() as u64;
+    }
 
     Ok(MonitoringResult {
         id: None, // This will be set by the database
@@ -42,34 +153,387 @@ pub async fn check_node(node: &Node) -> Result<MonitoringResult> {
         status,
         response_time: Some(response_time),
         details,
+        cert_expires_at,
+        ping_stats,
     })
 }
 
-async fn check_http(url: &str, expected_status: u16) -> Result<String> {
+/// What a check that got an answer found. Errors mean the target is offline.
+struct CheckOutput {
+    /// Online, or Degraded/Offline when the answer itself is a problem, such
+    /// as a certificate about to expire
+    status: NodeStatus,
+    details: String,
+    cert_expires_at: Option<DateTime<Utc>>,
+    ping_stats: Option<PingStats>,
+}
+
+impl From<String> for CheckOutput {
+    fn from(details: String) -> Self {
+        Self {
+            status: NodeStatus::Online,
+            details,
+            cert_expires_at: None,
+            ping_stats: None,
+        }
+    }
+}
+
+async fn check_http(
+    url: &str,
+    expected_status: u16,
+    timeout: u64,
+    options: &HttpOptions,
+) -> Result<CheckOutput> {
     info!("Checking HTTP for {}", url);
 
     // Normalize the URL to ensure it has a proper scheme
     let normalized_url = normalize_http_url(url);
     info!("Normalized URL: {}", normalized_url);
 
-    // Build client that accepts self-signed certificates
-    // This is necessary for monitoring internal services (e.g., Proxmox on private IPs)
+    let redirect_policy = match options.max_redirects {
+        0 => reqwest::redirect::Policy::none(),
+        max => reqwest::redirect::Policy::limited(max as usize),
+    };
+
+    let peer_certificate = PeerCertificate::default();
     let client = reqwest::Client::builder()
-        .danger_accept_invalid_certs(true)
-        .timeout(Duration::from_secs(30))
+        .use_preconfigured_tls(tls_config(&options.tls, Arc::clone(&peer_certificate))?)
+        .timeout(Duration::from_secs(timeout))
+        .redirect(redirect_policy)
         .build()?;
 
-    let res = client.get(&normalized_url).send().await?;
+    let method = reqwest::Method::from_bytes(options.method.to_string().as_bytes())?;
+    let mut request = client.request(method, &normalized_url);
+    for header in &options.headers {
+        request = request.header(&header.name, &header.value);
+    }
+    if let Some(body) = &options.body {
+        request = request.body(body.clone());
+    }
+
+    let mut res = request
+        .send()
+        .await
+        .map_err(|e| anyhow!(describe_error(&e)))?;
+
+    let cert_expires_at = match lock(&peer_certificate).as_ref() {
+        Some(certificate) => Some(certificate_expiry(certificate)?),
+        None => None,
+    };
+    let mut output = match check_response(&mut res, expected_status, options).await {
+        Ok(details) => CheckOutput {
+            status: NodeStatus::Online,
+            details,
+            cert_expires_at,
+            ping_stats: None,
+        },
+        Err(e) => CheckOutput {
+            status: NodeStatus::Offline,
+            details: e.to_string(),
+            cert_expires_at,
+            ping_stats: None,
+        },
+    };
+
+    if let (NodeStatus::Online, Some(expires_at)) = (output.status, cert_expires_at) {
+        let (status, details) = grade_certificate(expires_at, Utc::now(), &options.tls);
+        output.status = status;
+        output.details = match status {
+            NodeStatus::Online => format!("{}; {}", output.details, details),
+            _ => details,
+        };
+    }
+    Ok(output)
+}
+
+/// Checks the status and body of a response
+async fn check_response(
+    res: &mut reqwest::Response,
+    expected_status: u16,
+    options: &HttpOptions,
+) -> Result<String> {
     let status = res.status();
-    if status.as_u16() == expected_status {
-        Ok(format!("Responded with status {}", status))
+    if !options.accepts(expected_status, status.as_u16()) {
+        let expected = if options.accepted_statuses.is_empty() {
+            expected_status.to_string()
+        } else {
+            StatusRange::format_list(&options.accepted_statuses)
+        };
+        return Err(anyhow!("Expected status {} but got {}", expected, status));
+    }
+
+    let assertions = &options.assertions;
+    if !assertions.is_empty() {
+        let body = read_body(res, assertions.max_body_bytes).await?;
+        check_response_body(&body, assertions)?;
+    }
+
+    Ok(format!("Responded with status {}", status))
+}
+
+/// Describes an error together with its causes, which reqwest and lettre leave
+/// out of their own messages (such as why a certificate was rejected)
+pub fn describe_error(e: &dyn std::error::Error) -> String {
+    let mut description = e.to_string();
+    let mut source = e.source();
+    while let Some(cause) = source {
+        let cause_text = cause.to_string();
+        if !description.contains(&cause_text) {
+            description.push_str(": ");
+            description.push_str(&cause_text);
+        }
+        source = cause.source();
+    }
+    description
+}
+
+/// Server certificate of the latest TLS handshake of a check
+type PeerCertificate = Arc<Mutex<Option<CertificateDer<'static>>>>;
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Root certificates of the operating system, loaded on first use
+fn system_roots() -> &'static [CertificateDer<'static>] {
+    static ROOTS: OnceLock<Vec<CertificateDer<'static>>> = OnceLock::new();
+    ROOTS.get_or_init(|| {
+        let loaded = rustls_native_certs::load_native_certs();
+        for e in &loaded.errors {
+            warn!("Failed to load system root certificates: {}", e);
+        }
+        loaded.certs
+    })
+}
+
+/// TLS settings for requests the monitor makes on its own behalf, such as
+/// notifications, which always verify the server against the system roots
+pub fn verified_tls_config() -> Result<rustls::ClientConfig> {
+    tls_config(&TlsOptions::default(), PeerCertificate::default())
+}
+
+/// Builds the TLS settings of an HTTP check. The server certificate is
+/// recorded in `peer_certificate` so that its expiry can be checked.
+fn tls_config(tls: &TlsOptions, peer_certificate: PeerCertificate) -> Result<rustls::ClientConfig> {
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+
+    let webpki = match tls.mode {
+        TlsMode::Verify => {
+            let mut roots = rustls::RootCertStore::empty();
+            roots.add_parsable_certificates(system_roots().iter().cloned());
+            if let Some(path) = &tls.ca_bundle {
+                let certificates = CertificateDer::pem_file_iter(path)
+                    .and_then(|certificates| certificates.collect::<Result<Vec<_>, _>>())
+                    .map_err(|e| anyhow!("Failed to read CA bundle {}: {}", path.display(), e))?;
+                let (added, _) = roots.add_parsable_certificates(certificates);
+                if added == 0 {
+                    return Err(anyhow!(
+                        "No certificates found in CA bundle {}",
+                        path.display()
+                    ));
+                }
+            }
+            let verifier =
+                WebPkiServerVerifier::builder_with_provider(Arc::new(roots), Arc::clone(&provider))
+                    .build()
+                    .map_err(|e| anyhow!("Cannot verify certificates: {}", e))?;
+            Some(verifier)
+        }
+        TlsMode::AcceptInvalid | TlsMode::Pinned(_) => None,
+    };
+
+    let policy = CertificatePolicy {
+        mode: tls.mode,
+        webpki,
+        algorithms: provider.signature_verification_algorithms,
+        peer_certificate,
+    };
+    Ok(rustls::ClientConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()?
+        .dangerous()
+        .with_custom_certificate_verifier(Arc::new(policy))
+        .with_no_client_auth())
+}
+
+/// Accepts server certificates according to a node's `TlsMode`, recording
+/// each one it is shown
+#[derive(Debug)]
+struct CertificatePolicy {
+    mode: TlsMode,
+    /// Chain and host name verification, for `TlsMode::Verify`
+    webpki: Option<Arc<WebPkiServerVerifier>>,
+    algorithms: WebPkiSupportedAlgorithms,
+    peer_certificate: PeerCertificate,
+}
+
+impl ServerCertVerifier for CertificatePolicy {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        *lock(&self.peer_certificate) = Some(end_entity.clone().into_owned());
+
+        match (&self.mode, &self.webpki) {
+            (TlsMode::Verify, Some(webpki)) => webpki.verify_server_cert(
+                end_entity,
+                intermediates,
+                server_name,
+                ocsp_response,
+                now,
+            ),
+            (TlsMode::Pinned(pinned), _) => {
+                let fingerprint: [u8; 32] = Sha256::digest(end_entity).into();
+                if fingerprint == *pinned {
+                    Ok(ServerCertVerified::assertion())
+                } else {
+                    Err(rustls::Error::General(format!(
+                        "certificate fingerprint {} does not match the pinned one",
+                        format_fingerprint(&fingerprint)
+                    )))
+                }
+            }
+            _ => Ok(ServerCertVerified::assertion()),
+        }
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls12_signature(message, cert, dss, &self.algorithms)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls13_signature(message, cert, dss, &self.algorithms)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.algorithms.supported_schemes()
+    }
+}
+
+/// Reads the end of a certificate's validity period
+fn certificate_expiry(certificate: &CertificateDer<'_>) -> Result<DateTime<Utc>> {
+    let (_, parsed) = x509_parser::parse_x509_certificate(certificate)
+        .map_err(|e| anyhow!("Failed to parse the server certificate: {}", e))?;
+    DateTime::from_timestamp(parsed.validity().not_after.timestamp(), 0)
+        .ok_or_else(|| anyhow!("Server certificate has an invalid expiry date"))
+}
+
+/// Grades a certificate expiring at `expires_at` against the node's
+/// thresholds, returning the resulting status and a description
+fn grade_certificate(
+    expires_at: DateTime<Utc>,
+    now: DateTime<Utc>,
+    tls: &TlsOptions,
+) -> (NodeStatus, String) {
+    let remaining = expires_at - now;
+    let description = match remaining.num_days() {
+        _ if remaining < chrono::Duration::zero() => {
+            format!("certificate expired on {}", expires_at.format("%Y-%m-%d"))
+        }
+        0 => "certificate expires within a day".to_string(),
+        1 => "certificate expires in 1 day".to_string(),
+        days => format!("certificate expires in {} days", days),
+    };
+    let within = |days: u32| remaining < chrono::Duration::days(days.into());
+
+    // An accepted invalid certificate may well be expired, so its expiry is only reported
+    if tls.mode == TlsMode::AcceptInvalid {
+        return (NodeStatus::Online, description);
+    }
+    if within(tls.expiry_critical_days) {
+        (NodeStatus::Offline, capitalize(&description))
+    } else if within(tls.expiry_warning_days) {
+        (
+            NodeStatus::Degraded,
+            format!(
+                "{}, within the {} day warning threshold",
+                capitalize(&description),
+                tls.expiry_warning_days
+            ),
+        )
     } else {
-        Err(anyhow!(
-            "Expected status {} but got {}",
-            expected_status,
-            status
-        ))
+        (NodeStatus::Online, description)
+    }
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Reads the response body, failing as soon as it grows past `max_bytes`
+async fn read_body(res: &mut reqwest::Response, max_bytes: Option<u64>) -> Result<Vec<u8>> {
+    let mut body = Vec::new();
+    while let Some(chunk) = res.chunk().await? {
+        body.extend_from_slice(&chunk);
+        if let Some(max) = max_bytes {
+            if body.len() as u64 > max {
+                return Err(anyhow!("Response body is larger than {} bytes", max));
+            }
+        }
+    }
+    Ok(body)
+}
+
+/// Checks a response body against `assertions`, reporting the first that fails
+fn check_response_body(body: &[u8], assertions: &ResponseAssertions) -> Result<()> {
+    let text = String::from_utf8_lossy(body);
+
+    if let Some(expected) = &assertions.contains {
+        if !text.contains(expected.as_str()) {
+            return Err(anyhow!("Response body does not contain '{}'", expected));
+        }
+    }
+    if let Some(unwanted) = &assertions.not_contains {
+        if text.contains(unwanted.as_str()) {
+            return Err(anyhow!("Response body contains '{}'", unwanted));
+        }
+    }
+    if let Some(pattern) = &assertions.matches {
+        if !Regex::new(pattern)?.is_match(&text) {
+            return Err(anyhow!("Response body does not match '{}'", pattern));
+        }
+    }
+    if let Some(pattern) = &assertions.not_matches {
+        if Regex::new(pattern)?.is_match(&text) {
+            return Err(anyhow!("Response body matches '{}'", pattern));
+        }
+    }
+    if let Some(json) = &assertions.json {
+        let document: serde_json::Value = serde_json::from_slice(body)
+            .map_err(|e| anyhow!("Response body is not JSON: {}", e))?;
+        let actual = document
+            .pointer(&json.pointer)
+            .ok_or_else(|| anyhow!("JSON pointer '{}' not found in response", json.pointer))?;
+        if *actual != json.value {
+            return Err(anyhow!(
+                "Expected {} at '{}' but found {}",
+                json.value,
+                json.pointer,
+                actual
+            ));
+        }
     }
+    Ok(())
 }
 
 /// Normalize HTTP URL to ensure it has a proper scheme
@@ -84,34 +548,381 @@ pub fn normalize_http_url(url: &str) -> String {
     }
 }
 
-async fn check_ping(host: &str, timeout: u64) -> Result<String> {
+/// Gap between the probes of one ping check, as ping(8) leaves by default
+const PING_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Sends `count` echo requests to `host` and grades the replies against
+/// `thresholds`. Where the process may not open an ICMP socket, it times TCP
+/// connections to `fallback_port` instead.
+async fn check_ping(
+    host: &str,
+    count: u32,
+    timeout: u64,
+    fallback_port: u16,
+    thresholds: &PingThresholds,
+) -> Result<CheckOutput> {
     info!("Checking Ping for {}", host);
-    let addr = host
-        .parse::<std::net::IpAddr>()
-        .context("Invalid IP address")?;
+    let addr = resolve_ping_target(host).await?;
+    let timeout = Duration::from_secs(timeout);
+
+    let (probe, icmp_error) = match IcmpSocket::open(addr) {
+        Ok(socket) => (PingProbe::Icmp(Arc::new(socket)), None),
+        Err(e) => {
+            info!("Pinging {} over TCP port {}: {}", host, fallback_port, e);
+            (
+                PingProbe::Tcp(SocketAddr::new(addr, fallback_port)),
+                Some(e),
+            )
+        }
+    };
+
+    let round_trips = probe.ping(count, timeout).await?;
+    let stats = PingStats::from_round_trips(probe.method(), count, &round_trips);
+    let (status, mut details) = grade_ping(&stats, thresholds);
+    if let (NodeStatus::Offline, Some(e)) = (status, icmp_error) {
+        details = format!("{}; {}", details, e);
+    }
+    Ok(CheckOutput {
+        status,
+        details,
+        cert_expires_at: None,
+        ping_stats: Some(stats),
+    })
+}
+
+/// Resolves the host of a ping check, preferring IPv4 as ping(8) does
+async fn resolve_ping_target(host: &str) -> Result<IpAddr> {
+    let addrs: Vec<SocketAddr> = lookup_host((host, 0))
+        .await
+        .map_err(|e| anyhow!("Failed to resolve {}: {}", host, e))?
+        .collect();
+    addrs
+        .iter()
+        .find(|addr| addr.is_ipv4())
+        .or(addrs.first())
+        .map(|addr| addr.ip())
+        .ok_or_else(|| anyhow!("No addresses found for {}", host))
+}
+
+/// The way a ping check reaches its host
+enum PingProbe {
+    Icmp(Arc<IcmpSocket>),
+    /// Connects to a TCP port; a refused connection still shows the host is up
+    Tcp(SocketAddr),
+}
+
+impl PingProbe {
+    fn method(&self) -> PingMethod {
+        match self {
+            PingProbe::Icmp(socket) => match socket.kind() {
+                IcmpSocketKind::Datagram => PingMethod::Icmp,
+                IcmpSocketKind::Raw => PingMethod::RawIcmp,
+            },
+            PingProbe::Tcp(addr) => PingMethod::Tcp(addr.port()),
+        }
+    }
+
+    /// Sends `count` probes `PING_INTERVAL` apart and returns the round trip
+    /// times of those answered within `timeout`. Probes don't wait for the
+    /// replies to earlier ones, so the check takes at most
+    /// `(count - 1) * PING_INTERVAL + timeout`.
+    async fn ping(&self, count: u32, timeout: Duration) -> Result<Vec<Duration>> {
+        let count = u16::try_from(count).unwrap_or(u16::MAX);
+        match self {
+            PingProbe::Icmp(socket) => {
+                // The socket blocks, so wait off the async workers to keep other checks moving.
+                let socket = Arc::clone(socket);
+                let round_trips =
+                    tokio::task::spawn_blocking(move || socket.ping(count, PING_INTERVAL, timeout))
+                        .await
+                        .context("Ping task failed")?
+                        .map_err(|e| anyhow!("Ping over {} failed: {}", self.method(), e))?;
+                Ok(round_trips.into_iter().flatten().collect())
+            }
+            PingProbe::Tcp(addr) => {
+                let addr = *addr;
+                let started = tokio::time::Instant::now();
+                // Dropping the set, as an overrunning check does, cancels the probes
+                let mut probes = tokio::task::JoinSet::new();
+                for seq in 0..u32::from(count) {
+                    probes.spawn(async move {
+                        tokio::time::sleep_until(started + PING_INTERVAL * seq).await;
+                        let sent_at = Instant::now();
+                        let round_trip =
+                            match tokio::time::timeout(timeout, TcpStream::connect(addr)).await {
+                                Ok(Ok(_)) => Some(sent_at.elapsed()),
+                                Ok(Err(e)) if e.kind() == io::ErrorKind::ConnectionRefused => {
+                                    Some(sent_at.elapsed())
+                                }
+                                Ok(Err(_)) | Err(_) => None,
+                            };
+                        (seq, round_trip)
+                    });
+                }
+                let mut round_trips = Vec::new();
+                while let Some(probe) = probes.join_next().await {
+                    round_trips.push(probe.context("Ping task failed")?);
+                }
+                // Jitter compares consecutive round trips, so keep them in order
+                round_trips.sort_by_key(|(seq, _)| *seq);
+                Ok(round_trips
+                    .into_iter()
+                    .filter_map(|(_, round_trip)| round_trip)
+                    .collect())
+            }
+        }
+    }
+}
+
+/// Grades the replies of a ping check against the node's thresholds,
+/// returning the resulting status and a description
+fn grade_ping(stats: &PingStats, thresholds: &PingThresholds) -> (NodeStatus, String) {
+    if stats.received == 0 {
+        return (
+            NodeStatus::Offline,
+            format!(
+                "No reply to {} probes over {}",
+                stats.transmitted, stats.method
+            ),
+        );
+    }
+
+    let loss = stats.loss_percent();
+    let avg_rtt = stats.avg_rtt.unwrap_or_default();
+    let limits = [
+        (
+            NodeStatus::Offline,
+            "critical",
+            thresholds.loss_critical_percent,
+            thresholds.latency_critical_ms,
+        ),
+        (
+            NodeStatus::Degraded,
+            "warning",
+            thresholds.loss_warning_percent,
+            thresholds.latency_warning_ms,
+        ),
+    ];
+    for (status, level, loss_limit, latency_limit) in limits {
+        let problem = if loss_limit != 0 && loss >= f64::from(loss_limit) {
+            format!(
+                "Packet loss of {:.0}% reached the {}% {} threshold",
+                loss, loss_limit, level
+            )
+        } else if latency_limit != 0 && avg_rtt >= latency_limit as f64 {
+            format!(
+                "Mean round trip of {:.1} ms reached the {} ms {} threshold",
+                avg_rtt, latency_limit, level
+            )
+        } else {
+            continue;
+        };
+        return (status, format!("{} ({})", problem, stats));
+    }
+    (NodeStatus::Online, stats.to_string())
+}
+
+/// A DNS lookup sent straight to one server, bypassing the system resolver
+struct DnsCheck<'a> {
+    server: &'a str,
+    port: u16,
+    name: &'a str,
+    record_type: DnsRecordType,
+    protocol: DnsProtocol,
+    timeout: u64,
+}
+
+impl DnsCheck<'_> {
+    /// Looks up the name and checks that every `expected` answer is among
+    /// those returned
+    async fn run(&self, expected: &[String]) -> Result<String> {
+        info!(
+            "Checking DNS {} {} at {}:{}",
+            self.record_type, self.name, self.server, self.port
+        );
+        let server = lookup_host((self.server, self.port))
+            .await
+            .map_err(|e| anyhow!("Failed to resolve DNS server {}: {}", self.server, e))?
+            .next()
+            .ok_or_else(|| anyhow!("No addresses found for DNS server {}", self.server))?;
 
-    // Use the `ping` function which is simpler and matches the older API.
-    // The `count` parameter is not supported in this version's `ping` function.
-    match ping::ping(
-        addr,
-        Some(Duration::from_secs(timeout)),
-        None,
-        None,
-        None,
-        None,
-    ) {
-        Ok(_) => Ok("Ping successful".to_string()),
-        Err(e) => Err(anyhow!("Ping failed: {}", e)),
+        let query = self.query()?;
+        let response = tokio::time::timeout(Duration::from_secs(self.timeout), async {
+            match self.protocol {
+                DnsProtocol::Udp => {
+                    let response = exchange_udp(server, &query).await?;
+                    // The full answer didn't fit in a datagram
+                    if response.truncated() {
+                        exchange_tcp(server, &query).await
+                    } else {
+                        Ok(response)
+                    }
+                }
+                DnsProtocol::Tcp => exchange_tcp(server, &query).await,
+            }
+        })
+        .await
+        .map_err(|_| {
+            anyhow!(
+                "DNS server {} did not answer within {}s",
+                server,
+                self.timeout
+            )
+        })??;
+
+        let rcode = response.response_code();
+        if rcode != ResponseCode::NoError {
+            return Err(anyhow!(
+                "DNS lookup of {} {} failed: {}",
+                self.name,
+                self.record_type,
+                rcode
+            ));
+        }
+        let answers = dns_answers(&response, self.record_type);
+        if answers.is_empty() {
+            return Err(anyhow!("No {} records for {}", self.record_type, self.name));
+        }
+
+        let normalized: Vec<String> = answers
+            .iter()
+            .map(|answer| self.record_type.normalize_answer(answer))
+            .collect();
+        let missing: Vec<&str> = expected
+            .iter()
+            .filter(|answer| !normalized.contains(&self.record_type.normalize_answer(answer)))
+            .map(String::as_str)
+            .collect();
+        if !missing.is_empty() {
+            return Err(anyhow!(
+                "Expected {} for {} {} but got {}",
+                missing.join(", "),
+                self.name,
+                self.record_type,
+                answers.join(", ")
+            ));
+        }
+
+        Ok(format!(
+            "Resolved {} {} to {}",
+            self.name,
+            self.record_type,
+            answers.join(", ")
+        ))
+    }
+
+    fn query(&self) -> Result<Message> {
+        let mut name = Name::from_utf8(self.name.trim())
+            .map_err(|e| anyhow!("Invalid DNS name '{}': {}", self.name, e))?;
+        name.set_fqdn(true);
+        let record_type = match self.record_type {
+            DnsRecordType::A => RecordType::A,
+            DnsRecordType::Aaaa => RecordType::AAAA,
+            DnsRecordType::Cname => RecordType::CNAME,
+            DnsRecordType::Mx => RecordType::MX,
+            DnsRecordType::Txt => RecordType::TXT,
+            DnsRecordType::Srv => RecordType::SRV,
+        };
+
+        let mut message = Message::new();
+        message
+            .set_id(rand::random())
+            .set_message_type(MessageType::Query)
+            .set_op_code(OpCode::Query)
+            .set_recursion_desired(true)
+            .add_query(Query::query(name, record_type));
+        Ok(message)
     }
 }
 
+/// Sends `query` in a datagram and waits for the response with its ID
+async fn exchange_udp(server: SocketAddr, query: &Message) -> Result<Message> {
+    let local: SocketAddr = match server {
+        SocketAddr::V4(_) => (Ipv4Addr::UNSPECIFIED, 0).into(),
+        SocketAddr::V6(_) => (Ipv6Addr::UNSPECIFIED, 0).into(),
+    };
+    let socket = UdpSocket::bind(local).await?;
+    socket.connect(server).await?;
+    socket
+        .send(&query.to_vec()?)
+        .await
+        .map_err(|e| anyhow!("DNS query to {} failed: {}", server, e))?;
+
+    let mut buf = [0; 4096];
+    loop {
+        let len = socket
+            .recv(&mut buf)
+            .await
+            .map_err(|e| anyhow!("DNS query to {} failed: {}", server, e))?;
+        let response = parse_dns_response(&buf[..len])?;
+        // Anything else is a late answer to someone else's query
+        if response.id() == query.id() {
+            return Ok(response);
+        }
+    }
+}
+
+/// Sends `query` over a TCP connection, each message prefixed by its length
+async fn exchange_tcp(server: SocketAddr, query: &Message) -> Result<Message> {
+    let mut stream = TcpStream::connect(server)
+        .await
+        .map_err(|e| anyhow!("Failed to connect to DNS server {}: {}", server, e))?;
+    let bytes = query.to_vec()?;
+    let len = u16::try_from(bytes.len()).context("DNS query is too long")?;
+    stream.write_all(&len.to_be_bytes()).await?;
+    stream.write_all(&bytes).await?;
+
+    let len = stream
+        .read_u16()
+        .await
+        .map_err(|e| anyhow!("DNS server {} closed the connection: {}", server, e))?;
+    let mut response = vec![0; len.into()];
+    stream.read_exact(&mut response).await?;
+    parse_dns_response(&response)
+}
+
+fn parse_dns_response(bytes: &[u8]) -> Result<Message> {
+    Message::from_vec(bytes).map_err(|e| anyhow!("Invalid DNS response: {}", e))
+}
+
+/// The answers of the requested type, written the way they are expected;
+/// others, such as the CNAME records leading to an address, are skipped
+fn dns_answers(response: &Message, record_type: DnsRecordType) -> Vec<String> {
+    let host = |name: &Name| name.to_utf8().trim_end_matches('.').to_string();
+    response
+        .answers()
+        .iter()
+        .filter_map(|record| match (record_type, record.data()) {
+            (DnsRecordType::A, RData::A(a)) => Some(a.0.to_string()),
+            (DnsRecordType::Aaaa, RData::AAAA(aaaa)) => Some(aaaa.0.to_string()),
+            (DnsRecordType::Cname, RData::CNAME(cname)) => Some(host(&cname.0)),
+            (DnsRecordType::Mx, RData::MX(mx)) => {
+                Some(format!("{} {}", mx.preference(), host(mx.exchange())))
+            }
+            (DnsRecordType::Txt, RData::TXT(txt)) => Some(
+                txt.iter()
+                    .map(|part| String::from_utf8_lossy(part))
+                    .collect::<String>(),
+            ),
+            (DnsRecordType::Srv, RData::SRV(srv)) => Some(format!(
+                "{} {} {} {}",
+                srv.priority(),
+                srv.weight(),
+                srv.port(),
+                host(srv.target())
+            )),
+            _ => None,
+        })
+        .collect()
+}
+
 async fn check_tcp(host: &str, port: u16, timeout: u64) -> Result<String> {
     info!("Checking TCP connection to {}:{}", host, port);
 
     // Format the address and resolve DNS
     let addr_str = format!("{}:{}", host, port);
-    let socket_addrs: Vec<_> = addr_str
-        .to_socket_addrs()
+    let socket_addrs: Vec<_> = lookup_host(&addr_str)
+        .await
         .context(format!("Failed to resolve hostname: {}", host))?
         .collect();
 
@@ -124,15 +935,19 @@ async fn check_tcp(host: &str, port: u16, timeout: u64) -> Result<String> {
     let mut last_error = None;
 
     for socket_addr in socket_addrs {
-        match TcpStream::connect_timeout(&socket_addr, timeout_duration) {
-            Ok(_stream) => {
+        match tokio::time::timeout(timeout_duration, TcpStream::connect(socket_addr)).await {
+            Ok(Ok(_stream)) => {
                 return Ok(format!(
                     "TCP connection successful to {}:{} ({})",
                     host, port, socket_addr
                 ));
             }
-            Err(e) => {
-                last_error = Some(e);
+            Ok(Err(e)) => {
+                last_error = Some(e.to_string());
+                continue;
+            }
+            Err(_) => {
+                last_error = Some(format!("timed out after {}s", timeout));
                 continue;
             }
         }
@@ -146,9 +961,407 @@ async fn check_tcp(host: &str, port: u16, timeout: u64) -> Result<String> {
     }
 }
 
+/// Sends `payload` to a UDP service and checks the reply against `expected`.
+/// With `no_reply_is_up`, a service that stays silent passes unless the host
+/// reports the port unreachable.
+async fn check_udp(
+    host: &str,
+    port: u16,
+    payload: &UdpPayload,
+    expected: Option<&UdpResponseMatch>,
+    no_reply_is_up: bool,
+    timeout: u64,
+) -> Result<String> {
+    info!("Checking UDP service at {}:{}", host, port);
+    let addr = lookup_host((host, port))
+        .await
+        .map_err(|e| anyhow!("Failed to resolve {}: {}", host, e))?
+        .next()
+        .ok_or_else(|| anyhow!("No addresses found for {}", host))?;
+    let local: SocketAddr = match addr {
+        SocketAddr::V4(_) => (Ipv4Addr::UNSPECIFIED, 0).into(),
+        SocketAddr::V6(_) => (Ipv6Addr::UNSPECIFIED, 0).into(),
+    };
+    let socket = UdpSocket::bind(local).await?;
+    // A connected socket hears about ICMP port unreachable as a refused receive
+    socket.connect(addr).await?;
+    socket
+        .send(payload.as_bytes())
+        .await
+        .map_err(|e| anyhow!("Failed to send to {}: {}", addr, e))?;
+
+    let mut buf = vec![0; 65536];
+    match tokio::time::timeout(Duration::from_secs(timeout), socket.recv(&mut buf)).await {
+        Ok(Ok(len)) => {
+            let reply = &buf[..len];
+            match expected {
+                Some(expected) if !expected.matches(reply) => Err(anyhow!(
+                    "Reply from {} does not match '{}': {}",
+                    addr,
+                    expected,
+                    describe_datagram(reply)
+                )),
+                _ => Ok(format!(
+                    "{} replied with {} bytes: {}",
+                    addr,
+                    len,
+                    describe_datagram(reply)
+                )),
+            }
+        }
+        Ok(Err(e)) if e.kind() == io::ErrorKind::ConnectionRefused => {
+            Err(anyhow!("{} is closed (ICMP port unreachable)", addr))
+        }
+        Ok(Err(e)) => Err(anyhow!("Failed to receive from {}: {}", addr, e)),
+        Err(_) if no_reply_is_up => Ok(format!(
+            "No reply from {} within {}s and no port unreachable, so assuming it is up",
+            addr, timeout
+        )),
+        Err(_) => Err(anyhow!("No reply from {} within {}s", addr, timeout)),
+    }
+}
+
+/// Longest part of a datagram quoted in check details
+const DATAGRAM_PREVIEW_LEN: usize = 64;
+
+/// Quotes the start of a datagram, as text when it is printable and as hex otherwise
+fn describe_datagram(datagram: &[u8]) -> String {
+    let preview = &datagram[..datagram.len().min(DATAGRAM_PREVIEW_LEN)];
+    let ellipsis = if preview.len() < datagram.len() {
+        "..."
+    } else {
+        ""
+    };
+    match std::str::from_utf8(preview) {
+        Ok(text) if text.chars().all(|c| !c.is_control() || c.is_whitespace()) => {
+            format!("'{}{}'", text.trim_end(), ellipsis)
+        }
+        _ => format!("hex:{}{}", format_hex(preview), ellipsis),
+    }
+}
+
+/// Keys tried, in order, when a node has no credential and the SSH agent can't log in
+const DEFAULT_KEY_FILES: [&str; 3] = ["id_ed25519", "id_ecdsa", "id_rsa"];
+
+/// Longest command output included in a check's details
+const MAX_OUTPUT_DETAIL_CHARS: usize = 200;
+
+/// Settings of an SSH check, owned so the check can run on a blocking thread
+struct SshCheck {
+    host: String,
+    port: u16,
+    timeout: u64,
+    command: Option<String>,
+    expected_output: Option<String>,
+    host_key: Option<String>,
+}
+
+impl SshCheck {
+    /// Verifies the server's host key, logs in with `credential`, then runs
+    /// the command if there is one.
+    ///
+    /// The check passes when the login succeeds and the command exits with
+    /// status 0 and, if `expected_output` is set, its combined stdout and
+    /// stderr match that regular expression.
+    fn run(&self, credential: &SshCredential) -> Result<String> {
+        info!("Checking SSH for {}:{}", self.host, self.port);
+        let timeout = Duration::from_secs(self.timeout);
+
+        let addrs = (self.host.as_str(), self.port)
+            .to_socket_addrs()
+            .with_context(|| format!("Failed to resolve hostname: {}", self.host))?;
+        let mut last_error = None;
+        let mut stream = None;
+        for addr in addrs {
+            match std::net::TcpStream::connect_timeout(&addr, timeout) {
+                Ok(connected) => {
+                    stream = Some(connected);
+                    break;
+                }
+                Err(e) => last_error = Some(e),
+            }
+        }
+        let stream = stream.ok_or_else(|| match last_error {
+            Some(e) => anyhow!("Failed to connect to {}:{} - {}", self.host, self.port, e),
+            None => anyhow!("No addresses found for {}:{}", self.host, self.port),
+        })?;
+
+        let mut session = ssh2::Session::new()?;
+        session.set_timeout(u32::try_from(timeout.as_millis()).unwrap_or(u32::MAX));
+        session.set_tcp_stream(stream);
+        // Check details only show the outermost error, so keep ssh2's reason in it
+        session
+            .handshake()
+            .map_err(|e| anyhow!("SSH handshake failed: {}", e))?;
+        // Never hand credentials to a server that may not be the one we meant
+        self.verify_host_key(&session)?;
+
+        let username = authenticate(&session, credential)?;
+
+        let Some(command) = &self.command else {
+            return Ok(format!("Logged in as {}", username));
+        };
+
+        let run_error = |e: &dyn std::fmt::Display| anyhow!("Failed to run '{}': {}", command, e);
+        let mut channel = session.channel_session().map_err(|e| run_error(&e))?;
+        // Read stderr along with stdout, so neither can stall the other
+        channel
+            .handle_extended_data(ssh2::ExtendedData::Merge)
+            .map_err(|e| run_error(&e))?;
+        channel.exec(command).map_err(|e| run_error(&e))?;
+        let mut output = Vec::new();
+        channel
+            .read_to_end(&mut output)
+            .map_err(|e| run_error(&e))?;
+        channel.wait_close().map_err(|e| run_error(&e))?;
+        let output = String::from_utf8_lossy(&output);
+
+        let exit_status = channel.exit_status()?;
+        if exit_status != 0 {
+            return Err(anyhow!(
+                "'{}' exited with status {}: {}",
+                command,
+                exit_status,
+                summarize_output(&output)
+            ));
+        }
+
+        if let Some(pattern) = &self.expected_output {
+            let regex = Regex::new(pattern)
+                .map_err(|e| anyhow!("Invalid expected output pattern: {}", e))?;
+            if !regex.is_match(&output) {
+                return Err(anyhow!(
+                    "Output of '{}' did not match '{}': {}",
+                    command,
+                    pattern,
+                    summarize_output(&output)
+                ));
+            }
+        }
+
+        Ok(format!(
+            "'{}' succeeded: {}",
+            command,
+            summarize_output(&output)
+        ))
+    }
+
+    /// Checks the host key the server presented in `session` against the
+    /// pinned fingerprint or, without one, against `~/.ssh/known_hosts`
+    fn verify_host_key(&self, session: &ssh2::Session) -> Result<()> {
+        let no_key = || anyhow!("{}:{} presented no host key", self.host, self.port);
+
+        if let Some(pinned) = &self.host_key {
+            let fingerprint = session
+                .host_key_hash(ssh2::HashType::Sha256)
+                .ok_or_else(no_key)?;
+            if fingerprint != parse_host_key_fingerprint(pinned)? {
+                return Err(anyhow!(
+                    "Host key {} of {}:{} does not match the pinned {}",
+                    format_host_key_fingerprint(fingerprint),
+                    self.host,
+                    self.port,
+                    pinned
+                ));
+            }
+            return Ok(());
+        }
+
+        let (key, _) = session.host_key().ok_or_else(no_key)?;
+        let path = dirs::home_dir()
+            .map(|home| home.join(".ssh").join("known_hosts"))
+            .ok_or_else(|| anyhow!("Cannot find known_hosts without a home directory"))?;
+        let mut known_hosts = session.known_hosts()?;
+        if path.is_file() {
+            known_hosts
+                .read_file(&path, ssh2::KnownHostFileKind::OpenSSH)
+                .map_err(|e| anyhow!("Failed to read {}: {}", path.display(), e))?;
+        }
+        match known_hosts.check_port(&self.host, self.port, key) {
+            ssh2::CheckResult::Match => Ok(()),
+            ssh2::CheckResult::Mismatch => Err(anyhow!(
+                "Host key of {}:{} does not match the one in {}; it may have been replaced, or the connection intercepted",
+                self.host,
+                self.port,
+                path.display()
+            )),
+            ssh2::CheckResult::NotFound => Err(anyhow!(
+                "{}:{} is not in {}; connect once with ssh to add it, or pin its host key fingerprint",
+                self.host,
+                self.port,
+                path.display()
+            )),
+            ssh2::CheckResult::Failure => Err(anyhow!(
+                "Failed to check the host key of {}:{}",
+                self.host,
+                self.port
+            )),
+        }
+    }
+}
+
+/// Logs in to `session` with `credential` and returns the user name used
+fn authenticate(session: &ssh2::Session, credential: &SshCredential) -> Result<String> {
+    let (username, result) = match credential {
+        SshCredential::Password { username, password } => (
+            username.clone(),
+            session.userauth_password(username, password.as_str()),
+        ),
+        SshCredential::Key {
+            username,
+            private_key_path,
+            passphrase,
+        } => (
+            username.clone(),
+            session.userauth_pubkey_file(
+                username,
+                None,
+                private_key_path,
+                passphrase.as_ref().map(|p| p.as_str()),
+            ),
+        ),
+        SshCredential::KeyData {
+            username,
+            private_key_data,
+            passphrase,
+        } => (
+            username.clone(),
+            session.userauth_pubkey_memory(
+                username,
+                None,
+                private_key_data.as_str(),
+                passphrase.as_ref().map(|p| p.as_str()),
+            ),
+        ),
+        SshCredential::Default => {
+            let username = std::env::var("USER")
+                .or_else(|_| std::env::var("USERNAME"))
+                .map_err(|_| anyhow!("No credential set and the current user name is unknown"))?;
+            let result = session.userauth_agent(&username).or_else(|agent_error| {
+                default_key_files()
+                    .into_iter()
+                    .find_map(|key| {
+                        session
+                            .userauth_pubkey_file(&username, None, &key, None)
+                            .ok()
+                    })
+                    .ok_or(agent_error)
+            });
+            (username, result)
+        }
+    };
+
+    result.map_err(|e| anyhow!("SSH authentication as {} failed: {}", username, e))?;
+    if !session.authenticated() {
+        return Err(anyhow!("SSH authentication as {} failed", username));
+    }
+    Ok(username)
+}
+
+/// Private keys in the user's `~/.ssh` directory that exist
+fn default_key_files() -> Vec<PathBuf> {
+    let Some(ssh_dir) = dirs::home_dir().map(|home| home.join(".ssh")) else {
+        return Vec::new();
+    };
+    DEFAULT_KEY_FILES
+        .iter()
+        .map(|name| ssh_dir.join(name))
+        .filter(|path| path.is_file())
+        .collect()
+}
+
+/// Shortens command output to fit in a check's details
+fn summarize_output(output: &str) -> String {
+    let output = output.trim();
+    if output.is_empty() {
+        return "(no output)".to_string();
+    }
+    let mut summary: String = output
+        .chars()
+        .map(|c| if c.is_control() { ' ' } else { c })
+        .take(MAX_OUTPUT_DETAIL_CHARS)
+        .collect();
+    if output.chars().count() > MAX_OUTPUT_DETAIL_CHARS {
+        summary.push_str("...");
+    }
+    summary
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::JsonAssertion;
+
+    #[test]
+    fn test_check_response_body_text_assertions() {
+        let body = b"status: healthy, version 1.2";
+        let check = |assertions: ResponseAssertions| check_response_body(body, &assertions);
+
+        assert!(check(ResponseAssertions::default()).is_ok());
+        assert!(check(ResponseAssertions {
+            contains: Some("healthy".to_string()),
+            not_contains: Some("error".to_string()),
+            matches: Some(r"version \d+\.\d+".to_string()),
+            not_matches: Some("^down".to_string()),
+            ..Default::default()
+        })
+        .is_ok());
+
+        let err = check(ResponseAssertions {
+            contains: Some("ready".to_string()),
+            ..Default::default()
+        })
+        .unwrap_err();
+        assert_eq!(err.to_string(), "Response body does not contain 'ready'");
+
+        let err = check(ResponseAssertions {
+            not_matches: Some("heal+thy".to_string()),
+            ..Default::default()
+        })
+        .unwrap_err();
+        assert_eq!(err.to_string(), "Response body matches 'heal+thy'");
+    }
+
+    #[test]
+    fn test_check_response_body_json_assertion() {
+        let body = br#"{"status": "ok", "checks": [{"db": true}]}"#;
+        let check = |pointer: &str, value: serde_json::Value| {
+            check_response_body(
+                body,
+                &ResponseAssertions {
+                    json: Some(JsonAssertion {
+                        pointer: pointer.to_string(),
+                        value,
+                    }),
+                    ..Default::default()
+                },
+            )
+        };
+
+        assert!(check("/status", serde_json::json!("ok")).is_ok());
+        assert!(check("/checks/0/db", serde_json::json!(true)).is_ok());
+
+        let err = check("/status", serde_json::json!("degraded")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            r#"Expected "degraded" at '/status' but found "ok""#
+        );
+        let err = check("/missing", serde_json::json!(1)).unwrap_err();
+        assert!(err.to_string().contains("not found"));
+        assert!(check_response_body(
+            b"not json",
+            &ResponseAssertions {
+                json: Some(JsonAssertion {
+                    pointer: String::new(),
+                    value: serde_json::json!(null),
+                }),
+                ..Default::default()
+            }
+        )
+        .unwrap_err()
+        .to_string()
+        .contains("not JSON"));
+    }
 
     #[test]
     fn test_normalize_http_url_with_https() {
@@ -226,4 +1439,143 @@ mod tests {
             "http://192.168.1.100:8080"
         );
     }
+
+    #[test]
+    fn test_grade_certificate() {
+        let now = Utc::now();
+        let tls = TlsOptions::default();
+        let grade = |days: i64, tls: &TlsOptions| {
+            grade_certificate(now + chrono::Duration::days(days), now, tls)
+        };
+
+        assert_eq!(
+            grade(90, &tls),
+            (
+                NodeStatus::Online,
+                "certificate expires in 90 days".to_string()
+            )
+        );
+        assert_eq!(
+            grade(10, &tls),
+            (
+                NodeStatus::Degraded,
+                "Certificate expires in 10 days, within the 14 day warning threshold".to_string()
+            )
+        );
+        assert_eq!(
+            grade(1, &tls),
+            (
+                NodeStatus::Offline,
+                "Certificate expires in 1 day".to_string()
+            )
+        );
+        let (status, details) = grade(-2, &tls);
+        assert_eq!(status, NodeStatus::Offline);
+        assert!(details.starts_with("Certificate expired on "));
+
+        // Thresholds of 0 leave only an expired certificate failing
+        let lenient = TlsOptions {
+            expiry_warning_days: 0,
+            expiry_critical_days: 0,
+            ..Default::default()
+        };
+        assert_eq!(grade(0, &lenient).0, NodeStatus::Online);
+        assert_eq!(grade(-1, &lenient).0, NodeStatus::Offline);
+
+        // Accepted invalid certificates only have their expiry reported
+        let accept_invalid = TlsOptions {
+            mode: TlsMode::AcceptInvalid,
+            ..Default::default()
+        };
+        assert_eq!(grade(-2, &accept_invalid).0, NodeStatus::Online);
+    }
+
+    #[test]
+    fn test_grade_ping() {
+        let ms = Duration::from_millis;
+        let stats = PingStats::from_round_trips(PingMethod::Icmp, 4, &[ms(40), ms(60), ms(50)]);
+        let thresholds = PingThresholds {
+            loss_warning_percent: 20,
+            loss_critical_percent: 50,
+            latency_warning_ms: 40,
+            latency_critical_ms: 100,
+        };
+
+        // Without thresholds any reply is enough
+        assert_eq!(
+            grade_ping(&stats, &PingThresholds::default()),
+            (NodeStatus::Online, stats.to_string())
+        );
+
+        // Loss is reported before latency at the same level
+        assert_eq!(
+            grade_ping(&stats, &thresholds),
+            (
+                NodeStatus::Degraded,
+                format!(
+                    "Packet loss of 25% reached the 20% warning threshold ({})",
+                    stats
+                )
+            )
+        );
+
+        // A critical threshold outranks any warning
+        let slow = PingThresholds {
+            latency_critical_ms: 50,
+            ..thresholds.clone()
+        };
+        assert_eq!(
+            grade_ping(&stats, &slow),
+            (
+                NodeStatus::Offline,
+                format!(
+                    "Mean round trip of 50.0 ms reached the 50 ms critical threshold ({})",
+                    stats
+                )
+            )
+        );
+
+        let lossy = PingThresholds {
+            loss_critical_percent: 25,
+            ..thresholds
+        };
+        assert_eq!(grade_ping(&stats, &lossy).0, NodeStatus::Offline);
+
+        let lost = PingStats::from_round_trips(PingMethod::Icmp, 3, &[]);
+        assert_eq!(
+            grade_ping(&lost, &PingThresholds::default()),
+            (
+                NodeStatus::Offline,
+                "No reply to 3 probes over ICMP".to_string()
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tcp_ping_probe() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let probe = PingProbe::Tcp(addr);
+        assert_eq!(probe.method(), PingMethod::Tcp(addr.port()));
+        assert_eq!(
+            probe.ping(1, Duration::from_secs(1)).await.unwrap().len(),
+            1
+        );
+
+        // Probes go out a second apart, the last one without waiting on the others
+        let started = Instant::now();
+        assert_eq!(
+            probe.ping(3, Duration::from_secs(5)).await.unwrap().len(),
+            3
+        );
+        let elapsed = started.elapsed();
+        assert!(elapsed >= PING_INTERVAL * 2 && elapsed < PING_INTERVAL * 3);
+
+        // A refused connection still comes from a host that is up
+        drop(listener);
+        assert_eq!(
+            probe.ping(1, Duration::from_secs(1)).await.unwrap().len(),
+            1
+        );
+    }
 }
@@ -11,51 +11,182 @@
 //!   A status change event is recorded and persisted.
 //!
 //! Recovery from either Degraded or Offline is immediate on the first successful check.
+//! A check can also pass with a warning, such as a certificate close to expiry,
+//! which degrades the node without counting as a failure.
+//!
+//! Nodes can depend on parents, such as the switch in front of them. A failed
+//! check brings forward the next check of the node's parents, and a node is not
+//! confirmed offline while one of its parents is still failing unconfirmed or
+//! being checked. If a parent is down, the node becomes **Unreachable** instead
+//! of Offline, which is recorded but not alerted on. A node already confirmed
+//! offline stays offline.
+//!
+//! Checks run concurrently on a tokio runtime. The scheduler loop decides which
+//! nodes are due, spawns their checks (bounded by `EngineConfig::max_concurrent_checks`
+//! and `EngineConfig::check_deadline`), and applies the results one at a time so
+//! that state transitions are never evaluated in parallel for the same node.
+//!
+//! Confirmed status changes are passed to a `Notifier` when notifications are
+//! enabled (see `notifications`), and to a `HookRunner` that runs the global and
+//! per-node hook commands (see `hooks`). Changes during a maintenance window are
+//! recorded with `StatusChange::in_maintenance` set and passed to neither, and
+//! neither is the recovery that ends an outage which began in maintenance.
+//!
+//! When `EngineConfig::metrics` is enabled, the engine also serves Prometheus
+//! metrics of its checks, status changes and own health (see `metrics`).
+//!
+//! Every check result is stored as a compact sample. Once a minute the engine
+//! rolls closed buckets up into per-minute/hour/day statistics and prunes raw
+//! samples according to `EngineConfig::retention`.
 
+use crate::config::{HookSettings, MetricsSettings, NotificationSettings};
+use crate::credentials::SharedCredentialStore;
 use crate::database::Database;
-use crate::models::{Node, NodeStatus, StatusChange};
-use crate::monitor::check_node;
+use crate::hooks::HookRunner;
+use crate::metrics::{self, CheckResult, EngineMetrics};
+use crate::models::{
+    CheckSample, MonitoringResult, Node, NodeStatus, RetentionPolicy, StatusChange,
+};
+use crate::monitor::check_node_with_credentials;
+use crate::notifications::Notifier;
+use anyhow::Result;
 use chrono::{DateTime, Utc};
-use std::collections::HashMap;
-use std::sync::mpsc;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::{mpsc, Arc};
 use std::thread;
 use std::time::{Duration, Instant};
-use tracing::info;
+use tokio::sync::{mpsc as async_mpsc, Semaphore};
+use tracing::{error, info, warn};
+
+/// How often the scheduler wakes up to look for due checks and control messages.
+const SCHEDULER_TICK: Duration = Duration::from_millis(250);
+
+/// How often rollups are refreshed and old history is pruned.
+const MAINTENANCE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Default upper bound on the number of checks running at the same time.
+pub const DEFAULT_MAX_CONCURRENT_CHECKS: usize = 32;
+
+/// Default hard deadline for a single check.
+pub const DEFAULT_CHECK_DEADLINE_SECS: u64 = 45;
+
+/// Default maximum random delay applied to each node's first check.
+pub const DEFAULT_MAX_START_JITTER_SECS: u64 = 5;
 
 /// Commands sent to the monitoring thread to update its node configuration.
-#[derive(Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum NodeConfigUpdate {
     Add(Node),
     Update(Node),
     Delete(i64),
 }
 
+/// Scheduler settings for the monitoring engine.
+#[derive(Debug, Clone)]
+pub struct EngineConfig {
+    /// Maximum number of checks allowed to be in flight at once.
+    pub max_concurrent_checks: usize,
+    /// Deadline for a single check. A check still running when it expires
+    /// is abandoned and counted as a failure.
+    pub check_deadline: Duration,
+    /// Upper bound of the random delay before each node's first check, so that
+    /// a large node list does not fire all of its checks in the same instant.
+    pub max_start_jitter: Duration,
+    /// How long raw check samples and rollups are kept.
+    pub retention: RetentionPolicy,
+    /// Which status changes are announced, and where.
+    pub notifications: NotificationSettings,
+    /// Commands run on status changes of every node, and the hook timeout.
+    pub hooks: HookSettings,
+    /// Whether and where the engine's metrics are served.
+    pub metrics: MetricsSettings,
+}
+
+impl Default for EngineConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent_checks: DEFAULT_MAX_CONCURRENT_CHECKS,
+            check_deadline: Duration::from_secs(DEFAULT_CHECK_DEADLINE_SECS),
+            max_start_jitter: Duration::from_secs(DEFAULT_MAX_START_JITTER_SECS),
+            retention: RetentionPolicy::default(),
+            notifications: NotificationSettings::default(),
+            hooks: HookSettings::default(),
+            metrics: MetricsSettings::default(),
+        }
+    }
+}
+
 /// Handle returned when monitoring starts, used to control the background thread.
 pub struct MonitoringHandle {
     pub stop_tx: mpsc::Sender<()>,
     pub config_tx: mpsc::Sender<NodeConfigUpdate>,
 }
 
-/// Starts the monitoring engine in a background thread.
+/// Starts the monitoring engine in a background thread with the default
+/// scheduler settings.
 ///
 /// Returns a `MonitoringHandle` for sending stop/config signals, and uses the
 /// provided `update_tx` channel to send updated nodes back to the caller (TUI).
+#[allow(dead_code)] // The binary always passes the configured engine settings
 pub fn start_monitoring(
     db: Database,
     initial_nodes: Vec<Node>,
     update_tx: mpsc::Sender<Node>,
 ) -> MonitoringHandle {
-    info!("Starting monitoring engine");
+    start_monitoring_with_config(
+        db,
+        initial_nodes,
+        update_tx,
+        EngineConfig::default(),
+        SharedCredentialStore::default(),
+    )
+}
+
+/// Starts the monitoring engine in a background thread using `config` for
+/// concurrency, deadline and start jitter settings.
+///
+/// SSH checks and email notifications log in with credentials from
+/// `credentials`; a store unlocked after the engine has started is used from
+/// the next check onwards.
+pub fn start_monitoring_with_config(
+    db: Database,
+    initial_nodes: Vec<Node>,
+    update_tx: mpsc::Sender<Node>,
+    config: EngineConfig,
+    credentials: SharedCredentialStore,
+) -> MonitoringHandle {
+    info!(
+        "Starting monitoring engine (max {} concurrent checks, {}s deadline)",
+        config.max_concurrent_checks,
+        config.check_deadline.as_secs()
+    );
     let (stop_tx, stop_rx) = mpsc::channel();
     let (config_tx, config_rx) = mpsc::channel();
 
     thread::spawn(move || {
-        run_monitoring_loop(db, initial_nodes, update_tx, stop_rx, config_rx);
+        if let Err(e) = run_monitoring_loop(
+            db,
+            initial_nodes,
+            update_tx,
+            stop_rx,
+            config_rx,
+            config,
+            credentials,
+        ) {
+            error!("Monitoring engine stopped with error: {}", e);
+        }
     });
 
     MonitoringHandle { stop_tx, config_tx }
 }
 
+/// Result of a single check, sent from a check task back to the scheduler.
+struct CheckOutcome {
+    node_id: i64,
+    result: Result<MonitoringResult>,
+}
+
 /// The main monitoring loop that runs in a background thread.
 fn run_monitoring_loop(
     db: Database,
@@ -63,123 +194,527 @@ fn run_monitoring_loop(
     update_tx: mpsc::Sender<Node>,
     stop_rx: mpsc::Receiver<()>,
     config_rx: mpsc::Receiver<NodeConfigUpdate>,
-) {
-    let mut last_check_times: HashMap<i64, Instant> = HashMap::new();
+    config: EngineConfig,
+    credentials: SharedCredentialStore,
+) -> Result<()> {
+    let runtime = tokio::runtime::Runtime::new()?;
+    let mut scheduler = Scheduler::new(db, initial_nodes, config, credentials);
 
-    // Initialize previous_statuses from database to avoid duplicate records on restart
-    let mut previous_statuses: HashMap<i64, NodeStatus> = initial_nodes
-        .iter()
-        .filter_map(|n| {
-            n.id.and_then(|id| {
-                db.get_latest_monitoring_result(id)
-                    .ok()
-                    .flatten()
-                    .map(|result| (id, result.status))
-                    .or(Some((id, n.status)))
+    runtime.block_on(scheduler.run(&update_tx, &stop_rx, &config_rx));
+
+    // Abandon any checks that are still in flight rather than waiting out their timeouts
+    runtime.shutdown_background();
+    Ok(())
+}
+
+/// Scheduler state owned by the monitoring thread.
+struct Scheduler {
+    db: Database,
+    config: EngineConfig,
+    credentials: SharedCredentialStore,
+    current_nodes: Vec<Node>,
+    last_check_times: HashMap<i64, Instant>,
+    previous_statuses: HashMap<i64, NodeStatus>,
+    last_status_change_times: HashMap<i64, DateTime<Utc>>,
+    /// Earliest time each node's first check may start (jittered start).
+    start_times: HashMap<i64, Instant>,
+    in_flight: HashSet<i64>,
+    last_maintenance: Instant,
+    /// Announces status changes; `None` when notifications are disabled
+    notifier: Option<Notifier>,
+    hooks: HookRunner,
+    metrics: Arc<EngineMetrics>,
+}
+
+impl Scheduler {
+    fn new(
+        db: Database,
+        initial_nodes: Vec<Node>,
+        config: EngineConfig,
+        credentials: SharedCredentialStore,
+    ) -> Self {
+        // Initialize previous_statuses from database to avoid duplicate records on restart
+        let previous_statuses: HashMap<i64, NodeStatus> = initial_nodes
+            .iter()
+            .filter_map(|n| {
+                n.id.and_then(|id| {
+                    db.get_latest_monitoring_result(id)
+                        .ok()
+                        .flatten()
+                        .map(|result| (id, result.status))
+                        .or(Some((id, n.status)))
+                })
             })
-        })
-        .collect();
+            .collect();
+
+        let notifier = if config.notifications.enabled {
+            match Notifier::new(&config.notifications, db.clone(), credentials.clone()) {
+                Ok(notifier) => Some(notifier),
+                Err(e) => {
+                    warn!("Notifications are unavailable: {}", e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        let hooks = HookRunner::new(&config.hooks, db.clone());
+
+        let metrics = Arc::new(EngineMetrics::new());
+        metrics.set_nodes(&initial_nodes);
+
+        let now = Instant::now();
+        let start_times = initial_nodes
+            .iter()
+            .filter_map(|n| n.id)
+            .map(|id| (id, now + start_jitter(config.max_start_jitter)))
+            .collect();
+
+        Self {
+            db,
+            config,
+            credentials,
+            current_nodes: initial_nodes,
+            last_check_times: HashMap::new(),
+            previous_statuses,
+            last_status_change_times: HashMap::new(),
+            start_times,
+            in_flight: HashSet::new(),
+            last_maintenance: Instant::now(),
+            notifier,
+            hooks,
+            metrics,
+        }
+    }
 
-    let mut last_status_change_times: HashMap<i64, DateTime<Utc>> = HashMap::new();
-    let mut current_nodes = initial_nodes;
-    let runtime = tokio::runtime::Runtime::new().unwrap();
+    async fn run(
+        &mut self,
+        update_tx: &mpsc::Sender<Node>,
+        stop_rx: &mpsc::Receiver<()>,
+        config_rx: &mpsc::Receiver<NodeConfigUpdate>,
+    ) {
+        let semaphore = Arc::new(Semaphore::new(self.config.max_concurrent_checks.max(1)));
+        let (result_tx, mut result_rx) = async_mpsc::unbounded_channel();
+        let mut tick = tokio::time::interval(SCHEDULER_TICK);
+        tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+        if self.config.metrics.enabled {
+            let listen = self.config.metrics.listen;
+            match tokio::net::TcpListener::bind(listen).await {
+                Ok(listener) => {
+                    info!("Serving metrics at http://{}/metrics", listen);
+                    tokio::spawn(metrics::serve(listener, Arc::clone(&self.metrics)));
+                }
+                Err(e) => warn!("Metrics endpoint unavailable on {}: {}", listen, e),
+            }
+        }
 
-    loop {
-        // Process configuration updates
-        process_config_updates(
-            &config_rx,
-            &db,
-            &mut current_nodes,
-            &mut last_check_times,
-            &mut previous_statuses,
-            &mut last_status_change_times,
+        loop {
+            tokio::select! {
+                Some(outcome) = result_rx.recv() => {
+                    if !self.apply_outcome(outcome, update_tx) {
+                        break;
+                    }
+                }
+                scheduled = tick.tick() => {
+                    match stop_rx.try_recv() {
+                        Ok(()) | Err(mpsc::TryRecvError::Disconnected) => break,
+                        Err(mpsc::TryRecvError::Empty) => {}
+                    }
+
+                    let updates = process_config_updates(
+                        config_rx,
+                        &self.db,
+                        &mut self.current_nodes,
+                        &mut self.last_check_times,
+                        &mut self.previous_statuses,
+                        &mut self.last_status_change_times,
+                    );
+                    if updates > 0 {
+                        self.metrics.set_nodes(&self.current_nodes);
+                    }
+
+                    self.dispatch_due_checks(&semaphore, &result_tx);
+                    self.metrics
+                        .set_engine_health(scheduled.elapsed(), self.in_flight.len());
+
+                    if self.last_maintenance.elapsed() >= MAINTENANCE_INTERVAL {
+                        self.run_maintenance();
+                    }
+                }
+            }
+        }
+
+        info!(
+            "Monitoring engine stopping with {} check(s) in flight",
+            self.in_flight.len()
         );
+    }
+
+    /// Rolls up closed buckets and prunes history outside the retention policy.
+    fn run_maintenance(&mut self) {
+        self.last_maintenance = Instant::now();
+        let now = Utc::now();
+
+        match self.db.refresh_rollups(now) {
+            Ok(0) => {}
+            Ok(written) => info!("Updated {} rollup bucket(s)", written),
+            Err(e) => warn!("Failed to refresh rollups: {}", e),
+        }
 
-        // Check each node
-        for node in &mut current_nodes {
+        match self.db.prune_history(&self.config.retention, now) {
+            Ok(0) => {}
+            Ok(deleted) => info!("Pruned {} expired history row(s)", deleted),
+            Err(e) => warn!("Failed to prune history: {}", e),
+        }
+    }
+
+    /// Spawns a check task for every node that is due and not already being checked.
+    fn dispatch_due_checks(
+        &mut self,
+        semaphore: &Arc<Semaphore>,
+        result_tx: &async_mpsc::UnboundedSender<CheckOutcome>,
+    ) {
+        let now = Instant::now();
+
+        for node in &self.current_nodes {
             let node_id = node.id.unwrap_or(0);
-            if node_id == 0 {
+            if node_id == 0 || self.in_flight.contains(&node_id) {
                 continue;
             }
 
-            if !should_check_node(node, node_id, &last_check_times) {
+            if self
+                .start_times
+                .get(&node_id)
+                .is_some_and(|start_at| *start_at > now)
+            {
                 continue;
             }
 
-            last_check_times.insert(node_id, Instant::now());
-            let previous_status = previous_statuses.get(&node_id).copied();
-            let result = runtime.block_on(check_node(node));
-
-            if let Ok(mut check_result) = result {
-                let check_succeeded = check_result.status == NodeStatus::Online;
-
-                // Apply soft/hard state logic
-                let new_status = evaluate_node_status(node, check_succeeded);
-
-                check_result.status = new_status;
-
-                // Record status change events (only for confirmed transitions)
-                if let Some(prev_status) = previous_status {
-                    if should_record_status_change(prev_status, new_status) {
-                        let current_time = Utc::now();
-                        let duration_ms =
-                            last_status_change_times.get(&node_id).map(|last_change| {
-                                StatusChange::calculate_duration(*last_change, current_time)
-                            });
-
-                        let status_change = StatusChange {
-                            id: None,
-                            node_id,
-                            from_status: prev_status,
-                            to_status: new_status,
-                            changed_at: current_time,
-                            duration_ms,
-                        };
-
-                        let _ = db.add_status_change(&status_change);
-                        last_status_change_times.insert(node_id, current_time);
-                    }
-                }
+            if !should_check_node(node, node_id, &self.last_check_times) {
+                continue;
+            }
+
+            self.start_times.remove(&node_id);
+            self.last_check_times.insert(node_id, now);
+            self.in_flight.insert(node_id);
+
+            let node = node.clone();
+            let semaphore = Arc::clone(semaphore);
+            let result_tx = result_tx.clone();
+            let deadline = self.config.check_deadline;
+            let credentials = self.credentials.clone();
+
+            tokio::spawn(async move {
+                // The semaphore is never closed, so acquiring only fails if the
+                // runtime is shutting down, in which case the result is irrelevant.
+                let Ok(_permit) = semaphore.acquire_owned().await else {
+                    return;
+                };
+                let result = check_node_with_deadline(&node, &credentials, deadline).await;
+                let _ = result_tx.send(CheckOutcome { node_id, result });
+            });
+        }
+    }
 
-                previous_statuses.insert(node_id, new_status);
-                node.status = new_status;
-                node.last_check = Some(check_result.timestamp);
-                node.response_time = check_result.response_time;
-                check_result.node_id = node_id;
+    /// Applies a finished check to the node's state, persists it and forwards the
+    /// updated node. Returns `false` if the receiving side has gone away.
+    fn apply_outcome(&mut self, outcome: CheckOutcome, update_tx: &mpsc::Sender<Node>) -> bool {
+        let node_id = outcome.node_id;
+        self.in_flight.remove(&node_id);
+
+        // The node may have been deleted while its check was running
+        let Some(index) = self
+            .current_nodes
+            .iter()
+            .position(|n| n.id == Some(node_id))
+        else {
+            return true;
+        };
+
+        // A check that could not run, such as an SSH check whose credential is
+        // locked away, counts as a failed check with the error as its details
+        let (mut check_result, result) = match outcome.result {
+            Ok(check_result) => {
+                let result = CheckResult::from_status(check_result.status);
+                (check_result, result)
+            }
+            Err(e) => {
+                warn!(
+                    "Check for node {} failed to run: {:#}",
+                    self.current_nodes[index].name, e
+                );
+                let check_result = MonitoringResult {
+                    id: None,
+                    node_id,
+                    timestamp: Utc::now(),
+                    status: NodeStatus::Offline,
+                    response_time: None,
+                    details: Some(format!("{:#}", e)),
+                    cert_expires_at: None,
+                    ping_stats: None,
+                };
+                (check_result, CheckResult::Error)
+            }
+        };
+
+        let previous_status = self.previous_statuses.get(&node_id).copied();
+        let check_succeeded = check_result.status != NodeStatus::Offline;
+
+        let parents = if check_succeeded {
+            ParentState::Up
+        } else {
+            self.recheck_parents(index);
+            parent_state(
+                &self.current_nodes[index],
+                &self.current_nodes,
+                &self.in_flight,
+            )
+        };
+        let node = &mut self.current_nodes[index];
+
+        let sample = CheckSample {
+            node_id,
+            checked_at: check_result.timestamp,
+            success: check_succeeded,
+            response_time: check_result.response_time,
+        };
+        if let Err(e) = self.db.add_check_sample(&sample) {
+            warn!("Failed to record check sample for {}: {}", node.name, e);
+        }
 
-                let _ = db.update_node(node);
+        // Apply soft/hard state logic
+        let mut new_status = evaluate_node_status(node, check_succeeded);
+        if check_succeeded && check_result.status == NodeStatus::Degraded {
+            new_status = NodeStatus::Degraded;
+        }
 
-                // Record monitoring result on confirmed status changes or first check
-                if let Some(prev_status) = previous_status {
-                    if should_record_status_change(prev_status, new_status) {
-                        let _ = db.add_monitoring_result(&check_result);
+        // Only blame the node itself once its parents are known to be up
+        if new_status == NodeStatus::Offline && previous_status != Some(NodeStatus::Offline) {
+            match &parents {
+                ParentState::Up => {}
+                // Stay unreachable, or wait for the parent's check before confirming
+                ParentState::Pending if previous_status == Some(NodeStatus::Unreachable) => {
+                    new_status = NodeStatus::Unreachable;
+                }
+                ParentState::Pending => new_status = NodeStatus::Degraded,
+                ParentState::Down(parent) => {
+                    if previous_status != Some(NodeStatus::Unreachable) {
+                        info!("{} is unreachable while {} is down", node.name, parent);
                     }
-                } else {
-                    // First check ever for this node
-                    let _ = db.add_monitoring_result(&check_result);
+                    new_status = NodeStatus::Unreachable;
                 }
+            }
+        }
+
+        check_result.status = new_status;
+
+        // Record status change events (only for confirmed transitions)
+        if let Some(prev_status) = previous_status {
+            if should_record_status_change(prev_status, new_status) {
+                let current_time = Utc::now();
+                let duration_ms = self
+                    .last_status_change_times
+                    .get(&node_id)
+                    .map(|last_change| {
+                        StatusChange::calculate_duration(*last_change, current_time)
+                    });
+
+                let in_maintenance = self
+                    .db
+                    .in_maintenance(node_id, current_time)
+                    .unwrap_or_else(|e| {
+                        warn!("Failed to look up maintenance for {}: {}", node.name, e);
+                        false
+                    });
+
+                let mut status_change = StatusChange {
+                    id: None,
+                    node_id,
+                    from_status: prev_status,
+                    to_status: new_status,
+                    changed_at: current_time,
+                    duration_ms,
+                    in_maintenance,
+                };
+
+                // The recovery from an outage that began in maintenance stays quiet too
+                let silenced = in_maintenance
+                    || (status_change.is_recovery()
+                        && self
+                            .db
+                            .get_latest_status_change(node_id)
+                            .ok()
+                            .flatten()
+                            .is_some_and(|previous| previous.in_maintenance));
+
+                status_change.id = self.db.add_status_change(&status_change).ok();
+                self.metrics.record_status_change(&status_change);
+                self.last_status_change_times.insert(node_id, current_time);
+
+                if silenced {
+                    info!(
+                        "{} changed status during maintenance ({}); not alerting",
+                        node.name,
+                        status_change.description()
+                    );
+                } else {
+                    if let Some(notifier) = &self.notifier {
+                        notifier.notify(node, &status_change, check_result.details.as_deref());
+                    }
 
-                if update_tx.send(node.clone()).is_err() {
-                    break;
+                    // A recovering check has no error; report the one that took the node down
+                    let last_error = if status_change.is_recovery() {
+                        self.db
+                            .get_latest_monitoring_result(node_id)
+                            .ok()
+                            .flatten()
+                            .and_then(|result| result.details)
+                    } else {
+                        check_result.details.clone()
+                    };
+                    self.hooks.run(node, &status_change, last_error.as_deref());
                 }
             }
         }
 
-        // Check for stop signal with 1-second timeout
-        match stop_rx.recv_timeout(Duration::from_secs(1)) {
-            Ok(()) | Err(mpsc::RecvTimeoutError::Disconnected) => break,
-            Err(mpsc::RecvTimeoutError::Timeout) => {}
+        self.previous_statuses.insert(node_id, new_status);
+        node.status = new_status;
+        node.last_check = Some(check_result.timestamp);
+        node.response_time = check_result.response_time;
+        node.cert_expires_at = check_result.cert_expires_at;
+        node.ping_stats = check_result.ping_stats.clone();
+        check_result.node_id = node_id;
+
+        let _ = self.db.update_node(node);
+        self.metrics.record_check(node, result);
+
+        // Record monitoring result on confirmed status changes or first check
+        if let Some(prev_status) = previous_status {
+            if should_record_status_change(prev_status, new_status) {
+                let _ = self.db.add_monitoring_result(&check_result);
+            }
+        } else {
+            // First check ever for this node
+            let _ = self.db.add_monitoring_result(&check_result);
         }
+
+        update_tx.send(node.clone()).is_ok()
     }
+
+    /// Makes the parents of the node at `index` due for a check straight away,
+    /// unless they are being checked or were checked within their retry interval
+    fn recheck_parents(&mut self, index: usize) {
+        let now = Instant::now();
+        for parent in &self.current_nodes {
+            let Some(parent_id) = parent.id else {
+                continue;
+            };
+            if !self.current_nodes[index].parents.contains(&parent_id)
+                || self.in_flight.contains(&parent_id)
+            {
+                continue;
+            }
+            let recently_checked = self
+                .last_check_times
+                .get(&parent_id)
+                .is_some_and(|last| now.duration_since(*last).as_secs() < parent.retry_interval);
+            if !recently_checked {
+                self.last_check_times.remove(&parent_id);
+                self.start_times.remove(&parent_id);
+            }
+        }
+    }
+}
+
+/// Where the parents of a node that failed a check stand
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ParentState {
+    /// Every parent is up, or the node has none
+    Up,
+    /// A parent is failing checks without being confirmed down yet, or is
+    /// being checked
+    Pending,
+    /// The named parent is offline or unreachable itself
+    Down(String),
+}
+
+/// Finds where the parents of `node` stand among `nodes`, with `in_flight`
+/// holding the IDs of the nodes being checked
+fn parent_state(node: &Node, nodes: &[Node], in_flight: &HashSet<i64>) -> ParentState {
+    let parents: Vec<&Node> = nodes
+        .iter()
+        // A node listed as its own parent would wait on itself forever
+        .filter(|n| n.id != node.id && n.id.is_some_and(|id| node.parents.contains(&id)))
+        .collect();
+
+    if let Some(parent) = parents
+        .iter()
+        .find(|p| matches!(p.status, NodeStatus::Offline | NodeStatus::Unreachable))
+    {
+        return ParentState::Down(parent.name.clone());
+    }
+    let pending = parents.iter().any(|p| {
+        (p.status == NodeStatus::Degraded && p.consecutive_failures > 0)
+            || p.id.is_some_and(|id| in_flight.contains(&id))
+    });
+    if pending {
+        ParentState::Pending
+    } else {
+        ParentState::Up
+    }
+}
+
+/// Runs `check_node_with_credentials`, converting a check that overruns
+/// `deadline` into a failed result.
+async fn check_node_with_deadline(
+    node: &Node,
+    credentials: &SharedCredentialStore,
+    deadline: Duration,
+) -> Result<MonitoringResult> {
+    match tokio::time::timeout(deadline, check_node_with_credentials(node, credentials)).await {
+        Ok(result) => result,
+        Err(_) => {
+            warn!(
+                "Check for node {} exceeded its {}s deadline",
+                node.name,
+                deadline.as_secs()
+            );
+            Ok(MonitoringResult {
+                id: None,
+                node_id: node.id.unwrap_or(0),
+                timestamp: Utc::now(),
+                status: NodeStatus::Offline,
+                response_time: Some(deadline.as_millis() as u64),
+                details: Some(format!(
+                    "Check exceeded deadline of {}s",
+                    deadline.as_secs()
+                )),
+                cert_expires_at: None,
+                ping_stats: None,
+            })
+        }
+    }
+}
+
+/// Picks a random delay in `[0, max]` used to spread out first checks.
+fn start_jitter(max: Duration) -> Duration {
+    let max_ms = max.as_millis() as u64;
+    if max_ms == 0 {
+        return Duration::ZERO;
+    }
+    Duration::from_millis(rand::random_range(0..=max_ms))
 }
 
 /// Determines if a node should be checked based on its interval and current state.
 ///
-/// When degraded (soft failure), uses the shorter `retry_interval` for faster confirmation.
-/// Otherwise uses the normal `monitoring_interval`.
+/// When degraded by a failed check (soft failure), uses the shorter `retry_interval`
+/// for faster confirmation. Otherwise, including when a passing check degraded the
+/// node with a warning, uses the normal `monitoring_interval`.
 fn should_check_node(node: &Node, node_id: i64, last_check_times: &HashMap<i64, Instant>) -> bool {
     let now = Instant::now();
-    let interval = if node.status == NodeStatus::Degraded {
+    let interval = if node.status == NodeStatus::Degraded && node.consecutive_failures > 0 {
         node.retry_interval
     } else {
         node.monitoring_interval
@@ -228,7 +763,8 @@ fn should_record_status_change(prev: NodeStatus, new: NodeStatus) -> bool {
     prev != new
 }
 
-/// Process incoming configuration updates from the TUI.
+/// Process incoming configuration updates from the TUI, returning how many
+/// were processed.
 fn process_config_updates(
     config_rx: &mpsc::Receiver<NodeConfigUpdate>,
     db: &Database,
@@ -236,8 +772,10 @@ fn process_config_updates(
     last_check_times: &mut HashMap<i64, Instant>,
     previous_statuses: &mut HashMap<i64, NodeStatus>,
     last_status_change_times: &mut HashMap<i64, DateTime<Utc>>,
-) {
+) -> usize {
+    let mut processed = 0;
     while let Ok(config_update) = config_rx.try_recv() {
+        processed += 1;
         match config_update {
             NodeConfigUpdate::Add(node) => {
                 if !current_nodes.iter().any(|n| n.id == node.id) {
@@ -259,6 +797,8 @@ fn process_config_updates(
                     let last_check = node.last_check;
                     let response_time = node.response_time;
                     let consecutive_failures = node.consecutive_failures;
+                    let cert_expires_at = node.cert_expires_at;
+                    let ping_stats = node.ping_stats.take();
 
                     *node = updated_node;
                     // Preserve runtime state
@@ -266,6 +806,8 @@ fn process_config_updates(
                     node.last_check = last_check;
                     node.response_time = response_time;
                     node.consecutive_failures = consecutive_failures;
+                    node.cert_expires_at = cert_expires_at;
+                    node.ping_stats = ping_stats;
 
                     if let Some(node_id) = node.id {
                         last_check_times.remove(&node_id);
@@ -280,12 +822,13 @@ fn process_config_updates(
             }
         }
     }
+    processed
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::models::{MonitorDetail, DEFAULT_MAX_CHECK_ATTEMPTS};
+    use crate::models::{HookCommands, MonitorDetail, DEFAULT_MAX_CHECK_ATTEMPTS};
 
     fn make_node(status: NodeStatus, failures: u32, max_attempts: u32) -> Node {
         Node {
@@ -294,6 +837,8 @@ mod tests {
             detail: MonitorDetail::Http {
                 url: "https://example.com".to_string(),
                 expected_status: 200,
+                timeout: 30,
+                options: Default::default(),
             },
             status,
             last_check: None,
@@ -303,6 +848,12 @@ mod tests {
             consecutive_failures: failures,
             max_check_attempts: max_attempts,
             retry_interval: 15,
+            cert_expires_at: None,
+            ping_stats: None,
+            hooks: HookCommands::default(),
+            parents: Vec::new(),
+            group: None,
+            tags: Vec::new(),
         }
     }
 
@@ -402,6 +953,18 @@ mod tests {
         assert!(!should_check_node(&node, 1, &last_check_times));
     }
 
+    #[test]
+    fn test_should_check_warning_uses_monitoring_interval() {
+        // Degraded by a passing check with a warning, so there is nothing to confirm
+        let mut node = make_node(NodeStatus::Degraded, 0, 3);
+        node.retry_interval = 10;
+        node.monitoring_interval = 60;
+
+        let mut last_check_times = HashMap::new();
+        last_check_times.insert(1, Instant::now() - Duration::from_secs(11));
+        assert!(!should_check_node(&node, 1, &last_check_times));
+    }
+
     #[test]
     fn test_should_check_online_uses_monitoring_interval() {
         let mut node = make_node(NodeStatus::Online, 0, 3);
@@ -418,6 +981,45 @@ mod tests {
         assert!(should_check_node(&node, 1, &last_check_times));
     }
 
+    // -- scheduler helper tests --
+
+    #[test]
+    fn test_start_jitter_within_bounds() {
+        let max = Duration::from_millis(500);
+        for _ in 0..100 {
+            assert!(start_jitter(max) <= max);
+        }
+    }
+
+    #[test]
+    fn test_start_jitter_zero_max() {
+        assert_eq!(start_jitter(Duration::ZERO), Duration::ZERO);
+    }
+
+    #[tokio::test]
+    async fn test_deadline_exceeded_counts_as_failure() {
+        // The listener completes the TCP handshake but never answers the request
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let mut node = make_node(NodeStatus::Online, 0, 3);
+        node.detail = MonitorDetail::Http {
+            url: format!("http://127.0.0.1:{}", port),
+            expected_status: 200,
+            timeout: 30,
+            options: Default::default(),
+        };
+
+        let started = Instant::now();
+        let credentials = SharedCredentialStore::default();
+        let result = check_node_with_deadline(&node, &credentials, Duration::from_millis(200))
+            .await
+            .unwrap();
+
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert_eq!(result.status, NodeStatus::Offline);
+        assert!(result.details.unwrap().contains("deadline"));
+    }
+
     // -- should_record_status_change tests --
 
     #[test]
@@ -502,4 +1104,208 @@ mod tests {
         assert_eq!(s, NodeStatus::Online);
         assert_eq!(node.consecutive_failures, 0);
     }
+
+    // -- parent dependency tests --
+
+    fn make_child(parent: &Node) -> Node {
+        let mut child = make_node(NodeStatus::Online, 0, 1);
+        child.id = Some(2);
+        child.name = "Child".to_string();
+        child.parents = vec![parent.id.unwrap()];
+        child
+    }
+
+    fn failed_check(node_id: i64) -> CheckOutcome {
+        CheckOutcome {
+            node_id,
+            result: Ok(MonitoringResult {
+                id: None,
+                node_id,
+                timestamp: Utc::now(),
+                status: NodeStatus::Offline,
+                response_time: None,
+                details: Some("Connection refused".to_string()),
+                cert_expires_at: None,
+                ping_stats: None,
+            }),
+        }
+    }
+
+    #[test]
+    fn test_parent_state() {
+        let mut parent = make_node(NodeStatus::Online, 0, 3);
+        let child = make_child(&parent);
+        let mut in_flight = HashSet::new();
+        let state = |parent: &Node, in_flight: &HashSet<i64>| {
+            parent_state(&child, &[parent.clone(), child.clone()], in_flight)
+        };
+
+        assert_eq!(state(&parent, &in_flight), ParentState::Up);
+        // A degraded check that passed says nothing about reachability
+        parent.status = NodeStatus::Degraded;
+        assert_eq!(state(&parent, &in_flight), ParentState::Up);
+        parent.consecutive_failures = 1;
+        assert_eq!(state(&parent, &in_flight), ParentState::Pending);
+
+        parent.status = NodeStatus::Online;
+        parent.consecutive_failures = 0;
+        in_flight.insert(1);
+        assert_eq!(state(&parent, &in_flight), ParentState::Pending);
+
+        for status in [NodeStatus::Offline, NodeStatus::Unreachable] {
+            parent.status = status;
+            assert_eq!(
+                state(&parent, &in_flight),
+                ParentState::Down("Test".to_string())
+            );
+        }
+        assert_eq!(
+            parent_state(&parent, &[parent.clone()], &in_flight),
+            ParentState::Up
+        );
+
+        // A node listed as its own parent doesn't wait on itself
+        let mut own_parent = make_node(NodeStatus::Degraded, 1, 3);
+        own_parent.parents = vec![1];
+        assert_eq!(
+            parent_state(&own_parent, &[own_parent.clone()], &in_flight),
+            ParentState::Up
+        );
+    }
+
+    #[tokio::test]
+    async fn test_child_of_down_parent_is_unreachable() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let db = Database::new(dir.path().join("test.db")).unwrap();
+        let mut parent = make_node(NodeStatus::Offline, 3, 3);
+        parent.name = "Switch".to_string();
+        parent.id = Some(db.add_node(&parent).unwrap());
+        let mut child = make_child(&parent);
+        let child_id = db.add_node(&child).unwrap();
+        child.id = Some(child_id);
+        let mut scheduler = Scheduler::new(
+            db.clone(),
+            vec![parent, child],
+            EngineConfig::default(),
+            SharedCredentialStore::default(),
+        );
+        let (update_tx, _update_rx) = mpsc::channel();
+
+        scheduler.apply_outcome(failed_check(child_id), &update_tx);
+        assert_eq!(scheduler.current_nodes[1].status, NodeStatus::Unreachable);
+        let change = db.get_latest_status_change(child_id).unwrap().unwrap();
+        assert_eq!(change.from_status, NodeStatus::Online);
+        assert_eq!(change.to_status, NodeStatus::Unreachable);
+
+        // Once the parent is back, a failure is the child's own
+        scheduler.current_nodes[0].status = NodeStatus::Online;
+        scheduler.current_nodes[0].consecutive_failures = 0;
+        scheduler.apply_outcome(failed_check(child_id), &update_tx);
+        assert_eq!(scheduler.current_nodes[1].status, NodeStatus::Offline);
+
+        // A node already confirmed offline stays offline when its parent goes down
+        scheduler.current_nodes[0].status = NodeStatus::Offline;
+        scheduler.apply_outcome(failed_check(child_id), &update_tx);
+        assert_eq!(scheduler.current_nodes[1].status, NodeStatus::Offline);
+    }
+
+    #[tokio::test]
+    async fn test_child_waits_for_failing_parent() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let db = Database::new(dir.path().join("test.db")).unwrap();
+        let parent = make_node(NodeStatus::Degraded, 1, 3);
+        let child = make_child(&parent);
+        let child_id = child.id.unwrap();
+        let mut scheduler = Scheduler::new(
+            db,
+            vec![parent, child],
+            EngineConfig::default(),
+            SharedCredentialStore::default(),
+        );
+        scheduler
+            .last_check_times
+            .insert(1, Instant::now() - Duration::from_secs(60));
+        let (update_tx, _update_rx) = mpsc::channel();
+
+        // The child's failures would confirm it offline, but the parent is checked first
+        scheduler.apply_outcome(failed_check(child_id), &update_tx);
+        assert_eq!(scheduler.current_nodes[1].status, NodeStatus::Degraded);
+        assert!(!scheduler.last_check_times.contains_key(&1));
+        assert!(!scheduler.start_times.contains_key(&1));
+    }
+
+    #[tokio::test]
+    async fn test_check_that_fails_to_run_counts_as_failure() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let db = Database::new(dir.path().join("test.db")).unwrap();
+        let mut node = make_node(NodeStatus::Online, 0, 2);
+        let node_id = db.add_node(&node).unwrap();
+        node.id = Some(node_id);
+        let mut scheduler = Scheduler::new(
+            db.clone(),
+            vec![node],
+            EngineConfig::default(),
+            SharedCredentialStore::default(),
+        );
+        let (update_tx, update_rx) = mpsc::channel();
+        let locked = || CheckOutcome {
+            node_id,
+            result: Err(anyhow::anyhow!("The credential store is locked")),
+        };
+
+        scheduler.apply_outcome(locked(), &update_tx);
+        let updated = update_rx.try_recv().unwrap();
+        assert_eq!(updated.status, NodeStatus::Degraded);
+        assert_eq!(updated.consecutive_failures, 1);
+
+        scheduler.apply_outcome(locked(), &update_tx);
+        assert_eq!(scheduler.current_nodes[0].status, NodeStatus::Offline);
+        let result = db.get_latest_monitoring_result(node_id).unwrap().unwrap();
+        assert_eq!(
+            result.details.as_deref(),
+            Some("The credential store is locked")
+        );
+        // The metrics still tell checks that could not run from failed ones
+        let text = scheduler.metrics.render();
+        let errors = text
+            .lines()
+            .find(|line| {
+                line.starts_with("net_monitor_node_checks_total") && line.contains("\"error\"")
+            })
+            .unwrap();
+        assert!(errors.ends_with(" 2"), "{}", errors);
+    }
+
+    #[test]
+    fn test_config_update_keeps_runtime_state() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let db = Database::new(dir.path().join("test.db")).unwrap();
+        let mut node = make_node(NodeStatus::Degraded, 1, 3);
+        node.response_time = Some(42);
+        node.ping_stats = Some(crate::models::PingStats::from_round_trips(
+            crate::models::PingMethod::Icmp,
+            3,
+            &[Duration::from_millis(10), Duration::from_millis(12)],
+        ));
+        let mut current_nodes = vec![node.clone()];
+
+        let mut edited = make_node(NodeStatus::Online, 0, 3);
+        edited.name = "Renamed".to_string();
+        let (config_tx, config_rx) = mpsc::channel();
+        config_tx.send(NodeConfigUpdate::Update(edited)).unwrap();
+        process_config_updates(
+            &config_rx,
+            &db,
+            &mut current_nodes,
+            &mut HashMap::new(),
+            &mut HashMap::new(),
+            &mut HashMap::new(),
+        );
+
+        assert_eq!(current_nodes[0].name, "Renamed");
+        assert_eq!(current_nodes[0].status, NodeStatus::Degraded);
+        assert_eq!(current_nodes[0].response_time, Some(42));
+        assert_eq!(current_nodes[0].consecutive_failures, 1);
+        assert_eq!(current_nodes[0].ping_stats, node.ping_stats);
+    }
 }
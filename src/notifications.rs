@@ -0,0 +1,614 @@
+//! Notifications about confirmed status changes.
+//!
+//! The monitoring engine hands every status change it records to a `Notifier`,
+//! which uses the `notifications` settings to decide whether the change is worth
+//! announcing and then delivers it to each configured channel: webhooks, which
+//! receive the change as a JSON POST, and email through an SMTP server. Changes
+//! arriving within the email batch window share one email, so a switch taking
+//! a rack of nodes down sends one message rather than dozens.
+//!
+//! Each delivery runs in its own task, so a slow or failing channel never holds
+//! up checks. Failed deliveries are retried with exponential backoff and every
+//! attempt is recorded in the database. Retries still waiting when monitoring
+//! stops are dropped.
+
+use crate::config::{EmailSettings, NotificationSettings, SmtpSecurity, WebhookSettings};
+use crate::credentials::{SharedCredentialStore, SshCredential};
+use crate::database::Database;
+use crate::models::{format_duration, Node, NodeStatus, NotificationDelivery, StatusChange};
+use crate::monitor::{describe_error, verified_tls_config};
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use lettre::message::header::ContentType;
+use lettre::message::Message;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::transport::smtp::client::{Tls, TlsParameters};
+use lettre::{AsyncSmtpTransport, AsyncTransport, Tokio1Executor};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// How long a webhook has to accept a notification
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long each step of an SMTP conversation may take
+const SMTP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Longest wait between two delivery attempts
+pub const MAX_RETRY_DELAY: Duration = Duration::from_secs(300);
+
+/// Kind of status change a notification announces
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationEvent {
+    /// The node was confirmed offline
+    Offline,
+    /// The node started failing checks, or passed them with a warning
+    Degraded,
+    /// The node is back online
+    Recovery,
+}
+
+impl NotificationEvent {
+    /// Short label used in email subjects
+    pub fn tag(&self) -> &'static str {
+        match self {
+            NotificationEvent::Offline => "DOWN",
+            NotificationEvent::Degraded => "DEGRADED",
+            NotificationEvent::Recovery => "UP",
+        }
+    }
+
+    /// Returns the event announced for `change` under `settings`, or `None`
+    /// when nobody asked to hear about it.
+    ///
+    /// A recovery is only announced when the change that left the node
+    /// offline or degraded would have been announced too.
+    pub fn for_change(settings: &NotificationSettings, change: &StatusChange) -> Option<Self> {
+        let announced = |status| match status {
+            NodeStatus::Offline => settings.on_offline,
+            NodeStatus::Degraded => settings.on_degraded,
+            NodeStatus::Online | NodeStatus::Unreachable => false,
+        };
+
+        if change.is_recovery() {
+            (settings.on_recovery && announced(change.from_status))
+                .then_some(NotificationEvent::Recovery)
+        } else if change.is_degradation() && announced(change.to_status) {
+            match change.to_status {
+                NodeStatus::Offline => Some(NotificationEvent::Offline),
+                _ => Some(NotificationEvent::Degraded),
+            }
+        } else {
+            None
+        }
+    }
+}
+
+/// The node a notification is about
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NotifiedNode {
+    pub id: i64,
+    pub name: String,
+    /// Monitor type, such as `HTTP`
+    #[serde(rename = "type")]
+    pub monitor_type: String,
+    /// URL, host or address being monitored
+    pub target: String,
+}
+
+/// What a channel is told about a status change; the body of a webhook request
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Notification {
+    pub event: NotificationEvent,
+    pub node: NotifiedNode,
+    pub from_status: NodeStatus,
+    pub to_status: NodeStatus,
+    pub changed_at: DateTime<Utc>,
+    /// Milliseconds the node spent in `from_status`, when known
+    pub duration_ms: Option<i64>,
+    /// Consecutive failed checks behind the change; 0 when the last check passed
+    pub failed_checks: u32,
+    /// Details of the check that changed the status
+    pub details: Option<String>,
+}
+
+impl Notification {
+    pub fn new(
+        event: NotificationEvent,
+        node: &Node,
+        change: &StatusChange,
+        details: Option<&str>,
+    ) -> Self {
+        Self {
+            event,
+            node: NotifiedNode {
+                id: change.node_id,
+                name: node.name.clone(),
+                monitor_type: node.detail.to_string(),
+                target: node.detail.get_connection_target(),
+            },
+            from_status: change.from_status,
+            to_status: change.to_status,
+            changed_at: change.changed_at,
+            duration_ms: change.duration_ms,
+            failed_checks: node.consecutive_failures,
+            details: details.map(str::to_string),
+        }
+    }
+}
+
+/// Why a delivery attempt failed
+struct DeliveryError {
+    response_status: Option<u16>,
+    message: String,
+    /// Whether trying again later might succeed
+    retryable: bool,
+}
+
+/// A notification waiting to go out in the next email
+struct Queued {
+    notification: Arc<Notification>,
+    status_change_id: Option<i64>,
+}
+
+/// Delivers notifications about status changes to the configured channels
+#[derive(Clone)]
+pub struct Notifier {
+    settings: Arc<NotificationSettings>,
+    db: Database,
+    client: reqwest::Client,
+    credentials: SharedCredentialStore,
+    /// Notifications collected for the next email
+    email_batch: Arc<Mutex<Vec<Queued>>>,
+}
+
+impl Notifier {
+    /// Creates a notifier sending to the channels in `settings` and recording
+    /// delivery attempts in `db`. The SMTP login is looked up in `credentials`
+    /// each time an email is sent.
+    pub fn new(
+        settings: &NotificationSettings,
+        db: Database,
+        credentials: SharedCredentialStore,
+    ) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .use_preconfigured_tls(verified_tls_config()?)
+            .timeout(WEBHOOK_TIMEOUT)
+            .user_agent(concat!("net-monitor/", env!("CARGO_PKG_VERSION")))
+            .build()?;
+
+        Ok(Self {
+            settings: Arc::new(settings.clone()),
+            db,
+            client,
+            credentials,
+            email_batch: Arc::default(),
+        })
+    }
+
+    /// Announces `change` of `node` on every channel, if the settings ask for it.
+    ///
+    /// Deliveries run in the background on the current tokio runtime. Emails
+    /// wait for the batch window so that nodes failing together share one.
+    pub fn notify(&self, node: &Node, change: &StatusChange, details: Option<&str>) {
+        let Some(event) = NotificationEvent::for_change(&self.settings, change) else {
+            return;
+        };
+        if self.settings.webhooks.is_empty() && self.settings.email.is_none() {
+            return;
+        }
+
+        info!("Notifying that {} went {}", node.name, change.description());
+        let notification = Arc::new(Notification::new(event, node, change, details));
+        for webhook in &self.settings.webhooks {
+            let notifier = self.clone();
+            let webhook = webhook.clone();
+            let notification = Arc::clone(&notification);
+            let status_change_id = change.id;
+            tokio::spawn(async move {
+                notifier
+                    .deliver_webhook(&webhook, &notification, status_change_id)
+                    .await
+            });
+        }
+
+        if let Some(email) = &self.settings.email {
+            let mut batch = lock(&self.email_batch);
+            batch.push(Queued {
+                notification,
+                status_change_id: change.id,
+            });
+            // The first notification of a batch sends it once the window closes
+            if batch.len() == 1 {
+                let notifier = self.clone();
+                let window = Duration::from_secs(email.batch_window_secs);
+                tokio::spawn(async move {
+                    tokio::time::sleep(window).await;
+                    let batch = std::mem::take(&mut *lock(&notifier.email_batch));
+                    notifier.deliver_email(&batch).await
+                });
+            }
+        }
+    }
+
+    /// Posts `notification` to `webhook` until it is accepted, fails in a way
+    /// retrying cannot fix, or runs out of attempts, recording each attempt.
+    /// Returns whether the webhook accepted it.
+    pub async fn deliver_webhook(
+        &self,
+        webhook: &WebhookSettings,
+        notification: &Notification,
+        status_change_id: Option<i64>,
+    ) -> bool {
+        let queued = [(notification.node.id, status_change_id)];
+        // The rest of the URL often holds a secret token, so only the host is
+        // recorded
+        let host = webhook_host(&webhook.url);
+        self.deliver(
+            &format!("webhook:{}", host),
+            &format!("webhook {}", host),
+            &queued,
+            &notification.node.name,
+            || self.post_webhook(webhook, notification),
+        )
+        .await
+    }
+
+    /// Sends `batch` as one email, retrying like webhooks
+    async fn deliver_email(&self, batch: &[Queued]) -> bool {
+        let Some(email) = &self.settings.email else {
+            return false;
+        };
+        let queued: Vec<_> = batch
+            .iter()
+            .map(|q| (q.notification.node.id, q.status_change_id))
+            .collect();
+        let notifications: Vec<_> = batch.iter().map(|q| q.notification.as_ref()).collect();
+        let names = notifications
+            .iter()
+            .map(|n| n.node.name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let recipients = email.to.join(",");
+        let channel = format!("email to {}", recipients);
+
+        let message = match email_message(email, &notifications) {
+            Ok(message) => message,
+            Err(e) => {
+                warn!("Cannot build the notification email: {}", e);
+                return false;
+            }
+        };
+        self.deliver(
+            &format!("mailto:{}", recipients),
+            &channel,
+            &queued,
+            &names,
+            || self.send_email(email, &message),
+        )
+        .await
+    }
+
+    /// Makes attempts with `send` until one succeeds, fails in a way retrying
+    /// cannot fix, or the attempts run out, backing off between them. Each
+    /// attempt is recorded as going to `channel` for every
+    /// `(node_id, status_change_id)` in `queued`; logs name the channel `label`
+    /// and what it was told `about`.
+    async fn deliver<F, Fut>(
+        &self,
+        channel: &str,
+        label: &str,
+        queued: &[(i64, Option<i64>)],
+        about: &str,
+        mut send: F,
+    ) -> bool
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<u16, DeliveryError>>,
+    {
+        let max_attempts = self.settings.max_attempts.max(1);
+        let backoff = Duration::from_secs(self.settings.retry_backoff_secs);
+
+        for attempt in 1..=max_attempts {
+            let outcome = send().await;
+
+            let attempted_at = Utc::now();
+            for (node_id, status_change_id) in queued {
+                let delivery = NotificationDelivery {
+                    id: None,
+                    status_change_id: *status_change_id,
+                    node_id: *node_id,
+                    channel: channel.to_string(),
+                    attempt,
+                    attempted_at,
+                    success: outcome.is_ok(),
+                    response_status: match &outcome {
+                        Ok(status) => Some(*status),
+                        Err(e) => e.response_status,
+                    },
+                    error: outcome.as_ref().err().map(|e| e.message.clone()),
+                };
+                if let Err(e) = self.db.add_notification_delivery(&delivery) {
+                    warn!("Failed to record notification delivery: {}", e);
+                }
+            }
+
+            match outcome {
+                Ok(_) => {
+                    info!("Notified {} about {} (attempt {})", label, about, attempt);
+                    return true;
+                }
+                Err(e) if !e.retryable => {
+                    warn!("Notifying {} about {} failed: {}", label, about, e.message);
+                    return false;
+                }
+                Err(e) if attempt < max_attempts => {
+                    let delay = retry_delay(backoff, attempt);
+                    warn!(
+                        "Notifying {} failed (attempt {} of {}), retrying in {}s: {}",
+                        label,
+                        attempt,
+                        max_attempts,
+                        delay.as_secs(),
+                        e.message
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                Err(e) => warn!(
+                    "Giving up on notifying {} about {} after {} attempt(s): {}",
+                    label, about, max_attempts, e.message
+                ),
+            }
+        }
+        false
+    }
+
+    /// Makes one webhook request, returning the response status on success
+    async fn post_webhook(
+        &self,
+        webhook: &WebhookSettings,
+        notification: &Notification,
+    ) -> Result<u16, DeliveryError> {
+        let mut request = self.client.post(&webhook.url).json(notification);
+        for header in &webhook.headers {
+            request = request.header(&header.name, &header.value);
+        }
+
+        // The URL often holds a secret token, so it is kept out of the message
+        let response = request.send().await.map_err(|e| DeliveryError {
+            response_status: None,
+            message: describe_error(&e.without_url()),
+            retryable: true,
+        })?;
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(status.as_u16());
+        }
+        Err(DeliveryError {
+            response_status: Some(status.as_u16()),
+            message: format!("Webhook responded with status {}", status),
+            // Other client errors mean the request itself is wrong
+            retryable: status.is_server_error()
+                || status == reqwest::StatusCode::REQUEST_TIMEOUT
+                || status == reqwest::StatusCode::TOO_MANY_REQUESTS,
+        })
+    }
+
+    /// Sends one email, returning the SMTP reply code on success
+    async fn send_email(
+        &self,
+        email: &EmailSettings,
+        message: &Message,
+    ) -> Result<u16, DeliveryError> {
+        let transport = smtp_transport(email, &self.credentials).map_err(|e| DeliveryError {
+            response_status: None,
+            message: e.to_string(),
+            retryable: false,
+        })?;
+
+        match transport.send(message.clone()).await {
+            Ok(response) => Ok(response.code().into()),
+            Err(e) => Err(DeliveryError {
+                response_status: e.status().map(u16::from),
+                message: describe_error(&e),
+                retryable: !e.is_permanent(),
+            }),
+        }
+    }
+}
+
+/// Connects to the SMTP server of `email`, logging in with its credential
+fn smtp_transport(
+    email: &EmailSettings,
+    credentials: &SharedCredentialStore,
+) -> Result<AsyncSmtpTransport<Tokio1Executor>> {
+    let tls = match email.security {
+        SmtpSecurity::Starttls => Tls::Required(TlsParameters::new_rustls(email.host.clone())?),
+        SmtpSecurity::Tls => Tls::Wrapper(TlsParameters::new_rustls(email.host.clone())?),
+        SmtpSecurity::None => Tls::None,
+    };
+    let mut builder = AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&email.host)
+        .port(email.port())
+        .tls(tls)
+        .timeout(Some(SMTP_TIMEOUT));
+
+    if let Some(key) = &email.credential {
+        let credential = credentials
+            .find(key)
+            .map_err(|e| anyhow!("Cannot log in to {}: {}", email.host, e))?;
+        let SshCredential::Password { username, password } = &credential else {
+            return Err(anyhow!(
+                "Credential '{}' must be a user name and password to log in to {}",
+                key,
+                email.host
+            ));
+        };
+        builder = builder.credentials(Credentials::new(
+            username.clone(),
+            password.as_str().to_string(),
+        ));
+    }
+    Ok(builder.build())
+}
+
+/// Builds the email announcing `notifications`
+fn email_message(email: &EmailSettings, notifications: &[&Notification]) -> Result<Message> {
+    let mut builder = Message::builder()
+        .from(email.from.parse()?)
+        .subject(email_subject(notifications))
+        .header(ContentType::TEXT_PLAIN);
+    for address in &email.to {
+        builder = builder.to(address.parse()?);
+    }
+    Ok(builder.body(email_body(notifications))?)
+}
+
+/// Subject line of an email announcing `notifications`, such as
+/// `[DOWN] Proxmox (HTTP) after 3 attempts`, or `[DOWN] 2 nodes: NAS, Router`
+/// when several nodes changed status together
+pub fn email_subject(notifications: &[&Notification]) -> String {
+    match notifications {
+        [] => String::new(),
+        [notification] => {
+            let node = &notification.node;
+            let tag = notification.event.tag();
+            match notification.event {
+                NotificationEvent::Offline => format!(
+                    "[{}] {} ({}) after {} attempt{}",
+                    tag,
+                    node.name,
+                    node.monitor_type,
+                    notification.failed_checks,
+                    if notification.failed_checks == 1 {
+                        ""
+                    } else {
+                        "s"
+                    }
+                ),
+                NotificationEvent::Recovery => match notification.duration_ms {
+                    Some(duration_ms) => format!(
+                        "[{}] {} ({}) after {} {}",
+                        tag,
+                        node.name,
+                        node.monitor_type,
+                        format_duration(duration_ms),
+                        notification.from_status.to_string().to_lowercase()
+                    ),
+                    None => format!("[{}] {} ({})", tag, node.name, node.monitor_type),
+                },
+                NotificationEvent::Degraded => {
+                    format!("[{}] {} ({})", tag, node.name, node.monitor_type)
+                }
+            }
+        }
+        _ => {
+            let names = notifications
+                .iter()
+                .map(|n| n.node.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ");
+            let events = [
+                NotificationEvent::Offline,
+                NotificationEvent::Degraded,
+                NotificationEvent::Recovery,
+            ];
+            let counts: Vec<_> = events
+                .iter()
+                .map(|event| {
+                    let count = notifications.iter().filter(|n| n.event == *event).count();
+                    (event, count)
+                })
+                .filter(|(_, count)| *count > 0)
+                .collect();
+            match counts.as_slice() {
+                [(event, count)] => format!("[{}] {} nodes: {}", event.tag(), count, names),
+                _ => {
+                    let summary = counts
+                        .iter()
+                        .map(|(event, count)| format!("{} {}", count, event.tag().to_lowercase()))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    format!("[ALERT] {}: {}", summary, names)
+                }
+            }
+        }
+    }
+}
+
+/// Plain text body of an email announcing `notifications`, one paragraph each
+pub fn email_body(notifications: &[&Notification]) -> String {
+    notifications
+        .iter()
+        .map(|notification| {
+            let node = &notification.node;
+            let mut lines = vec![
+                format!(
+                    "{}: {} ({})",
+                    notification.event.tag(),
+                    node.name,
+                    node.monitor_type
+                ),
+                format!("Target:  {}", node.target),
+                format!(
+                    "Status:  {} -> {}",
+                    notification.from_status, notification.to_status
+                ),
+            ];
+            let mut changed = format!(
+                "Changed: {}",
+                notification.changed_at.format("%Y-%m-%d %H:%M:%S UTC")
+            );
+            if let Some(duration_ms) = notification.duration_ms {
+                changed.push_str(&format!(
+                    " after {} {}",
+                    format_duration(duration_ms),
+                    notification.from_status.to_string().to_lowercase()
+                ));
+            }
+            lines.push(changed);
+            if notification.failed_checks > 0 {
+                lines.push(format!(
+                    "Failed:  {} check(s) in a row",
+                    notification.failed_checks
+                ));
+            }
+            if let Some(details) = &notification.details {
+                lines.push(format!("Details: {}", details));
+            }
+            lines.join("\n")
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+        + "\n"
+}
+
+/// Delay before retrying after failed attempt number `attempt`: `backoff`,
+/// doubling with each further attempt, up to `MAX_RETRY_DELAY`
+pub fn retry_delay(backoff: Duration, attempt: u32) -> Duration {
+    let factor = 1u32
+        .checked_shl(attempt.saturating_sub(1))
+        .unwrap_or(u32::MAX);
+    backoff
+        .checked_mul(factor)
+        .unwrap_or(MAX_RETRY_DELAY)
+        .min(MAX_RETRY_DELAY)
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Host of a webhook URL, for logging without the secret tokens many
+/// services put in the path
+fn webhook_host(url: &str) -> String {
+    reqwest::Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+        .unwrap_or_else(|| "(invalid URL)".to_string())
+}
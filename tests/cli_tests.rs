@@ -0,0 +1,1481 @@
+mod common;
+
+use clap::Parser;
+use common::{fixtures, TestDatabase};
+use net_monitor::cli::{self, CredentialCommand, NodeCommand};
+use net_monitor::config::{AppConfig, CredentialBackend, NodeDefaults};
+use net_monitor::credentials::{
+    CredentialStore, FileCredentialStore, KeyringCredentialStore, SharedCredentialStore,
+    SshCredential,
+};
+use net_monitor::database::Database;
+use net_monitor::models::{
+    DnsProtocol, DnsRecordType, HookCommands, HttpMethod, MaintenanceScope, MaintenanceWindow,
+    MonitorDetail, NodeImport, NodeStatus, NotificationDelivery, PingThresholds, StatusChange,
+    StatusRange, TlsMode, UdpPayload, UdpResponseMatch,
+};
+use net_monitor::paths::{AppPaths, PathOverrides};
+use std::net::TcpListener;
+use std::path::Path;
+use std::process::ExitCode;
+use tempfile::TempDir;
+
+#[derive(Parser)]
+struct TestCli {
+    #[command(subcommand)]
+    command: NodeCommand,
+}
+
+/// Parses `args` as a node command and runs it, returning the exit code and output
+fn run(db: &Database, args: &[&str]) -> anyhow::Result<(ExitCode, String)> {
+    run_with_defaults(db, &NodeDefaults::default(), args)
+}
+
+/// Like `run`, with `defaults` standing in for the config file's node defaults
+fn run_with_defaults(
+    db: &Database,
+    defaults: &NodeDefaults,
+    args: &[&str],
+) -> anyhow::Result<(ExitCode, String)> {
+    run_with(db, defaults, &SharedCredentialStore::default(), args)
+}
+
+/// Like `run`, resolving credential names in `credentials`
+fn run_with_credentials(
+    db: &Database,
+    credentials: &SharedCredentialStore,
+    args: &[&str],
+) -> anyhow::Result<(ExitCode, String)> {
+    run_with(db, &NodeDefaults::default(), credentials, args)
+}
+
+fn run_with(
+    db: &Database,
+    defaults: &NodeDefaults,
+    credentials: &SharedCredentialStore,
+    args: &[&str],
+) -> anyhow::Result<(ExitCode, String)> {
+    let cli = TestCli::try_parse_from(std::iter::once("net-monitor").chain(args.iter().copied()))?;
+    let mut out = Vec::new();
+    let code = cli::run(db, defaults, credentials, cli.command, &mut out)?;
+    Ok((code, String::from_utf8(out).unwrap()))
+}
+
+/// An unlocked file credential store holding a single password credential named "Router"
+fn router_credentials(dir: &TempDir) -> (SharedCredentialStore, String) {
+    let mut store =
+        FileCredentialStore::with_path("master".to_string(), &dir.path().join("credentials.enc"))
+            .unwrap();
+    let id = store
+        .store_credential(
+            "Router".to_string(),
+            None,
+            SshCredential::Password {
+                username: "admin".to_string(),
+                password: "secret".into(),
+            },
+        )
+        .unwrap();
+    let credentials = SharedCredentialStore::default();
+    credentials.set(Some(Box::new(store)));
+    (credentials, id)
+}
+
+// ========== Add / Edit / Remove Tests ==========
+
+#[test]
+fn test_add_http_node() {
+    let test_db = TestDatabase::new();
+
+    let (code, output) = run(
+        &test_db.db,
+        &[
+            "add",
+            "http",
+            "Web",
+            "https://example.com",
+            "--expected-status",
+            "204",
+            "--interval",
+            "30",
+        ],
+    )
+    .unwrap();
+
+    assert_eq!(code, ExitCode::SUCCESS);
+    assert!(output.contains("Added node 'Web'"));
+
+    let nodes = test_db.db.get_all_nodes().unwrap();
+    assert_eq!(nodes.len(), 1);
+    assert_eq!(nodes[0].monitoring_interval, 30);
+    assert_eq!(
+        nodes[0].detail,
+        MonitorDetail::Http {
+            url: "https://example.com".to_string(),
+            expected_status: 204,
+            timeout: 30,
+            options: Default::default(),
+        }
+    );
+}
+
+#[test]
+fn test_add_uses_defaults() {
+    let test_db = TestDatabase::new();
+
+    run(&test_db.db, &["add", "tcp", "SSH", "10.0.0.1", "22"]).unwrap();
+    run(&test_db.db, &["add", "ping", "Router", "10.0.0.254"]).unwrap();
+
+    let nodes = test_db.db.get_all_nodes().unwrap();
+    assert_eq!(
+        nodes[0].detail,
+        MonitorDetail::Tcp {
+            host: "10.0.0.1".to_string(),
+            port: 22,
+            timeout: 5,
+        }
+    );
+    assert_eq!(
+        nodes[1].detail,
+        MonitorDetail::Ping {
+            host: "10.0.0.254".to_string(),
+            count: 4,
+            timeout: 5,
+            fallback_port: 80,
+            thresholds: Default::default(),
+        }
+    );
+    assert_eq!(
+        nodes[1].monitoring_interval,
+        net_monitor::config::DEFAULT_MONITORING_INTERVAL
+    );
+    assert_eq!(
+        nodes[1].max_check_attempts,
+        net_monitor::models::DEFAULT_MAX_CHECK_ATTEMPTS
+    );
+}
+
+#[test]
+fn test_add_uses_configured_defaults() {
+    let test_db = TestDatabase::new();
+    let defaults = NodeDefaults {
+        monitoring_interval: 60,
+        max_check_attempts: 5,
+        retry_interval: 10,
+        http_timeout: 8,
+    };
+
+    run_with_defaults(
+        &test_db.db,
+        &defaults,
+        &["add", "http", "Web", "https://example.com"],
+    )
+    .unwrap();
+    run_with_defaults(
+        &test_db.db,
+        &defaults,
+        &[
+            "add",
+            "http",
+            "API",
+            "https://api.example.com",
+            "--timeout",
+            "3",
+            "--interval",
+            "15",
+        ],
+    )
+    .unwrap();
+
+    let nodes = test_db.db.get_all_nodes().unwrap();
+    assert_eq!(nodes[0].monitoring_interval, 60);
+    assert_eq!(nodes[0].max_check_attempts, 5);
+    assert_eq!(nodes[0].retry_interval, 10);
+    assert!(matches!(
+        nodes[0].detail,
+        MonitorDetail::Http { timeout: 8, .. }
+    ));
+    assert_eq!(nodes[1].monitoring_interval, 15);
+    assert!(matches!(
+        nodes[1].detail,
+        MonitorDetail::Http { timeout: 3, .. }
+    ));
+}
+
+#[test]
+fn test_add_rejects_invalid_arguments() {
+    let test_db = TestDatabase::new();
+
+    assert!(run(&test_db.db, &["add", "tcp", "SSH", "10.0.0.1", "70000"]).is_err());
+    assert!(run(
+        &test_db.db,
+        &["add", "ping", "Router", "10.0.0.1", "--interval", "0"]
+    )
+    .is_err());
+    assert!(run(&test_db.db, &["add", "ping", "", "10.0.0.1"]).is_err());
+    assert!(test_db.db.get_all_nodes().unwrap().is_empty());
+}
+
+#[test]
+fn test_edit_node() {
+    let test_db = TestDatabase::new();
+    run(&test_db.db, &["add", "tcp", "SSH", "10.0.0.1", "22"]).unwrap();
+
+    run(
+        &test_db.db,
+        &[
+            "edit",
+            "SSH",
+            "--name",
+            "SSH alt",
+            "--port",
+            "2222",
+            "--max-check-attempts",
+            "5",
+        ],
+    )
+    .unwrap();
+
+    let node = cli::find_node(&test_db.db, "SSH alt").unwrap();
+    assert_eq!(node.max_check_attempts, 5);
+    assert_eq!(
+        node.detail,
+        MonitorDetail::Tcp {
+            host: "10.0.0.1".to_string(),
+            port: 2222,
+            timeout: 5,
+        }
+    );
+}
+
+#[test]
+fn test_edit_rejects_options_for_other_monitor_types() {
+    let test_db = TestDatabase::new();
+    run(&test_db.db, &["add", "http", "Web", "https://example.com"]).unwrap();
+
+    let err = run(&test_db.db, &["edit", "Web", "--port", "8080"]).unwrap_err();
+    assert!(err.to_string().contains("--port"));
+
+    run(&test_db.db, &["add", "tcp", "DB", "10.0.0.2", "5432"]).unwrap();
+    let err = run(&test_db.db, &["edit", "DB", "--command", "uptime"]).unwrap_err();
+    assert_eq!(err.to_string(), "--command does not apply to TCP nodes");
+    let err = run(&test_db.db, &["edit", "Web", "--credential", "Router"]).unwrap_err();
+    assert_eq!(err.to_string(), "--credential does not apply to HTTP nodes");
+}
+
+#[test]
+fn test_add_and_edit_http_options() {
+    let test_db = TestDatabase::new();
+
+    run(
+        &test_db.db,
+        &[
+            "add",
+            "http",
+            "API",
+            "https://api.example.com/health",
+            "--method",
+            "post",
+            "--header",
+            "Authorization: Bearer abc",
+            "--header",
+            "Accept: application/json",
+            "--body",
+            "{}",
+            "--accept-status",
+            "200-299",
+            "--max-redirects",
+            "0",
+            "--contains",
+            "healthy",
+            "--json-pointer",
+            "/ok",
+            "--json-value",
+            "true",
+        ],
+    )
+    .unwrap();
+
+    let node = cli::find_node(&test_db.db, "API").unwrap();
+    let MonitorDetail::Http { options, .. } = &node.detail else {
+        panic!("Expected HTTP detail");
+    };
+    assert_eq!(options.method, HttpMethod::Post);
+    assert_eq!(options.headers.len(), 2);
+    assert_eq!(options.headers[1].to_string(), "Accept: application/json");
+    assert_eq!(options.body.as_deref(), Some("{}"));
+    assert_eq!(
+        StatusRange::format_list(&options.accepted_statuses),
+        "200-299"
+    );
+    assert_eq!(options.max_redirects, 0);
+    assert_eq!(options.assertions.contains.as_deref(), Some("healthy"));
+    let json = options.assertions.json.as_ref().unwrap();
+    assert_eq!(
+        (json.pointer.as_str(), &json.value),
+        ("/ok", &serde_json::json!(true))
+    );
+
+    // Only the options given change; empty values remove them
+    run(
+        &test_db.db,
+        &[
+            "edit",
+            "API",
+            "--header",
+            "",
+            "--body",
+            "",
+            "--contains",
+            "",
+            "--json-value",
+            "up",
+            "--max-body-size",
+            "1024",
+        ],
+    )
+    .unwrap();
+
+    let node = cli::find_node(&test_db.db, "API").unwrap();
+    let MonitorDetail::Http { options, .. } = &node.detail else {
+        panic!("Expected HTTP detail");
+    };
+    assert_eq!(options.method, HttpMethod::Post);
+    assert!(options.headers.is_empty());
+    assert_eq!(options.body, None);
+    assert_eq!(options.assertions.contains, None);
+    let json = options.assertions.json.as_ref().unwrap();
+    assert_eq!(
+        (json.pointer.as_str(), &json.value),
+        ("/ok", &serde_json::json!("up"))
+    );
+    assert_eq!(options.assertions.max_body_bytes, Some(1024));
+
+    run(&test_db.db, &["edit", "API", "--json-pointer", ""]).unwrap();
+    let node = cli::find_node(&test_db.db, "API").unwrap();
+    assert!(matches!(
+        &node.detail,
+        MonitorDetail::Http { options, .. } if options.assertions.json.is_none()
+    ));
+}
+
+#[test]
+fn test_http_options_rejected_when_invalid_or_inapplicable() {
+    let test_db = TestDatabase::new();
+    let add = |extra: &[&str]| {
+        let mut args = vec!["add", "http", "Web", "https://example.com"];
+        args.extend_from_slice(extra);
+        run(&test_db.db, &args).unwrap_err().to_string()
+    };
+
+    assert!(add(&["--header", "no separator"]).contains("Invalid header"));
+    assert!(add(&["--accept-status", "200-600"]).contains("Invalid status range"));
+    assert!(add(&["--matches", "("]).contains("Invalid response body pattern"));
+    assert_eq!(
+        add(&["--json-pointer", "/ok"]),
+        "--json-pointer needs a --json-value"
+    );
+    assert!(add(&["--method", "FETCH"]).contains("FETCH"));
+    assert!(test_db.db.get_all_nodes().unwrap().is_empty());
+
+    run(&test_db.db, &["add", "tcp", "DB", "10.0.0.2", "5432"]).unwrap();
+    let err = run(&test_db.db, &["edit", "DB", "--max-redirects", "2"]).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "--max-redirects does not apply to TCP nodes"
+    );
+}
+
+#[test]
+fn test_add_and_edit_tls_options() {
+    let test_db = TestDatabase::new();
+    let tls = |db: &Database| {
+        let node = cli::find_node(db, "Intranet").unwrap();
+        match node.detail {
+            MonitorDetail::Http { options, .. } => options.tls,
+            _ => panic!("Expected HTTP detail"),
+        }
+    };
+
+    run(
+        &test_db.db,
+        &[
+            "add",
+            "http",
+            "Intranet",
+            "https://intranet.local",
+            "--ca-bundle",
+            "/etc/ssl/internal-ca.pem",
+            "--cert-warning-days",
+            "30",
+        ],
+    )
+    .unwrap();
+    let options = tls(&test_db.db);
+    assert_eq!(options.mode, TlsMode::Verify);
+    assert_eq!(
+        options.ca_bundle.as_deref(),
+        Some(Path::new("/etc/ssl/internal-ca.pem"))
+    );
+    assert_eq!(options.expiry_warning_days, 30);
+    assert_eq!(options.expiry_critical_days, 3);
+
+    // A bundle only makes sense when verifying, so switching mode means clearing it
+    let err = run(
+        &test_db.db,
+        &["edit", "Intranet", "--tls", "accept-invalid"],
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("CA bundle"));
+    run(
+        &test_db.db,
+        &[
+            "edit",
+            "Intranet",
+            "--tls",
+            "accept-invalid",
+            "--ca-bundle",
+            "",
+            "--cert-critical-days",
+            "7",
+        ],
+    )
+    .unwrap();
+    let options = tls(&test_db.db);
+    assert_eq!(options.mode, TlsMode::AcceptInvalid);
+    assert_eq!(options.ca_bundle, None);
+    assert_eq!(options.expiry_critical_days, 7);
+
+    let err = run(&test_db.db, &["edit", "Intranet", "--tls", "sha256:abc"]).unwrap_err();
+    assert!(err.to_string().contains("Invalid SHA-256 fingerprint"));
+
+    run(&test_db.db, &["add", "tcp", "DB", "10.0.0.2", "5432"]).unwrap();
+    let err = run(&test_db.db, &["edit", "DB", "--tls", "verify"]).unwrap_err();
+    assert_eq!(err.to_string(), "--tls does not apply to TCP nodes");
+}
+
+#[test]
+fn test_add_and_edit_ssh_node() {
+    let test_db = TestDatabase::new();
+    let temp_dir = TempDir::new().unwrap();
+    let (credentials, id) = router_credentials(&temp_dir);
+
+    run_with_credentials(
+        &test_db.db,
+        &credentials,
+        &[
+            "add",
+            "ssh",
+            "Core",
+            "10.0.0.1",
+            "--credential",
+            "Router",
+            "--command",
+            "uptime",
+            "--expected-output",
+            "load average",
+            "--host-key",
+            "SHA256:uNiVztksCsDhcc0u9e8BujQXVUpKZIDTMczCvj3tD2s",
+        ],
+    )
+    .unwrap();
+
+    let node = cli::find_node(&test_db.db, "Core").unwrap();
+    assert_eq!(node.credential_id, Some(id));
+    assert_eq!(
+        node.detail,
+        MonitorDetail::Ssh {
+            host: "10.0.0.1".to_string(),
+            port: 22,
+            timeout: 10,
+            command: Some("uptime".to_string()),
+            expected_output: Some("load average".to_string()),
+            host_key: Some("SHA256:uNiVztksCsDhcc0u9e8BujQXVUpKZIDTMczCvj3tD2s".to_string()),
+        }
+    );
+
+    run(
+        &test_db.db,
+        &[
+            "edit",
+            "Core",
+            "--port",
+            "2222",
+            "--command",
+            "",
+            "--expected-output",
+            "",
+            "--host-key",
+            "",
+            "--no-credential",
+        ],
+    )
+    .unwrap();
+
+    let node = cli::find_node(&test_db.db, "Core").unwrap();
+    assert_eq!(node.credential_id, None);
+    assert_eq!(
+        node.detail,
+        MonitorDetail::Ssh {
+            host: "10.0.0.1".to_string(),
+            port: 2222,
+            timeout: 10,
+            command: None,
+            expected_output: None,
+            host_key: None,
+        }
+    );
+}
+
+#[test]
+fn test_add_and_edit_dns_node() {
+    let test_db = TestDatabase::new();
+
+    run(
+        &test_db.db,
+        &[
+            "add",
+            "dns",
+            "Mail DNS",
+            "10.0.0.53",
+            "example.com",
+            "--type",
+            "mx",
+            "--expect",
+            "10 mx1.example.com",
+            "--expect",
+            "20 mx2.example.com",
+        ],
+    )
+    .unwrap();
+
+    let node = cli::find_node(&test_db.db, "Mail DNS").unwrap();
+    assert_eq!(
+        node.detail,
+        MonitorDetail::Dns {
+            server: "10.0.0.53".to_string(),
+            port: 53,
+            name: "example.com".to_string(),
+            record_type: DnsRecordType::Mx,
+            expected: vec![
+                "10 mx1.example.com".to_string(),
+                "20 mx2.example.com".to_string()
+            ],
+            protocol: DnsProtocol::Udp,
+            timeout: 5,
+        }
+    );
+
+    run(
+        &test_db.db,
+        &[
+            "edit",
+            "Mail DNS",
+            "--server",
+            "ns1.example.com",
+            "--lookup",
+            "www.example.com",
+            "--type",
+            "A",
+            "--expect",
+            "",
+            "--protocol",
+            "tcp",
+            "--port",
+            "5353",
+        ],
+    )
+    .unwrap();
+
+    let node = cli::find_node(&test_db.db, "Mail DNS").unwrap();
+    assert_eq!(
+        node.detail,
+        MonitorDetail::Dns {
+            server: "ns1.example.com".to_string(),
+            port: 5353,
+            name: "www.example.com".to_string(),
+            record_type: DnsRecordType::A,
+            expected: vec![],
+            protocol: DnsProtocol::Tcp,
+            timeout: 5,
+        }
+    );
+
+    // Answers are checked against the record type
+    let err = run(&test_db.db, &["edit", "Mail DNS", "--expect", "mx1"]).unwrap_err();
+    assert!(err.to_string().contains("Invalid expected A answer 'mx1'"));
+    let err = run(&test_db.db, &["edit", "Mail DNS", "--host", "x"]).unwrap_err();
+    assert_eq!(err.to_string(), "--host does not apply to DNS nodes");
+}
+
+#[test]
+fn test_add_and_edit_udp_node() {
+    let test_db = TestDatabase::new();
+
+    run(
+        &test_db.db,
+        &[
+            "add",
+            "udp",
+            "WireGuard",
+            "vpn.corp.local",
+            "51820",
+            "--no-reply-is-up",
+        ],
+    )
+    .unwrap();
+
+    let node = cli::find_node(&test_db.db, "WireGuard").unwrap();
+    assert_eq!(
+        node.detail,
+        MonitorDetail::Udp {
+            host: "vpn.corp.local".to_string(),
+            port: 51820,
+            payload: UdpPayload::default(),
+            expected_response: None,
+            no_reply_is_up: true,
+            timeout: 5,
+        }
+    );
+
+    run(
+        &test_db.db,
+        &[
+            "edit",
+            "WireGuard",
+            "--payload",
+            "hex:ff ff ff ff 54",
+            "--expect",
+            "hex:ffff",
+            "--require-reply",
+        ],
+    )
+    .unwrap();
+
+    let node = cli::find_node(&test_db.db, "WireGuard").unwrap();
+    assert_eq!(
+        node.detail,
+        MonitorDetail::Udp {
+            host: "vpn.corp.local".to_string(),
+            port: 51820,
+            payload: UdpPayload::Hex(vec![0xff, 0xff, 0xff, 0xff, 0x54]),
+            expected_response: Some(UdpResponseMatch::HexPrefix(vec![0xff, 0xff])),
+            no_reply_is_up: false,
+            timeout: 5,
+        }
+    );
+
+    run(&test_db.db, &["edit", "WireGuard", "--expect", ""]).unwrap();
+    let node = cli::find_node(&test_db.db, "WireGuard").unwrap();
+    assert!(matches!(
+        node.detail,
+        MonitorDetail::Udp {
+            expected_response: None,
+            ..
+        }
+    ));
+
+    let err = run(&test_db.db, &["edit", "WireGuard", "--expect", "("]).unwrap_err();
+    assert!(err
+        .to_string()
+        .starts_with("Invalid expected response pattern"));
+    assert!(run(
+        &test_db.db,
+        &["edit", "WireGuard", "--expect", "a", "--expect", "b"]
+    )
+    .is_err());
+    let err = run(&test_db.db, &["edit", "WireGuard", "--lookup", "x"]).unwrap_err();
+    assert_eq!(err.to_string(), "--lookup does not apply to UDP nodes");
+}
+
+#[test]
+fn test_add_and_edit_ping_thresholds() {
+    let test_db = TestDatabase::new();
+
+    run(
+        &test_db.db,
+        &[
+            "add",
+            "ping",
+            "Uplink",
+            "gw.corp.local",
+            "--count",
+            "10",
+            "--loss-warning",
+            "10",
+            "--loss-critical",
+            "50",
+            "--latency-warning",
+            "80",
+        ],
+    )
+    .unwrap();
+
+    let node = cli::find_node(&test_db.db, "Uplink").unwrap();
+    assert_eq!(
+        node.detail,
+        MonitorDetail::Ping {
+            host: "gw.corp.local".to_string(),
+            count: 10,
+            timeout: 5,
+            fallback_port: 80,
+            thresholds: PingThresholds {
+                loss_warning_percent: 10,
+                loss_critical_percent: 50,
+                latency_warning_ms: 80,
+                latency_critical_ms: 0,
+            },
+        }
+    );
+
+    run(
+        &test_db.db,
+        &[
+            "edit",
+            "Uplink",
+            "--loss-warning",
+            "0",
+            "--latency-critical",
+            "250",
+            "--fallback-port",
+            "443",
+        ],
+    )
+    .unwrap();
+    let node = cli::find_node(&test_db.db, "Uplink").unwrap();
+    let MonitorDetail::Ping {
+        fallback_port,
+        thresholds,
+        ..
+    } = node.detail
+    else {
+        panic!("Expected a ping node");
+    };
+    assert_eq!(fallback_port, 443);
+    assert_eq!(
+        thresholds,
+        PingThresholds {
+            loss_warning_percent: 0,
+            loss_critical_percent: 50,
+            latency_warning_ms: 80,
+            latency_critical_ms: 250,
+        }
+    );
+
+    let err = run(&test_db.db, &["edit", "Uplink", "--loss-critical", "150"]).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "The packet loss critical threshold must be a percentage from 0 to 100"
+    );
+    assert!(run(&test_db.db, &["edit", "Uplink", "--count", "0"]).is_err());
+    let err = run(
+        &test_db.db,
+        &["add", "ping", "Backup uplink", "10.0.0.2", "--count", "0"],
+    )
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "A ping check needs to send at least one echo request"
+    );
+    assert!(run(&test_db.db, &["edit", "Uplink", "--fallback-port", "0"]).is_err());
+
+    run(&test_db.db, &["add", "tcp", "SSH port", "10.0.0.1", "22"]).unwrap();
+    let err = run(&test_db.db, &["edit", "SSH port", "--loss-warning", "5"]).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "--loss-warning does not apply to TCP nodes"
+    );
+}
+
+#[test]
+fn test_add_ssh_node_rejects_bad_options() {
+    let test_db = TestDatabase::new();
+
+    // An expected output needs a command to match against
+    assert!(run(
+        &test_db.db,
+        &["add", "ssh", "Core", "10.0.0.1", "--expected-output", "ok"]
+    )
+    .is_err());
+
+    let err = run(
+        &test_db.db,
+        &[
+            "add",
+            "ssh",
+            "Core",
+            "10.0.0.1",
+            "--command",
+            "uptime",
+            "--expected-output",
+            "(",
+        ],
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("Invalid expected output pattern"));
+
+    // Credential names can't be resolved while the store is locked
+    let err = run(
+        &test_db.db,
+        &["add", "ssh", "Core", "10.0.0.1", "--credential", "Router"],
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("locked"));
+
+    let temp_dir = TempDir::new().unwrap();
+    let (credentials, _) = router_credentials(&temp_dir);
+    let err = run_with_credentials(
+        &test_db.db,
+        &credentials,
+        &["add", "ssh", "Core", "10.0.0.1", "--credential", "Switch"],
+    )
+    .unwrap_err();
+    assert_eq!(err.to_string(), "No credential named 'Switch'");
+
+    assert!(test_db.db.get_all_nodes().unwrap().is_empty());
+}
+
+#[test]
+fn test_add_and_edit_node_hooks() {
+    let test_db = TestDatabase::new();
+
+    run(
+        &test_db.db,
+        &[
+            "add",
+            "tcp",
+            "NAS",
+            "10.0.0.20",
+            "445",
+            "--on-down",
+            "/usr/local/bin/plug off",
+        ],
+    )
+    .unwrap();
+    let node = cli::find_node(&test_db.db, "NAS").unwrap();
+    assert_eq!(
+        node.hooks.on_down.as_deref(),
+        Some("/usr/local/bin/plug off")
+    );
+    assert_eq!(node.hooks.on_recovery, None);
+
+    run(
+        &test_db.db,
+        &[
+            "edit",
+            "NAS",
+            "--on-recovery",
+            "/usr/local/bin/plug on",
+            "--on-down",
+            "",
+        ],
+    )
+    .unwrap();
+    let node = cli::find_node(&test_db.db, "NAS").unwrap();
+    assert_eq!(
+        node.hooks,
+        HookCommands {
+            on_recovery: Some("/usr/local/bin/plug on".to_string()),
+            ..HookCommands::default()
+        }
+    );
+}
+
+#[test]
+fn test_add_and_edit_node_parents() {
+    let test_db = TestDatabase::new();
+    run(&test_db.db, &["add", "ping", "Core Switch", "10.0.0.1"]).unwrap();
+    run(&test_db.db, &["add", "ping", "Router", "10.0.0.2"]).unwrap();
+    let switch_id = cli::find_node(&test_db.db, "Core Switch")
+        .unwrap()
+        .id
+        .unwrap();
+    let router_id = cli::find_node(&test_db.db, "Router").unwrap().id.unwrap();
+
+    run(
+        &test_db.db,
+        &[
+            "add",
+            "ping",
+            "Office AP",
+            "10.0.0.20",
+            "--parent",
+            "Core Switch",
+            "--parent",
+            &router_id.to_string(),
+        ],
+    )
+    .unwrap();
+    let node = cli::find_node(&test_db.db, "Office AP").unwrap();
+    assert_eq!(node.parents, vec![switch_id, router_id]);
+
+    let (_, table) = run(&test_db.db, &["list"]).unwrap();
+    assert!(table.lines().next().unwrap().contains("PARENTS"));
+    let ap_line = table.lines().find(|l| l.contains("Office AP")).unwrap();
+    assert!(ap_line.contains("Core Switch, Router"), "{}", ap_line);
+
+    // A node can't end up depending on itself through its children
+    let err = run(
+        &test_db.db,
+        &["edit", "Core Switch", "--parent", "Office AP"],
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("already depends on it"), "{}", err);
+    let err = run(
+        &test_db.db,
+        &["add", "ping", "NAS", "10.0.0.30", "--parent", "Printer"],
+    )
+    .unwrap_err();
+    assert!(
+        err.to_string().contains("No node named 'Printer'"),
+        "{}",
+        err
+    );
+
+    run(&test_db.db, &["edit", "Office AP", "--parent", "Router"]).unwrap();
+    let node = cli::find_node(&test_db.db, "Office AP").unwrap();
+    assert_eq!(node.parents, vec![router_id]);
+    run(&test_db.db, &["edit", "Office AP", "--no-parents"]).unwrap();
+    assert!(cli::find_node(&test_db.db, "Office AP")
+        .unwrap()
+        .parents
+        .is_empty());
+}
+
+#[test]
+fn test_add_and_edit_node_group_and_tags() {
+    let test_db = TestDatabase::new();
+    run(
+        &test_db.db,
+        &[
+            "add",
+            "tcp",
+            "Office NAS",
+            "10.0.0.30",
+            "445",
+            "--group",
+            "Office",
+            "--tag",
+            "prod,backup",
+            "--tag",
+            "Prod",
+        ],
+    )
+    .unwrap();
+    let node = cli::find_node(&test_db.db, "Office NAS").unwrap();
+    assert_eq!(node.group.as_deref(), Some("Office"));
+    assert_eq!(node.tags, vec!["prod", "backup"]);
+
+    let (_, table) = run(&test_db.db, &["list"]).unwrap();
+    let header = table.lines().next().unwrap();
+    assert!(
+        header.contains("GROUP") && header.contains("TAGS"),
+        "{}",
+        header
+    );
+    let nas_line = table.lines().find(|l| l.contains("Office NAS")).unwrap();
+    assert!(nas_line.contains("Office"), "{}", nas_line);
+    assert!(nas_line.contains("prod, backup"), "{}", nas_line);
+
+    // Editing the tags replaces them, and an empty group removes it
+    run(&test_db.db, &["edit", "Office NAS", "--tag", "lab"]).unwrap();
+    let node = cli::find_node(&test_db.db, "Office NAS").unwrap();
+    assert_eq!(node.tags, vec!["lab"]);
+    assert_eq!(node.group.as_deref(), Some("Office"));
+    run(
+        &test_db.db,
+        &["edit", "Office NAS", "--group", "", "--no-tags"],
+    )
+    .unwrap();
+    let node = cli::find_node(&test_db.db, "Office NAS").unwrap();
+    assert_eq!(node.group, None);
+    assert!(node.tags.is_empty());
+
+    assert!(run(
+        &test_db.db,
+        &["edit", "Office NAS", "--tag", "lab", "--no-tags"]
+    )
+    .is_err());
+}
+
+#[test]
+fn test_remove_node_by_name_and_id() {
+    let test_db = TestDatabase::new();
+    run(&test_db.db, &["add", "ping", "A", "10.0.0.1"]).unwrap();
+    run(&test_db.db, &["add", "ping", "B", "10.0.0.2"]).unwrap();
+    let b_id = cli::find_node(&test_db.db, "B").unwrap().id.unwrap();
+
+    run(&test_db.db, &["remove", "A"]).unwrap();
+    run(&test_db.db, &["remove", &b_id.to_string()]).unwrap();
+
+    assert!(test_db.db.get_all_nodes().unwrap().is_empty());
+    assert!(run(&test_db.db, &["remove", "A"]).is_err());
+}
+
+#[test]
+fn test_find_node_rejects_ambiguous_name() {
+    let test_db = TestDatabase::new();
+    run(&test_db.db, &["add", "ping", "Dup", "10.0.0.1"]).unwrap();
+    run(&test_db.db, &["add", "ping", "Dup", "10.0.0.2"]).unwrap();
+
+    let err = cli::find_node(&test_db.db, "Dup").unwrap_err();
+    assert!(err.to_string().contains("use the node ID"));
+}
+
+// ========== List / Import / Export Tests ==========
+
+#[test]
+fn test_list_table_and_json() {
+    let test_db = TestDatabase::new();
+    test_db.db.add_node(&fixtures::http_node()).unwrap();
+    test_db.db.add_node(&fixtures::ping_node()).unwrap();
+
+    let (_, table) = run(&test_db.db, &["list"]).unwrap();
+    let lines: Vec<&str> = table.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].starts_with("ID"));
+    assert!(lines[1].contains("Test HTTP Node"));
+    assert!(lines[2].contains("127.0.0.1"));
+
+    let (_, json) = run(&test_db.db, &["list", "--json"]).unwrap();
+    let nodes: Vec<net_monitor::models::Node> = serde_json::from_str(&json).unwrap();
+    assert_eq!(nodes.len(), 2);
+}
+
+#[test]
+fn test_export_then_import_round_trip() {
+    let source = TestDatabase::new();
+    source.db.add_node(&fixtures::http_node()).unwrap();
+    source.db.add_node(&fixtures::ping_node()).unwrap();
+
+    let dir = TempDir::new().unwrap();
+    let file = dir.path().join("nodes.json");
+    let file_arg = file.to_str().unwrap();
+    run(&source.db, &["export", file_arg]).unwrap();
+
+    let exported: Vec<NodeImport> =
+        serde_json::from_str(&std::fs::read_to_string(&file).unwrap()).unwrap();
+    assert_eq!(exported.len(), 2);
+
+    let target = TestDatabase::new();
+    let (_, output) = run(&target.db, &["import", file_arg]).unwrap();
+    assert_eq!(output.lines().count(), 2);
+
+    let imported = target.db.get_all_nodes().unwrap();
+    assert_eq!(imported.len(), 2);
+    assert_eq!(imported[0].name, "Test HTTP Node");
+    assert_eq!(imported[1].detail, fixtures::ping_node().detail);
+    assert!(imported.iter().all(|n| n.status == NodeStatus::Offline));
+}
+
+#[test]
+fn test_export_then_import_keeps_parents() {
+    let source = TestDatabase::new();
+    run(&source.db, &["add", "ping", "Core Switch", "10.0.0.1"]).unwrap();
+    run(
+        &source.db,
+        &[
+            "add",
+            "ping",
+            "Office AP",
+            "10.0.0.20",
+            "--parent",
+            "Core Switch",
+        ],
+    )
+    .unwrap();
+
+    let dir = TempDir::new().unwrap();
+    let file = dir.path().join("nodes.json");
+    let file_arg = file.to_str().unwrap();
+    run(&source.db, &["export", file_arg]).unwrap();
+
+    // Parents are imported by name, whatever IDs the nodes get
+    let target = TestDatabase::new();
+    run(&target.db, &["add", "ping", "Printer", "10.0.0.40"]).unwrap();
+    run(&target.db, &["import", file_arg]).unwrap();
+    let switch = cli::find_node(&target.db, "Core Switch").unwrap();
+    let ap = cli::find_node(&target.db, "Office AP").unwrap();
+    assert_eq!(ap.parents, vec![switch.id.unwrap()]);
+}
+
+#[test]
+fn test_import_rejects_unknown_parent() {
+    let test_db = TestDatabase::new();
+    let dir = TempDir::new().unwrap();
+    let file = dir.path().join("nodes.json");
+    std::fs::write(
+        &file,
+        r#"[
+            {"name": "Office AP", "detail": {"type": "Ping", "host": "10.0.0.20", "count": 1, "timeout": 1}, "monitoring_interval": 60, "credential_id": null, "parents": ["Core Switch"]}
+        ]"#,
+    )
+    .unwrap();
+
+    let err = run(&test_db.db, &["import", file.to_str().unwrap()]).unwrap_err();
+    assert!(
+        err.to_string().contains("no node named 'Core Switch'"),
+        "{}",
+        err
+    );
+    assert!(test_db.db.get_all_nodes().unwrap().is_empty());
+}
+
+#[test]
+fn test_import_rejects_dependency_cycle() {
+    let test_db = TestDatabase::new();
+    let dir = TempDir::new().unwrap();
+    let file = dir.path().join("nodes.json");
+    std::fs::write(
+        &file,
+        r#"[
+            {"name": "A", "detail": {"type": "Ping", "host": "10.0.0.1", "count": 1, "timeout": 1}, "monitoring_interval": 60, "credential_id": null, "parents": ["B"]},
+            {"name": "B", "detail": {"type": "Ping", "host": "10.0.0.2", "count": 1, "timeout": 1}, "monitoring_interval": 60, "credential_id": null, "parents": ["A"]},
+            {"name": "C", "detail": {"type": "Ping", "host": "10.0.0.3", "count": 1, "timeout": 1}, "monitoring_interval": 60, "credential_id": null, "parents": ["C"]}
+        ]"#,
+    )
+    .unwrap();
+
+    let err = run(&test_db.db, &["import", file.to_str().unwrap()]).unwrap_err();
+    assert!(
+        format!("{:#}", err).contains("'A' can't depend on 'B', which already depends on it"),
+        "{:#}",
+        err
+    );
+    assert!(test_db.db.get_all_nodes().unwrap().is_empty());
+
+    // On its own, the node depending on itself is refused too
+    std::fs::write(
+        &file,
+        r#"[{"name": "C", "detail": {"type": "Ping", "host": "10.0.0.3", "count": 1, "timeout": 1}, "monitoring_interval": 60, "credential_id": null, "parents": ["C"]}]"#,
+    )
+    .unwrap();
+    let err = run(&test_db.db, &["import", file.to_str().unwrap()]).unwrap_err();
+    assert!(
+        format!("{:#}", err).contains("'C' can't depend on itself"),
+        "{:#}",
+        err
+    );
+    assert!(test_db.db.get_all_nodes().unwrap().is_empty());
+}
+
+#[test]
+fn test_import_is_all_or_nothing_on_invalid_node() {
+    let test_db = TestDatabase::new();
+    let dir = TempDir::new().unwrap();
+    let file = dir.path().join("nodes.json");
+    std::fs::write(
+        &file,
+        r#"[
+            {"name": "Good", "detail": {"type": "Ping", "host": "10.0.0.1", "count": 1, "timeout": 1}, "monitoring_interval": 60, "credential_id": null},
+            {"name": "Bad", "detail": {"type": "Ping", "host": "", "count": 1, "timeout": 1}, "monitoring_interval": 60, "credential_id": null}
+        ]"#,
+    )
+    .unwrap();
+
+    assert!(run(&test_db.db, &["import", file.to_str().unwrap()]).is_err());
+    assert!(test_db.db.get_all_nodes().unwrap().is_empty());
+}
+
+// ========== Check / History Tests ==========
+
+#[test]
+fn test_check_exit_code_reflects_status() {
+    let test_db = TestDatabase::new();
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let open_port = listener.local_addr().unwrap().port().to_string();
+
+    // Bind and drop to find a port nothing is listening on
+    let closed_port = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port()
+        .to_string();
+
+    run(
+        &test_db.db,
+        &[
+            "add",
+            "tcp",
+            "Open",
+            "127.0.0.1",
+            &open_port,
+            "--timeout",
+            "2",
+        ],
+    )
+    .unwrap();
+    run(
+        &test_db.db,
+        &[
+            "add",
+            "tcp",
+            "Closed",
+            "127.0.0.1",
+            &closed_port,
+            "--timeout",
+            "2",
+        ],
+    )
+    .unwrap();
+
+    let (code, output) = run(&test_db.db, &["check", "Open"]).unwrap();
+    assert_eq!(code, ExitCode::SUCCESS);
+    assert!(output.starts_with("Open: Online"));
+
+    let (code, output) = run(&test_db.db, &["check", "Closed"]).unwrap();
+    assert_eq!(code, ExitCode::FAILURE);
+    assert!(output.starts_with("Closed: Offline"));
+}
+
+#[test]
+fn test_history_lists_status_changes() {
+    let test_db = TestDatabase::new();
+    let node_id = test_db.db.add_node(&fixtures::ping_node()).unwrap();
+
+    let (_, output) = run(&test_db.db, &["history", "Test Ping Node"]).unwrap();
+    assert!(output.contains("No status changes"));
+
+    let now = chrono::Utc::now();
+    for (i, (from, to)) in [
+        (NodeStatus::Offline, NodeStatus::Online),
+        (NodeStatus::Online, NodeStatus::Degraded),
+        (NodeStatus::Degraded, NodeStatus::Online),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        test_db
+            .db
+            .add_status_change(&StatusChange {
+                id: None,
+                node_id,
+                from_status: from,
+                to_status: to,
+                changed_at: now - chrono::Duration::minutes(10 - i as i64),
+                duration_ms: Some(60_000),
+                in_maintenance: i == 1,
+            })
+            .unwrap();
+    }
+
+    let (_, output) = run(&test_db.db, &["history", "Test Ping Node", "-n", "2"]).unwrap();
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[1].contains("Degraded") && lines[1].contains("Online"));
+    assert!(lines[1].contains("1m 0s"));
+    assert!(lines[2].contains("Degraded (maintenance)"));
+
+    let (_, json) = run(&test_db.db, &["history", "Test Ping Node", "--json"]).unwrap();
+    let changes: Vec<StatusChange> = serde_json::from_str(&json).unwrap();
+    assert_eq!(changes.len(), 3);
+    assert_eq!(changes[0].to_status, NodeStatus::Online);
+
+    // Hook runs show up against the change that started them
+    test_db
+        .db
+        .add_notification_delivery(&NotificationDelivery {
+            id: None,
+            status_change_id: changes[0].id,
+            node_id,
+            channel: "hook:systemctl restart app".to_string(),
+            attempt: 1,
+            attempted_at: now,
+            success: false,
+            response_status: Some(1),
+            error: Some("Exited with status 1".to_string()),
+        })
+        .unwrap();
+    let (_, output) = run(&test_db.db, &["history", "Test Ping Node", "-n", "2"]).unwrap();
+    let lines: Vec<&str> = output.lines().collect();
+    assert!(lines[0].contains("HOOKS"));
+    assert!(lines[1].trim_end().ends_with("exit 1"));
+    assert!(lines[2].trim_end().ends_with('-'));
+}
+
+#[test]
+fn test_maintenance_windows() {
+    let test_db = TestDatabase::new();
+    test_db.db.add_node(&fixtures::ping_node()).unwrap();
+
+    let (_, output) = run(&test_db.db, &["maintenance", "list"]).unwrap();
+    assert!(output.contains("No maintenance windows"));
+
+    let (_, output) = run(
+        &test_db.db,
+        &[
+            "maintenance",
+            "add",
+            "--node",
+            "Test Ping Node",
+            "--minutes",
+            "90",
+            "--every",
+            "0 2 * * sun",
+            "--start",
+            "2026-01-01 00:00",
+            "--reason",
+            "Router updates",
+        ],
+    )
+    .unwrap();
+    assert!(output.contains("for 'Test Ping Node'"), "{}", output);
+    run(&test_db.db, &["maintenance", "add", "--minutes", "30"]).unwrap();
+    let (_, output) = run(
+        &test_db.db,
+        &["maintenance", "add", "--tag", " prod ", "--minutes", "15"],
+    )
+    .unwrap();
+    assert!(output.contains("for nodes tagged 'prod'"), "{}", output);
+
+    let (_, output) = run(&test_db.db, &["maintenance", "list"]).unwrap();
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines.len(), 4);
+    assert!(lines[1].contains("Test Ping Node") && lines[1].contains("0 2 * * sun from"));
+    assert!(lines[1].contains("1h 30m") && lines[1].contains("Router updates"));
+    assert!(lines[2].contains("all") && lines[2].contains("active until"));
+    assert!(lines[3].contains("tag:prod") && lines[3].contains("active until"));
+
+    let (_, json) = run(&test_db.db, &["maintenance", "list", "--json"]).unwrap();
+    let windows: Vec<MaintenanceWindow> = serde_json::from_str(&json).unwrap();
+    assert_eq!(windows[1].scope, MaintenanceScope::All);
+    assert_eq!(windows[2].scope, MaintenanceScope::Tag("prod".to_string()));
+
+    let id = windows[0].id.unwrap().to_string();
+    run(&test_db.db, &["maintenance", "remove", &id]).unwrap();
+    let err = run(&test_db.db, &["maintenance", "remove", &id]).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        format!("No maintenance window with ID {}", id)
+    );
+
+    let err = run(
+        &test_db.db,
+        &["maintenance", "add", "--minutes", "5", "--every", "sundays"],
+    )
+    .unwrap_err();
+    assert!(err.to_string().starts_with("Invalid maintenance schedule"));
+    let err = run(
+        &test_db.db,
+        &["maintenance", "add", "--minutes", "5", "--tag", "prod edge"],
+    )
+    .unwrap_err();
+    assert_eq!(err.to_string(), "'prod edge' is not a single tag");
+    assert!(run(
+        &test_db.db,
+        &[
+            "maintenance",
+            "add",
+            "--minutes",
+            "5",
+            "--start",
+            "tomorrow"
+        ]
+    )
+    .is_err());
+}
+
+// ========== Credential Tests ==========
+
+#[test]
+fn test_list_credentials() {
+    let temp_dir = TempDir::new().unwrap();
+    let paths = AppPaths::resolve(PathOverrides {
+        data_dir: Some(temp_dir.path().to_path_buf()),
+        ..Default::default()
+    })
+    .unwrap();
+    let (credentials, id) = router_credentials(&temp_dir);
+    let mut config = AppConfig::default();
+
+    let mut out = Vec::new();
+    cli::run_credentials(
+        &paths,
+        &mut config,
+        None,
+        &credentials,
+        CredentialCommand::List { json: false },
+        &mut out,
+    )
+    .unwrap();
+    let output = String::from_utf8(out).unwrap();
+    assert!(output.contains(&id));
+    assert!(output.contains("Router") && output.contains("admin"));
+    assert!(!output.contains("secret"));
+
+    let err = cli::run_credentials(
+        &paths,
+        &mut config,
+        None,
+        &SharedCredentialStore::default(),
+        CredentialCommand::List { json: true },
+        &mut Vec::new(),
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("locked"));
+}
+
+#[test]
+fn test_migrate_to_keyring_moves_credentials_and_switches_backend() {
+    keyring::set_default_credential_builder(keyring::mock::default_credential_builder());
+    let temp_dir = TempDir::new().unwrap();
+    let paths = AppPaths::resolve(PathOverrides {
+        data_dir: Some(temp_dir.path().to_path_buf()),
+        ..Default::default()
+    })
+    .unwrap();
+
+    let id = FileCredentialStore::with_path("master".to_string(), &paths.credentials)
+        .unwrap()
+        .store_credential(
+            "Router".to_string(),
+            None,
+            SshCredential::Password {
+                username: "admin".to_string(),
+                password: "secret".into(),
+            },
+        )
+        .unwrap();
+
+    let mut config = AppConfig::default();
+    let err = cli::run_credentials(
+        &paths,
+        &mut config,
+        None,
+        &SharedCredentialStore::default(),
+        CredentialCommand::MigrateToKeyring,
+        &mut Vec::new(),
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("master password"));
+    assert_eq!(config.credentials.backend, CredentialBackend::File);
+
+    let mut out = Vec::new();
+    let code = cli::run_credentials(
+        &paths,
+        &mut config,
+        Some("master".into()),
+        &SharedCredentialStore::default(),
+        CredentialCommand::MigrateToKeyring,
+        &mut out,
+    )
+    .unwrap();
+    assert_eq!(code, ExitCode::SUCCESS);
+    assert!(String::from_utf8(out)
+        .unwrap()
+        .contains("Moved 1 credential(s) to the keyring"));
+
+    assert_eq!(config.credentials.backend, CredentialBackend::Keyring);
+    let saved = AppConfig::load_from(&paths.config).unwrap();
+    assert_eq!(saved.credentials.backend, CredentialBackend::Keyring);
+
+    let keyring_store = KeyringCredentialStore::with_path(&paths.keyring_metadata).unwrap();
+    let summaries = keyring_store.list_credentials().unwrap();
+    assert_eq!(summaries.len(), 1);
+    assert_eq!(summaries[0].id, id);
+
+    let file_store =
+        FileCredentialStore::with_path("master".to_string(), &paths.credentials).unwrap();
+    assert!(file_store.list_credentials().unwrap().is_empty());
+}
@@ -1,5 +1,7 @@
 use net_monitor::database::Database;
-use net_monitor::models::{MonitorDetail, Node, NodeStatus};
+use net_monitor::models::{
+    DnsProtocol, DnsRecordType, HookCommands, HttpOptions, MonitorDetail, Node, NodeStatus,
+};
 use std::fs;
 use std::path::PathBuf;
 use tempfile::NamedTempFile;
@@ -76,20 +78,114 @@ impl NodeBuilder {
         self.detail = Some(MonitorDetail::Http {
             url: url.into(),
             expected_status,
+            timeout: 30,
+            options: Default::default(),
         });
         self
     }
 
+    /// Sets the request and response options of an HTTP node
+    #[allow(dead_code)]
+    pub fn http_options(mut self, http_options: HttpOptions) -> Self {
+        match &mut self.detail {
+            Some(MonitorDetail::Http { options, .. }) => *options = http_options,
+            _ => panic!("HTTP options need an HTTP node (use .http() first)"),
+        }
+        self
+    }
+
     /// Configures as a Ping node
     pub fn ping(mut self, host: impl Into<String>, count: u32, timeout: u64) -> Self {
         self.detail = Some(MonitorDetail::Ping {
             host: host.into(),
             count,
             timeout,
+            fallback_port: 80,
+            thresholds: Default::default(),
         });
         self
     }
 
+    /// Configures as a TCP node
+    #[allow(dead_code)]
+    pub fn tcp(mut self, host: impl Into<String>, port: u16, timeout: u64) -> Self {
+        self.detail = Some(MonitorDetail::Tcp {
+            host: host.into(),
+            port,
+            timeout,
+        });
+        self
+    }
+
+    /// Configures as an SSH node with a 5 second timeout
+    #[allow(dead_code)]
+    pub fn ssh(
+        mut self,
+        host: impl Into<String>,
+        port: u16,
+        command: Option<&str>,
+        expected_output: Option<&str>,
+    ) -> Self {
+        self.detail = Some(MonitorDetail::Ssh {
+            host: host.into(),
+            port,
+            timeout: 5,
+            command: command.map(String::from),
+            expected_output: expected_output.map(String::from),
+            host_key: None,
+        });
+        self
+    }
+
+    /// Configures as a DNS node querying over UDP with a 2 second timeout
+    #[allow(dead_code)]
+    pub fn dns(
+        mut self,
+        server: impl Into<String>,
+        port: u16,
+        name: impl Into<String>,
+        record_type: DnsRecordType,
+        expected: &[&str],
+    ) -> Self {
+        self.detail = Some(MonitorDetail::Dns {
+            server: server.into(),
+            port,
+            name: name.into(),
+            record_type,
+            expected: expected.iter().map(|answer| answer.to_string()).collect(),
+            protocol: DnsProtocol::Udp,
+            timeout: 2,
+        });
+        self
+    }
+
+    /// Configures as a UDP node sending `payload` with a 1 second timeout
+    #[allow(dead_code)]
+    pub fn udp(
+        mut self,
+        host: impl Into<String>,
+        port: u16,
+        payload: &str,
+        expected_response: Option<&str>,
+    ) -> Self {
+        self.detail = Some(MonitorDetail::Udp {
+            host: host.into(),
+            port,
+            payload: payload.parse().unwrap(),
+            expected_response: expected_response.map(|e| e.parse().unwrap()),
+            no_reply_is_up: false,
+            timeout: 1,
+        });
+        self
+    }
+
+    /// Sets the credential used to log in
+    #[allow(dead_code)]
+    pub fn credential_id(mut self, id: impl Into<String>) -> Self {
+        self.credential_id = Some(id.into());
+        self
+    }
+
     /// Sets the monitoring interval
     pub fn monitoring_interval(mut self, seconds: u64) -> Self {
         self.monitoring_interval = seconds;
@@ -112,6 +208,12 @@ impl NodeBuilder {
             consecutive_failures: self.consecutive_failures,
             max_check_attempts: self.max_check_attempts,
             retry_interval: self.retry_interval,
+            cert_expires_at: None,
+            ping_stats: None,
+            hooks: HookCommands::default(),
+            parents: Vec::new(),
+            group: None,
+            tags: Vec::new(),
         }
     }
 }
@@ -164,6 +266,8 @@ pub mod fixtures {
             detail: MonitorDetail::Http {
                 url: "https://example.com".to_string(),
                 expected_status: 200,
+                timeout: 30,
+                options: Default::default(),
             },
             status: NodeStatus::Online,
             last_check: Some(Utc::now()),
@@ -173,6 +277,12 @@ pub mod fixtures {
             consecutive_failures: 0,
             max_check_attempts: 3,
             retry_interval: 15,
+            cert_expires_at: None,
+            ping_stats: None,
+            hooks: HookCommands::default(),
+            parents: Vec::new(),
+            group: None,
+            tags: Vec::new(),
         }
     }
 
@@ -186,6 +296,8 @@ pub mod fixtures {
                 host: "192.168.1.1".to_string(),
                 count: 4,
                 timeout: 5,
+                fallback_port: 80,
+                thresholds: Default::default(),
             },
             status: NodeStatus::Offline,
             last_check: None,
@@ -195,6 +307,12 @@ pub mod fixtures {
             consecutive_failures: 0,
             max_check_attempts: 3,
             retry_interval: 15,
+            cert_expires_at: None,
+            ping_stats: None,
+            hooks: HookCommands::default(),
+            parents: Vec::new(),
+            group: None,
+            tags: Vec::new(),
         }
     }
 }
@@ -209,6 +327,8 @@ pub mod assertions {
             MonitorDetail::Http {
                 url,
                 expected_status: status,
+                timeout: 30,
+                ..
             } => {
                 assert_eq!(url, expected_url);
                 assert_eq!(*status, expected_status);
@@ -229,6 +349,7 @@ pub mod assertions {
                 host,
                 count,
                 timeout,
+                ..
             } => {
                 assert_eq!(host, expected_host);
                 assert_eq!(*count, expected_count);
@@ -1,6 +1,9 @@
 // Unit tests for config module
 
-use net_monitor::config::AppConfig;
+use net_monitor::config::{
+    AppConfig, CredentialBackend, NodeColumn, SmtpSecurity, Theme, WebhookSettings, SETTING_KEYS,
+};
+use std::time::Duration;
 use tempfile::TempDir;
 
 #[test]
@@ -12,20 +15,20 @@ fn test_app_config_default() {
 
 #[test]
 fn test_app_config_clone() {
-    let config = AppConfig {};
+    let config = AppConfig::default();
     let _cloned = config.clone();
 }
 
 #[test]
 fn test_app_config_debug() {
-    let config = AppConfig {};
+    let config = AppConfig::default();
     let debug_str = format!("{:?}", config);
     assert!(debug_str.contains("AppConfig"));
 }
 
 #[test]
 fn test_app_config_serialization() {
-    let config = AppConfig {};
+    let config = AppConfig::default();
     let json = serde_json::to_string(&config).unwrap();
     assert!(json.contains('{'));
     assert!(json.contains('}'));
@@ -42,7 +45,7 @@ fn test_app_config_deserialization() {
 fn test_app_config_save_and_load() {
     let _temp_dir = TempDir::new().unwrap();
 
-    let config = AppConfig {};
+    let config = AppConfig::default();
 
     // Test serialization round-trip
     let json = serde_json::to_string_pretty(&config).unwrap();
@@ -51,7 +54,7 @@ fn test_app_config_save_and_load() {
 
 #[test]
 fn test_app_config_pretty_serialization() {
-    let config = AppConfig {};
+    let config = AppConfig::default();
     let json = serde_json::to_string_pretty(&config).unwrap();
     assert!(json.contains('{'));
     assert!(json.contains('}'));
@@ -73,11 +76,332 @@ fn test_app_config_deserialization_extra_fields() {
 
 #[test]
 fn test_app_config_save_and_load_roundtrip() {
-    let original_config = AppConfig {};
+    let original_config = AppConfig::default();
 
     // Serialize (like save)
     let json = serde_json::to_string_pretty(&original_config).unwrap();
 
     // Deserialize (like load)
-    let _loaded_config: AppConfig = serde_json::from_str(&json).unwrap();
+    let loaded_config: AppConfig = serde_json::from_str(&json).unwrap();
+    assert_eq!(loaded_config, original_config);
+}
+
+#[test]
+fn test_app_config_load_from_creates_default_file() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("instance").join("config.json");
+
+    let _config = AppConfig::load_from(&path).unwrap();
+    assert!(path.exists());
+
+    // A second load reads the file that was just written
+    let _config = AppConfig::load_from(&path).unwrap();
+}
+
+#[test]
+fn test_app_config_load_from_rejects_invalid_file() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("config.json");
+    std::fs::write(&path, "{ not json").unwrap();
+
+    assert!(AppConfig::load_from(&path).is_err());
+}
+
+#[test]
+fn test_app_config_partial_file_uses_defaults() {
+    let json = r#"{"defaults":{"monitoring_interval":30},"ui":{"theme":"light"}}"#;
+    let config: AppConfig = serde_json::from_str(json).unwrap();
+
+    assert_eq!(config.defaults.monitoring_interval, 30);
+    assert_eq!(
+        config.defaults.http_timeout,
+        AppConfig::default().defaults.http_timeout
+    );
+    assert_eq!(config.ui.theme, Theme::Light);
+    assert_eq!(config.ui.columns, NodeColumn::ALL.to_vec());
+    assert_eq!(config.engine, AppConfig::default().engine);
+    assert_eq!(config.notifications, AppConfig::default().notifications);
+    assert_eq!(config.credentials.backend, CredentialBackend::File);
+}
+
+#[test]
+fn test_app_config_default_is_valid() {
+    AppConfig::default().validate().unwrap();
+}
+
+#[test]
+fn test_app_config_validate_rejects_out_of_range_values() {
+    let mut config = AppConfig::default();
+    config.defaults.monitoring_interval = 0;
+    let err = config.validate().unwrap_err().to_string();
+    assert!(err.contains("defaults.monitoring_interval"), "{}", err);
+
+    let mut config = AppConfig::default();
+    config.engine.max_concurrent_checks = 0;
+    assert!(config.validate().is_err());
+
+    let mut config = AppConfig::default();
+    config.ui.refresh_rate_ms = 5;
+    let err = config.validate().unwrap_err().to_string();
+    assert!(err.contains("ui.refresh_rate_ms"), "{}", err);
+
+    let mut config = AppConfig::default();
+    config.ui.columns.clear();
+    assert!(config.validate().is_err());
+
+    let mut config = AppConfig::default();
+    config.ui.columns = vec![NodeColumn::Name, NodeColumn::Status, NodeColumn::Name];
+    let err = config.validate().unwrap_err().to_string();
+    assert!(err.contains("'name'"), "{}", err);
+}
+
+#[test]
+fn test_app_config_load_from_rejects_invalid_settings() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("config.json");
+    std::fs::write(&path, r#"{"engine":{"max_concurrent_checks":0}}"#).unwrap();
+
+    let err = AppConfig::load_from(&path).unwrap_err().to_string();
+    assert!(err.contains("engine.max_concurrent_checks"), "{}", err);
+}
+
+#[test]
+fn test_app_config_save_to_and_load_from_round_trip() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("config.json");
+
+    let mut config = AppConfig::default();
+    config.defaults.retry_interval = 15;
+    config.ui.columns = vec![NodeColumn::Status, NodeColumn::Name];
+    config.notifications.enabled = true;
+    config.save_to(&path).unwrap();
+
+    assert_eq!(AppConfig::load_from(&path).unwrap(), config);
+}
+
+#[test]
+fn test_app_config_every_setting_key_round_trips() {
+    let config = AppConfig::default();
+    let mut copy = AppConfig::default();
+
+    for key in SETTING_KEYS {
+        let value = config
+            .get_setting(key)
+            .unwrap_or_else(|| panic!("missing setting {}", key));
+        copy.set_setting(key, &value).unwrap();
+    }
+
+    assert_eq!(copy, config);
+}
+
+#[test]
+fn test_app_config_set_setting_parses_values() {
+    let mut config = AppConfig::default();
+
+    config.set_setting("defaults.http_timeout", "10").unwrap();
+    config.set_setting("ui.theme", "Light").unwrap();
+    config
+        .set_setting("ui.columns", "status, name,last_check")
+        .unwrap();
+    config.set_setting("notifications.enabled", "true").unwrap();
+    config
+        .set_setting("credentials.backend", "keyring")
+        .unwrap();
+
+    assert_eq!(config.defaults.http_timeout, 10);
+    assert_eq!(config.ui.theme, Theme::Light);
+    assert_eq!(
+        config.ui.columns,
+        vec![NodeColumn::Status, NodeColumn::Name, NodeColumn::LastCheck]
+    );
+    assert!(config.notifications.enabled);
+    assert_eq!(config.credentials.backend, CredentialBackend::Keyring);
+    assert_eq!(
+        config.get_setting("ui.columns").unwrap(),
+        "status,name,last_check"
+    );
+}
+
+#[test]
+fn test_app_config_set_setting_rejects_bad_input() {
+    let mut config = AppConfig::default();
+
+    let err = config
+        .set_setting("defaults.retry_interval", "soon")
+        .unwrap_err();
+    assert!(err.to_string().contains("defaults.retry_interval"));
+    assert!(config.set_setting("ui.theme", "neon").is_err());
+    assert!(config.set_setting("credentials.backend", "vault").is_err());
+    assert!(config.set_setting("ui.columns", "name,colour").is_err());
+    assert!(config.set_setting("no.such_key", "1").is_err());
+    assert!(config.get_setting("no.such_key").is_none());
+
+    // A failed update leaves the previous value in place
+    assert_eq!(config, AppConfig::default());
+}
+
+#[test]
+fn test_app_config_webhook_settings() {
+    let json = r#"{"notifications":{"enabled":true,"webhooks":[
+        {"url":"https://hooks.example.com/a","headers":["Authorization: Bearer abc"]}
+    ]}}"#;
+    let mut config: AppConfig = serde_json::from_str(json).unwrap();
+    config.validate().unwrap();
+    assert_eq!(config.notifications.max_attempts, 5);
+    assert_eq!(
+        config.get_setting("notifications.webhooks").unwrap(),
+        "https://hooks.example.com/a"
+    );
+
+    // Editing the URL list keeps the headers of webhooks that remain
+    config
+        .set_setting(
+            "notifications.webhooks",
+            "https://hooks.example.com/a, http://10.0.0.9:8080/alert",
+        )
+        .unwrap();
+    let webhooks = &config.notifications.webhooks;
+    assert_eq!(webhooks.len(), 2);
+    assert_eq!(
+        webhooks[0].headers[0].to_string(),
+        "Authorization: Bearer abc"
+    );
+    assert_eq!(
+        webhooks[1],
+        WebhookSettings::new("http://10.0.0.9:8080/alert")
+    );
+    config.validate().unwrap();
+
+    config
+        .set_setting("notifications.webhooks", "ftp://example.com")
+        .unwrap();
+    let err = config.validate().unwrap_err().to_string();
+    assert!(err.contains("notifications.webhooks"), "{}", err);
+    config.set_setting("notifications.webhooks", "").unwrap();
+    assert!(config.notifications.webhooks.is_empty());
+
+    config.notifications.max_attempts = 0;
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn test_app_config_email_settings() {
+    let json = r#"{"notifications":{"enabled":true,"email":{
+        "host":"smtp.example.com","security":"tls","credential":"smtp",
+        "from":"net-monitor <alerts@example.com>","to":["noc@example.com"]
+    }}}"#;
+    let mut config: AppConfig = serde_json::from_str(json).unwrap();
+    config.validate().unwrap();
+    let email = config.notifications.email.as_mut().unwrap();
+    assert_eq!(email.security, SmtpSecurity::Tls);
+    assert_eq!(email.port(), 465);
+    assert_eq!(email.batch_window_secs, 10);
+    email.port = Some(2465);
+    assert_eq!(email.port(), 2465);
+
+    email.to.push("not an address".to_string());
+    let err = config.validate().unwrap_err().to_string();
+    assert!(err.contains("notifications.email.to"), "{}", err);
+
+    let email = config.notifications.email.as_mut().unwrap();
+    email.to.clear();
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn test_app_config_hook_settings() {
+    let json = r#"{"hooks":{"on_down":"/usr/local/bin/page-oncall"}}"#;
+    let mut config: AppConfig = serde_json::from_str(json).unwrap();
+    config.validate().unwrap();
+    assert_eq!(
+        config.hooks.commands.on_down.as_deref(),
+        Some("/usr/local/bin/page-oncall")
+    );
+    assert_eq!(config.hooks.timeout_secs, 30);
+    assert_eq!(config.get_setting("hooks.on_recovery").unwrap(), "");
+
+    config
+        .set_setting("hooks.on_recovery", "logger recovered")
+        .unwrap();
+    config.set_setting("hooks.on_down", "").unwrap();
+    assert_eq!(
+        config.hooks.commands.on_recovery.as_deref(),
+        Some("logger recovered")
+    );
+    assert_eq!(config.hooks.commands.on_down, None);
+    assert_eq!(config.engine_config().hooks, config.hooks);
+
+    config.set_setting("hooks.timeout_secs", "0").unwrap();
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn test_app_config_metrics_settings() {
+    let mut config = AppConfig::default();
+    assert!(!config.metrics.enabled);
+    assert_eq!(
+        config.get_setting("metrics.listen").unwrap(),
+        "127.0.0.1:9469"
+    );
+
+    let json = r#"{"metrics":{"enabled":true,"listen":"0.0.0.0:9100"}}"#;
+    config = serde_json::from_str(json).unwrap();
+    config.validate().unwrap();
+    assert!(config.metrics.enabled);
+    assert_eq!(config.metrics.listen.port(), 9100);
+    assert_eq!(config.engine_config().metrics, config.metrics);
+
+    config.set_setting("metrics.listen", "[::1]:9200").unwrap();
+    assert_eq!(config.get_setting("metrics.listen").unwrap(), "[::1]:9200");
+    assert!(config.set_setting("metrics.listen", "localhost").is_err());
+    assert!(config.set_setting("metrics.enabled", "maybe").is_err());
+}
+
+#[test]
+fn test_app_config_api_settings() {
+    let mut config = AppConfig::default();
+    assert!(!config.api.enabled);
+    assert_eq!(config.get_setting("api.listen").unwrap(), "127.0.0.1:9470");
+
+    // The API cannot be enabled without a token
+    config.set_setting("api.enabled", "true").unwrap();
+    assert!(config.validate().is_err());
+    config.api.token = Some("  ".to_string());
+    assert!(config.validate().is_err());
+
+    let json = r#"{"api":{"enabled":true,"listen":"0.0.0.0:8080","token":"abc123"}}"#;
+    config = serde_json::from_str(json).unwrap();
+    config.validate().unwrap();
+    assert_eq!(config.api.token(), Some("abc123"));
+    assert_eq!(config.api.listen.port(), 8080);
+}
+
+#[test]
+fn test_app_config_engine_config_includes_notifications() {
+    let mut config = AppConfig::default();
+    config.notifications.enabled = true;
+    config.engine.max_concurrent_checks = 8;
+
+    let engine = config.engine_config();
+    assert_eq!(engine.notifications, config.notifications);
+    assert_eq!(engine.max_concurrent_checks, 8);
+}
+
+#[test]
+fn test_engine_settings_engine_config() {
+    let mut config = AppConfig::default();
+    config.engine.max_concurrent_checks = 8;
+    config.engine.check_deadline_secs = 20;
+    config.engine.max_start_jitter_secs = 0;
+    config.engine.raw_retention_days = 3;
+
+    let engine = config.engine.engine_config();
+    assert_eq!(engine.max_concurrent_checks, 8);
+    assert_eq!(engine.check_deadline, Duration::from_secs(20));
+    assert_eq!(engine.max_start_jitter, Duration::ZERO);
+    assert_eq!(engine.retention.raw_days, 3);
+    assert_eq!(
+        engine.retention.hour_rollup_days,
+        config.engine.hour_rollup_retention_days
+    );
 }
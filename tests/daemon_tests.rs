@@ -0,0 +1,67 @@
+use net_monitor::daemon::{pid_file_path, PidFile};
+use std::path::Path;
+use tempfile::TempDir;
+
+#[test]
+fn test_pid_file_contains_current_pid() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("net-monitor.pid");
+
+    let pid_file = PidFile::acquire(&path).unwrap();
+
+    assert_eq!(pid_file.path(), path);
+    let contents = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(contents.trim(), std::process::id().to_string());
+}
+
+#[test]
+fn test_pid_file_prevents_second_owner() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("net-monitor.pid");
+
+    let _first = PidFile::acquire(&path).unwrap();
+    let err = PidFile::acquire(&path)
+        .err()
+        .expect("second lock must fail");
+
+    let message = err.to_string();
+    assert!(message.contains("already running"));
+    assert!(message.contains(&std::process::id().to_string()));
+}
+
+#[test]
+fn test_pid_file_cleared_on_drop() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("net-monitor.pid");
+
+    {
+        let _pid_file = PidFile::acquire(&path).unwrap();
+        assert!(path.exists());
+    }
+
+    // The file stays behind, empty, so the lock is never split across two files
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "");
+    // The lock is free again once the previous owner is gone
+    assert!(PidFile::acquire(&path).is_ok());
+}
+
+#[test]
+fn test_pid_file_reuses_stale_file() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("net-monitor.pid");
+
+    // Left behind by a process that crashed without cleaning up
+    std::fs::write(&path, "999999\n").unwrap();
+
+    let _pid_file = PidFile::acquire(&path).unwrap();
+    let contents = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(contents.trim(), std::process::id().to_string());
+}
+
+#[test]
+fn test_pid_file_path_follows_database() {
+    assert_eq!(
+        pid_file_path(Path::new("/data/network_monitor.db")),
+        Path::new("/data/network_monitor.pid")
+    );
+}
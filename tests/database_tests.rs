@@ -1,8 +1,13 @@
 mod common;
 
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
 use common::{assertions, fixtures, NodeBuilder, TestDatabase};
-use net_monitor::models::{MonitorDetail, MonitoringResult, NodeStatus, StatusChange};
+use net_monitor::models::{
+    CheckSample, DnsProtocol, DnsRecordType, HttpMethod, HttpOptions, JsonAssertion,
+    MaintenanceScope, MaintenanceWindow, MonitorDetail, MonitoringResult, NodeStatus, PingMethod,
+    PingStats, PingThresholds, ResponseAssertions, RetentionPolicy, RollupResolution, StatusChange,
+    StatusRange, TlsMode, TlsOptions, UdpPayload, UdpResponseMatch,
+};
 
 #[test]
 fn test_database_persistence() {
@@ -92,6 +97,283 @@ fn test_update_node() {
     assert_eq!(updated_nodes[0].response_time, Some(150));
 }
 
+#[test]
+fn test_http_timeout_persists() {
+    let test_db = TestDatabase::new();
+
+    let mut node = fixtures::http_node();
+    node.detail = MonitorDetail::Http {
+        url: "https://example.com".to_string(),
+        expected_status: 200,
+        timeout: 7,
+        options: Default::default(),
+    };
+    let node_id = test_db.db.add_node(&node).unwrap();
+
+    let nodes = test_db.db.get_all_nodes().unwrap();
+    assert!(matches!(
+        nodes[0].detail,
+        MonitorDetail::Http { timeout: 7, .. }
+    ));
+
+    node.id = Some(node_id);
+    node.detail = MonitorDetail::Http {
+        url: "https://example.com".to_string(),
+        expected_status: 200,
+        timeout: 12,
+        options: Default::default(),
+    };
+    test_db.db.update_node(&node).unwrap();
+
+    let nodes = test_db.db.get_all_nodes().unwrap();
+    assert!(matches!(
+        nodes[0].detail,
+        MonitorDetail::Http { timeout: 12, .. }
+    ));
+}
+
+#[test]
+fn test_ssh_node_persists() {
+    let test_db = TestDatabase::new();
+
+    let mut node = NodeBuilder::new()
+        .name("Core switch")
+        .ssh("10.0.0.1", 2222, Some("show version"), Some("uptime is"))
+        .credential_id("cred_1")
+        .build();
+    let node_id = test_db.db.add_node(&node).unwrap();
+
+    let nodes = test_db.db.get_all_nodes().unwrap();
+    assert_eq!(nodes[0].detail, node.detail);
+    assert_eq!(nodes[0].credential_id.as_deref(), Some("cred_1"));
+
+    node.id = Some(node_id);
+    node.detail = MonitorDetail::Ssh {
+        host: "10.0.0.1".to_string(),
+        port: 22,
+        timeout: 15,
+        command: None,
+        expected_output: None,
+        host_key: Some("SHA256:uNiVztksCsDhcc0u9e8BujQXVUpKZIDTMczCvj3tD2s".to_string()),
+    };
+    test_db.db.update_node(&node).unwrap();
+
+    let nodes = test_db.db.get_all_nodes().unwrap();
+    assert_eq!(nodes[0].detail, node.detail);
+}
+
+#[test]
+fn test_dns_node_persists() {
+    let test_db = TestDatabase::new();
+
+    let mut node = NodeBuilder::new()
+        .name("Internal DNS")
+        .dns(
+            "10.0.0.53",
+            5353,
+            "_ldap._tcp.corp.local",
+            DnsRecordType::Srv,
+            &["0 100 389 dc1.corp.local", "0 100 389 dc2.corp.local"],
+        )
+        .build();
+    let node_id = test_db.db.add_node(&node).unwrap();
+
+    let nodes = test_db.db.get_all_nodes().unwrap();
+    assert_eq!(nodes[0].detail, node.detail);
+
+    node.id = Some(node_id);
+    node.detail = MonitorDetail::Dns {
+        server: "ns1.corp.local".to_string(),
+        port: 53,
+        name: "corp.local".to_string(),
+        record_type: DnsRecordType::Txt,
+        expected: vec![],
+        protocol: DnsProtocol::Tcp,
+        timeout: 3,
+    };
+    test_db.db.update_node(&node).unwrap();
+
+    let nodes = test_db.db.get_all_nodes().unwrap();
+    assert_eq!(nodes[0].detail, node.detail);
+}
+
+#[test]
+fn test_udp_node_persists() {
+    let test_db = TestDatabase::new();
+
+    let mut node = NodeBuilder::new()
+        .name("Syslog")
+        .udp("10.0.0.20", 514, "", None)
+        .build();
+    let node_id = test_db.db.add_node(&node).unwrap();
+
+    let nodes = test_db.db.get_all_nodes().unwrap();
+    assert_eq!(nodes[0].detail, node.detail);
+
+    node.id = Some(node_id);
+    node.detail = MonitorDetail::Udp {
+        host: "game.corp.local".to_string(),
+        port: 27015,
+        payload: UdpPayload::Hex(vec![0xff, 0xff, 0xff, 0xff, 0x54]),
+        expected_response: Some(UdpResponseMatch::HexPrefix(vec![0xff, 0xff])),
+        no_reply_is_up: true,
+        timeout: 3,
+    };
+    test_db.db.update_node(&node).unwrap();
+
+    let nodes = test_db.db.get_all_nodes().unwrap();
+    assert_eq!(nodes[0].detail, node.detail);
+}
+
+#[test]
+fn test_ping_options_and_stats_persist() {
+    let test_db = TestDatabase::new();
+
+    let mut node = NodeBuilder::new()
+        .name("Core Switch")
+        .ping("switch.corp.local", 5, 2)
+        .build();
+    if let MonitorDetail::Ping {
+        fallback_port,
+        thresholds,
+        ..
+    } = &mut node.detail
+    {
+        *fallback_port = 443;
+        *thresholds = PingThresholds {
+            loss_warning_percent: 20,
+            loss_critical_percent: 60,
+            latency_warning_ms: 50,
+            latency_critical_ms: 0,
+        };
+    }
+    let node_id = test_db.db.add_node(&node).unwrap();
+
+    let nodes = test_db.db.get_all_nodes().unwrap();
+    assert_eq!(nodes[0].detail, node.detail);
+    assert_eq!(nodes[0].ping_stats, None);
+
+    let ms = std::time::Duration::from_millis;
+    let stats = PingStats::from_round_trips(PingMethod::Tcp(443), 5, &[ms(3), ms(4), ms(9), ms(5)]);
+    node.id = Some(node_id);
+    node.ping_stats = Some(stats.clone());
+    test_db.db.update_node(&node).unwrap();
+    let nodes = test_db.db.get_all_nodes().unwrap();
+    assert_eq!(nodes[0].ping_stats, Some(stats.clone()));
+
+    let result = MonitoringResult {
+        id: None,
+        node_id,
+        timestamp: Utc::now(),
+        status: NodeStatus::Degraded,
+        response_time: Some(5),
+        details: Some(stats.to_string()),
+        cert_expires_at: None,
+        ping_stats: Some(stats.clone()),
+    };
+    test_db.db.add_monitoring_result(&result).unwrap();
+    let latest = test_db
+        .db
+        .get_latest_monitoring_result(node_id)
+        .unwrap()
+        .unwrap();
+    assert_eq!(latest.ping_stats, Some(stats));
+}
+
+#[test]
+fn test_http_options_persist() {
+    let test_db = TestDatabase::new();
+
+    let options = HttpOptions {
+        method: HttpMethod::Put,
+        headers: vec![
+            "Authorization: Bearer abc".parse().unwrap(),
+            "Accept: application/json".parse().unwrap(),
+        ],
+        body: Some("{}".to_string()),
+        accepted_statuses: StatusRange::parse_list("200-204,409").unwrap(),
+        max_redirects: 3,
+        assertions: ResponseAssertions {
+            not_contains: Some("maintenance".to_string()),
+            json: Some(JsonAssertion {
+                pointer: "/count".to_string(),
+                value: serde_json::json!(3),
+            }),
+            max_body_bytes: Some(4096),
+            ..Default::default()
+        },
+        tls: TlsOptions {
+            mode: TlsMode::Verify,
+            ca_bundle: Some("/etc/ssl/internal-ca.pem".into()),
+            expiry_warning_days: 30,
+            expiry_critical_days: 0,
+        },
+    };
+    let mut node = NodeBuilder::new()
+        .http("https://api.example.com", 200)
+        .http_options(options)
+        .build();
+    let node_id = test_db.db.add_node(&node).unwrap();
+
+    let nodes = test_db.db.get_all_nodes().unwrap();
+    assert_eq!(nodes[0].detail, node.detail);
+
+    // Clearing the options stores the defaults again
+    node = NodeBuilder::new()
+        .http("https://api.example.com", 200)
+        .build();
+    node.id = Some(node_id);
+    test_db.db.update_node(&node).unwrap();
+
+    let nodes = test_db.db.get_all_nodes().unwrap();
+    assert_eq!(nodes[0].detail, node.detail);
+}
+
+#[test]
+fn test_tls_options_and_certificate_expiry_persist() {
+    let test_db = TestDatabase::new();
+
+    let mut node = NodeBuilder::new()
+        .http("https://appliance.local", 200)
+        .http_options(HttpOptions {
+            tls: TlsOptions {
+                mode: "sha256:E92D80D4A58548E2151B9D60C2B74842C04144E6409966F3BB93F4AE5842C771"
+                    .parse()
+                    .unwrap(),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .build();
+    node.cert_expires_at = Some(
+        DateTime::parse_from_rfc3339("2027-03-01T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc),
+    );
+    test_db.db.add_node(&node).unwrap();
+
+    let nodes = test_db.db.get_all_nodes().unwrap();
+    assert_eq!(nodes[0].detail, node.detail);
+    assert_eq!(nodes[0].cert_expires_at, node.cert_expires_at);
+}
+
+#[test]
+fn test_node_hooks_persist() {
+    let test_db = TestDatabase::new();
+
+    let mut node = fixtures::ping_node();
+    node.hooks.on_down = Some("/usr/local/bin/plug off".to_string());
+    node.hooks.on_recovery = Some("/usr/local/bin/plug on".to_string());
+    let node_id = test_db.db.add_node(&node).unwrap();
+    assert_eq!(test_db.db.get_all_nodes().unwrap()[0].hooks, node.hooks);
+
+    node.id = Some(node_id);
+    node.hooks.on_down = None;
+    node.hooks.on_degraded = Some("logger degraded".to_string());
+    test_db.db.update_node(&node).unwrap();
+    assert_eq!(test_db.db.get_all_nodes().unwrap()[0].hooks, node.hooks);
+}
+
 #[test]
 fn test_delete_node() {
     let test_db = TestDatabase::new();
@@ -168,9 +450,12 @@ fn test_unit_add_and_get_http_node() {
         MonitorDetail::Http {
             url,
             expected_status,
+            timeout,
+            ..
         } => {
             assert_eq!(url, "https://example.com");
             assert_eq!(*expected_status, 200);
+            assert_eq!(*timeout, 30);
         }
         _ => panic!("Expected HTTP monitor detail"),
     }
@@ -198,6 +483,7 @@ fn test_unit_add_and_get_ping_node() {
             host,
             count,
             timeout,
+            ..
         } => {
             assert_eq!(host, "192.168.1.1");
             assert_eq!(*count, 4);
@@ -256,6 +542,8 @@ fn test_add_monitoring_result() {
         status: NodeStatus::Online,
         response_time: Some(150),
         details: Some("Success".to_string()),
+        cert_expires_at: None,
+        ping_stats: None,
     };
 
     let result_id = test_db.db.add_monitoring_result(&result).unwrap();
@@ -346,6 +634,7 @@ fn test_add_status_change() {
         to_status: NodeStatus::Online,
         changed_at: Utc::now(),
         duration_ms: None,
+        in_maintenance: false,
     };
 
     let change_id = test_db.db.add_status_change(&status_change).unwrap();
@@ -368,6 +657,7 @@ fn test_get_status_changes() {
             to_status: NodeStatus::Online,
             changed_at: now - Duration::seconds(300),
             duration_ms: None,
+            in_maintenance: false,
         },
         StatusChange {
             id: None,
@@ -376,6 +666,7 @@ fn test_get_status_changes() {
             to_status: NodeStatus::Offline,
             changed_at: now - Duration::seconds(200),
             duration_ms: Some(100000),
+            in_maintenance: false,
         },
         StatusChange {
             id: None,
@@ -384,6 +675,7 @@ fn test_get_status_changes() {
             to_status: NodeStatus::Online,
             changed_at: now - Duration::seconds(100),
             duration_ms: Some(100000),
+            in_maintenance: false,
         },
     ];
 
@@ -424,6 +716,7 @@ fn test_get_latest_status_change() {
         to_status: NodeStatus::Online,
         changed_at: now,
         duration_ms: None,
+        in_maintenance: false,
     };
     test_db.db.add_status_change(&status_change).unwrap();
 
@@ -441,6 +734,7 @@ fn test_get_latest_status_change() {
         to_status: NodeStatus::Offline,
         changed_at: now + Duration::seconds(60),
         duration_ms: Some(60000),
+        in_maintenance: false,
     };
     test_db.db.add_status_change(&second_change).unwrap();
 
@@ -469,6 +763,7 @@ fn test_get_current_status_duration() {
         to_status: NodeStatus::Online,
         changed_at,
         duration_ms: None,
+        in_maintenance: false,
     };
     test_db.db.add_status_change(&status_change).unwrap();
 
@@ -506,6 +801,7 @@ fn test_calculate_uptime_percentage() {
             to_status: NodeStatus::Online,
             changed_at: start_time,
             duration_ms: None,
+            in_maintenance: false,
         },
         StatusChange {
             id: None,
@@ -514,6 +810,7 @@ fn test_calculate_uptime_percentage() {
             to_status: NodeStatus::Offline,
             changed_at: start_time + Duration::seconds(400),
             duration_ms: Some(400000),
+            in_maintenance: false,
         },
         StatusChange {
             id: None,
@@ -522,6 +819,7 @@ fn test_calculate_uptime_percentage() {
             to_status: NodeStatus::Online,
             changed_at: start_time + Duration::seconds(700),
             duration_ms: Some(300000),
+            in_maintenance: false,
         },
     ];
 
@@ -561,6 +859,7 @@ fn test_uptime_with_offline_period_starting_before_window() {
             to_status: NodeStatus::Online,
             changed_at: base_time,
             duration_ms: None,
+            in_maintenance: false,
         },
         StatusChange {
             id: None,
@@ -569,6 +868,7 @@ fn test_uptime_with_offline_period_starting_before_window() {
             to_status: NodeStatus::Offline,
             changed_at: base_time + Duration::seconds(200),
             duration_ms: Some(200000),
+            in_maintenance: false,
         },
         StatusChange {
             id: None,
@@ -577,6 +877,7 @@ fn test_uptime_with_offline_period_starting_before_window() {
             to_status: NodeStatus::Online,
             changed_at: base_time + Duration::seconds(800),
             duration_ms: Some(600000),
+            in_maintenance: false,
         },
     ];
 
@@ -622,6 +923,7 @@ fn test_uptime_with_node_offline_at_window_start() {
             to_status: NodeStatus::Offline,
             changed_at: base_time,
             duration_ms: Some(100000),
+            in_maintenance: false,
         },
         StatusChange {
             id: None,
@@ -630,6 +932,7 @@ fn test_uptime_with_node_offline_at_window_start() {
             to_status: NodeStatus::Online,
             changed_at: base_time + Duration::seconds(700),
             duration_ms: Some(700000),
+            in_maintenance: false,
         },
     ];
 
@@ -675,6 +978,7 @@ fn test_uptime_with_node_offline_past_window_end() {
             to_status: NodeStatus::Offline,
             changed_at: base_time + Duration::seconds(300),
             duration_ms: Some(300000),
+            in_maintenance: false,
         },
         StatusChange {
             id: None,
@@ -683,6 +987,7 @@ fn test_uptime_with_node_offline_past_window_end() {
             to_status: NodeStatus::Online,
             changed_at: base_time + Duration::seconds(1500),
             duration_ms: Some(1200000),
+            in_maintenance: false,
         },
     ];
 
@@ -733,6 +1038,7 @@ fn test_uptime_with_multiple_transitions_across_boundaries() {
             to_status: NodeStatus::Offline,
             changed_at: base_time,
             duration_ms: None,
+            in_maintenance: false,
         },
         StatusChange {
             id: None,
@@ -741,6 +1047,7 @@ fn test_uptime_with_multiple_transitions_across_boundaries() {
             to_status: NodeStatus::Online,
             changed_at: base_time + Duration::seconds(100),
             duration_ms: Some(100000),
+            in_maintenance: false,
         },
         StatusChange {
             id: None,
@@ -749,6 +1056,7 @@ fn test_uptime_with_multiple_transitions_across_boundaries() {
             to_status: NodeStatus::Offline,
             changed_at: base_time + Duration::seconds(200),
             duration_ms: Some(100000),
+            in_maintenance: false,
         },
         StatusChange {
             id: None,
@@ -757,6 +1065,7 @@ fn test_uptime_with_multiple_transitions_across_boundaries() {
             to_status: NodeStatus::Online,
             changed_at: base_time + Duration::seconds(600),
             duration_ms: Some(400000),
+            in_maintenance: false,
         },
         StatusChange {
             id: None,
@@ -765,6 +1074,7 @@ fn test_uptime_with_multiple_transitions_across_boundaries() {
             to_status: NodeStatus::Offline,
             changed_at: base_time + Duration::seconds(800),
             duration_ms: Some(200000),
+            in_maintenance: false,
         },
         StatusChange {
             id: None,
@@ -773,6 +1083,7 @@ fn test_uptime_with_multiple_transitions_across_boundaries() {
             to_status: NodeStatus::Online,
             changed_at: base_time + Duration::seconds(1000),
             duration_ms: Some(200000),
+            in_maintenance: false,
         },
         StatusChange {
             id: None,
@@ -781,6 +1092,7 @@ fn test_uptime_with_multiple_transitions_across_boundaries() {
             to_status: NodeStatus::Offline,
             changed_at: base_time + Duration::seconds(1800),
             duration_ms: Some(800000),
+            in_maintenance: false,
         },
     ];
 
@@ -852,6 +1164,7 @@ fn test_uptime_no_status_changes_before_window() {
         to_status: NodeStatus::Offline,
         changed_at: base_time + Duration::seconds(500),
         duration_ms: Some(500000),
+        in_maintenance: false,
     };
 
     test_db.db.add_status_change(&change).unwrap();
@@ -887,6 +1200,7 @@ fn test_status_change_with_duration() {
         to_status: NodeStatus::Offline,
         changed_at: Utc::now(),
         duration_ms: Some(120000), // 2 minutes
+        in_maintenance: false,
     };
 
     test_db.db.add_status_change(&status_change).unwrap();
@@ -906,6 +1220,7 @@ fn test_status_change_helper_methods() {
         to_status: NodeStatus::Offline,
         changed_at: Utc::now(),
         duration_ms: None,
+        in_maintenance: false,
     };
     assert!(degradation.is_degradation());
     assert!(!degradation.is_recovery());
@@ -918,6 +1233,7 @@ fn test_status_change_helper_methods() {
         to_status: NodeStatus::Online,
         changed_at: Utc::now(),
         duration_ms: None,
+        in_maintenance: false,
     };
     assert!(recovery.is_recovery());
     assert!(!recovery.is_degradation());
@@ -950,6 +1266,7 @@ fn test_status_changes_cascade_delete() {
         to_status: NodeStatus::Online,
         changed_at: Utc::now(),
         duration_ms: None,
+        in_maintenance: false,
     };
     test_db.db.add_status_change(&status_change).unwrap();
 
@@ -990,6 +1307,8 @@ fn test_get_latest_monitoring_result_single_result() {
         status: NodeStatus::Online,
         response_time: Some(100),
         details: Some("Test result".to_string()),
+        cert_expires_at: None,
+        ping_stats: None,
     };
     test_db
         .db
@@ -1022,6 +1341,8 @@ fn test_get_latest_monitoring_result_multiple_results() {
         status: NodeStatus::Offline,
         response_time: Some(100),
         details: Some("First check".to_string()),
+        cert_expires_at: None,
+        ping_stats: None,
     };
     test_db.db.add_monitoring_result(&result1).unwrap();
 
@@ -1036,6 +1357,8 @@ fn test_get_latest_monitoring_result_multiple_results() {
         status: NodeStatus::Online,
         response_time: Some(150),
         details: Some("Second check".to_string()),
+        cert_expires_at: None,
+        ping_stats: None,
     };
     test_db.db.add_monitoring_result(&result2).unwrap();
 
@@ -1064,6 +1387,8 @@ fn test_monitoring_result_not_recorded_on_unchanged_status() {
         status: NodeStatus::Online,
         response_time: Some(100),
         details: Some("First check - Online".to_string()),
+        cert_expires_at: None,
+        ping_stats: None,
     };
     test_db.db.add_monitoring_result(&result1).unwrap();
 
@@ -1085,6 +1410,8 @@ fn test_monitoring_result_not_recorded_on_unchanged_status() {
         status: NodeStatus::Offline,
         response_time: Some(200),
         details: Some("Status changed to Offline".to_string()),
+        cert_expires_at: None,
+        ping_stats: None,
     };
     test_db.db.add_monitoring_result(&result2).unwrap();
 
@@ -1157,3 +1484,668 @@ fn test_update_display_orders() {
     assert_eq!(nodes[1].name, "Bravo");
     assert_eq!(nodes[2].name, "Alpha");
 }
+
+// ========== Check Time Series Tests ==========
+
+fn add_sample(test_db: &TestDatabase, node_id: i64, at: chrono::DateTime<Utc>, ok: bool, ms: u64) {
+    test_db
+        .db
+        .add_check_sample(&CheckSample {
+            node_id,
+            checked_at: at,
+            success: ok,
+            response_time: Some(ms),
+        })
+        .unwrap();
+}
+
+#[test]
+fn test_check_samples_round_trip() {
+    let test_db = TestDatabase::new();
+    let node_id = test_db.db.add_node(&fixtures::http_node()).unwrap();
+    let now = Utc::now();
+
+    add_sample(&test_db, node_id, now - Duration::minutes(2), true, 40);
+    add_sample(&test_db, node_id, now - Duration::minutes(1), false, 5000);
+
+    let samples = test_db
+        .db
+        .get_check_samples(node_id, now - Duration::hours(1), now)
+        .unwrap();
+    assert_eq!(samples.len(), 2);
+    assert!(samples[0].success);
+    assert_eq!(samples[0].response_time, Some(40));
+    assert!(!samples[1].success);
+    assert_eq!(
+        samples[0].checked_at.timestamp_millis(),
+        (now - Duration::minutes(2)).timestamp_millis()
+    );
+}
+
+#[test]
+fn test_refresh_rollups_builds_all_resolutions() {
+    let test_db = TestDatabase::new();
+    let node_id = test_db.db.add_node(&fixtures::http_node()).unwrap();
+
+    // Three samples in the first minute of a day, one in the next minute
+    let day = chrono::DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z")
+        .unwrap()
+        .with_timezone(&Utc);
+    add_sample(&test_db, node_id, day + Duration::seconds(5), true, 10);
+    add_sample(&test_db, node_id, day + Duration::seconds(25), true, 30);
+    add_sample(&test_db, node_id, day + Duration::seconds(45), false, 9000);
+    add_sample(&test_db, node_id, day + Duration::seconds(65), true, 50);
+
+    let written = test_db.db.refresh_rollups(day + Duration::days(2)).unwrap();
+    // Two minute buckets, one hour bucket, one day bucket
+    assert_eq!(written, 4);
+
+    let minutes = test_db
+        .db
+        .get_rollups(
+            node_id,
+            RollupResolution::Minute,
+            day,
+            day + Duration::days(1),
+        )
+        .unwrap();
+    assert_eq!(minutes.len(), 2);
+    assert_eq!(minutes[0].bucket_start, day);
+    assert_eq!(minutes[0].stats.sample_count, 3);
+    assert_eq!(minutes[0].stats.success_count, 2);
+    assert_eq!(minutes[0].stats.min_response_time, Some(10));
+    assert_eq!(minutes[0].stats.max_response_time, Some(30));
+    assert_eq!(minutes[0].stats.avg_response_time, Some(20.0));
+
+    let days = test_db
+        .db
+        .get_rollups(node_id, RollupResolution::Day, day, day + Duration::days(1))
+        .unwrap();
+    assert_eq!(days.len(), 1);
+    assert_eq!(days[0].stats.sample_count, 4);
+    assert_eq!(days[0].stats.p95_response_time, Some(50));
+
+    // A second refresh has nothing new to do
+    assert_eq!(
+        test_db.db.refresh_rollups(day + Duration::days(2)).unwrap(),
+        0
+    );
+}
+
+#[test]
+fn test_refresh_rollups_skips_open_buckets() {
+    let test_db = TestDatabase::new();
+    let node_id = test_db.db.add_node(&fixtures::http_node()).unwrap();
+    let now = Utc::now();
+
+    add_sample(&test_db, node_id, now, true, 10);
+    test_db.db.refresh_rollups(now).unwrap();
+
+    let minutes = test_db
+        .db
+        .get_rollups(
+            node_id,
+            RollupResolution::Minute,
+            now - Duration::hours(1),
+            now + Duration::hours(1),
+        )
+        .unwrap();
+    assert!(minutes.is_empty());
+}
+
+#[test]
+fn test_prune_history_keeps_rollups() {
+    let test_db = TestDatabase::new();
+    let node_id = test_db.db.add_node(&fixtures::http_node()).unwrap();
+    let now = Utc::now();
+    let old = now - Duration::days(10);
+
+    add_sample(&test_db, node_id, old, true, 10);
+    add_sample(&test_db, node_id, now - Duration::days(1), true, 20);
+    test_db.db.refresh_rollups(now).unwrap();
+
+    let deleted = test_db
+        .db
+        .prune_history(&RetentionPolicy::default(), now)
+        .unwrap();
+    assert_eq!(deleted, 1);
+
+    let samples = test_db
+        .db
+        .get_check_samples(node_id, now - Duration::days(30), now)
+        .unwrap();
+    assert_eq!(samples.len(), 1);
+    assert_eq!(samples[0].response_time, Some(20));
+
+    // The pruned sample is still represented in its rollups
+    let hours = test_db
+        .db
+        .get_rollups(
+            node_id,
+            RollupResolution::Hour,
+            old - Duration::hours(1),
+            old + Duration::hours(1),
+        )
+        .unwrap();
+    assert_eq!(hours.len(), 1);
+    assert_eq!(hours[0].stats.min_response_time, Some(10));
+}
+
+#[test]
+fn test_prune_history_waits_for_rollups() {
+    let test_db = TestDatabase::new();
+    let node_id = test_db.db.add_node(&fixtures::http_node()).unwrap();
+    let now = Utc::now();
+
+    add_sample(&test_db, node_id, now - Duration::days(10), true, 10);
+
+    // Nothing has been rolled up yet, so no raw rows may be dropped
+    let deleted = test_db
+        .db
+        .prune_history(&RetentionPolicy::default(), now)
+        .unwrap();
+    assert_eq!(deleted, 0);
+}
+
+#[test]
+fn test_prune_history_expires_minute_rollups() {
+    let test_db = TestDatabase::new();
+    let node_id = test_db.db.add_node(&fixtures::http_node()).unwrap();
+    let now = Utc::now();
+    let old = now - Duration::days(40);
+
+    add_sample(&test_db, node_id, old, true, 10);
+    test_db.db.refresh_rollups(now).unwrap();
+    test_db
+        .db
+        .prune_history(&RetentionPolicy::default(), now)
+        .unwrap();
+
+    let window = (old - Duration::days(1), old + Duration::days(1));
+    let minutes = test_db
+        .db
+        .get_rollups(node_id, RollupResolution::Minute, window.0, window.1)
+        .unwrap();
+    let hours = test_db
+        .db
+        .get_rollups(node_id, RollupResolution::Hour, window.0, window.1)
+        .unwrap();
+    assert!(minutes.is_empty());
+    assert_eq!(hours.len(), 1);
+}
+
+#[test]
+fn test_delete_node_removes_check_history() {
+    let test_db = TestDatabase::new();
+    let node_id = test_db.db.add_node(&fixtures::http_node()).unwrap();
+    let now = Utc::now();
+
+    add_sample(&test_db, node_id, now - Duration::hours(2), true, 10);
+    test_db.db.refresh_rollups(now).unwrap();
+    test_db.db.delete_node(node_id).unwrap();
+
+    let samples = test_db
+        .db
+        .get_check_samples(node_id, now - Duration::days(1), now)
+        .unwrap();
+    let hours = test_db
+        .db
+        .get_rollups(
+            node_id,
+            RollupResolution::Hour,
+            now - Duration::days(1),
+            now,
+        )
+        .unwrap();
+    assert!(samples.is_empty());
+    assert!(hours.is_empty());
+}
+
+// ========== Maintenance Window Tests ==========
+
+fn maintenance(
+    scope: MaintenanceScope,
+    starts_at: DateTime<Utc>,
+    minutes: u32,
+) -> MaintenanceWindow {
+    MaintenanceWindow {
+        id: None,
+        scope,
+        starts_at,
+        duration_mins: minutes,
+        recurrence: None,
+        reason: Some("Switch firmware".to_string()),
+    }
+}
+
+#[test]
+fn test_maintenance_windows_round_trip() {
+    let test_db = TestDatabase::new();
+    let first = test_db.db.add_node(&fixtures::http_node()).unwrap();
+    let second = test_db.db.add_node(&fixtures::ping_node()).unwrap();
+    let now = Utc::now();
+
+    let everything = maintenance(MaintenanceScope::All, now + Duration::hours(1), 30);
+    let mut weekly = maintenance(
+        MaintenanceScope::Node(first),
+        now - Duration::minutes(5),
+        60,
+    );
+    weekly.recurrence = Some("0 3 * * sun".to_string());
+    test_db.db.add_maintenance_window(&everything).unwrap();
+    let weekly_id = test_db.db.add_maintenance_window(&weekly).unwrap();
+
+    let windows = test_db.db.get_maintenance_windows().unwrap();
+    assert_eq!(windows.len(), 2);
+    assert_eq!(windows[0].id, Some(weekly_id));
+    assert_eq!(windows[0].recurrence, weekly.recurrence);
+    assert_eq!(windows[1].scope, MaintenanceScope::All);
+    assert_eq!(windows[1].reason.as_deref(), Some("Switch firmware"));
+
+    let for_second = test_db.db.get_maintenance_windows_for_node(second).unwrap();
+    assert_eq!(for_second.len(), 1);
+    assert_eq!(for_second[0].scope, MaintenanceScope::All);
+
+    let silenced = maintenance(
+        MaintenanceScope::Node(second),
+        now - Duration::minutes(1),
+        10,
+    );
+    test_db.db.add_maintenance_window(&silenced).unwrap();
+    assert!(test_db.db.in_maintenance(second, now).unwrap());
+    assert!(!test_db
+        .db
+        .in_maintenance(second, now + Duration::minutes(10))
+        .unwrap());
+    assert!(test_db
+        .db
+        .in_maintenance(second, now + Duration::minutes(70))
+        .unwrap());
+
+    assert!(test_db.db.delete_maintenance_window(weekly_id).unwrap());
+    assert!(!test_db.db.delete_maintenance_window(weekly_id).unwrap());
+
+    // A node's windows go with it
+    test_db.db.delete_node(second).unwrap();
+    assert_eq!(test_db.db.get_maintenance_windows().unwrap().len(), 1);
+}
+
+#[test]
+fn test_tag_maintenance_windows() {
+    let test_db = TestDatabase::new();
+    let mut tagged = fixtures::http_node();
+    tagged.tags = vec!["prod".to_string(), "edge".to_string()];
+    let tagged_id = test_db.db.add_node(&tagged).unwrap();
+    tagged.id = Some(tagged_id);
+    let other = test_db.db.add_node(&fixtures::ping_node()).unwrap();
+    let now = Utc::now();
+
+    let window = maintenance(
+        MaintenanceScope::Tag("Prod".to_string()),
+        now - Duration::minutes(5),
+        30,
+    );
+    test_db.db.add_maintenance_window(&window).unwrap();
+    assert_eq!(
+        test_db.db.get_maintenance_windows().unwrap()[0].scope,
+        MaintenanceScope::Tag("Prod".to_string())
+    );
+
+    // Tags match without regard to case
+    assert_eq!(
+        test_db
+            .db
+            .get_maintenance_windows_for_node(tagged_id)
+            .unwrap()
+            .len(),
+        1
+    );
+    assert!(test_db.db.in_maintenance(tagged_id, now).unwrap());
+    assert!(test_db
+        .db
+        .get_maintenance_windows_for_node(other)
+        .unwrap()
+        .is_empty());
+    assert!(!test_db.db.in_maintenance(other, now).unwrap());
+
+    // Dropping the tag takes the node out of the window
+    tagged.tags = vec!["edge".to_string()];
+    test_db.db.update_node(&tagged).unwrap();
+    assert!(!test_db.db.in_maintenance(tagged_id, now).unwrap());
+}
+
+#[test]
+fn test_status_change_in_maintenance_persists() {
+    let test_db = TestDatabase::new();
+    let node_id = test_db.db.add_node(&fixtures::http_node()).unwrap();
+
+    test_db
+        .db
+        .add_status_change(&StatusChange {
+            id: None,
+            node_id,
+            from_status: NodeStatus::Online,
+            to_status: NodeStatus::Offline,
+            changed_at: Utc::now(),
+            duration_ms: None,
+            in_maintenance: true,
+        })
+        .unwrap();
+
+    let change = test_db
+        .db
+        .get_latest_status_change(node_id)
+        .unwrap()
+        .unwrap();
+    assert!(change.in_maintenance);
+    assert!(test_db.db.get_status_changes(node_id, None).unwrap()[0].in_maintenance);
+}
+
+#[test]
+fn test_uptime_excludes_maintenance() {
+    let test_db = TestDatabase::new();
+    let node_id = test_db.db.add_node(&fixtures::http_node()).unwrap();
+    let start_time = DateTime::parse_from_rfc3339("2026-07-01T00:00:00Z")
+        .unwrap()
+        .with_timezone(&Utc);
+    let end_time = start_time + Duration::hours(10);
+
+    // Offline from 2h to 5h
+    for (from, to, at) in [
+        (NodeStatus::Offline, NodeStatus::Online, 0),
+        (NodeStatus::Online, NodeStatus::Offline, 2),
+        (NodeStatus::Offline, NodeStatus::Online, 5),
+    ] {
+        test_db
+            .db
+            .add_status_change(&StatusChange {
+                id: None,
+                node_id,
+                from_status: from,
+                to_status: to,
+                changed_at: start_time + Duration::hours(at),
+                duration_ms: None,
+                in_maintenance: false,
+            })
+            .unwrap();
+    }
+    let uptime = test_db
+        .db
+        .calculate_uptime_percentage(node_id, start_time, end_time)
+        .unwrap();
+    assert!((uptime - 70.0).abs() < 0.01, "Expected 70%, got {}", uptime);
+
+    // Maintenance from 4h to 6h covers the last hour of the outage, and an
+    // overlapping window is not subtracted twice
+    for window in [
+        maintenance(
+            MaintenanceScope::Node(node_id),
+            start_time + Duration::hours(4),
+            120,
+        ),
+        maintenance(
+            MaintenanceScope::All,
+            start_time + Duration::minutes(270),
+            30,
+        ),
+    ] {
+        test_db.db.add_maintenance_window(&window).unwrap();
+    }
+    let uptime = test_db
+        .db
+        .calculate_uptime_percentage(node_id, start_time, end_time)
+        .unwrap();
+    assert!((uptime - 80.0).abs() < 0.01, "Expected 80%, got {}", uptime);
+
+    // Windows of other nodes don't count
+    let other = test_db.db.add_node(&fixtures::ping_node()).unwrap();
+    test_db
+        .db
+        .add_maintenance_window(&maintenance(
+            MaintenanceScope::Node(other),
+            start_time + Duration::hours(2),
+            60,
+        ))
+        .unwrap();
+    let uptime = test_db
+        .db
+        .calculate_uptime_percentage(node_id, start_time, end_time)
+        .unwrap();
+    assert!((uptime - 80.0).abs() < 0.01, "Expected 80%, got {}", uptime);
+
+    // Nor do windows for tags the node doesn't carry, while one for its tag does
+    let mut node = test_db
+        .db
+        .get_all_nodes()
+        .unwrap()
+        .into_iter()
+        .find(|node| node.id == Some(node_id))
+        .unwrap();
+    node.tags = vec!["prod".to_string()];
+    test_db.db.update_node(&node).unwrap();
+    for tag in ["staging", "PROD"] {
+        test_db
+            .db
+            .add_maintenance_window(&maintenance(
+                MaintenanceScope::Tag(tag.to_string()),
+                start_time + Duration::hours(2),
+                60,
+            ))
+            .unwrap();
+    }
+    let uptime = test_db
+        .db
+        .calculate_uptime_percentage(node_id, start_time, end_time)
+        .unwrap();
+    assert!((uptime - 90.0).abs() < 0.01, "Expected 90%, got {}", uptime);
+}
+
+// ========== Dependency Tests ==========
+
+#[test]
+fn test_node_parents_round_trip() {
+    let test_db = TestDatabase::new();
+    let switch = test_db
+        .db
+        .add_node(
+            &NodeBuilder::new()
+                .name("Core Switch")
+                .ping("10.0.0.1", 1, 5)
+                .build(),
+        )
+        .unwrap();
+    let router = test_db
+        .db
+        .add_node(
+            &NodeBuilder::new()
+                .name("Router")
+                .ping("10.0.0.1", 1, 5)
+                .build(),
+        )
+        .unwrap();
+
+    let mut ap = NodeBuilder::new()
+        .name("Office AP")
+        .ping("10.0.0.1", 1, 5)
+        .build();
+    ap.parents = vec![router, switch];
+    let ap_id = test_db.db.add_node(&ap).unwrap();
+    let stored = |id| {
+        test_db
+            .db
+            .get_all_nodes()
+            .unwrap()
+            .into_iter()
+            .find(|n| n.id == Some(id))
+            .unwrap()
+    };
+    // Parents keep the order they were given in
+    assert_eq!(stored(ap_id).parents, vec![router, switch]);
+    assert!(stored(switch).parents.is_empty());
+
+    ap.id = Some(ap_id);
+    ap.parents = vec![switch];
+    test_db.db.update_node(&ap).unwrap();
+    assert_eq!(stored(ap_id).parents, vec![switch]);
+
+    // Deleting a parent drops it from its children
+    test_db.db.delete_node(switch).unwrap();
+    assert!(stored(ap_id).parents.is_empty());
+}
+
+#[test]
+fn test_node_parents_rejected() {
+    let test_db = TestDatabase::new();
+    let mut switch = NodeBuilder::new()
+        .name("Core Switch")
+        .ping("10.0.0.1", 1, 5)
+        .build();
+    let switch_id = test_db.db.add_node(&switch).unwrap();
+    switch.id = Some(switch_id);
+    let mut ap = NodeBuilder::new()
+        .name("Office AP")
+        .ping("10.0.0.1", 1, 5)
+        .build();
+    ap.parents = vec![switch_id];
+    let ap_id = test_db.db.add_node(&ap).unwrap();
+
+    let mut missing = NodeBuilder::new()
+        .name("Printer")
+        .ping("10.0.0.1", 1, 5)
+        .build();
+    missing.parents = vec![ap_id + 100];
+    let err = test_db.db.add_node(&missing).unwrap_err();
+    assert!(err.to_string().contains("No node with ID"), "{}", err);
+
+    switch.parents = vec![switch_id];
+    let err = test_db.db.update_node(&switch).unwrap_err();
+    assert!(
+        err.to_string().contains("can't depend on itself"),
+        "{}",
+        err
+    );
+
+    switch.parents = vec![ap_id];
+    let err = test_db.db.update_node(&switch).unwrap_err();
+    assert!(err.to_string().contains("already depends on it"), "{}", err);
+    assert_eq!(test_db.db.get_all_nodes().unwrap().len(), 2);
+}
+
+#[test]
+fn test_uptime_excludes_unreachable() {
+    let test_db = TestDatabase::new();
+    let node_id = test_db.db.add_node(&fixtures::http_node()).unwrap();
+    let start_time = DateTime::parse_from_rfc3339("2026-07-01T00:00:00Z")
+        .unwrap()
+        .with_timezone(&Utc);
+    let end_time = start_time + Duration::hours(10);
+
+    // Unreachable from 2h to 4h, then offline until 5h
+    for (from, to, at) in [
+        (NodeStatus::Offline, NodeStatus::Online, 0),
+        (NodeStatus::Online, NodeStatus::Unreachable, 2),
+        (NodeStatus::Unreachable, NodeStatus::Offline, 4),
+        (NodeStatus::Offline, NodeStatus::Online, 5),
+    ] {
+        test_db
+            .db
+            .add_status_change(&StatusChange {
+                id: None,
+                node_id,
+                from_status: from,
+                to_status: to,
+                changed_at: start_time + Duration::hours(at),
+                duration_ms: None,
+                in_maintenance: false,
+            })
+            .unwrap();
+    }
+    let uptime = test_db
+        .db
+        .calculate_uptime_percentage(node_id, start_time, end_time)
+        .unwrap();
+    assert!((uptime - 90.0).abs() < 0.01, "Expected 90%, got {}", uptime);
+    let unreachable = test_db
+        .db
+        .calculate_unreachable_percentage(node_id, start_time, end_time)
+        .unwrap();
+    assert!(
+        (unreachable - 20.0).abs() < 0.01,
+        "Expected 20%, got {}",
+        unreachable
+    );
+}
+
+// ========== Group and Tag Tests ==========
+
+#[test]
+fn test_node_group_and_tags_round_trip() {
+    let test_db = TestDatabase::new();
+    let mut node = NodeBuilder::new()
+        .name("Core Switch")
+        .ping("10.0.0.1", 1, 5)
+        .build();
+    node.group = Some("Office".to_string());
+    node.tags = vec!["prod".to_string(), "network".to_string()];
+    let node_id = test_db.db.add_node(&node).unwrap();
+    let stored = || {
+        test_db
+            .db
+            .get_all_nodes()
+            .unwrap()
+            .into_iter()
+            .find(|n| n.id == Some(node_id))
+            .unwrap()
+    };
+    let mut loaded = stored();
+    assert_eq!(loaded.group.as_deref(), Some("Office"));
+    assert_eq!(loaded.tags, node.tags);
+
+    loaded.group = None;
+    loaded.tags.clear();
+    test_db.db.update_node(&loaded).unwrap();
+    let loaded = stored();
+    assert_eq!(loaded.group, None);
+    assert!(loaded.tags.is_empty());
+}
+
+#[test]
+fn test_group_uptime_percentage() {
+    let test_db = TestDatabase::new();
+    let flaky = test_db.db.add_node(&fixtures::http_node()).unwrap();
+    let steady = test_db.db.add_node(&fixtures::http_node()).unwrap();
+    let start_time = DateTime::parse_from_rfc3339("2026-07-01T00:00:00Z")
+        .unwrap()
+        .with_timezone(&Utc);
+    let end_time = start_time + Duration::hours(10);
+
+    // Offline for the first half of the period
+    for (from, to, at) in [
+        (NodeStatus::Online, NodeStatus::Offline, 0),
+        (NodeStatus::Offline, NodeStatus::Online, 5),
+    ] {
+        test_db
+            .db
+            .add_status_change(&StatusChange {
+                id: None,
+                node_id: flaky,
+                from_status: from,
+                to_status: to,
+                changed_at: start_time + Duration::hours(at),
+                duration_ms: None,
+                in_maintenance: false,
+            })
+            .unwrap();
+    }
+
+    let uptime = test_db
+        .db
+        .calculate_group_uptime_percentage(&[flaky, steady], start_time, end_time)
+        .unwrap();
+    assert!((uptime - 75.0).abs() < 0.01, "Expected 75%, got {}", uptime);
+    let uptime = test_db
+        .db
+        .calculate_group_uptime_percentage(&[], start_time, end_time)
+        .unwrap();
+    assert_eq!(uptime, 100.0);
+}
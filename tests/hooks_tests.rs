@@ -0,0 +1,255 @@
+#![cfg(unix)]
+
+mod common;
+
+use chrono::Utc;
+use common::{NodeBuilder, TestDatabase};
+use net_monitor::config::HookSettings;
+use net_monitor::credentials::SharedCredentialStore;
+use net_monitor::database::Database;
+use net_monitor::hooks::{hook_environment, hook_results, HookEvent, HookRunner};
+use net_monitor::models::{HookCommands, NodeStatus, StatusChange};
+use net_monitor::monitoring_engine::{start_monitoring_with_config, EngineConfig};
+use std::time::Duration;
+use tempfile::TempDir;
+
+fn change(from_status: NodeStatus, to_status: NodeStatus) -> StatusChange {
+    StatusChange {
+        id: None,
+        node_id: 1,
+        from_status,
+        to_status,
+        changed_at: Utc::now(),
+        duration_ms: Some(90_000),
+        in_maintenance: false,
+    }
+}
+
+fn commands(on_down: Option<&str>, on_degraded: Option<&str>) -> HookCommands {
+    HookCommands {
+        on_down: on_down.map(String::from),
+        on_degraded: on_degraded.map(String::from),
+        on_recovery: Some("recovered".to_string()),
+    }
+}
+
+fn test_runner(timeout_secs: u64, test_db: &TestDatabase) -> HookRunner {
+    let settings = HookSettings {
+        timeout_secs,
+        ..HookSettings::default()
+    };
+    HookRunner::new(&settings, Database::new(test_db.path()).unwrap())
+}
+
+/// Answers every connection on a local port with a server error
+async fn serve_errors() -> u16 {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        while let Ok((mut socket, _)) = listener.accept().await {
+            let mut buf = [0; 4096];
+            let _ = socket.read(&mut buf).await;
+            let _ = socket
+                .write_all(
+                    b"HTTP/1.1 500 Internal Server Error\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                )
+                .await;
+        }
+    });
+    port
+}
+
+// ========== Event Selection Tests ==========
+
+#[test]
+fn test_hook_event_for_change() {
+    let event =
+        |commands: &HookCommands, from, to| HookEvent::for_change(commands, &change(from, to));
+    use NodeStatus::{Degraded, Offline, Online};
+
+    let down_only = commands(Some("down"), None);
+    assert_eq!(event(&down_only, Online, Offline), Some(HookEvent::Down));
+    assert_eq!(event(&down_only, Degraded, Offline), Some(HookEvent::Down));
+    assert_eq!(
+        event(&down_only, Offline, Online),
+        Some(HookEvent::Recovery)
+    );
+    // Nothing ran when the node degraded, so nothing runs when it recovers
+    assert_eq!(event(&down_only, Online, Degraded), None);
+    assert_eq!(event(&down_only, Degraded, Online), None);
+
+    let both = commands(Some("down"), Some("degraded"));
+    assert_eq!(event(&both, Online, Degraded), Some(HookEvent::Degraded));
+    assert_eq!(event(&both, Degraded, Online), Some(HookEvent::Recovery));
+    assert_eq!(event(&both, Offline, Degraded), None);
+
+    let no_recovery = HookCommands {
+        on_recovery: None,
+        ..both
+    };
+    assert_eq!(event(&no_recovery, Offline, Online), None);
+    assert_eq!(event(&HookCommands::default(), Online, Offline), None);
+}
+
+#[test]
+fn test_hook_environment() {
+    let mut node = NodeBuilder::new()
+        .name("DB")
+        .tcp("10.0.0.5", 5432, 5)
+        .build();
+    node.consecutive_failures = 3;
+    let outage = change(NodeStatus::Online, NodeStatus::Offline);
+
+    let env = hook_environment(HookEvent::Down, &node, &outage, Some("Connection refused"));
+    let var = |name: &str| {
+        env.iter()
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value.as_str())
+    };
+    assert_eq!(var("NET_MONITOR_EVENT"), Some("down"));
+    assert_eq!(var("NET_MONITOR_NODE_ID"), Some("1"));
+    assert_eq!(var("NET_MONITOR_NODE_NAME"), Some("DB"));
+    assert_eq!(var("NET_MONITOR_NODE_TYPE"), Some("TCP"));
+    assert_eq!(var("NET_MONITOR_TARGET"), Some("10.0.0.5:5432"));
+    assert_eq!(var("NET_MONITOR_FROM_STATUS"), Some("Online"));
+    assert_eq!(var("NET_MONITOR_TO_STATUS"), Some("Offline"));
+    assert_eq!(var("NET_MONITOR_FAILED_CHECKS"), Some("3"));
+    assert_eq!(var("NET_MONITOR_DURATION_MS"), Some("90000"));
+    assert_eq!(var("NET_MONITOR_LAST_ERROR"), Some("Connection refused"));
+
+    let mut first = outage.clone();
+    first.duration_ms = None;
+    let env = hook_environment(HookEvent::Down, &node, &first, None);
+    assert!(env
+        .iter()
+        .all(|(key, _)| *key != "NET_MONITOR_DURATION_MS" && *key != "NET_MONITOR_LAST_ERROR"));
+}
+
+// ========== Execution Tests ==========
+
+#[tokio::test]
+async fn test_run_hook_records_results() {
+    let test_db = TestDatabase::new();
+    let node = NodeBuilder::new()
+        .name("DB")
+        .tcp("10.0.0.5", 5432, 5)
+        .build();
+    let node_id = test_db.db.add_node(&node).unwrap();
+    let mut outage = change(NodeStatus::Online, NodeStatus::Offline);
+    outage.node_id = node_id;
+    let change_id = test_db.db.add_status_change(&outage).unwrap();
+    let env = hook_environment(HookEvent::Down, &node, &outage, None);
+    let runner = test_runner(1, &test_db);
+
+    // The environment reaches the command
+    assert!(
+        runner
+            .run_hook(
+                "test \"$NET_MONITOR_NODE_NAME\" = DB && echo switched off",
+                &env,
+                node_id,
+                Some(change_id),
+            )
+            .await
+    );
+    assert!(
+        !runner
+            .run_hook(
+                "echo no such plug >&2; exit 3",
+                &env,
+                node_id,
+                Some(change_id)
+            )
+            .await
+    );
+    assert!(
+        !runner
+            .run_hook("sleep 10", &env, node_id, Some(change_id))
+            .await
+    );
+
+    let runs = test_db
+        .db
+        .get_notification_deliveries(node_id, None)
+        .unwrap();
+    assert_eq!(runs.len(), 3);
+    assert_eq!(runs[0].channel, "hook:sleep 10");
+    assert_eq!(runs[0].error.as_deref(), Some("Timed out after 1s"));
+    assert_eq!(runs[0].response_status, None);
+    assert_eq!(runs[1].response_status, Some(3));
+    assert_eq!(
+        runs[1].error.as_deref(),
+        Some("Exited with status 3: no such plug")
+    );
+    assert!(runs[2].success);
+    assert_eq!(runs[2].response_status, Some(0));
+
+    assert_eq!(
+        hook_results(&runs, change_id).as_deref(),
+        Some("ok, exit 3, Timed out after 1s")
+    );
+    assert_eq!(hook_results(&runs, change_id + 1), None);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_engine_runs_node_hook_on_outage() {
+    let test_db = TestDatabase::new();
+    let dir = TempDir::new().unwrap();
+    let marker = dir.path().join("down");
+
+    let port = serve_errors().await;
+    let mut node = NodeBuilder::new()
+        .name("Checkout")
+        .http(format!("http://127.0.0.1:{}", port), 200)
+        .build();
+    node.status = NodeStatus::Online;
+    node.max_check_attempts = 1;
+    node.hooks.on_down = Some(format!(
+        "echo \"$NET_MONITOR_NODE_NAME $NET_MONITOR_TO_STATUS\" > '{}'",
+        marker.display()
+    ));
+    let node_id = test_db.db.add_node(&node).unwrap();
+    node.id = Some(node_id);
+
+    let config = EngineConfig {
+        max_start_jitter: Duration::ZERO,
+        ..EngineConfig::default()
+    };
+    let (update_tx, _update_rx) = std::sync::mpsc::channel();
+    let handle = start_monitoring_with_config(
+        Database::new(test_db.path()).unwrap(),
+        vec![node],
+        update_tx,
+        config,
+        SharedCredentialStore::default(),
+    );
+
+    // The run is recorded against the status change that started it
+    let mut runs = Vec::new();
+    for _ in 0..100 {
+        runs = test_db
+            .db
+            .get_notification_deliveries(node_id, None)
+            .unwrap();
+        if !runs.is_empty() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    handle.stop_tx.send(()).unwrap();
+
+    let change = test_db
+        .db
+        .get_latest_status_change(node_id)
+        .unwrap()
+        .unwrap();
+    assert_eq!(runs.len(), 1);
+    assert!(runs[0].success);
+    assert_eq!(runs[0].status_change_id, change.id);
+    assert_eq!(
+        std::fs::read_to_string(&marker).unwrap().trim(),
+        "Checkout Offline"
+    );
+}
@@ -1,7 +1,7 @@
 mod common;
 
 use common::{fixtures, TestDatabase};
-use net_monitor::models::{NodeImport, NodeStatus};
+use net_monitor::models::{HookCommands, NodeImport, NodeStatus};
 
 #[test]
 fn test_node_import_export_workflow() {
@@ -19,6 +19,10 @@ fn test_node_import_export_workflow() {
         credential_id: None,
         max_check_attempts: node.max_check_attempts,
         retry_interval: node.retry_interval,
+        hooks: HookCommands::default(),
+        parents: Vec::new(),
+        group: None,
+        tags: Vec::new(),
     };
 
     // Serialize to JSON
@@ -44,6 +48,12 @@ fn test_node_import_export_workflow() {
         consecutive_failures: 0,
         max_check_attempts: imported_node.max_check_attempts,
         retry_interval: imported_node.retry_interval,
+        cert_expires_at: None,
+        ping_stats: None,
+        hooks: HookCommands::default(),
+        parents: Vec::new(),
+        group: None,
+        tags: Vec::new(),
     };
 
     let new_node_id = test_db.db.add_node(&new_node).unwrap();
@@ -77,6 +87,10 @@ fn test_export_multiple_nodes_to_json() {
             credential_id: n.credential_id.clone(),
             max_check_attempts: n.max_check_attempts,
             retry_interval: n.retry_interval,
+            hooks: HookCommands::default(),
+            parents: Vec::new(),
+            group: None,
+            tags: Vec::new(),
         })
         .collect();
 
@@ -137,6 +151,12 @@ fn test_import_nodes_from_json() {
             consecutive_failures: 0,
             max_check_attempts: import.max_check_attempts,
             retry_interval: import.retry_interval,
+            cert_expires_at: None,
+            ping_stats: None,
+            hooks: HookCommands::default(),
+            parents: Vec::new(),
+            group: None,
+            tags: Vec::new(),
         };
         test_db.db.add_node(&node).unwrap();
     }
@@ -165,6 +185,10 @@ fn test_node_import_preserves_detail_types() {
         credential_id: None,
         max_check_attempts: 3,
         retry_interval: 15,
+        hooks: HookCommands::default(),
+        parents: Vec::new(),
+        group: None,
+        tags: Vec::new(),
     };
 
     let http_json = serde_json::to_string(&http_import).unwrap();
@@ -180,6 +204,10 @@ fn test_node_import_preserves_detail_types() {
         credential_id: None,
         max_check_attempts: 3,
         retry_interval: 15,
+        hooks: HookCommands::default(),
+        parents: Vec::new(),
+        group: None,
+        tags: Vec::new(),
     };
 
     let ping_json = serde_json::to_string(&ping_import).unwrap();
@@ -187,3 +215,23 @@ fn test_node_import_preserves_detail_types() {
 
     assert_eq!(ping_reimport.detail, ping_node.detail);
 }
+
+#[test]
+fn test_node_import_conversions_round_trip() {
+    let mut node = fixtures::ping_node();
+    node.id = Some(42);
+    node.status = NodeStatus::Online;
+    node.consecutive_failures = 2;
+
+    let import = NodeImport::from(&node);
+    assert_eq!(import.name, node.name);
+    assert_eq!(import.max_check_attempts, node.max_check_attempts);
+
+    // Runtime state is not carried over to the new node
+    let restored = net_monitor::models::Node::from(import);
+    assert_eq!(restored.id, None);
+    assert_eq!(restored.status, NodeStatus::Offline);
+    assert_eq!(restored.consecutive_failures, 0);
+    assert_eq!(restored.detail, node.detail);
+    assert_eq!(restored.monitoring_interval, node.monitoring_interval);
+}
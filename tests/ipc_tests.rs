@@ -0,0 +1,218 @@
+#![cfg(unix)]
+
+use net_monitor::ipc::{attach, socket_path, MonitorServer};
+use net_monitor::models::{
+    HookCommands, MonitorDetail, Node, NodeStatus, DEFAULT_MAX_CHECK_ATTEMPTS,
+    DEFAULT_RETRY_INTERVAL,
+};
+use net_monitor::monitoring_engine::NodeConfigUpdate;
+use std::path::Path;
+use std::sync::mpsc;
+use std::time::Duration;
+use tempfile::TempDir;
+
+fn node_with_id(id: i64, name: &str) -> Node {
+    Node {
+        id: Some(id),
+        name: name.to_string(),
+        detail: MonitorDetail::Ping {
+            host: "127.0.0.1".to_string(),
+            count: 1,
+            timeout: 1,
+            fallback_port: 80,
+            thresholds: Default::default(),
+        },
+        status: NodeStatus::Offline,
+        last_check: None,
+        response_time: None,
+        monitoring_interval: 60,
+        credential_id: None,
+        consecutive_failures: 0,
+        max_check_attempts: DEFAULT_MAX_CHECK_ATTEMPTS,
+        retry_interval: DEFAULT_RETRY_INTERVAL,
+        cert_expires_at: None,
+        ping_stats: None,
+        hooks: HookCommands::default(),
+        parents: Vec::new(),
+        group: None,
+        tags: Vec::new(),
+    }
+}
+
+#[test]
+fn test_socket_path_follows_database() {
+    assert_eq!(
+        socket_path(Path::new("/data/network_monitor.db")),
+        Path::new("/data/network_monitor.sock")
+    );
+}
+
+#[test]
+fn test_attach_receives_snapshot_and_updates() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("monitor.sock");
+    let (config_tx, _config_rx) = mpsc::channel();
+    let server = MonitorServer::bind(&path, vec![node_with_id(1, "Router")], config_tx).unwrap();
+
+    let (update_tx, update_rx) = mpsc::channel();
+    let (_handle, nodes) = attach(&path, update_tx, mpsc::channel().0).unwrap();
+    assert_eq!(nodes.len(), 1);
+    assert_eq!(nodes[0].name, "Router");
+
+    let mut checked = node_with_id(1, "Router");
+    checked.status = NodeStatus::Online;
+    checked.response_time = Some(12);
+    server.broadcast(&checked);
+
+    let update = update_rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert_eq!(update.id, Some(1));
+    assert_eq!(update.status, NodeStatus::Online);
+    assert_eq!(update.response_time, Some(12));
+}
+
+#[test]
+fn test_client_commands_reach_engine() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("monitor.sock");
+    let (config_tx, config_rx) = mpsc::channel();
+    let _server = MonitorServer::bind(&path, vec![], config_tx).unwrap();
+
+    let (update_tx, _update_rx) = mpsc::channel();
+    let (handle, _) = attach(&path, update_tx, mpsc::channel().0).unwrap();
+
+    handle
+        .config_tx
+        .send(NodeConfigUpdate::Add(node_with_id(7, "NAS")))
+        .unwrap();
+    handle.config_tx.send(NodeConfigUpdate::Delete(3)).unwrap();
+
+    match config_rx.recv_timeout(Duration::from_secs(5)).unwrap() {
+        NodeConfigUpdate::Add(node) => assert_eq!(node.name, "NAS"),
+        other => panic!("Expected Add, got {:?}", other),
+    }
+    assert!(matches!(
+        config_rx.recv_timeout(Duration::from_secs(5)).unwrap(),
+        NodeConfigUpdate::Delete(3)
+    ));
+}
+
+#[test]
+fn test_snapshot_includes_nodes_added_by_other_clients() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("monitor.sock");
+    let (config_tx, config_rx) = mpsc::channel();
+    let _server = MonitorServer::bind(&path, vec![], config_tx).unwrap();
+
+    let (first_tx, _first_rx) = mpsc::channel();
+    let (first, _) = attach(&path, first_tx, mpsc::channel().0).unwrap();
+    first
+        .config_tx
+        .send(NodeConfigUpdate::Add(node_with_id(2, "Printer")))
+        .unwrap();
+    config_rx.recv_timeout(Duration::from_secs(5)).unwrap();
+
+    let (second_tx, _second_rx) = mpsc::channel();
+    let (_second, nodes) = attach(&path, second_tx, mpsc::channel().0).unwrap();
+    assert_eq!(nodes.len(), 1);
+    assert_eq!(nodes[0].name, "Printer");
+}
+
+#[test]
+fn test_commands_reach_other_clients() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("monitor.sock");
+    let (config_tx, config_rx) = mpsc::channel();
+    let server = MonitorServer::bind(&path, vec![node_with_id(1, "Router")], config_tx).unwrap();
+
+    let (first_tx, _first_rx) = mpsc::channel();
+    let (first, _) = attach(&path, first_tx, mpsc::channel().0).unwrap();
+    let (update_tx, update_rx) = mpsc::channel();
+    let (removed_tx, removed_rx) = mpsc::channel();
+    let (_second, _) = attach(&path, update_tx, removed_tx).unwrap();
+
+    let mut renamed = node_with_id(1, "Edge Router");
+    first
+        .config_tx
+        .send(NodeConfigUpdate::Update(renamed.clone()))
+        .unwrap();
+    let update = update_rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert_eq!(update.name, "Edge Router");
+
+    // Commands from outside the socket, such as the API's, are relayed too
+    server
+        .command_sender()
+        .send(NodeConfigUpdate::Delete(1))
+        .unwrap();
+    assert_eq!(removed_rx.recv_timeout(Duration::from_secs(5)), Ok(1));
+    assert!(matches!(
+        config_rx.recv_timeout(Duration::from_secs(5)).unwrap(),
+        NodeConfigUpdate::Update(_)
+    ));
+    assert!(matches!(
+        config_rx.recv_timeout(Duration::from_secs(5)).unwrap(),
+        NodeConfigUpdate::Delete(1)
+    ));
+
+    // A check that finishes after the node was deleted doesn't bring it back
+    renamed.status = NodeStatus::Online;
+    server.broadcast(&renamed);
+    let (update_tx, _update_rx) = mpsc::channel();
+    let (_third, nodes) = attach(&path, update_tx, mpsc::channel().0).unwrap();
+    assert!(nodes.is_empty());
+}
+
+#[test]
+fn test_detach_leaves_server_running() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("monitor.sock");
+    let (config_tx, _config_rx) = mpsc::channel();
+    let server = MonitorServer::bind(&path, vec![node_with_id(1, "Router")], config_tx).unwrap();
+
+    let (update_tx, update_rx) = mpsc::channel();
+    let (handle, _) = attach(&path, update_tx, mpsc::channel().0).unwrap();
+    handle.stop_tx.send(()).unwrap();
+
+    // The client's update channel closes once the connection is torn down
+    assert_eq!(
+        update_rx.recv_timeout(Duration::from_secs(5)),
+        Err(mpsc::RecvTimeoutError::Disconnected)
+    );
+
+    // Broadcasting to a detached client is harmless and new clients can still attach
+    server.broadcast(&node_with_id(1, "Router"));
+    let (update_tx, _update_rx) = mpsc::channel();
+    assert!(attach(&path, update_tx, mpsc::channel().0).is_ok());
+}
+
+#[test]
+fn test_socket_is_owner_only() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("monitor.sock");
+    let (config_tx, _config_rx) = mpsc::channel();
+    let _server = MonitorServer::bind(&path, vec![], config_tx).unwrap();
+
+    let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+    // Nothing is left over from binding
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    let (update_tx, _update_rx) = mpsc::channel();
+    assert!(attach(&path, update_tx, mpsc::channel().0).is_ok());
+}
+
+#[test]
+fn test_socket_removed_when_server_dropped() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("monitor.sock");
+    let (config_tx, _config_rx) = mpsc::channel();
+
+    {
+        let _server = MonitorServer::bind(&path, vec![], config_tx).unwrap();
+        assert!(path.exists());
+    }
+
+    assert!(!path.exists());
+    let (update_tx, _update_rx) = mpsc::channel();
+    assert!(attach(&path, update_tx, mpsc::channel().0).is_err());
+}
@@ -0,0 +1,251 @@
+mod common;
+
+use chrono::Utc;
+use common::{NodeBuilder, TestDatabase};
+use net_monitor::config::MetricsSettings;
+use net_monitor::credentials::SharedCredentialStore;
+use net_monitor::database::Database;
+use net_monitor::metrics::{serve, CheckResult, EngineMetrics};
+use net_monitor::models::{Node, NodeStatus, StatusChange};
+use net_monitor::monitoring_engine::{start_monitoring_with_config, EngineConfig};
+use std::sync::Arc;
+use std::time::Duration;
+
+fn node(id: i64, name: &str) -> Node {
+    let mut node = NodeBuilder::new()
+        .name(name)
+        .http("https://example.com", 200)
+        .build();
+    node.id = Some(id);
+    node
+}
+
+fn status_change(node_id: i64, to_status: NodeStatus) -> StatusChange {
+    StatusChange {
+        id: None,
+        node_id,
+        from_status: NodeStatus::Online,
+        to_status,
+        changed_at: Utc::now(),
+        duration_ms: None,
+        in_maintenance: false,
+    }
+}
+
+/// The line of `metric` for the node with `id`, with any further labels
+fn sample<'a>(text: &'a str, metric: &str, id: i64, extra: &str) -> Option<&'a str> {
+    text.lines().find(|line| {
+        line.starts_with(&format!("{}{{id=\"{}\",", metric, id)) && line.contains(extra)
+    })
+}
+
+#[test]
+fn test_render_node_gauges_and_labels() {
+    let metrics = EngineMetrics::new();
+    let mut web = node(1, "Web");
+    web.tags = vec!["prod".to_string(), "edge".to_string()];
+    web.status = NodeStatus::Degraded;
+    web.response_time = Some(250);
+    web.consecutive_failures = 2;
+    metrics.set_nodes(&[web]);
+
+    let text = metrics.render();
+    let labels =
+        "{id=\"1\",node=\"Web\",type=\"HTTP\",target=\"https://example.com\",tags=\"prod,edge\"}";
+    assert!(text.contains(&format!("net_monitor_node_up{} 0\n", labels)));
+    assert!(text.contains(&format!("net_monitor_node_degraded{} 1\n", labels)));
+    assert!(text.contains(&format!(
+        "net_monitor_node_response_time_seconds{} 0.25\n",
+        labels
+    )));
+    assert!(text.contains(&format!(
+        "net_monitor_node_consecutive_failures{} 2\n",
+        labels
+    )));
+    assert!(text.contains("# TYPE net_monitor_node_up gauge\n"));
+    assert!(text.contains("# TYPE net_monitor_node_checks_total counter\n"));
+    assert!(text.contains("net_monitor_engine_nodes 1\n"));
+}
+
+#[test]
+fn test_render_escapes_label_values() {
+    let metrics = EngineMetrics::new();
+    metrics.set_nodes(&[node(1, "Say \"hi\"\\\nbye")]);
+
+    let text = metrics.render();
+    assert!(text.contains("node=\"Say \\\"hi\\\"\\\\\\nbye\""));
+    // A node that was never checked has no response time
+    assert!(sample(&text, "net_monitor_node_response_time_seconds", 1, "").is_none());
+}
+
+#[test]
+fn test_check_and_status_change_counters() {
+    let metrics = EngineMetrics::new();
+    let mut web = node(1, "Web");
+    metrics.set_nodes(std::slice::from_ref(&web));
+
+    metrics.record_check(&web, CheckResult::Success);
+    metrics.record_check(&web, CheckResult::Success);
+    web.status = NodeStatus::Offline;
+    metrics.record_check(&web, CheckResult::Failure);
+    metrics.record_status_change(&status_change(1, NodeStatus::Offline));
+
+    let text = metrics.render();
+    let count = |metric: &str, extra: &str| {
+        sample(&text, metric, 1, extra)
+            .and_then(|line| line.rsplit(' ').next())
+            .unwrap_or_default()
+            .to_string()
+    };
+    assert_eq!(
+        count("net_monitor_node_checks_total", "result=\"success\""),
+        "2"
+    );
+    assert_eq!(
+        count("net_monitor_node_checks_total", "result=\"failure\""),
+        "1"
+    );
+    assert_eq!(
+        count("net_monitor_node_checks_total", "result=\"error\""),
+        "0"
+    );
+    assert_eq!(
+        count("net_monitor_node_status_changes_total", "to=\"offline\""),
+        "1"
+    );
+    assert_eq!(
+        count("net_monitor_node_status_changes_total", "to=\"online\""),
+        "0"
+    );
+    // The gauges follow the node as it stands after its latest check
+    assert_eq!(count("net_monitor_node_up", ""), "0");
+
+    // Removed nodes disappear along with their counters
+    metrics.set_nodes(&[node(2, "NAS")]);
+    let text = metrics.render();
+    assert!(sample(&text, "net_monitor_node_checks_total", 1, "").is_none());
+    assert!(sample(&text, "net_monitor_node_checks_total", 2, "").is_some());
+}
+
+#[test]
+fn test_render_engine_health() {
+    let metrics = EngineMetrics::new();
+    metrics.set_engine_health(Duration::from_millis(1500), 3);
+
+    let text = metrics.render();
+    assert!(text.contains("net_monitor_engine_loop_lag_seconds 1.5\n"));
+    assert!(text.contains("net_monitor_engine_checks_in_flight 3\n"));
+    assert!(text.contains("net_monitor_engine_nodes 0\n"));
+}
+
+#[tokio::test]
+async fn test_serve_metrics() {
+    let metrics = Arc::new(EngineMetrics::new());
+    metrics.set_nodes(&[node(1, "Web")]);
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let server = tokio::spawn(serve(listener, Arc::clone(&metrics)));
+
+    let response = reqwest::get(format!("http://{}/metrics", address))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    assert!(response.headers()["content-type"]
+        .to_str()
+        .unwrap()
+        .starts_with("text/plain; version=0.0.4"));
+    assert!(response
+        .text()
+        .await
+        .unwrap()
+        .contains("net_monitor_engine_nodes 1\n"));
+
+    let response = reqwest::get(format!("http://{}/", address)).await.unwrap();
+    assert_eq!(response.status(), 404);
+
+    server.abort();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_engine_serves_metrics() {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let test_db = TestDatabase::new();
+
+    // A target that always fails its check
+    let target = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let target_port = target.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        while let Ok((mut socket, _)) = target.accept().await {
+            let mut buf = [0; 4096];
+            let _ = socket.read(&mut buf).await;
+            let _ = socket
+                .write_all(
+                    b"HTTP/1.1 500 Internal Server Error\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                )
+                .await;
+        }
+    });
+    let mut node = NodeBuilder::new()
+        .name("Checkout")
+        .http(format!("http://127.0.0.1:{}", target_port), 200)
+        .build();
+    node.status = NodeStatus::Online;
+    node.max_check_attempts = 1;
+    let node_id = test_db.db.add_node(&node).unwrap();
+    node.id = Some(node_id);
+
+    // Find a free port for the endpoint
+    let listen = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+    let config = EngineConfig {
+        max_start_jitter: Duration::ZERO,
+        metrics: MetricsSettings {
+            enabled: true,
+            listen,
+        },
+        ..EngineConfig::default()
+    };
+    let (update_tx, update_rx) = std::sync::mpsc::channel();
+    let handle = start_monitoring_with_config(
+        Database::new(test_db.path()).unwrap(),
+        vec![node],
+        update_tx,
+        config,
+        SharedCredentialStore::default(),
+    );
+    tokio::task::spawn_blocking(move || update_rx.recv_timeout(Duration::from_secs(10)))
+        .await
+        .unwrap()
+        .expect("The node was never checked");
+
+    let text = reqwest::get(format!("http://{}/metrics", listen))
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+    let failures = sample(
+        &text,
+        "net_monitor_node_checks_total",
+        node_id,
+        "result=\"failure\"",
+    )
+    .unwrap();
+    assert!(failures.ends_with(" 1"), "{}", failures);
+    let offline = sample(
+        &text,
+        "net_monitor_node_status_changes_total",
+        node_id,
+        "to=\"offline\"",
+    )
+    .unwrap();
+    assert!(offline.ends_with(" 1"), "{}", offline);
+    assert!(sample(&text, "net_monitor_node_up", node_id, "")
+        .unwrap()
+        .ends_with(" 0"));
+
+    handle.stop_tx.send(()).unwrap();
+}
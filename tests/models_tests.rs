@@ -1,10 +1,17 @@
 // Unit tests for models module
 // Moved from src/models.rs to follow Rust best practices
 
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Local, TimeZone, Utc};
 use net_monitor::connection::ConnectionType;
 use net_monitor::models::{
-    MonitorDetail, MonitoringResult, Node, NodeImport, NodeStatus, StatusChange,
+    check_parents, dependency_order, format_host_key_fingerprint, normalize_group, normalize_tags,
+    parse_host_key_fingerprint, resolve_node_ids, CheckSample, CheckStats, DnsProtocol,
+    DnsRecordType, HookCommands, HttpHeader, HttpMethod, HttpOptions, JsonAssertion,
+    MaintenanceScope, MaintenanceWindow, MonitorDetail, MonitoringResult, Node, NodeFilter,
+    NodeImport, NodeStatus, PingMethod, PingStats, PingThresholds, ResponseAssertions,
+    RetentionPolicy, RollupResolution, StatusChange, StatusRange, TlsMode, TlsOptions, UdpPayload,
+    UdpResponseMatch, DEFAULT_CERT_CRITICAL_DAYS, DEFAULT_DNS_PORT, DEFAULT_DNS_TIMEOUT,
+    DEFAULT_HTTP_MAX_REDIRECTS, DEFAULT_UDP_TIMEOUT,
 };
 
 // ========== MonitorDetail Tests ==========
@@ -14,6 +21,8 @@ fn test_monitor_detail_display_http() {
     let http_detail = MonitorDetail::Http {
         url: "https://example.com".to_string(),
         expected_status: 200,
+        timeout: 30,
+        options: Default::default(),
     };
     assert_eq!(http_detail.to_string(), "HTTP");
 }
@@ -24,6 +33,8 @@ fn test_monitor_detail_display_ping() {
         host: "192.168.1.1".to_string(),
         count: 4,
         timeout: 5,
+        fallback_port: 80,
+        thresholds: Default::default(),
     };
     assert_eq!(ping_detail.to_string(), "Ping");
 }
@@ -43,6 +54,8 @@ fn test_monitor_detail_serialization() {
     let http_detail = MonitorDetail::Http {
         url: "https://example.com".to_string(),
         expected_status: 200,
+        timeout: 30,
+        options: Default::default(),
     };
     let serialized = serde_json::to_string(&http_detail).unwrap();
     let deserialized: MonitorDetail = serde_json::from_str(&serialized).unwrap();
@@ -52,6 +65,8 @@ fn test_monitor_detail_serialization() {
         host: "192.168.1.1".to_string(),
         count: 4,
         timeout: 5,
+        fallback_port: 80,
+        thresholds: Default::default(),
     };
     let serialized = serde_json::to_string(&ping_detail).unwrap();
     let deserialized: MonitorDetail = serde_json::from_str(&serialized).unwrap();
@@ -80,6 +95,8 @@ fn test_node_creation() {
         detail: MonitorDetail::Http {
             url: "https://example.com".to_string(),
             expected_status: 200,
+            timeout: 30,
+            options: Default::default(),
         },
         status: NodeStatus::Online,
         last_check: Some(Utc::now()),
@@ -89,6 +106,12 @@ fn test_node_creation() {
         consecutive_failures: 0,
         max_check_attempts: 3,
         retry_interval: 15,
+        cert_expires_at: None,
+        ping_stats: None,
+        hooks: HookCommands::default(),
+        parents: Vec::new(),
+        group: None,
+        tags: Vec::new(),
     };
 
     assert_eq!(node.id, Some(1));
@@ -108,6 +131,8 @@ fn test_node_serialization() {
             host: "192.168.1.1".to_string(),
             count: 4,
             timeout: 5,
+            fallback_port: 80,
+            thresholds: Default::default(),
         },
         status: NodeStatus::Online,
         last_check: Some(Utc::now()),
@@ -117,6 +142,12 @@ fn test_node_serialization() {
         consecutive_failures: 0,
         max_check_attempts: 3,
         retry_interval: 15,
+        cert_expires_at: None,
+        ping_stats: None,
+        hooks: HookCommands::default(),
+        parents: Vec::new(),
+        group: None,
+        tags: Vec::new(),
     };
 
     let serialized = serde_json::to_string(&node).unwrap();
@@ -139,6 +170,8 @@ fn test_monitoring_result_creation() {
         status: NodeStatus::Online,
         response_time: Some(150),
         details: Some("Success".to_string()),
+        cert_expires_at: None,
+        ping_stats: None,
     };
 
     assert_eq!(result.id, Some(1));
@@ -155,11 +188,17 @@ fn test_node_import_creation() {
         detail: MonitorDetail::Http {
             url: "https://example.com".to_string(),
             expected_status: 200,
+            timeout: 30,
+            options: Default::default(),
         },
         monitoring_interval: 60,
         credential_id: None,
         max_check_attempts: 3,
         retry_interval: 15,
+        hooks: HookCommands::default(),
+        parents: Vec::new(),
+        group: None,
+        tags: Vec::new(),
     };
 
     assert_eq!(node_import.name, "Test Node");
@@ -174,11 +213,17 @@ fn test_node_import_serialization() {
         detail: MonitorDetail::Http {
             url: "https://example.com".to_string(),
             expected_status: 200,
+            timeout: 30,
+            options: Default::default(),
         },
         monitoring_interval: 60,
         credential_id: None,
         max_check_attempts: 3,
         retry_interval: 15,
+        hooks: HookCommands::default(),
+        parents: Vec::new(),
+        group: None,
+        tags: Vec::new(),
     };
 
     let serialized = serde_json::to_string(&node_import).unwrap();
@@ -195,14 +240,20 @@ fn test_monitor_detail_partial_eq() {
     let http1 = MonitorDetail::Http {
         url: "https://example.com".to_string(),
         expected_status: 200,
+        timeout: 30,
+        options: Default::default(),
     };
     let http2 = MonitorDetail::Http {
         url: "https://example.com".to_string(),
         expected_status: 200,
+        timeout: 30,
+        options: Default::default(),
     };
     let http3 = MonitorDetail::Http {
         url: "https://example.org".to_string(),
         expected_status: 200,
+        timeout: 30,
+        options: Default::default(),
     };
 
     assert_eq!(http1, http2);
@@ -223,6 +274,8 @@ fn test_monitor_detail_get_connection_target_http() {
     let detail = MonitorDetail::Http {
         url: "https://example.com".to_string(),
         expected_status: 200,
+        timeout: 30,
+        options: Default::default(),
     };
     assert_eq!(detail.get_connection_target(), "https://example.com");
 }
@@ -233,6 +286,8 @@ fn test_monitor_detail_get_connection_target_ping() {
         host: "192.168.1.1".to_string(),
         count: 4,
         timeout: 5,
+        fallback_port: 80,
+        thresholds: Default::default(),
     };
     assert_eq!(detail.get_connection_target(), "192.168.1.1");
 }
@@ -252,6 +307,8 @@ fn test_monitor_detail_get_connection_type_http() {
     let detail = MonitorDetail::Http {
         url: "https://example.com".to_string(),
         expected_status: 200,
+        timeout: 30,
+        options: Default::default(),
     };
     assert_eq!(detail.get_connection_type(), ConnectionType::Http);
 }
@@ -262,6 +319,8 @@ fn test_monitor_detail_get_connection_type_ping() {
         host: "192.168.1.1".to_string(),
         count: 4,
         timeout: 5,
+        fallback_port: 80,
+        thresholds: Default::default(),
     };
     assert_eq!(detail.get_connection_type(), ConnectionType::Ping);
 }
@@ -276,6 +335,628 @@ fn test_monitor_detail_get_connection_type_tcp() {
     assert_eq!(detail.get_connection_type(), ConnectionType::Tcp);
 }
 
+#[test]
+fn test_monitor_detail_get_connection_type_ssh() {
+    let detail = MonitorDetail::Ssh {
+        host: "192.168.1.1".to_string(),
+        port: 2222,
+        timeout: 10,
+        command: None,
+        expected_output: None,
+        host_key: None,
+    };
+    assert_eq!(detail.get_connection_type(), ConnectionType::Ssh);
+    assert_eq!(detail.get_connection_target(), "192.168.1.1:2222");
+    assert_eq!(detail.to_string(), "SSH");
+}
+
+// ========== MonitorDetail SSH Tests ==========
+
+#[test]
+fn test_monitor_detail_ssh_deserializes_without_command() {
+    let detail: MonitorDetail =
+        serde_json::from_str(r#"{"type":"Ssh","host":"router","port":22,"timeout":10}"#).unwrap();
+    assert_eq!(
+        detail,
+        MonitorDetail::Ssh {
+            host: "router".to_string(),
+            port: 22,
+            timeout: 10,
+            command: None,
+            expected_output: None,
+            host_key: None,
+        }
+    );
+}
+
+#[test]
+fn test_monitor_detail_ssh_validate() {
+    let ssh = |command: Option<&str>, expected_output: Option<&str>| MonitorDetail::Ssh {
+        host: "router".to_string(),
+        port: 22,
+        timeout: 10,
+        command: command.map(String::from),
+        expected_output: expected_output.map(String::from),
+        host_key: None,
+    };
+
+    assert!(ssh(None, None).validate().is_ok());
+    assert!(ssh(Some("uptime"), Some(r"load average: \d"))
+        .validate()
+        .is_ok());
+    assert!(ssh(None, Some("ok")).validate().is_err());
+    let err = ssh(Some("uptime"), Some("(")).validate().unwrap_err();
+    assert!(err.to_string().contains("Invalid expected output pattern"));
+}
+
+#[test]
+fn test_host_key_fingerprint_parse_and_format() {
+    let fingerprint = "SHA256:uNiVztksCsDhcc0u9e8BujQXVUpKZIDTMczCvj3tD2s";
+    let hash = parse_host_key_fingerprint(fingerprint).unwrap();
+    assert_eq!(format_host_key_fingerprint(&hash), fingerprint);
+    // Padding is tolerated, though ssh-keygen leaves it out
+    assert_eq!(
+        parse_host_key_fingerprint(&format!("{}=", fingerprint)).unwrap(),
+        hash
+    );
+
+    for invalid in [
+        "uNiVztksCsDhcc0u9e8BujQXVUpKZIDTMczCvj3tD2s",
+        "MD5:16:27:ac:a5:76:28:2d:36:63:1b:56:4d:eb:df:a6:48",
+        "SHA256:uNiVztks",
+        "SHA256:not base64 at all",
+    ] {
+        let err = parse_host_key_fingerprint(invalid).unwrap_err();
+        assert!(err.to_string().contains("Invalid host key fingerprint"));
+    }
+
+    let ssh = |host_key: &str| MonitorDetail::Ssh {
+        host: "router".to_string(),
+        port: 22,
+        timeout: 10,
+        command: None,
+        expected_output: None,
+        host_key: Some(host_key.to_string()),
+    };
+    assert!(ssh(fingerprint).validate().is_ok());
+    assert!(ssh("SHA256:oops").validate().is_err());
+}
+
+// ========== MonitorDetail DNS Tests ==========
+
+#[test]
+fn test_monitor_detail_dns_defaults() {
+    let detail: MonitorDetail = serde_json::from_str(
+        r#"{"type":"Dns","server":"10.0.0.53","name":"intranet.local","record_type":"AAAA"}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        detail,
+        MonitorDetail::Dns {
+            server: "10.0.0.53".to_string(),
+            port: DEFAULT_DNS_PORT,
+            name: "intranet.local".to_string(),
+            record_type: DnsRecordType::Aaaa,
+            expected: vec![],
+            protocol: DnsProtocol::Udp,
+            timeout: DEFAULT_DNS_TIMEOUT,
+        }
+    );
+    assert_eq!(detail.to_string(), "DNS");
+    assert_eq!(detail.get_connection_type(), ConnectionType::Dns);
+    assert_eq!(
+        detail.get_connection_target(),
+        "intranet.local @10.0.0.53:53"
+    );
+
+    // Empty expectations are left out, and the rest round-trips
+    let json = serde_json::to_value(&detail).unwrap();
+    assert!(json.get("expected").is_none());
+    assert_eq!(json["protocol"], "udp");
+    assert_eq!(
+        serde_json::from_value::<MonitorDetail>(json).unwrap(),
+        detail
+    );
+}
+
+#[test]
+fn test_dns_record_type_parse_and_normalize() {
+    assert_eq!(
+        "aaaa".parse::<DnsRecordType>().unwrap(),
+        DnsRecordType::Aaaa
+    );
+    assert_eq!(" MX ".parse::<DnsRecordType>().unwrap(), DnsRecordType::Mx);
+    assert!("PTR".parse::<DnsRecordType>().is_err());
+    assert_eq!(DnsRecordType::Srv.to_string(), "SRV");
+    assert_eq!("TCP".parse::<DnsProtocol>().unwrap(), DnsProtocol::Tcp);
+    assert!("quic".parse::<DnsProtocol>().is_err());
+
+    assert_eq!(
+        DnsRecordType::Aaaa.normalize_answer("2001:db8:0:0::1"),
+        "2001:db8::1"
+    );
+    assert_eq!(
+        DnsRecordType::Mx.normalize_answer(" 10  Mail.Example.com. "),
+        "10 mail.example.com"
+    );
+    assert_eq!(
+        DnsRecordType::Txt.normalize_answer("v=spf1 -all"),
+        "v=spf1 -all"
+    );
+}
+
+#[test]
+fn test_monitor_detail_dns_validate() {
+    let dns = |record_type: DnsRecordType, expected: &[&str]| MonitorDetail::Dns {
+        server: "10.0.0.53".to_string(),
+        port: 53,
+        name: "example.com".to_string(),
+        record_type,
+        expected: expected.iter().map(|a| a.to_string()).collect(),
+        protocol: DnsProtocol::Udp,
+        timeout: 5,
+    };
+
+    assert!(dns(DnsRecordType::A, &["10.0.0.5", "10.0.0.6"])
+        .validate()
+        .is_ok());
+    assert!(dns(DnsRecordType::Mx, &["10 mail.example.com"])
+        .validate()
+        .is_ok());
+    assert!(dns(DnsRecordType::Srv, &["0 5 5060 sip.example.com"])
+        .validate()
+        .is_ok());
+    assert!(dns(DnsRecordType::Txt, &["anything at all"])
+        .validate()
+        .is_ok());
+
+    let err = dns(DnsRecordType::A, &["::1"]).validate().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Invalid expected A answer '::1' (expected an IPv4 address)"
+    );
+    assert!(dns(DnsRecordType::Mx, &["mail.example.com"])
+        .validate()
+        .is_err());
+    assert!(dns(DnsRecordType::Srv, &["10 5 sip.example.com"])
+        .validate()
+        .is_err());
+
+    let mut detail = dns(DnsRecordType::A, &[]);
+    if let MonitorDetail::Dns { name, .. } = &mut detail {
+        name.clear();
+    }
+    assert!(detail.validate().is_err());
+}
+
+#[test]
+fn test_monitor_detail_ping_thresholds() {
+    // Definitions from before thresholds and the TCP fallback existed still
+    // load, and unset thresholds are left out when saving
+    let json = r#"{"type":"Ping","host":"10.0.0.1","count":4,"timeout":5}"#;
+    let detail: MonitorDetail = serde_json::from_str(json).unwrap();
+    assert_eq!(
+        detail,
+        MonitorDetail::Ping {
+            host: "10.0.0.1".to_string(),
+            count: 4,
+            timeout: 5,
+            fallback_port: 80,
+            thresholds: PingThresholds::default(),
+        }
+    );
+    assert_eq!(
+        serde_json::to_string(&detail).unwrap(),
+        r#"{"type":"Ping","host":"10.0.0.1","count":4,"timeout":5,"fallback_port":80}"#
+    );
+
+    let ping = |count: u32, thresholds: PingThresholds| MonitorDetail::Ping {
+        host: "10.0.0.1".to_string(),
+        count,
+        timeout: 5,
+        fallback_port: 80,
+        thresholds,
+    };
+    let thresholds = PingThresholds {
+        loss_warning_percent: 25,
+        loss_critical_percent: 75,
+        latency_warning_ms: 100,
+        ..Default::default()
+    };
+    let detail = ping(4, thresholds.clone());
+    assert!(detail.validate().is_ok());
+    let json = serde_json::to_string(&detail).unwrap();
+    assert!(json.contains(r#""loss_warning_percent":25"#));
+    assert_eq!(
+        serde_json::from_str::<MonitorDetail>(&json).unwrap(),
+        detail
+    );
+
+    assert!(ping(0, PingThresholds::default()).validate().is_err());
+    let no_fallback = MonitorDetail::Ping {
+        host: "10.0.0.1".to_string(),
+        count: 4,
+        timeout: 5,
+        fallback_port: 0,
+        thresholds: PingThresholds::default(),
+    };
+    assert!(no_fallback.validate().is_err());
+    let over_100 = PingThresholds {
+        loss_critical_percent: 101,
+        ..Default::default()
+    };
+    assert!(ping(4, over_100).validate().is_err());
+    let inverted = PingThresholds {
+        latency_warning_ms: 500,
+        latency_critical_ms: 200,
+        ..Default::default()
+    };
+    let err = ping(4, inverted).validate().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "The latency warning (500ms) must not be above the critical threshold (200ms)"
+    );
+}
+
+#[test]
+fn test_ping_stats_from_round_trips() {
+    let ms = std::time::Duration::from_millis;
+    let stats = PingStats::from_round_trips(PingMethod::Icmp, 4, &[ms(10), ms(16), ms(13)]);
+    assert_eq!(stats.transmitted, 4);
+    assert_eq!(stats.received, 3);
+    assert_eq!(stats.loss_percent(), 25.0);
+    assert!((stats.min_rtt.unwrap() - 10.0).abs() < 1e-9);
+    assert!((stats.avg_rtt.unwrap() - 13.0).abs() < 1e-9);
+    assert!((stats.max_rtt.unwrap() - 16.0).abs() < 1e-9);
+    // Consecutive differences of 6 and 3 ms
+    assert!((stats.jitter.unwrap() - 4.5).abs() < 1e-9);
+    assert_eq!(
+        stats.to_string(),
+        "3/4 replies over ICMP, 25% loss, rtt min/avg/max 10.0/13.0/16.0 ms, jitter 4.5 ms"
+    );
+
+    // Jitter needs two replies
+    let single = PingStats::from_round_trips(PingMethod::Icmp, 1, &[ms(5)]);
+    assert_eq!(single.loss_percent(), 0.0);
+    assert_eq!(single.jitter, None);
+
+    let lost = PingStats::from_round_trips(PingMethod::Tcp(443), 3, &[]);
+    assert_eq!(lost.loss_percent(), 100.0);
+    assert_eq!(lost.avg_rtt, None);
+    assert_eq!(lost.to_string(), "0/3 replies over TCP port 443, 100% loss");
+
+    // Stats saved before the method was recorded came from raw sockets
+    let saved: PingStats = serde_json::from_str(r#"{"transmitted":4,"received":4}"#).unwrap();
+    assert_eq!(saved.method, PingMethod::RawIcmp);
+    assert_eq!(saved.to_string(), "4/4 replies over raw ICMP, 0% loss");
+}
+
+// ========== MonitorDetail UDP Tests ==========
+
+#[test]
+fn test_monitor_detail_udp_serde() {
+    let detail: MonitorDetail = serde_json::from_str(
+        r#"{"type":"Udp","host":"vpn.corp.local","port":51820,"payload":"hex:01 00 00 00"}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        detail,
+        MonitorDetail::Udp {
+            host: "vpn.corp.local".to_string(),
+            port: 51820,
+            payload: UdpPayload::Hex(vec![1, 0, 0, 0]),
+            expected_response: None,
+            no_reply_is_up: false,
+            timeout: DEFAULT_UDP_TIMEOUT,
+        }
+    );
+    assert_eq!(detail.to_string(), "UDP");
+    assert_eq!(detail.get_connection_type(), ConnectionType::Udp);
+    assert_eq!(detail.get_connection_target(), "vpn.corp.local:51820");
+
+    let json = serde_json::to_value(&detail).unwrap();
+    assert_eq!(json["payload"], "hex:01000000");
+    assert!(json.get("expected_response").is_none());
+    assert!(json.get("no_reply_is_up").is_none());
+    assert_eq!(
+        serde_json::from_value::<MonitorDetail>(json).unwrap(),
+        detail
+    );
+}
+
+#[test]
+fn test_udp_payload_and_response_match() {
+    assert_eq!(
+        "status".parse::<UdpPayload>().unwrap(),
+        UdpPayload::Text("status".to_string())
+    );
+    assert_eq!(
+        "hex:FF ff".parse::<UdpPayload>().unwrap().as_bytes(),
+        &[0xff, 0xff]
+    );
+    let err = "hex:abc".parse::<UdpPayload>().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Invalid hex 'abc' (expected pairs of hex digits)"
+    );
+    assert!("hex:zz".parse::<UdpPayload>().is_err());
+    assert!(UdpPayload::default().is_empty());
+
+    let pattern: UdpResponseMatch = "^PONG".parse().unwrap();
+    assert!(pattern.matches(b"PONG 1"));
+    assert!(!pattern.matches(b"PING"));
+    let prefix: UdpResponseMatch = "hex:ffff".parse().unwrap();
+    assert_eq!(prefix, UdpResponseMatch::HexPrefix(vec![0xff, 0xff]));
+    assert!(prefix.matches(&[0xff, 0xff, 0x00]));
+    assert!(!prefix.matches(&[0xff]));
+    assert_eq!(prefix.to_string(), "hex:ffff");
+
+    let detail = MonitorDetail::Udp {
+        host: "10.0.0.9".to_string(),
+        port: 514,
+        payload: UdpPayload::default(),
+        expected_response: Some(UdpResponseMatch::Pattern("(".to_string())),
+        no_reply_is_up: true,
+        timeout: 2,
+    };
+    let err = detail.validate().unwrap_err();
+    assert!(err
+        .to_string()
+        .starts_with("Invalid expected response pattern"));
+}
+
+// ========== HTTP Options Tests ==========
+
+#[test]
+fn test_http_detail_deserializes_without_options() {
+    let detail: MonitorDetail = serde_json::from_str(
+        r#"{"type":"Http","url":"https://example.com","expected_status":200,"timeout":30}"#,
+    )
+    .unwrap();
+    match detail {
+        MonitorDetail::Http { options, .. } => {
+            assert_eq!(options, HttpOptions::default());
+            assert_eq!(options.method, HttpMethod::Get);
+            assert_eq!(options.max_redirects, DEFAULT_HTTP_MAX_REDIRECTS);
+        }
+        _ => panic!("Expected HTTP detail"),
+    }
+}
+
+#[test]
+fn test_http_options_serialization_round_trip() {
+    let detail = MonitorDetail::Http {
+        url: "https://api.example.com/health".to_string(),
+        expected_status: 200,
+        timeout: 10,
+        options: HttpOptions {
+            method: HttpMethod::Post,
+            headers: vec!["Authorization: Bearer abc".parse().unwrap()],
+            body: Some(r#"{"probe":true}"#.to_string()),
+            accepted_statuses: StatusRange::parse_list("200-299, 304").unwrap(),
+            max_redirects: 0,
+            assertions: ResponseAssertions {
+                contains: Some("healthy".to_string()),
+                not_matches: Some("(?i)error".to_string()),
+                json: Some(JsonAssertion {
+                    pointer: "/checks/0/ok".to_string(),
+                    value: serde_json::json!(true),
+                }),
+                max_body_bytes: Some(65536),
+                ..Default::default()
+            },
+            tls: Default::default(),
+        },
+    };
+
+    let json = serde_json::to_value(&detail).unwrap();
+    // Options sit alongside the basic fields, in their readable string forms
+    assert_eq!(json["method"], "POST");
+    assert_eq!(json["headers"][0], "Authorization: Bearer abc");
+    assert_eq!(
+        json["accepted_statuses"],
+        serde_json::json!(["200-299", "304"])
+    );
+
+    let deserialized: MonitorDetail = serde_json::from_value(json).unwrap();
+    assert_eq!(deserialized, detail);
+}
+
+#[test]
+fn test_http_method_parse() {
+    assert_eq!("post".parse::<HttpMethod>().unwrap(), HttpMethod::Post);
+    assert_eq!("DELETE".parse::<HttpMethod>().unwrap(), HttpMethod::Delete);
+    assert_eq!(HttpMethod::Options.to_string(), "OPTIONS");
+    assert!("FETCH".parse::<HttpMethod>().is_err());
+}
+
+#[test]
+fn test_http_header_parse() {
+    let header: HttpHeader = "X-Api-Key:  secret value ".parse().unwrap();
+    assert_eq!(header.name, "X-Api-Key");
+    assert_eq!(header.value, "secret value");
+    assert_eq!(header.to_string(), "X-Api-Key: secret value");
+
+    assert!("no separator".parse::<HttpHeader>().is_err());
+    assert!("Bad Name: x".parse::<HttpHeader>().is_err());
+    assert!("X-Line: a\nb".parse::<HttpHeader>().is_err());
+}
+
+#[test]
+fn test_status_ranges() {
+    let ranges = StatusRange::parse_list("200-299,304").unwrap();
+    assert_eq!(StatusRange::format_list(&ranges), "200-299,304");
+    assert!(ranges[0].contains(250));
+    assert!(!ranges[0].contains(300));
+    assert!(ranges[1].contains(304));
+    assert!(StatusRange::parse_list("").unwrap().is_empty());
+
+    assert!(StatusRange::parse_list("299-200").is_err());
+    assert!(StatusRange::parse_list("99").is_err());
+    assert!(StatusRange::parse_list("2xx").is_err());
+}
+
+#[test]
+fn test_http_options_accepts() {
+    let options = HttpOptions::default();
+    assert!(options.accepts(204, 204));
+    assert!(!options.accepts(204, 200));
+
+    // Accepted ranges replace the expected status
+    let options = HttpOptions {
+        accepted_statuses: StatusRange::parse_list("200-299").unwrap(),
+        ..Default::default()
+    };
+    assert!(options.accepts(204, 200));
+    assert!(!options.accepts(301, 301));
+}
+
+#[test]
+fn test_http_detail_validate() {
+    let http = |assertions: ResponseAssertions| MonitorDetail::Http {
+        url: "https://example.com".to_string(),
+        expected_status: 200,
+        timeout: 30,
+        options: HttpOptions {
+            assertions,
+            ..Default::default()
+        },
+    };
+
+    assert!(http(ResponseAssertions::default()).validate().is_ok());
+    let err = http(ResponseAssertions {
+        matches: Some("(".to_string()),
+        ..Default::default()
+    })
+    .validate()
+    .unwrap_err();
+    assert!(err.to_string().contains("Invalid response body pattern"));
+    assert!(http(ResponseAssertions {
+        json: Some(JsonAssertion {
+            pointer: "status".to_string(),
+            value: serde_json::json!("ok"),
+        }),
+        ..Default::default()
+    })
+    .validate()
+    .is_err());
+    assert!(http(ResponseAssertions {
+        max_body_bytes: Some(0),
+        ..Default::default()
+    })
+    .validate()
+    .is_err());
+}
+
+#[test]
+fn test_tls_mode_parse_and_display() {
+    assert_eq!("verify".parse::<TlsMode>().unwrap(), TlsMode::Verify);
+    assert_eq!(
+        "Accept-Invalid".parse::<TlsMode>().unwrap(),
+        TlsMode::AcceptInvalid
+    );
+
+    // openssl's colon-separated form and plain lowercase hex are the same pin
+    let colons = "sha256:E9:2D:80:D4:A5:85:48:E2:15:1B:9D:60:C2:B7:48:42:C0:41:44:E6:40:99:66:F3:BB:93:F4:AE:58:42:C7:71";
+    let mode: TlsMode = colons.parse().unwrap();
+    assert_eq!(
+        "SHA256:e92d80d4a58548e2151b9d60c2b74842c04144e6409966f3bb93f4ae5842c771"
+            .parse::<TlsMode>()
+            .unwrap(),
+        mode
+    );
+    assert_eq!(mode.to_string(), colons);
+
+    let err = "insecure".parse::<TlsMode>().unwrap_err();
+    assert!(err.to_string().contains("Unknown TLS mode"));
+    let err = "sha256:E9:2D".parse::<TlsMode>().unwrap_err();
+    assert!(err.to_string().contains("Invalid SHA-256 fingerprint"));
+    assert!(format!("sha256:{}", "zz".repeat(32))
+        .parse::<TlsMode>()
+        .is_err());
+}
+
+#[test]
+fn test_tls_options_serialization() {
+    // Default TLS settings are left out of the JSON entirely
+    let detail = MonitorDetail::Http {
+        url: "https://example.com".to_string(),
+        expected_status: 200,
+        timeout: 10,
+        options: HttpOptions::default(),
+    };
+    let json = serde_json::to_value(&detail).unwrap();
+    assert!(json.get("tls").is_none());
+
+    let json = serde_json::json!({
+        "type": "Http",
+        "url": "https://printer.local",
+        "expected_status": 200,
+        "timeout": 10,
+        "tls": { "mode": "accept-invalid", "expiry_warning_days": 30 }
+    });
+    let detail: MonitorDetail = serde_json::from_value(json).unwrap();
+    match &detail {
+        MonitorDetail::Http { options, .. } => {
+            assert_eq!(options.tls.mode, TlsMode::AcceptInvalid);
+            assert_eq!(options.tls.expiry_warning_days, 30);
+            assert_eq!(options.tls.expiry_critical_days, DEFAULT_CERT_CRITICAL_DAYS);
+        }
+        _ => panic!("Expected HTTP detail"),
+    }
+    let round_trip: MonitorDetail =
+        serde_json::from_value(serde_json::to_value(&detail).unwrap()).unwrap();
+    assert_eq!(round_trip, detail);
+}
+
+#[test]
+fn test_tls_options_validate() {
+    let http = |tls: TlsOptions| MonitorDetail::Http {
+        url: "https://example.com".to_string(),
+        expected_status: 200,
+        timeout: 30,
+        options: HttpOptions {
+            tls,
+            ..Default::default()
+        },
+    };
+
+    assert!(http(TlsOptions::default()).validate().is_ok());
+    assert!(http(TlsOptions {
+        ca_bundle: Some("/etc/ssl/internal-ca.pem".into()),
+        ..Default::default()
+    })
+    .validate()
+    .is_ok());
+
+    let err = http(TlsOptions {
+        mode: TlsMode::AcceptInvalid,
+        ca_bundle: Some("/etc/ssl/internal-ca.pem".into()),
+        ..Default::default()
+    })
+    .validate()
+    .unwrap_err();
+    assert!(err.to_string().contains("CA bundle"));
+
+    let err = http(TlsOptions {
+        expiry_warning_days: 7,
+        expiry_critical_days: 10,
+        ..Default::default()
+    })
+    .validate()
+    .unwrap_err();
+    assert!(err.to_string().contains("must not be shorter"));
+
+    // Turning the warning off leaves the critical threshold alone
+    assert!(http(TlsOptions {
+        expiry_warning_days: 0,
+        expiry_critical_days: 10,
+        ..Default::default()
+    })
+    .validate()
+    .is_ok());
+}
+
 // ========== MonitorDetail TCP Tests ==========
 
 #[test]
@@ -318,6 +999,8 @@ fn test_monitor_detail_clone() {
     let original = MonitorDetail::Http {
         url: "https://test.com".to_string(),
         expected_status: 404,
+        timeout: 30,
+        options: Default::default(),
     };
     let cloned = original.clone();
     assert_eq!(original, cloned);
@@ -329,6 +1012,8 @@ fn test_monitor_detail_debug() {
         host: "example.com".to_string(),
         count: 3,
         timeout: 10,
+        fallback_port: 80,
+        thresholds: Default::default(),
     };
     let debug_str = format!("{:?}", detail);
     assert!(debug_str.contains("Ping"));
@@ -347,6 +1032,7 @@ fn test_status_change_creation() {
         to_status: NodeStatus::Offline,
         changed_at: now,
         duration_ms: Some(60000),
+        in_maintenance: false,
     };
 
     assert_eq!(change.id, Some(1));
@@ -388,6 +1074,7 @@ fn test_status_change_is_degradation_online_to_offline() {
         to_status: NodeStatus::Offline,
         changed_at: Utc::now(),
         duration_ms: None,
+        in_maintenance: false,
     };
     assert!(change.is_degradation());
 }
@@ -401,6 +1088,7 @@ fn test_status_change_is_degradation_offline_to_online() {
         to_status: NodeStatus::Online,
         changed_at: Utc::now(),
         duration_ms: None,
+        in_maintenance: false,
     };
     assert!(!change.is_degradation());
 }
@@ -414,6 +1102,7 @@ fn test_status_change_is_recovery_offline_to_online() {
         to_status: NodeStatus::Online,
         changed_at: Utc::now(),
         duration_ms: None,
+        in_maintenance: false,
     };
     assert!(change.is_recovery());
 }
@@ -427,6 +1116,7 @@ fn test_status_change_is_recovery_online_to_offline() {
         to_status: NodeStatus::Offline,
         changed_at: Utc::now(),
         duration_ms: None,
+        in_maintenance: false,
     };
     assert!(!change.is_recovery());
 }
@@ -440,6 +1130,7 @@ fn test_status_change_description() {
         to_status: NodeStatus::Offline,
         changed_at: Utc::now(),
         duration_ms: None,
+        in_maintenance: false,
     };
     assert_eq!(change.description(), "Online → Offline");
 }
@@ -453,6 +1144,7 @@ fn test_status_change_description_recovery() {
         to_status: NodeStatus::Online,
         changed_at: Utc::now(),
         duration_ms: None,
+        in_maintenance: false,
     };
     assert_eq!(change.description(), "Offline → Online");
 }
@@ -466,6 +1158,7 @@ fn test_status_change_clone() {
         to_status: NodeStatus::Offline,
         changed_at: Utc::now(),
         duration_ms: Some(5000),
+        in_maintenance: false,
     };
     let cloned = original.clone();
     assert_eq!(cloned.id, original.id);
@@ -483,6 +1176,7 @@ fn test_status_change_debug() {
         to_status: NodeStatus::Offline,
         changed_at: Utc::now(),
         duration_ms: Some(30000),
+        in_maintenance: false,
     };
     let debug_str = format!("{:?}", change);
     assert!(debug_str.contains("StatusChange"));
@@ -498,6 +1192,7 @@ fn test_status_change_serialization() {
         to_status: NodeStatus::Offline,
         changed_at: Utc::now(),
         duration_ms: Some(60000),
+        in_maintenance: false,
     };
     let json = serde_json::to_string(&change).unwrap();
     let deserialized: StatusChange = serde_json::from_str(&json).unwrap();
@@ -527,6 +1222,12 @@ fn test_node_with_tcp_detail() {
         consecutive_failures: 0,
         max_check_attempts: 3,
         retry_interval: 15,
+        cert_expires_at: None,
+        ping_stats: None,
+        hooks: HookCommands::default(),
+        parents: Vec::new(),
+        group: None,
+        tags: Vec::new(),
     };
 
     assert_eq!(node.name, "TCP Node");
@@ -543,6 +1244,8 @@ fn test_node_without_optional_fields() {
             host: "8.8.8.8".to_string(),
             count: 4,
             timeout: 5,
+            fallback_port: 80,
+            thresholds: Default::default(),
         },
         status: NodeStatus::Offline,
         last_check: None,
@@ -552,6 +1255,12 @@ fn test_node_without_optional_fields() {
         consecutive_failures: 0,
         max_check_attempts: 3,
         retry_interval: 15,
+        cert_expires_at: None,
+        ping_stats: None,
+        hooks: HookCommands::default(),
+        parents: Vec::new(),
+        group: None,
+        tags: Vec::new(),
     };
 
     assert!(node.id.is_none());
@@ -568,6 +1277,8 @@ fn test_node_clone() {
         detail: MonitorDetail::Http {
             url: "https://example.com".to_string(),
             expected_status: 200,
+            timeout: 30,
+            options: Default::default(),
         },
         status: NodeStatus::Online,
         last_check: Some(Utc::now()),
@@ -577,6 +1288,12 @@ fn test_node_clone() {
         consecutive_failures: 0,
         max_check_attempts: 3,
         retry_interval: 15,
+        cert_expires_at: None,
+        ping_stats: None,
+        hooks: HookCommands::default(),
+        parents: Vec::new(),
+        group: None,
+        tags: Vec::new(),
     };
     let cloned = original.clone();
     assert_eq!(cloned.id, original.id);
@@ -592,6 +1309,8 @@ fn test_node_debug() {
         detail: MonitorDetail::Http {
             url: "https://example.com".to_string(),
             expected_status: 200,
+            timeout: 30,
+            options: Default::default(),
         },
         status: NodeStatus::Online,
         last_check: None,
@@ -601,6 +1320,12 @@ fn test_node_debug() {
         consecutive_failures: 0,
         max_check_attempts: 3,
         retry_interval: 15,
+        cert_expires_at: None,
+        ping_stats: None,
+        hooks: HookCommands::default(),
+        parents: Vec::new(),
+        group: None,
+        tags: Vec::new(),
     };
     let debug_str = format!("{:?}", node);
     assert!(debug_str.contains("Node"));
@@ -615,6 +1340,8 @@ fn test_node_partial_eq() {
         detail: MonitorDetail::Http {
             url: "https://example.com".to_string(),
             expected_status: 200,
+            timeout: 30,
+            options: Default::default(),
         },
         status: NodeStatus::Online,
         last_check: None,
@@ -624,6 +1351,12 @@ fn test_node_partial_eq() {
         consecutive_failures: 0,
         max_check_attempts: 3,
         retry_interval: 15,
+        cert_expires_at: None,
+        ping_stats: None,
+        hooks: HookCommands::default(),
+        parents: Vec::new(),
+        group: None,
+        tags: Vec::new(),
     };
 
     let node2 = Node {
@@ -632,6 +1365,8 @@ fn test_node_partial_eq() {
         detail: MonitorDetail::Http {
             url: "https://example.com".to_string(),
             expected_status: 200,
+            timeout: 30,
+            options: Default::default(),
         },
         status: NodeStatus::Online,
         last_check: None,
@@ -641,6 +1376,12 @@ fn test_node_partial_eq() {
         consecutive_failures: 0,
         max_check_attempts: 3,
         retry_interval: 15,
+        cert_expires_at: None,
+        ping_stats: None,
+        hooks: HookCommands::default(),
+        parents: Vec::new(),
+        group: None,
+        tags: Vec::new(),
     };
 
     assert_eq!(node1, node2);
@@ -661,6 +1402,10 @@ fn test_node_import_with_tcp() {
         credential_id: Some("cred_abc".to_string()),
         max_check_attempts: 3,
         retry_interval: 15,
+        hooks: HookCommands::default(),
+        parents: Vec::new(),
+        group: None,
+        tags: Vec::new(),
     };
 
     assert_eq!(import.name, "TCP Import");
@@ -675,11 +1420,17 @@ fn test_node_import_clone() {
             host: "8.8.8.8".to_string(),
             count: 4,
             timeout: 5,
+            fallback_port: 80,
+            thresholds: Default::default(),
         },
         monitoring_interval: 60,
         credential_id: None,
         max_check_attempts: 3,
         retry_interval: 15,
+        hooks: HookCommands::default(),
+        parents: Vec::new(),
+        group: None,
+        tags: Vec::new(),
     };
     let cloned = original.clone();
     assert_eq!(cloned.name, original.name);
@@ -693,11 +1444,17 @@ fn test_node_import_debug() {
         detail: MonitorDetail::Http {
             url: "https://test.com".to_string(),
             expected_status: 200,
+            timeout: 30,
+            options: Default::default(),
         },
         monitoring_interval: 120,
         credential_id: None,
         max_check_attempts: 3,
         retry_interval: 15,
+        hooks: HookCommands::default(),
+        parents: Vec::new(),
+        group: None,
+        tags: Vec::new(),
     };
     let debug_str = format!("{:?}", import);
     assert!(debug_str.contains("NodeImport"));
@@ -715,6 +1472,8 @@ fn test_monitoring_result_without_optional_fields() {
         status: NodeStatus::Offline,
         response_time: None,
         details: None,
+        cert_expires_at: None,
+        ping_stats: None,
     };
 
     assert!(result.id.is_none());
@@ -731,6 +1490,8 @@ fn test_monitoring_result_with_details() {
         status: NodeStatus::Online,
         response_time: Some(250),
         details: Some("Connection successful".to_string()),
+        cert_expires_at: None,
+        ping_stats: None,
     };
 
     assert_eq!(result.details, Some("Connection successful".to_string()));
@@ -746,6 +1507,8 @@ fn test_monitoring_result_clone() {
         status: NodeStatus::Online,
         response_time: Some(100),
         details: Some("Test".to_string()),
+        cert_expires_at: None,
+        ping_stats: None,
     };
     let cloned = original.clone();
     assert_eq!(cloned.id, original.id);
@@ -762,6 +1525,8 @@ fn test_monitoring_result_debug() {
         status: NodeStatus::Online,
         response_time: Some(150),
         details: Some("Success".to_string()),
+        cert_expires_at: None,
+        ping_stats: None,
     };
     let debug_str = format!("{:?}", result);
     assert!(debug_str.contains("MonitoringResult"));
@@ -776,9 +1541,429 @@ fn test_monitoring_result_serialization() {
         status: NodeStatus::Online,
         response_time: Some(150),
         details: Some("Success".to_string()),
+        cert_expires_at: None,
+        ping_stats: None,
     };
     let json = serde_json::to_string(&result).unwrap();
     let deserialized: MonitoringResult = serde_json::from_str(&json).unwrap();
     assert_eq!(deserialized.id, result.id);
     assert_eq!(deserialized.node_id, result.node_id);
 }
+
+// ========== Check Time Series Tests ==========
+
+fn sample(success: bool, response_time: Option<u64>) -> CheckSample {
+    CheckSample {
+        node_id: 1,
+        checked_at: Utc::now(),
+        success,
+        response_time,
+    }
+}
+
+#[test]
+fn test_check_stats_empty() {
+    let stats = CheckStats::from_samples(&[]);
+    assert_eq!(stats.sample_count, 0);
+    assert_eq!(stats.success_ratio(), None);
+    assert_eq!(stats.min_response_time, None);
+    assert_eq!(stats.p95_response_time, None);
+}
+
+#[test]
+fn test_check_stats_latency_figures() {
+    let samples: Vec<CheckSample> = (1..=20).map(|ms| sample(true, Some(ms * 10))).collect();
+    let stats = CheckStats::from_samples(&samples);
+
+    assert_eq!(stats.sample_count, 20);
+    assert_eq!(stats.success_count, 20);
+    assert_eq!(stats.min_response_time, Some(10));
+    assert_eq!(stats.max_response_time, Some(200));
+    assert_eq!(stats.avg_response_time, Some(105.0));
+    // Nearest rank: ceil(0.95 * 20) = 19th value
+    assert_eq!(stats.p95_response_time, Some(190));
+    assert_eq!(stats.success_ratio(), Some(1.0));
+}
+
+#[test]
+fn test_check_stats_ignores_failed_latency() {
+    let samples = vec![
+        sample(true, Some(20)),
+        sample(true, Some(40)),
+        sample(false, Some(30_000)),
+        sample(false, None),
+    ];
+    let stats = CheckStats::from_samples(&samples);
+
+    assert_eq!(stats.sample_count, 4);
+    assert_eq!(stats.success_count, 2);
+    assert_eq!(stats.max_response_time, Some(40));
+    assert_eq!(stats.avg_response_time, Some(30.0));
+    assert_eq!(stats.success_ratio(), Some(0.5));
+}
+
+#[test]
+fn test_check_stats_all_failed() {
+    let stats = CheckStats::from_samples(&[sample(false, Some(100))]);
+    assert_eq!(stats.success_ratio(), Some(0.0));
+    assert_eq!(stats.avg_response_time, None);
+}
+
+#[test]
+fn test_rollup_resolution_bucket_start() {
+    // 2024-01-01T12:34:56.789Z
+    let ts = 1_704_112_496_789;
+    assert_eq!(RollupResolution::Minute.bucket_start(ts), 1_704_112_440_000);
+    assert_eq!(RollupResolution::Hour.bucket_start(ts), 1_704_110_400_000);
+    assert_eq!(RollupResolution::Day.bucket_start(ts), 1_704_067_200_000);
+}
+
+#[test]
+fn test_rollup_resolution_display() {
+    assert_eq!(RollupResolution::Minute.to_string(), "minute");
+    assert_eq!(RollupResolution::Hour.to_string(), "hour");
+    assert_eq!(RollupResolution::Day.to_string(), "day");
+}
+
+#[test]
+fn test_retention_policy_defaults() {
+    let policy = RetentionPolicy::default();
+    assert_eq!(policy.raw_days, 7);
+    assert_eq!(policy.minute_rollup_days, 30);
+    assert_eq!(policy.hour_rollup_days, 365);
+}
+
+// ========== Maintenance Window Tests ==========
+
+/// A local time in July, clear of daylight saving changes
+fn local(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+    Local
+        .with_ymd_and_hms(2026, 7, day, hour, minute, 0)
+        .unwrap()
+        .with_timezone(&Utc)
+}
+
+fn window(starts_at: DateTime<Utc>, recurrence: Option<&str>) -> MaintenanceWindow {
+    MaintenanceWindow {
+        id: None,
+        scope: MaintenanceScope::Node(3),
+        starts_at,
+        duration_mins: 60,
+        recurrence: recurrence.map(String::from),
+        reason: None,
+    }
+}
+
+#[test]
+fn test_maintenance_scope_applies_to() {
+    let tags = vec!["prod".to_string(), "edge".to_string()];
+    assert!(MaintenanceScope::All.applies_to(7, &[]));
+    assert!(MaintenanceScope::Node(3).applies_to(3, &[]));
+    assert!(!MaintenanceScope::Node(3).applies_to(7, &tags));
+    assert!(MaintenanceScope::Tag("Prod".to_string()).applies_to(7, &tags));
+    assert!(!MaintenanceScope::Tag("staging".to_string()).applies_to(7, &tags));
+}
+
+#[test]
+fn test_one_off_maintenance_window() {
+    let window = window(local(10, 22, 0), None);
+    window.validate().unwrap();
+
+    assert_eq!(window.active_until(local(10, 21, 59)), None);
+    assert_eq!(
+        window.active_until(local(10, 22, 0)),
+        Some(local(10, 23, 0))
+    );
+    assert_eq!(
+        window.active_until(local(10, 22, 59)),
+        Some(local(10, 23, 0))
+    );
+    assert_eq!(window.active_until(local(10, 23, 0)), None);
+    assert_eq!(
+        window.occurrences(local(10, 0, 0), local(11, 0, 0)),
+        vec![(local(10, 22, 0), local(10, 23, 0))]
+    );
+    assert!(window
+        .occurrences(local(11, 0, 0), local(12, 0, 0))
+        .is_empty());
+}
+
+#[test]
+fn test_recurring_maintenance_window() {
+    // 2am every day, from the 10th
+    let window = window(local(10, 0, 0), Some("0 2 * * *"));
+    window.validate().unwrap();
+
+    assert_eq!(window.active_until(local(12, 2, 30)), Some(local(12, 3, 0)));
+    assert_eq!(window.active_until(local(12, 3, 0)), None);
+    assert_eq!(window.active_until(local(9, 2, 30)), None);
+
+    // An occurrence already under way when the range starts is included
+    assert_eq!(
+        window.occurrences(local(11, 2, 30), local(13, 2, 0)),
+        vec![
+            (local(11, 2, 0), local(11, 3, 0)),
+            (local(12, 2, 0), local(12, 3, 0)),
+        ]
+    );
+}
+
+#[test]
+fn test_maintenance_window_validation() {
+    let mut invalid = window(local(10, 0, 0), Some("every sunday"));
+    let err = invalid.validate().unwrap_err();
+    assert!(err.to_string().contains("every sunday"), "{}", err);
+    assert!(invalid
+        .occurrences(local(1, 0, 0), local(31, 0, 0))
+        .is_empty());
+
+    invalid.recurrence = None;
+    invalid.duration_mins = 0;
+    assert!(invalid.validate().is_err());
+}
+
+// ========== Dependency Tests ==========
+
+fn dependent_node(id: i64, name: &str, parents: &[i64]) -> Node {
+    Node {
+        id: Some(id),
+        name: name.to_string(),
+        detail: MonitorDetail::Tcp {
+            host: "10.0.0.1".to_string(),
+            port: 22,
+            timeout: 5,
+        },
+        status: NodeStatus::Online,
+        last_check: None,
+        response_time: None,
+        monitoring_interval: 60,
+        credential_id: None,
+        consecutive_failures: 0,
+        max_check_attempts: 3,
+        retry_interval: 15,
+        cert_expires_at: None,
+        ping_stats: None,
+        hooks: HookCommands::default(),
+        parents: parents.to_vec(),
+        group: None,
+        tags: Vec::new(),
+    }
+}
+
+#[test]
+fn test_unreachable_status() {
+    assert_eq!(NodeStatus::Unreachable.to_string(), "Unreachable");
+
+    let change = |from_status, to_status| StatusChange {
+        id: None,
+        node_id: 1,
+        from_status,
+        to_status,
+        changed_at: Utc::now(),
+        duration_ms: None,
+        in_maintenance: false,
+    };
+    // Losing sight of a node behind a down parent is not an outage of its own
+    let hidden = change(NodeStatus::Online, NodeStatus::Unreachable);
+    assert!(!hidden.is_degradation() && !hidden.is_recovery());
+    let back = change(NodeStatus::Unreachable, NodeStatus::Online);
+    assert!(!back.is_degradation() && !back.is_recovery());
+    assert!(change(NodeStatus::Unreachable, NodeStatus::Offline).is_degradation());
+}
+
+#[test]
+fn test_dependency_order() {
+    let nodes = vec![
+        dependent_node(1, "Office AP", &[3]),
+        dependent_node(2, "Web", &[]),
+        dependent_node(3, "Core Switch", &[]),
+        dependent_node(4, "Printer", &[1, 3]),
+        dependent_node(5, "Office NAS", &[3]),
+    ];
+    assert_eq!(
+        dependency_order(&nodes),
+        vec![(1, 0), (2, 0), (0, 1), (3, 2), (4, 1)]
+    );
+
+    // Nodes that depend on each other are still listed, once each
+    let cycle = vec![
+        dependent_node(1, "A", &[2]),
+        dependent_node(2, "B", &[1]),
+        dependent_node(3, "C", &[3]),
+    ];
+    assert_eq!(dependency_order(&cycle), vec![(2, 0), (0, 0), (1, 1)]);
+}
+
+#[test]
+fn test_check_parents() {
+    let nodes = vec![
+        dependent_node(1, "Core Switch", &[]),
+        dependent_node(2, "Office AP", &[1]),
+        dependent_node(3, "Printer", &[2]),
+    ];
+    assert!(check_parents(&dependent_node(4, "NAS", &[1, 3]), &nodes).is_ok());
+    assert!(check_parents(&dependent_node(3, "Printer", &[1]), &nodes).is_ok());
+
+    let err = check_parents(&dependent_node(1, "Core Switch", &[1]), &nodes).unwrap_err();
+    assert_eq!(err.to_string(), "'Core Switch' can't depend on itself");
+    // Directly or further down the tree, a child can't become a parent
+    let err = check_parents(&dependent_node(1, "Core Switch", &[2]), &nodes).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "'Core Switch' can't depend on 'Office AP', which already depends on it"
+    );
+    assert!(check_parents(&dependent_node(1, "Core Switch", &[3]), &nodes).is_err());
+
+    // A node that isn't stored yet can't close a loop
+    let mut new = dependent_node(0, "New", &[1]);
+    new.id = None;
+    assert!(check_parents(&new, &nodes).is_ok());
+}
+
+#[test]
+fn test_resolve_node_ids() {
+    let nodes = vec![
+        dependent_node(1, "Core Switch", &[]),
+        dependent_node(2, "Web", &[]),
+        dependent_node(3, "Web", &[]),
+        dependent_node(4, "2", &[]),
+    ];
+    let keys = |keys: &[&str]| keys.iter().map(|k| k.to_string()).collect::<Vec<_>>();
+
+    assert_eq!(
+        resolve_node_ids(&keys(&["Core Switch", "3"]), &nodes).unwrap(),
+        vec![1, 3]
+    );
+    // Names are matched before IDs
+    assert_eq!(resolve_node_ids(&keys(&["2"]), &nodes).unwrap(), vec![4]);
+
+    let err = resolve_node_ids(&keys(&["Web"]), &nodes).unwrap_err();
+    assert!(err.to_string().contains("use the node ID"), "{}", err);
+    let err = resolve_node_ids(&keys(&["Router"]), &nodes).unwrap_err();
+    assert!(
+        err.to_string().contains("No node named 'Router'"),
+        "{}",
+        err
+    );
+    assert!(resolve_node_ids(&keys(&["9"]), &nodes).is_err());
+}
+
+#[test]
+fn test_node_import_export_names_parents() {
+    let nodes = vec![
+        dependent_node(1, "Core Switch", &[]),
+        dependent_node(2, "Office AP", &[1]),
+    ];
+    let exported = NodeImport::export(&nodes);
+    assert!(exported[0].parents.is_empty());
+    assert_eq!(exported[1].parents, vec!["Core Switch".to_string()]);
+
+    let json = serde_json::to_string(&exported[0]).unwrap();
+    assert!(!json.contains("parents"));
+    let json = serde_json::to_string(&exported[1]).unwrap();
+    let imported: NodeImport = serde_json::from_str(&json).unwrap();
+    assert_eq!(imported.parents, exported[1].parents);
+
+    // IDs mean nothing in another database, so the node itself has none
+    assert!(Node::from(imported).parents.is_empty());
+}
+
+// ========== Group and Tag Tests ==========
+
+#[test]
+fn test_normalize_tags() {
+    assert_eq!(
+        normalize_tags(&["prod, homelab", "  dns  ", "Prod", ""]),
+        vec!["prod", "homelab", "dns"]
+    );
+    assert_eq!(normalize_tags(&["web db,,edge"]), vec!["web", "db", "edge"]);
+    assert!(normalize_tags::<&str>(&[]).is_empty());
+}
+
+#[test]
+fn test_normalize_group() {
+    assert_eq!(normalize_group("  Office "), Some("Office".to_string()));
+    assert_eq!(normalize_group("   "), None);
+}
+
+#[test]
+fn test_node_import_carries_group_and_tags() {
+    let mut node = dependent_node(1, "Core Switch", &[]);
+    node.group = Some("Office".to_string());
+    node.tags = vec!["prod".to_string(), "network".to_string()];
+
+    let exported = NodeImport::from(&node);
+    let json = serde_json::to_string(&exported).unwrap();
+    let imported: NodeImport = serde_json::from_str(&json).unwrap();
+    assert_eq!(imported.group.as_deref(), Some("Office"));
+    assert_eq!(imported.tags, node.tags);
+
+    // Nodes without either leave them out of the file
+    let json = serde_json::to_string(&NodeImport::from(&dependent_node(2, "Web", &[]))).unwrap();
+    assert!(!json.contains("group") && !json.contains("tags"));
+
+    // Hand-written files are cleaned up on import
+    let messy: NodeImport = serde_json::from_str(
+        r#"{"name": "NAS", "detail": {"type": "Tcp", "host": "10.0.0.9", "port": 445, "timeout": 5}, "monitoring_interval": 60, "credential_id": null, "group": " ", "tags": ["lab, Lab", "backup"]}"#,
+    )
+    .unwrap();
+    let node = Node::from(messy);
+    assert_eq!(node.group, None);
+    assert_eq!(node.tags, vec!["lab", "backup"]);
+}
+
+// ========== Filter Tests ==========
+
+#[test]
+fn test_node_filter_text() {
+    let mut node = dependent_node(1, "Core Switch", &[]);
+    node.tags = vec!["Prod".to_string()];
+    let matches = |query: &str| NodeFilter::parse(query).matches(&node);
+
+    assert!(NodeFilter::parse("  ").is_empty());
+    assert!(matches(""));
+    assert!(matches("core"));
+    assert!(matches("SWITCH core"));
+    assert!(matches("10.0.0.1:22"));
+    assert!(matches("tcp"));
+    assert!(matches("pro"));
+    assert!(!matches("core router"));
+    assert!(matches("\"core switch\""));
+    assert!(!matches("\"switch core\""));
+}
+
+#[test]
+fn test_node_filter_keys() {
+    let mut node = dependent_node(1, "Office NAS", &[]);
+    node.status = NodeStatus::Offline;
+    node.group = Some("Head Office".to_string());
+    node.tags = vec!["prod".to_string(), "backup".to_string()];
+    let matches = |query: &str| NodeFilter::parse(query).matches(&node);
+
+    assert!(matches("status:offline"));
+    assert!(matches("status:down"));
+    assert!(matches("Status:Off"));
+    assert!(!matches("status:online"));
+    assert!(matches("status:degraded,offline"));
+    assert!(matches("type:tcp tag:prod"));
+    assert!(!matches("type:http"));
+    assert!(matches("tag:Backup"));
+    // Tags have to match in full
+    assert!(!matches("tag:pro"));
+    assert!(matches("group:\"head office\""));
+    assert!(!matches("group:office"));
+    assert!(matches("nas status:offline tag:lab,prod"));
+    assert!(!matches("nas status:offline tag:lab"));
+
+    // A key without a value yet doesn't filter, and other keys are plain text
+    assert!(NodeFilter::parse("status:").is_empty());
+    assert!(!matches("owner:ops"));
+    let mut web = dependent_node(2, "Web", &[]);
+    web.detail = MonitorDetail::Http {
+        url: "https://example.com".to_string(),
+        expected_status: 200,
+        timeout: 30,
+        options: Default::default(),
+    };
+    assert!(NodeFilter::parse("https://example").matches(&web));
+}
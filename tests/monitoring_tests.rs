@@ -1,9 +1,21 @@
 mod common;
 
-use chrono::Utc;
+use chrono::{Duration, Utc};
 use common::{fixtures, NodeBuilder, TestDatabase};
-use net_monitor::models::{MonitorDetail, Node, NodeStatus};
-use net_monitor::monitor::check_node;
+use hickory_proto::op::{Message, MessageType, OpCode, ResponseCode};
+use hickory_proto::rr::rdata::{A, CNAME, MX, SRV, TXT};
+use hickory_proto::rr::{Name, RData, Record, RecordType};
+use net_monitor::credentials::{
+    CredentialStore, FileCredentialStore, SharedCredentialStore, SshCredential,
+};
+use net_monitor::database::Database;
+use net_monitor::models::{
+    DnsProtocol, DnsRecordType, HookCommands, HttpOptions, MonitorDetail, Node, NodeStatus,
+    TlsMode, TlsOptions,
+};
+use net_monitor::monitor::{check_node, check_node_with_credentials};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 #[tokio::test]
 #[cfg_attr(not(feature = "network-tests"), ignore)]
@@ -203,6 +215,8 @@ fn create_test_http_node() -> Node {
         detail: MonitorDetail::Http {
             url: "https://httpbin.org/status/200".to_string(),
             expected_status: 200,
+            timeout: 30,
+            options: Default::default(),
         },
         status: NodeStatus::Offline,
         last_check: None,
@@ -212,6 +226,12 @@ fn create_test_http_node() -> Node {
         consecutive_failures: 0,
         max_check_attempts: 3,
         retry_interval: 15,
+        cert_expires_at: None,
+        ping_stats: None,
+        hooks: HookCommands::default(),
+        parents: Vec::new(),
+        group: None,
+        tags: Vec::new(),
     }
 }
 
@@ -238,6 +258,7 @@ async fn test_check_node_http_failure() {
         detail: MonitorDetail::Http {
             url: "https://httpbin.org/status/404".to_string(),
             expected_status: 200, // Expecting 200 but will get 404
+            options: Default::default(),
         },
         status: NodeStatus::Offline,
         last_check: None,
@@ -267,6 +288,8 @@ async fn test_check_node_invalid_url() {
         detail: MonitorDetail::Http {
             url: "https://invalid-domain-that-does-not-exist-12345.com".to_string(),
             expected_status: 200,
+            timeout: 30,
+            options: Default::default(),
         },
         status: NodeStatus::Offline,
         last_check: None,
@@ -276,6 +299,12 @@ async fn test_check_node_invalid_url() {
         consecutive_failures: 0,
         max_check_attempts: 3,
         retry_interval: 15,
+        cert_expires_at: None,
+        ping_stats: None,
+        hooks: HookCommands::default(),
+        parents: Vec::new(),
+        group: None,
+        tags: Vec::new(),
     };
 
     let result = check_node(&node).await;
@@ -288,6 +317,482 @@ async fn test_check_node_invalid_url() {
     assert!(monitoring_result.details.is_some());
 }
 
+/// Serves `response` to every connection on a local port, passing each raw
+/// request (head and body) to the returned receiver
+async fn serve_http(response: &'static str) -> (u16, tokio::sync::mpsc::UnboundedReceiver<String>) {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let (request_tx, request_rx) = tokio::sync::mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        while let Ok((mut socket, _)) = listener.accept().await {
+            let mut request = Vec::new();
+            let mut buf = [0; 4096];
+            // Read the head, then as much body as Content-Length announces
+            loop {
+                let n = socket.read(&mut buf).await.unwrap_or(0);
+                request.extend_from_slice(&buf[..n]);
+                let text = String::from_utf8_lossy(&request).to_lowercase();
+                let complete = text.split_once("\r\n\r\n").is_some_and(|(head, body)| {
+                    let length = head
+                        .lines()
+                        .find_map(|line| line.strip_prefix("content-length:"))
+                        .map_or(0, |len| len.trim().parse().unwrap_or(0));
+                    body.len() >= length
+                });
+                if n == 0 || complete {
+                    break;
+                }
+            }
+            let _ = request_tx.send(String::from_utf8_lossy(&request).into_owned());
+            let _ = socket.write_all(response.as_bytes()).await;
+        }
+    });
+    (port, request_rx)
+}
+
+#[tokio::test]
+async fn test_check_http_sends_request_options_and_checks_body() {
+    let (port, mut requests) = serve_http(
+        "HTTP/1.1 201 Created\r\nContent-Type: application/json\r\nContent-Length: 25\r\nConnection: close\r\n\r\n{\"status\":\"ok\",\"count\":3}",
+    )
+    .await;
+
+    let options: HttpOptions = serde_json::from_value(serde_json::json!({
+        "method": "POST",
+        "headers": ["X-Token: abc123"],
+        "body": "ping=1",
+        "accepted_statuses": ["200-299"],
+        "assertions": {
+            "contains": "count",
+            "not_matches": "error|fail",
+            "json": { "pointer": "/status", "value": "ok" },
+            "max_body_bytes": 1024
+        }
+    }))
+    .unwrap();
+    let node = NodeBuilder::new()
+        .http(format!("http://127.0.0.1:{}/health", port), 200)
+        .http_options(options)
+        .build();
+
+    let result = check_node(&node).await.unwrap();
+    assert_eq!(result.status, NodeStatus::Online, "{:?}", result.details);
+
+    let request = requests.recv().await.unwrap();
+    assert!(request.starts_with("POST /health HTTP/1.1"));
+    assert!(request.to_lowercase().contains("x-token: abc123"));
+    assert!(request.ends_with("\r\n\r\nping=1"));
+}
+
+#[tokio::test]
+async fn test_check_http_reports_failed_assertions() {
+    let (port, _requests) = serve_http(
+        "HTTP/1.1 200 OK\r\nContent-Length: 21\r\nConnection: close\r\n\r\n{\"status\":\"degraded\"}",
+    )
+    .await;
+    let url = format!("http://127.0.0.1:{}", port);
+
+    let check = |options: serde_json::Value| {
+        let node = NodeBuilder::new()
+            .http(url.clone(), 200)
+            .http_options(serde_json::from_value(options).unwrap())
+            .build();
+        async move {
+            let result = check_node(&node).await.unwrap();
+            assert_eq!(result.status, NodeStatus::Offline);
+            result.details.unwrap()
+        }
+    };
+
+    let details = check(serde_json::json!({
+        "assertions": { "json": { "pointer": "/status", "value": "ok" } }
+    }))
+    .await;
+    assert!(details.contains("Expected \"ok\" at '/status' but found \"degraded\""));
+
+    let details = check(serde_json::json!({ "assertions": { "max_body_bytes": 8 } })).await;
+    assert!(details.contains("Response body is larger than 8 bytes"));
+
+    let details = check(serde_json::json!({ "accepted_statuses": ["500-599"] })).await;
+    assert!(details.contains("Expected status 500-599 but got 200 OK"));
+}
+
+/// Serves `response` over HTTPS with the localhost certificate from
+/// `tests/fixtures/tls`, which is signed by the `ca.pem` there
+async fn serve_https(response: &'static str) -> u16 {
+    use rustls::pki_types::pem::PemObject;
+    use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let certificate = CertificateDer::from_pem_file(tls_fixture("localhost.pem")).unwrap();
+    let key = PrivateKeyDer::from_pem_file(tls_fixture("localhost.key")).unwrap();
+    let config = rustls::ServerConfig::builder_with_provider(Arc::new(
+        rustls::crypto::ring::default_provider(),
+    ))
+    .with_safe_default_protocol_versions()
+    .unwrap()
+    .with_no_client_auth()
+    .with_single_cert(vec![certificate], key)
+    .unwrap();
+    let acceptor = tokio_rustls::TlsAcceptor::from(Arc::new(config));
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        while let Ok((socket, _)) = listener.accept().await {
+            let acceptor = acceptor.clone();
+            tokio::spawn(async move {
+                // Clients that reject the certificate end the handshake here
+                let Ok(mut stream) = acceptor.accept(socket).await else {
+                    return;
+                };
+                let mut request = Vec::new();
+                let mut buf = [0; 4096];
+                while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                    match stream.read(&mut buf).await {
+                        Ok(0) | Err(_) => return,
+                        Ok(n) => request.extend_from_slice(&buf[..n]),
+                    }
+                }
+                let _ = stream.write_all(response.as_bytes()).await;
+                let _ = stream.shutdown().await;
+            });
+        }
+    });
+    port
+}
+
+fn tls_fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/tls")
+        .join(name)
+}
+
+/// SHA-256 fingerprint of `tests/fixtures/tls/localhost.pem`
+const LOCALHOST_FINGERPRINT: &str =
+    "sha256:E9:2D:80:D4:A5:85:48:E2:15:1B:9D:60:C2:B7:48:42:C0:41:44:E6:40:99:66:F3:BB:93:F4:AE:58:42:C7:71";
+
+#[tokio::test]
+async fn test_check_https_tls_modes() {
+    let port =
+        serve_https("HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok").await;
+    let url = format!("https://localhost:{}/", port);
+
+    let check = |tls: TlsOptions| {
+        let node = NodeBuilder::new()
+            .http(url.clone(), 200)
+            .http_options(HttpOptions {
+                tls,
+                ..Default::default()
+            })
+            .build();
+        async move { check_node(&node).await.unwrap() }
+    };
+
+    // The test CA isn't a system root, so verification needs the bundle
+    let result = check(TlsOptions::default()).await;
+    assert_eq!(result.status, NodeStatus::Offline);
+    assert!(
+        result.details.as_deref().unwrap().contains("UnknownIssuer"),
+        "{:?}",
+        result.details
+    );
+
+    let result = check(TlsOptions {
+        ca_bundle: Some(tls_fixture("ca.pem")),
+        ..Default::default()
+    })
+    .await;
+    assert_eq!(result.status, NodeStatus::Online, "{:?}", result.details);
+    // The fixture certificate is valid until 2126
+    assert!(result.cert_expires_at.unwrap() > Utc::now() + Duration::days(365 * 90));
+
+    let result = check(TlsOptions {
+        mode: TlsMode::AcceptInvalid,
+        ..Default::default()
+    })
+    .await;
+    assert_eq!(result.status, NodeStatus::Online, "{:?}", result.details);
+
+    let result = check(TlsOptions {
+        mode: LOCALHOST_FINGERPRINT.parse().unwrap(),
+        ..Default::default()
+    })
+    .await;
+    assert_eq!(result.status, NodeStatus::Online, "{:?}", result.details);
+
+    let result = check(TlsOptions {
+        mode: TlsMode::Pinned([0; 32]),
+        ..Default::default()
+    })
+    .await;
+    assert_eq!(result.status, NodeStatus::Offline);
+    assert!(
+        result
+            .details
+            .as_deref()
+            .unwrap()
+            .contains("does not match the pinned one"),
+        "{:?}",
+        result.details
+    );
+}
+
+#[tokio::test]
+async fn test_check_https_certificate_expiry_thresholds() {
+    let port =
+        serve_https("HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok").await;
+    let check = |expiry_warning_days, expiry_critical_days| {
+        let node = NodeBuilder::new()
+            .http(format!("https://127.0.0.1:{}/", port), 200)
+            .http_options(HttpOptions {
+                tls: TlsOptions {
+                    ca_bundle: Some(tls_fixture("ca.pem")),
+                    expiry_warning_days,
+                    expiry_critical_days,
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .build();
+        async move { check_node(&node).await.unwrap() }
+    };
+
+    let result = check(14, 3).await;
+    assert_eq!(result.status, NodeStatus::Online, "{:?}", result.details);
+    assert!(result.details.unwrap().contains("certificate expires in"));
+
+    let result = check(40_000, 3).await;
+    assert_eq!(result.status, NodeStatus::Degraded);
+    assert!(result
+        .details
+        .unwrap()
+        .contains("within the 40000 day warning threshold"));
+
+    let result = check(0, 40_000).await;
+    assert_eq!(result.status, NodeStatus::Offline);
+    assert!(result
+        .details
+        .unwrap()
+        .starts_with("Certificate expires in"));
+}
+
+#[tokio::test]
+async fn test_check_http_redirect_limit() {
+    let (port, _requests) = serve_http(
+        "HTTP/1.1 302 Found\r\nLocation: /elsewhere\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+    )
+    .await;
+
+    // Not following redirects checks the redirect response itself
+    let node = NodeBuilder::new()
+        .http(format!("http://127.0.0.1:{}", port), 302)
+        .http_options(HttpOptions {
+            max_redirects: 0,
+            ..Default::default()
+        })
+        .build();
+    let result = check_node(&node).await.unwrap();
+    assert_eq!(result.status, NodeStatus::Online, "{:?}", result.details);
+
+    // Following them ends in an error once the limit is reached
+    let node = NodeBuilder::new()
+        .http(format!("http://127.0.0.1:{}", port), 200)
+        .http_options(HttpOptions {
+            max_redirects: 2,
+            ..Default::default()
+        })
+        .build();
+    let result = check_node(&node).await.unwrap();
+    assert_eq!(result.status, NodeStatus::Offline);
+}
+
+#[tokio::test]
+async fn test_check_ssh_node_fails_against_non_ssh_service() {
+    use tokio::io::AsyncWriteExt;
+
+    // Answers like a web server instead of sending an SSH banner
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        while let Ok((mut socket, _)) = listener.accept().await {
+            let _ = socket.write_all(b"HTTP/1.1 400 Bad Request\r\n\r\n").await;
+        }
+    });
+
+    let node = NodeBuilder::new()
+        .ssh("127.0.0.1", port, None, None)
+        .build();
+    let result = check_node(&node).await.unwrap();
+
+    assert_eq!(result.status, NodeStatus::Offline);
+    assert!(result.details.unwrap().contains("SSH handshake failed"));
+}
+
+#[tokio::test]
+async fn test_check_ssh_node_needs_unlocked_credential_store() {
+    let node = NodeBuilder::new()
+        .ssh("127.0.0.1", 22, Some("uptime"), None)
+        .credential_id("missing")
+        .build();
+
+    let result = check_node(&node).await.unwrap();
+    assert_eq!(result.status, NodeStatus::Offline);
+    assert!(result
+        .details
+        .unwrap()
+        .contains("Credential store is locked"));
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let store = FileCredentialStore::with_path(
+        "master".to_string(),
+        &temp_dir.path().join("credentials.enc"),
+    )
+    .unwrap();
+    let credentials = SharedCredentialStore::default();
+    credentials.set(Some(Box::new(store)));
+
+    let result = check_node_with_credentials(&node, &credentials)
+        .await
+        .unwrap();
+    assert_eq!(result.status, NodeStatus::Offline);
+    assert!(result
+        .details
+        .unwrap()
+        .contains("Credential missing not found"));
+}
+
+/// Runs `sshd` on a free local port, authorizing a freshly generated key for
+/// the current user. Returns `None` when OpenSSH isn't installed.
+#[cfg(unix)]
+fn spawn_sshd(dir: &std::path::Path) -> Option<(std::process::Child, u16, std::path::PathBuf)> {
+    use std::process::{Command, Stdio};
+
+    let sshd = ["/usr/sbin/sshd", "/usr/local/sbin/sshd"]
+        .into_iter()
+        .map(std::path::PathBuf::from)
+        .find(|path| path.exists())?;
+
+    let keygen = |path: &std::path::Path| {
+        Command::new("ssh-keygen")
+            .args(["-q", "-t", "ed25519", "-N", "", "-f"])
+            .arg(path)
+            .status()
+            .map(|status| status.success())
+            .unwrap_or(false)
+    };
+    let host_key = dir.join("host_key");
+    let user_key = dir.join("user_key");
+    if !keygen(&host_key) || !keygen(&user_key) {
+        return None;
+    }
+    std::fs::copy(dir.join("user_key.pub"), dir.join("authorized_keys")).unwrap();
+
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let config = dir.join("sshd_config");
+    std::fs::write(
+        &config,
+        format!(
+            "Port {port}\nListenAddress 127.0.0.1\nHostKey {}\nAuthorizedKeysFile {}\n\
+             PidFile none\nStrictModes no\nUsePAM no\nPasswordAuthentication no\n",
+            host_key.display(),
+            dir.join("authorized_keys").display(),
+        ),
+    )
+    .unwrap();
+
+    let child = Command::new(sshd)
+        .args(["-D", "-e", "-f"])
+        .arg(&config)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+
+    // Wait for sshd to start listening
+    for _ in 0..50 {
+        if std::net::TcpStream::connect(("127.0.0.1", port)).is_ok() {
+            return Some((child, port, user_key));
+        }
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+    None
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_check_ssh_node_against_local_sshd() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let Some((mut sshd, port, user_key)) = spawn_sshd(temp_dir.path()) else {
+        eprintln!("sshd not available; skipping");
+        return;
+    };
+
+    let mut store = FileCredentialStore::with_path(
+        "master".to_string(),
+        &temp_dir.path().join("credentials.enc"),
+    )
+    .unwrap();
+    let id = store
+        .store_credential(
+            "Local key".to_string(),
+            None,
+            SshCredential::Key {
+                username: whoami(),
+                private_key_path: user_key,
+                passphrase: None,
+            },
+        )
+        .unwrap();
+    let credentials = SharedCredentialStore::default();
+    credentials.set(Some(Box::new(store)));
+
+    let check = |command: Option<&'static str>, expected_output: Option<&'static str>| {
+        let node = NodeBuilder::new()
+            .ssh("127.0.0.1", port, command, expected_output)
+            .credential_id(id.clone())
+            .build();
+        let credentials = credentials.clone();
+        async move {
+            check_node_with_credentials(&node, &credentials)
+                .await
+                .unwrap()
+        }
+    };
+
+    let login = check(None, None).await;
+    assert_eq!(login.status, NodeStatus::Online, "{:?}", login.details);
+
+    let matched = check(Some("echo healthy"), Some("^heal")).await;
+    assert_eq!(matched.status, NodeStatus::Online, "{:?}", matched.details);
+
+    let mismatched = check(Some("echo degraded"), Some("^healthy$")).await;
+    assert_eq!(mismatched.status, NodeStatus::Offline);
+    assert!(mismatched.details.unwrap().contains("did not match"));
+
+    let failed = check(Some("exit 3"), None).await;
+    assert_eq!(failed.status, NodeStatus::Offline);
+    assert!(failed.details.unwrap().contains("exited with status 3"));
+
+    let _ = sshd.kill();
+    let _ = sshd.wait();
+}
+
+#[cfg(unix)]
+fn whoami() -> String {
+    let output = std::process::Command::new("id")
+        .arg("-un")
+        .output()
+        .unwrap();
+    String::from_utf8(output.stdout).unwrap().trim().to_string()
+}
+
 // Note: Tests for check_http and check_ping were removed because these are
 // private functions. Their functionality is tested through check_node tests.
 
@@ -321,14 +826,19 @@ fn test_monitor_detail_variants() {
     let http_detail = MonitorDetail::Http {
         url: "https://example.com".to_string(),
         expected_status: 200,
+        timeout: 30,
+        options: Default::default(),
     };
     match http_detail {
         MonitorDetail::Http {
             url,
             expected_status,
+            timeout,
+            ..
         } => {
             assert_eq!(url, "https://example.com");
             assert_eq!(expected_status, 200);
+            assert_eq!(timeout, 30);
         }
         _ => panic!("Expected HTTP variant"),
     }
@@ -337,12 +847,15 @@ fn test_monitor_detail_variants() {
         host: "192.168.1.1".to_string(),
         count: 4,
         timeout: 5,
+        fallback_port: 80,
+        thresholds: Default::default(),
     };
     match ping_detail {
         MonitorDetail::Ping {
             host,
             count,
             timeout,
+            ..
         } => {
             assert_eq!(host, "192.168.1.1");
             assert_eq!(count, 4);
@@ -439,6 +952,12 @@ async fn test_tcp_monitoring_failure() {
         consecutive_failures: 0,
         max_check_attempts: 3,
         retry_interval: 15,
+        cert_expires_at: None,
+        ping_stats: None,
+        hooks: HookCommands::default(),
+        parents: Vec::new(),
+        group: None,
+        tags: Vec::new(),
     };
 
     let node_id = test_db.db.add_node(&node).unwrap();
@@ -513,6 +1032,12 @@ async fn test_tcp_monitoring_workflow() {
         consecutive_failures: 0,
         max_check_attempts: 3,
         retry_interval: 15,
+        cert_expires_at: None,
+        ping_stats: None,
+        hooks: HookCommands::default(),
+        parents: Vec::new(),
+        group: None,
+        tags: Vec::new(),
     };
 
     // Add node to database
@@ -546,6 +1071,8 @@ async fn test_check_http_with_timeout() {
         detail: MonitorDetail::Http {
             url: "http://example.com:81".to_string(), // Non-standard port likely to timeout
             expected_status: 200,
+            timeout: 30,
+            options: Default::default(),
         },
         status: NodeStatus::Offline,
         last_check: None,
@@ -555,6 +1082,12 @@ async fn test_check_http_with_timeout() {
         consecutive_failures: 0,
         max_check_attempts: 3,
         retry_interval: 15,
+        cert_expires_at: None,
+        ping_stats: None,
+        hooks: HookCommands::default(),
+        parents: Vec::new(),
+        group: None,
+        tags: Vec::new(),
     };
 
     // This should timeout or fail
@@ -586,6 +1119,12 @@ async fn test_check_tcp_multiple_addresses() {
         consecutive_failures: 0,
         max_check_attempts: 3,
         retry_interval: 15,
+        cert_expires_at: None,
+        ping_stats: None,
+        hooks: HookCommands::default(),
+        parents: Vec::new(),
+        group: None,
+        tags: Vec::new(),
     };
 
     let result = check_node(&node).await;
@@ -614,6 +1153,12 @@ async fn test_check_tcp_connection_refused() {
         consecutive_failures: 0,
         max_check_attempts: 3,
         retry_interval: 15,
+        cert_expires_at: None,
+        ping_stats: None,
+        hooks: HookCommands::default(),
+        parents: Vec::new(),
+        group: None,
+        tags: Vec::new(),
     };
 
     let result = check_node(&node).await;
@@ -634,6 +1179,8 @@ async fn test_check_ping_invalid_format() {
             host: "not-a-valid-ip-address-!!!".to_string(),
             count: 4,
             timeout: 5,
+            fallback_port: 80,
+            thresholds: Default::default(),
         },
         status: NodeStatus::Offline,
         last_check: None,
@@ -643,6 +1190,12 @@ async fn test_check_ping_invalid_format() {
         consecutive_failures: 0,
         max_check_attempts: 3,
         retry_interval: 15,
+        cert_expires_at: None,
+        ping_stats: None,
+        hooks: HookCommands::default(),
+        parents: Vec::new(),
+        group: None,
+        tags: Vec::new(),
     };
 
     let result = check_node(&node).await;
@@ -692,6 +1245,12 @@ async fn test_check_tcp_short_timeout() {
         consecutive_failures: 0,
         max_check_attempts: 3,
         retry_interval: 15,
+        cert_expires_at: None,
+        ping_stats: None,
+        hooks: HookCommands::default(),
+        parents: Vec::new(),
+        group: None,
+        tags: Vec::new(),
     };
 
     let result = check_node(&node).await;
@@ -709,6 +1268,8 @@ async fn test_check_ping_short_timeout() {
             host: "8.8.8.8".to_string(),
             count: 1,
             timeout: 1, // Very short timeout
+            fallback_port: 80,
+            thresholds: Default::default(),
         },
         status: NodeStatus::Offline,
         last_check: None,
@@ -718,6 +1279,12 @@ async fn test_check_ping_short_timeout() {
         consecutive_failures: 0,
         max_check_attempts: 3,
         retry_interval: 15,
+        cert_expires_at: None,
+        ping_stats: None,
+        hooks: HookCommands::default(),
+        parents: Vec::new(),
+        group: None,
+        tags: Vec::new(),
     };
 
     let result = check_node(&node).await;
@@ -740,3 +1307,399 @@ async fn test_monitoring_result_timestamp_accuracy() {
         assert!(monitoring_result.timestamp <= after);
     }
 }
+
+// ========== DNS Monitoring Tests ==========
+
+/// Runs a DNS server on 127.0.0.1 that answers from `records` over both UDP
+/// and TCP, and with NXDOMAIN for names it has no records for. With
+/// `truncate_udp`, UDP answers only carry the truncation flag, so clients
+/// have to retry over TCP.
+async fn serve_dns(records: Vec<Record>, truncate_udp: bool) -> u16 {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let udp = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let port = udp.local_addr().unwrap().port();
+    let tcp = tokio::net::TcpListener::bind(("127.0.0.1", port))
+        .await
+        .unwrap();
+    let records = Arc::new(records);
+
+    let udp_records = Arc::clone(&records);
+    tokio::spawn(async move {
+        let mut buf = [0; 512];
+        while let Ok((len, peer)) = udp.recv_from(&mut buf).await {
+            let response = dns_response(&buf[..len], &udp_records, truncate_udp);
+            let _ = udp.send_to(&response, peer).await;
+        }
+    });
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = tcp.accept().await {
+            let Ok(len) = stream.read_u16().await else {
+                continue;
+            };
+            let mut request = vec![0; len.into()];
+            if stream.read_exact(&mut request).await.is_err() {
+                continue;
+            }
+            let response = dns_response(&request, &records, false);
+            let _ = stream
+                .write_all(&(response.len() as u16).to_be_bytes())
+                .await;
+            let _ = stream.write_all(&response).await;
+        }
+    });
+    port
+}
+
+fn dns_response(request: &[u8], records: &[Record], truncate: bool) -> Vec<u8> {
+    let request = Message::from_vec(request).unwrap();
+    let query = request.queries()[0].clone();
+    let mut response = Message::new();
+    response
+        .set_id(request.id())
+        .set_message_type(MessageType::Response)
+        .set_op_code(OpCode::Query)
+        .add_query(query.clone());
+
+    let known: Vec<&Record> = records
+        .iter()
+        .filter(|r| r.name() == query.name())
+        .collect();
+    if known.is_empty() {
+        response.set_response_code(ResponseCode::NXDomain);
+    } else if truncate {
+        response.set_truncated(true);
+    } else {
+        // Like a real server, answer an address query for an alias with the CNAME
+        for record in known {
+            if record.record_type() == query.query_type()
+                || record.record_type() == RecordType::CNAME
+            {
+                response.add_answer(record.clone());
+            }
+        }
+    }
+    response.to_vec().unwrap()
+}
+
+fn dns_name(name: &str) -> Name {
+    Name::from_ascii(name).unwrap()
+}
+
+fn example_zone() -> Vec<Record> {
+    let record = |name: &str, rdata: RData| Record::from_rdata(dns_name(name), 300, rdata);
+    vec![
+        record("www.example.com.", RData::A(A::new(10, 0, 0, 5))),
+        record("www.example.com.", RData::A(A::new(10, 0, 0, 6))),
+        record(
+            "alias.example.com.",
+            RData::CNAME(CNAME(dns_name("www.example.com."))),
+        ),
+        record(
+            "example.com.",
+            RData::MX(MX::new(10, dns_name("mx1.example.com."))),
+        ),
+        record(
+            "example.com.",
+            RData::TXT(TXT::new(vec!["v=spf1 ".to_string(), "-all".to_string()])),
+        ),
+        record(
+            "_sip._tcp.example.com.",
+            RData::SRV(SRV::new(10, 5, 5060, dns_name("sip.example.com."))),
+        ),
+    ]
+}
+
+#[tokio::test]
+async fn test_check_dns_compares_answers() {
+    let port = serve_dns(example_zone(), false).await;
+    let check = |name: &'static str, record_type: DnsRecordType, expected: &'static [&str]| {
+        let node = NodeBuilder::new()
+            .dns("127.0.0.1", port, name, record_type, expected)
+            .build();
+        async move { check_node(&node).await.unwrap() }
+    };
+
+    let result = check("www.example.com", DnsRecordType::A, &["10.0.0.6"]).await;
+    assert_eq!(result.status, NodeStatus::Online, "{:?}", result.details);
+    assert_eq!(
+        result.details.as_deref(),
+        Some("Resolved www.example.com A to 10.0.0.5, 10.0.0.6")
+    );
+    assert!(result.response_time.is_some());
+
+    let result = check("www.example.com", DnsRecordType::A, &["10.0.0.7"]).await;
+    assert_eq!(result.status, NodeStatus::Offline);
+    assert_eq!(
+        result.details.as_deref(),
+        Some("Expected 10.0.0.7 for www.example.com A but got 10.0.0.5, 10.0.0.6")
+    );
+
+    // Names compare without regard to case or the trailing dot
+    let result = check(
+        "alias.example.com",
+        DnsRecordType::Cname,
+        &["WWW.example.com."],
+    )
+    .await;
+    assert_eq!(result.status, NodeStatus::Online, "{:?}", result.details);
+    let result = check("example.com", DnsRecordType::Mx, &["10 mx1.example.com"]).await;
+    assert_eq!(result.status, NodeStatus::Online, "{:?}", result.details);
+    let result = check(
+        "_sip._tcp.example.com",
+        DnsRecordType::Srv,
+        &["10 5 5060 sip.example.com"],
+    )
+    .await;
+    assert_eq!(result.status, NodeStatus::Online, "{:?}", result.details);
+    let result = check("example.com", DnsRecordType::Txt, &["v=spf1 -all"]).await;
+    assert_eq!(result.status, NodeStatus::Online, "{:?}", result.details);
+
+    // The CNAME leading to the addresses isn't an A record itself
+    let result = check("alias.example.com", DnsRecordType::A, &[]).await;
+    assert_eq!(result.status, NodeStatus::Offline);
+    assert_eq!(
+        result.details.as_deref(),
+        Some("No A records for alias.example.com")
+    );
+
+    let result = check("missing.example.com", DnsRecordType::A, &[]).await;
+    assert_eq!(result.status, NodeStatus::Offline);
+    assert!(result.details.unwrap().contains("Non-Existent Domain"));
+}
+
+#[tokio::test]
+async fn test_check_dns_over_tcp() {
+    // Every UDP answer is truncated, so only TCP gets the records
+    let port = serve_dns(example_zone(), true).await;
+
+    let mut node = NodeBuilder::new()
+        .dns(
+            "127.0.0.1",
+            port,
+            "www.example.com",
+            DnsRecordType::A,
+            &["10.0.0.5"],
+        )
+        .build();
+    let result = check_node(&node).await.unwrap();
+    assert_eq!(result.status, NodeStatus::Online, "{:?}", result.details);
+
+    if let MonitorDetail::Dns { protocol, .. } = &mut node.detail {
+        *protocol = DnsProtocol::Tcp;
+    }
+    let result = check_node(&node).await.unwrap();
+    assert_eq!(result.status, NodeStatus::Online, "{:?}", result.details);
+}
+
+#[tokio::test]
+async fn test_check_dns_timeout() {
+    // Bound but never answering
+    let silent = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let port = silent.local_addr().unwrap().port();
+
+    let mut node = NodeBuilder::new()
+        .dns("127.0.0.1", port, "www.example.com", DnsRecordType::A, &[])
+        .build();
+    if let MonitorDetail::Dns { timeout, .. } = &mut node.detail {
+        *timeout = 1;
+    }
+    let result = check_node(&node).await.unwrap();
+    assert_eq!(result.status, NodeStatus::Offline);
+    assert_eq!(
+        result.details.unwrap(),
+        format!("DNS server 127.0.0.1:{} did not answer within 1s", port)
+    );
+}
+
+// ========== UDP Monitoring Tests ==========
+
+/// Runs a UDP service on 127.0.0.1 that answers every datagram with `reply`
+async fn serve_udp(reply: &'static [u8]) -> u16 {
+    let socket = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let port = socket.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        let mut buf = [0; 512];
+        while let Ok((_, peer)) = socket.recv_from(&mut buf).await {
+            let _ = socket.send_to(reply, peer).await;
+        }
+    });
+    port
+}
+
+#[tokio::test]
+async fn test_check_udp_matches_reply() {
+    let port = serve_udp(b"\xffPONG ok").await;
+    let check = |expected: Option<&'static str>| {
+        let node = NodeBuilder::new()
+            .udp("127.0.0.1", port, "hex:ff 50 49 4e 47", expected)
+            .build();
+        async move { check_node(&node).await.unwrap() }
+    };
+
+    let result = check(None).await;
+    assert_eq!(result.status, NodeStatus::Online, "{:?}", result.details);
+    assert_eq!(
+        result.details.unwrap(),
+        format!(
+            "127.0.0.1:{} replied with 8 bytes: hex:ff504f4e47206f6b",
+            port
+        )
+    );
+
+    let result = check(Some("PONG (ok|ready)")).await;
+    assert_eq!(result.status, NodeStatus::Online, "{:?}", result.details);
+    let result = check(Some("hex:ff50")).await;
+    assert_eq!(result.status, NodeStatus::Online, "{:?}", result.details);
+
+    let result = check(Some("hex:fe")).await;
+    assert_eq!(result.status, NodeStatus::Offline);
+    assert_eq!(
+        result.details.unwrap(),
+        format!(
+            "Reply from 127.0.0.1:{} does not match 'hex:fe': hex:ff504f4e47206f6b",
+            port
+        )
+    );
+}
+
+#[tokio::test]
+async fn test_check_udp_without_reply() {
+    // Bound but never answering
+    let silent = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let port = silent.local_addr().unwrap().port();
+
+    let mut node = NodeBuilder::new()
+        .udp("127.0.0.1", port, "status", None)
+        .build();
+    let result = check_node(&node).await.unwrap();
+    assert_eq!(result.status, NodeStatus::Offline);
+    assert_eq!(
+        result.details.unwrap(),
+        format!("No reply from 127.0.0.1:{} within 1s", port)
+    );
+
+    if let MonitorDetail::Udp { no_reply_is_up, .. } = &mut node.detail {
+        *no_reply_is_up = true;
+    }
+    let result = check_node(&node).await.unwrap();
+    assert_eq!(result.status, NodeStatus::Online, "{:?}", result.details);
+
+    // Once nothing listens, the host reports the port unreachable
+    drop(silent);
+    let result = check_node(&node).await.unwrap();
+    assert_eq!(result.status, NodeStatus::Offline);
+    assert_eq!(
+        result.details.unwrap(),
+        format!("127.0.0.1:{} is closed (ICMP port unreachable)", port)
+    );
+}
+
+// ========== Monitoring Engine Scheduler Tests ==========
+
+/// Waits for the first update for `node_id` on the engine's update channel.
+fn wait_for_update(
+    update_rx: &std::sync::mpsc::Receiver<Node>,
+    node_id: i64,
+    timeout: std::time::Duration,
+) -> Option<(Node, std::time::Duration)> {
+    let started = std::time::Instant::now();
+    while started.elapsed() < timeout {
+        if let Ok(node) = update_rx.recv_timeout(std::time::Duration::from_millis(100)) {
+            if node.id == Some(node_id) {
+                return Some((node, started.elapsed()));
+            }
+        }
+    }
+    None
+}
+
+#[test]
+fn test_engine_slow_node_does_not_block_other_checks() {
+    use net_monitor::monitoring_engine::{start_monitoring_with_config, EngineConfig};
+    use std::net::TcpListener;
+    use std::time::Duration;
+
+    let test_db = TestDatabase::new();
+
+    // Accepts connections at the kernel level but never answers the HTTP request
+    let silent_listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let silent_port = silent_listener.local_addr().unwrap().port();
+    let open_listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let open_port = open_listener.local_addr().unwrap().port();
+
+    let mut slow_node = NodeBuilder::new()
+        .name("Hanging HTTP")
+        .http(format!("http://127.0.0.1:{}", silent_port), 200)
+        .build();
+    slow_node.id = Some(test_db.db.add_node(&slow_node).unwrap());
+
+    let mut fast_node = NodeBuilder::new()
+        .name("Local TCP")
+        .tcp("127.0.0.1", open_port, 2)
+        .build();
+    fast_node.id = Some(test_db.db.add_node(&fast_node).unwrap());
+
+    let config = EngineConfig {
+        max_concurrent_checks: 4,
+        check_deadline: Duration::from_secs(2),
+        max_start_jitter: Duration::ZERO,
+        ..EngineConfig::default()
+    };
+
+    let (update_tx, update_rx) = std::sync::mpsc::channel();
+    let handle = start_monitoring_with_config(
+        Database::new(test_db.path()).unwrap(),
+        vec![slow_node.clone(), fast_node.clone()],
+        update_tx,
+        config,
+        SharedCredentialStore::default(),
+    );
+
+    // The TCP node must report long before the hanging HTTP check gives up
+    let (fast, fast_elapsed) =
+        wait_for_update(&update_rx, fast_node.id.unwrap(), Duration::from_secs(5))
+            .expect("TCP node was never checked");
+    assert_eq!(fast.status, NodeStatus::Online);
+    assert!(fast_elapsed < Duration::from_secs(2));
+
+    // Every check is kept in the time series, not only status transitions
+    let samples = test_db
+        .db
+        .get_check_samples(
+            fast_node.id.unwrap(),
+            Utc::now() - chrono::Duration::minutes(1),
+            Utc::now() + chrono::Duration::minutes(1),
+        )
+        .unwrap();
+    assert_eq!(samples.len(), 1);
+    assert!(samples[0].success);
+
+    // The hanging check is cut off by the deadline and counted as a soft failure
+    let (slow, _) = wait_for_update(&update_rx, slow_node.id.unwrap(), Duration::from_secs(6))
+        .expect("Hanging node never reported");
+    assert_eq!(slow.status, NodeStatus::Degraded);
+    assert_eq!(slow.consecutive_failures, 1);
+
+    handle.stop_tx.send(()).unwrap();
+    drop(silent_listener);
+    drop(open_listener);
+}
+
+#[test]
+fn test_engine_stops_on_signal() {
+    use net_monitor::monitoring_engine::start_monitoring;
+    use std::time::Duration;
+
+    let test_db = TestDatabase::new();
+    let (update_tx, update_rx) = std::sync::mpsc::channel::<Node>();
+    let handle = start_monitoring(Database::new(test_db.path()).unwrap(), vec![], update_tx);
+
+    handle.stop_tx.send(()).unwrap();
+
+    // Once the engine thread exits it drops its sender, disconnecting the channel
+    assert_eq!(
+        update_rx.recv_timeout(Duration::from_secs(5)),
+        Err(std::sync::mpsc::RecvTimeoutError::Disconnected)
+    );
+}
@@ -0,0 +1,666 @@
+mod common;
+
+use chrono::Utc;
+use common::{NodeBuilder, TestDatabase};
+use net_monitor::config::{EmailSettings, NotificationSettings, SmtpSecurity, WebhookSettings};
+use net_monitor::credentials::{
+    CredentialStore, FileCredentialStore, SharedCredentialStore, SshCredential,
+};
+use net_monitor::database::Database;
+use net_monitor::models::{MaintenanceScope, MaintenanceWindow, NodeStatus, StatusChange};
+use net_monitor::monitoring_engine::{start_monitoring_with_config, EngineConfig};
+use net_monitor::notifications::{
+    email_body, email_subject, retry_delay, Notification, NotificationEvent, Notifier,
+    MAX_RETRY_DELAY,
+};
+use std::time::Duration;
+
+/// Answers each connection on a local port with the next of `responses`,
+/// repeating the last one, and passes each raw request to the returned receiver
+async fn serve_http(
+    responses: Vec<&'static str>,
+) -> (u16, tokio::sync::mpsc::UnboundedReceiver<String>) {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let (request_tx, request_rx) = tokio::sync::mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        let mut served = 0;
+        while let Ok((mut socket, _)) = listener.accept().await {
+            let mut request = Vec::new();
+            let mut buf = [0; 4096];
+            // Read the head, then as much body as Content-Length announces
+            loop {
+                let n = socket.read(&mut buf).await.unwrap_or(0);
+                request.extend_from_slice(&buf[..n]);
+                let text = String::from_utf8_lossy(&request).to_lowercase();
+                let complete = text.split_once("\r\n\r\n").is_some_and(|(head, body)| {
+                    let length = head
+                        .lines()
+                        .find_map(|line| line.strip_prefix("content-length:"))
+                        .map_or(0, |len| len.trim().parse().unwrap_or(0));
+                    body.len() >= length
+                });
+                if n == 0 || complete {
+                    break;
+                }
+            }
+            let _ = request_tx.send(String::from_utf8_lossy(&request).into_owned());
+            let response = responses[served.min(responses.len() - 1)];
+            served += 1;
+            let _ = socket.write_all(response.as_bytes()).await;
+        }
+    });
+    (port, request_rx)
+}
+
+const ACCEPTED: &str = "HTTP/1.1 204 No Content\r\nConnection: close\r\n\r\n";
+const UNAVAILABLE: &str =
+    "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+const BAD_REQUEST: &str =
+    "HTTP/1.1 400 Bad Request\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+
+fn change(from_status: NodeStatus, to_status: NodeStatus) -> StatusChange {
+    StatusChange {
+        id: None,
+        node_id: 1,
+        from_status,
+        to_status,
+        changed_at: Utc::now(),
+        duration_ms: Some(90_000),
+        in_maintenance: false,
+    }
+}
+
+fn settings(webhook_port: u16) -> NotificationSettings {
+    NotificationSettings {
+        enabled: true,
+        webhooks: vec![WebhookSettings {
+            url: format!("http://127.0.0.1:{}/hooks/net-monitor", webhook_port),
+            headers: vec!["Authorization: Bearer s3cret".parse().unwrap()],
+        }],
+        retry_backoff_secs: 0,
+        ..NotificationSettings::default()
+    }
+}
+
+fn test_notifier(settings: &NotificationSettings, test_db: &TestDatabase) -> Notifier {
+    Notifier::new(
+        settings,
+        Database::new(test_db.path()).unwrap(),
+        SharedCredentialStore::default(),
+    )
+    .unwrap()
+}
+
+/// Splits a raw request into its head and JSON body
+fn parse_request(request: &str) -> (String, serde_json::Value) {
+    let (head, body) = request.split_once("\r\n\r\n").unwrap();
+    (head.to_lowercase(), serde_json::from_str(body).unwrap())
+}
+
+/// Accepts mail on a local port like a plain SMTP server that takes any login,
+/// passing each session's commands and message to the returned receiver
+async fn serve_smtp() -> (
+    u16,
+    tokio::sync::mpsc::UnboundedReceiver<(Vec<String>, String)>,
+) {
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let (mail_tx, mail_rx) = tokio::sync::mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        while let Ok((socket, _)) = listener.accept().await {
+            let (reader, mut writer) = socket.into_split();
+            let mut lines = BufReader::new(reader).lines();
+            let mut commands = Vec::new();
+            let mut message = String::new();
+            let _ = writer.write_all(b"220 sink ESMTP\r\n").await;
+            while let Ok(Some(line)) = lines.next_line().await {
+                let verb = line.split(' ').next().unwrap_or_default().to_uppercase();
+                commands.push(line);
+                let reply: &[u8] = match verb.as_str() {
+                    "EHLO" => b"250-sink\r\n250 AUTH PLAIN LOGIN\r\n",
+                    "AUTH" => b"235 Authenticated\r\n",
+                    "DATA" => {
+                        let _ = writer.write_all(b"354 Go ahead\r\n").await;
+                        while let Ok(Some(line)) = lines.next_line().await {
+                            if line == "." {
+                                break;
+                            }
+                            message.push_str(&line);
+                            message.push('\n');
+                        }
+                        b"250 Queued\r\n"
+                    }
+                    "QUIT" => b"221 Bye\r\n",
+                    _ => b"250 OK\r\n",
+                };
+                let _ = writer.write_all(reply).await;
+                if verb == "QUIT" {
+                    break;
+                }
+            }
+            let _ = mail_tx.send((commands, message));
+        }
+    });
+    (port, mail_rx)
+}
+
+// ========== Event Selection Tests ==========
+
+#[test]
+fn test_notification_event_for_change() {
+    let defaults = NotificationSettings::default();
+    let event = |settings: &NotificationSettings, from, to| {
+        NotificationEvent::for_change(settings, &change(from, to))
+    };
+    use NodeStatus::{Degraded, Offline, Online};
+
+    assert_eq!(
+        event(&defaults, Online, Offline),
+        Some(NotificationEvent::Offline)
+    );
+    assert_eq!(
+        event(&defaults, Degraded, Offline),
+        Some(NotificationEvent::Offline)
+    );
+    assert_eq!(
+        event(&defaults, Offline, Online),
+        Some(NotificationEvent::Recovery)
+    );
+    // Degraded nodes are not announced by default, and neither is their recovery
+    assert_eq!(event(&defaults, Online, Degraded), None);
+    assert_eq!(event(&defaults, Degraded, Online), None);
+    assert_eq!(event(&defaults, Offline, Degraded), None);
+
+    let everything = NotificationSettings {
+        on_degraded: true,
+        ..NotificationSettings::default()
+    };
+    assert_eq!(
+        event(&everything, Online, Degraded),
+        Some(NotificationEvent::Degraded)
+    );
+    assert_eq!(
+        event(&everything, Degraded, Online),
+        Some(NotificationEvent::Recovery)
+    );
+
+    let no_recovery = NotificationSettings {
+        on_recovery: false,
+        ..NotificationSettings::default()
+    };
+    assert_eq!(event(&no_recovery, Offline, Online), None);
+}
+
+#[test]
+fn test_retry_delay_doubles_up_to_cap() {
+    let backoff = Duration::from_secs(5);
+    assert_eq!(retry_delay(backoff, 1), Duration::from_secs(5));
+    assert_eq!(retry_delay(backoff, 2), Duration::from_secs(10));
+    assert_eq!(retry_delay(backoff, 4), Duration::from_secs(40));
+    assert_eq!(retry_delay(backoff, 10), MAX_RETRY_DELAY);
+    assert_eq!(retry_delay(backoff, u32::MAX), MAX_RETRY_DELAY);
+    assert_eq!(retry_delay(Duration::ZERO, 3), Duration::ZERO);
+}
+
+// ========== Email Tests ==========
+
+#[test]
+fn test_email_subject_and_body() {
+    let mut proxmox = NodeBuilder::new()
+        .name("Proxmox")
+        .http("https://pve.lan:8006", 200)
+        .build();
+    proxmox.consecutive_failures = 3;
+    let nas = NodeBuilder::new()
+        .name("NAS")
+        .ping("10.0.0.20", 4, 2)
+        .build();
+
+    let down = Notification::new(
+        NotificationEvent::Offline,
+        &proxmox,
+        &change(NodeStatus::Online, NodeStatus::Offline),
+        Some("Connection refused"),
+    );
+    assert_eq!(
+        email_subject(&[&down]),
+        "[DOWN] Proxmox (HTTP) after 3 attempts"
+    );
+    let body = email_body(&[&down]);
+    assert!(body.starts_with("DOWN: Proxmox (HTTP)\n"), "{}", body);
+    assert!(body.contains("Target:  https://pve.lan:8006\n"));
+    assert!(body.contains("Status:  Online -> Offline\n"));
+    assert!(body.contains("after 1m 30s online"));
+    assert!(body.contains("Failed:  3 check(s) in a row\n"));
+    assert!(body.contains("Details: Connection refused\n"));
+
+    let nas_down = Notification::new(
+        NotificationEvent::Offline,
+        &nas,
+        &change(NodeStatus::Online, NodeStatus::Offline),
+        None,
+    );
+    assert_eq!(
+        email_subject(&[&down, &nas_down]),
+        "[DOWN] 2 nodes: Proxmox, NAS"
+    );
+    assert_eq!(email_body(&[&down, &nas_down]).matches("DOWN: ").count(), 2);
+
+    let nas_up = Notification::new(
+        NotificationEvent::Recovery,
+        &nas,
+        &change(NodeStatus::Offline, NodeStatus::Online),
+        None,
+    );
+    assert_eq!(
+        email_subject(&[&nas_up]),
+        "[UP] NAS (Ping) after 1m 30s offline"
+    );
+    assert_eq!(
+        email_subject(&[&down, &nas_up]),
+        "[ALERT] 1 down, 1 up: Proxmox, NAS"
+    );
+}
+
+#[tokio::test]
+async fn test_email_batches_simultaneous_changes() {
+    let test_db = TestDatabase::new();
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let mut store = FileCredentialStore::with_path(
+        "master".to_string(),
+        &temp_dir.path().join("credentials.enc"),
+    )
+    .unwrap();
+    store
+        .store_credential(
+            "smtp".to_string(),
+            None,
+            SshCredential::Password {
+                username: "noc".to_string(),
+                password: "hunter2".into(),
+            },
+        )
+        .unwrap();
+    let credentials = SharedCredentialStore::default();
+    credentials.set(Some(Box::new(store)));
+
+    let (port, mut mail) = serve_smtp().await;
+    let settings = NotificationSettings {
+        enabled: true,
+        email: Some(EmailSettings {
+            host: "127.0.0.1".to_string(),
+            port: Some(port),
+            security: SmtpSecurity::None,
+            credential: Some("smtp".to_string()),
+            from: "net-monitor <monitor@example.com>".to_string(),
+            to: vec!["noc@example.com".to_string()],
+            batch_window_secs: 1,
+        }),
+        retry_backoff_secs: 0,
+        ..NotificationSettings::default()
+    };
+    let notifier = Notifier::new(
+        &settings,
+        Database::new(test_db.path()).unwrap(),
+        credentials,
+    )
+    .unwrap();
+
+    let mut node_ids = Vec::new();
+    for name in ["Switch", "Printer"] {
+        let mut node = NodeBuilder::new().name(name).ping("10.0.0.1", 4, 2).build();
+        node.id = Some(test_db.db.add_node(&node).unwrap());
+        node.consecutive_failures = 3;
+        let mut offline = change(NodeStatus::Online, NodeStatus::Offline);
+        offline.node_id = node.id.unwrap();
+        offline.id = Some(test_db.db.add_status_change(&offline).unwrap());
+        notifier.notify(&node, &offline, Some("Request timed out"));
+        node_ids.push((node.id.unwrap(), offline.id));
+    }
+
+    let (commands, message) = tokio::time::timeout(Duration::from_secs(10), mail.recv())
+        .await
+        .expect("No email was sent")
+        .unwrap();
+    assert!(commands.contains(&"AUTH PLAIN AG5vYwBodW50ZXIy".to_string()));
+    assert!(commands.contains(&"MAIL FROM:<monitor@example.com>".to_string()));
+    assert!(commands.contains(&"RCPT TO:<noc@example.com>".to_string()));
+    assert!(
+        message.contains("Subject: [DOWN] 2 nodes: Switch, Printer\n"),
+        "{}",
+        message
+    );
+    assert!(message.contains("DOWN: Switch (Ping)"));
+    assert!(message.contains("DOWN: Printer (Ping)"));
+
+    // Both status changes went out in that one email
+    for (node_id, status_change_id) in node_ids {
+        let mut deliveries = Vec::new();
+        for _ in 0..50 {
+            deliveries = test_db
+                .db
+                .get_notification_deliveries(node_id, None)
+                .unwrap();
+            if !deliveries.is_empty() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        assert_eq!(deliveries.len(), 1);
+        assert!(deliveries[0].success);
+        assert_eq!(deliveries[0].channel, "mailto:noc@example.com");
+        assert_eq!(deliveries[0].response_status, Some(250));
+        assert_eq!(deliveries[0].status_change_id, status_change_id);
+    }
+    assert!(mail.try_recv().is_err());
+}
+
+// ========== Webhook Delivery Tests ==========
+
+#[tokio::test]
+async fn test_webhook_retries_until_accepted() {
+    let test_db = TestDatabase::new();
+    let node = NodeBuilder::new()
+        .name("API")
+        .http("https://api.example.com/health", 200)
+        .build();
+    let node_id = test_db.db.add_node(&node).unwrap();
+    let mut offline = change(NodeStatus::Degraded, NodeStatus::Offline);
+    offline.node_id = node_id;
+    offline.id = Some(test_db.db.add_status_change(&offline).unwrap());
+
+    let (port, mut requests) = serve_http(vec![UNAVAILABLE, UNAVAILABLE, ACCEPTED]).await;
+    let settings = settings(port);
+    let notifier = test_notifier(&settings, &test_db);
+    let notification = Notification::new(
+        NotificationEvent::Offline,
+        &node,
+        &offline,
+        Some("Expected status 200 but got 502 Bad Gateway"),
+    );
+
+    assert!(
+        notifier
+            .deliver_webhook(&settings.webhooks[0], &notification, offline.id)
+            .await
+    );
+
+    let (head, body) = parse_request(&requests.recv().await.unwrap());
+    assert!(
+        head.starts_with("post /hooks/net-monitor http/1.1"),
+        "{}",
+        head
+    );
+    assert!(head.contains("authorization: bearer s3cret"));
+    assert!(head.contains("content-type: application/json"));
+    assert_eq!(body["event"], "offline");
+    assert_eq!(body["node"]["name"], "API");
+    assert_eq!(body["node"]["type"], "HTTP");
+    assert_eq!(body["node"]["target"], "https://api.example.com/health");
+    assert_eq!(body["from_status"], "Degraded");
+    assert_eq!(body["to_status"], "Offline");
+    assert_eq!(body["duration_ms"], 90_000);
+    assert_eq!(
+        body["details"],
+        "Expected status 200 but got 502 Bad Gateway"
+    );
+    assert_eq!(
+        serde_json::from_value::<Notification>(body).unwrap(),
+        notification
+    );
+
+    let mut deliveries = test_db
+        .db
+        .get_notification_deliveries(node_id, None)
+        .unwrap();
+    deliveries.reverse();
+    assert_eq!(deliveries.len(), 3);
+    assert_eq!(
+        deliveries.iter().map(|d| d.attempt).collect::<Vec<_>>(),
+        vec![1, 2, 3]
+    );
+    assert!(!deliveries[0].success);
+    assert_eq!(deliveries[0].response_status, Some(503));
+    assert_eq!(
+        deliveries[0].error.as_deref(),
+        Some("Webhook responded with status 503 Service Unavailable")
+    );
+    assert!(deliveries[2].success);
+    assert_eq!(deliveries[2].response_status, Some(204));
+    assert_eq!(deliveries[2].error, None);
+    assert!(deliveries
+        .iter()
+        .all(|d| d.status_change_id == offline.id && d.channel == "webhook:127.0.0.1"));
+}
+
+#[tokio::test]
+async fn test_webhook_gives_up() {
+    let test_db = TestDatabase::new();
+    let node = NodeBuilder::new()
+        .name("DB")
+        .tcp("10.0.0.5", 5432, 5)
+        .build();
+    let node_id = test_db.db.add_node(&node).unwrap();
+    let mut recovery = change(NodeStatus::Offline, NodeStatus::Online);
+    recovery.node_id = node_id;
+
+    // A client error will not go away by retrying
+    let (port, _requests) = serve_http(vec![BAD_REQUEST]).await;
+    let settings = settings(port);
+    let notifier = test_notifier(&settings, &test_db);
+    let notification = Notification::new(NotificationEvent::Recovery, &node, &recovery, None);
+    assert!(
+        !notifier
+            .deliver_webhook(&settings.webhooks[0], &notification, None)
+            .await
+    );
+    let deliveries = test_db
+        .db
+        .get_notification_deliveries(node_id, None)
+        .unwrap();
+    assert_eq!(deliveries.len(), 1);
+    assert_eq!(deliveries[0].response_status, Some(400));
+
+    // Server errors are retried until the attempts run out
+    let (port, _requests) = serve_http(vec![UNAVAILABLE]).await;
+    let settings = NotificationSettings {
+        max_attempts: 2,
+        ..self::settings(port)
+    };
+    let notifier = test_notifier(&settings, &test_db);
+    assert!(
+        !notifier
+            .deliver_webhook(&settings.webhooks[0], &notification, None)
+            .await
+    );
+    let deliveries = test_db
+        .db
+        .get_notification_deliveries(node_id, Some(2))
+        .unwrap();
+    assert_eq!(deliveries.len(), 2);
+    assert!(deliveries.iter().all(|d| !d.success));
+    assert_eq!(deliveries[0].attempt, 2);
+
+    // A webhook that can't be reached fails without its secret path on record
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let settings = NotificationSettings {
+        max_attempts: 1,
+        ..self::settings(port)
+    };
+    let notifier = test_notifier(&settings, &test_db);
+    assert!(
+        !notifier
+            .deliver_webhook(&settings.webhooks[0], &notification, None)
+            .await
+    );
+    let deliveries = test_db
+        .db
+        .get_notification_deliveries(node_id, Some(1))
+        .unwrap();
+    let error = deliveries[0].error.as_deref().unwrap();
+    assert!(!error.contains("/hooks/net-monitor"), "{}", error);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_engine_notifies_on_confirmed_outage() {
+    let test_db = TestDatabase::new();
+
+    let (target_port, _checks) = serve_http(vec![
+        "HTTP/1.1 500 Internal Server Error\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+    ])
+    .await;
+    let mut node = NodeBuilder::new()
+        .name("Checkout")
+        .http(format!("http://127.0.0.1:{}", target_port), 200)
+        .build();
+    node.status = NodeStatus::Online;
+    node.max_check_attempts = 1;
+    let node_id = test_db.db.add_node(&node).unwrap();
+    node.id = Some(node_id);
+
+    let (webhook_port, mut requests) = serve_http(vec![ACCEPTED]).await;
+    let config = EngineConfig {
+        max_start_jitter: Duration::ZERO,
+        notifications: settings(webhook_port),
+        ..EngineConfig::default()
+    };
+    let (update_tx, _update_rx) = std::sync::mpsc::channel();
+    let handle = start_monitoring_with_config(
+        Database::new(test_db.path()).unwrap(),
+        vec![node],
+        update_tx,
+        config,
+        SharedCredentialStore::default(),
+    );
+
+    let request = tokio::time::timeout(Duration::from_secs(10), requests.recv())
+        .await
+        .expect("Webhook was never called")
+        .unwrap();
+    let (_, body) = parse_request(&request);
+    assert_eq!(body["event"], "offline");
+    assert_eq!(body["node"]["id"], node_id);
+    assert_eq!(body["from_status"], "Online");
+    assert_eq!(body["to_status"], "Offline");
+    assert_eq!(
+        body["details"],
+        "Expected status 200 but got 500 Internal Server Error"
+    );
+
+    // The delivery is recorded against the status change it announced
+    let mut deliveries = Vec::new();
+    for _ in 0..50 {
+        deliveries = test_db
+            .db
+            .get_notification_deliveries(node_id, None)
+            .unwrap();
+        if !deliveries.is_empty() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    let change = test_db
+        .db
+        .get_latest_status_change(node_id)
+        .unwrap()
+        .unwrap();
+    assert_eq!(deliveries.len(), 1);
+    assert!(deliveries[0].success);
+    assert_eq!(deliveries[0].status_change_id, change.id);
+
+    handle.stop_tx.send(()).unwrap();
+}
+
+/// Runs a failing node with `tags` through the engine while a window with the
+/// scope `scope` gives for its ID is open, and checks nobody is notified
+async fn assert_silenced_in_maintenance(
+    tags: &[&str],
+    scope: impl FnOnce(i64) -> MaintenanceScope,
+) {
+    let test_db = TestDatabase::new();
+
+    let (target_port, _checks) = serve_http(vec![
+        "HTTP/1.1 500 Internal Server Error\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+    ])
+    .await;
+    let mut node = NodeBuilder::new()
+        .name("Checkout")
+        .http(format!("http://127.0.0.1:{}", target_port), 200)
+        .build();
+    node.status = NodeStatus::Online;
+    node.max_check_attempts = 1;
+    node.tags = tags.iter().map(|tag| tag.to_string()).collect();
+    let node_id = test_db.db.add_node(&node).unwrap();
+    node.id = Some(node_id);
+    test_db
+        .db
+        .add_maintenance_window(&MaintenanceWindow {
+            id: None,
+            scope: scope(node_id),
+            starts_at: Utc::now(),
+            duration_mins: 30,
+            recurrence: None,
+            reason: None,
+        })
+        .unwrap();
+
+    let (webhook_port, mut requests) = serve_http(vec![ACCEPTED]).await;
+    let config = EngineConfig {
+        max_start_jitter: Duration::ZERO,
+        notifications: settings(webhook_port),
+        ..EngineConfig::default()
+    };
+    let (update_tx, _update_rx) = std::sync::mpsc::channel();
+    let handle = start_monitoring_with_config(
+        Database::new(test_db.path()).unwrap(),
+        vec![node],
+        update_tx,
+        config,
+        SharedCredentialStore::default(),
+    );
+
+    let mut change = None;
+    for _ in 0..100 {
+        change = test_db.db.get_latest_status_change(node_id).unwrap();
+        if change.is_some() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    let change = change.expect("The outage was never recorded");
+    assert_eq!(change.to_status, NodeStatus::Offline);
+    assert!(change.in_maintenance);
+
+    // The webhook is never called
+    let request = tokio::time::timeout(Duration::from_secs(2), requests.recv()).await;
+    assert!(request.is_err(), "Unexpected webhook call: {:?}", request);
+    assert!(test_db
+        .db
+        .get_notification_deliveries(node_id, None)
+        .unwrap()
+        .is_empty());
+
+    handle.stop_tx.send(()).unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_engine_silences_changes_in_maintenance() {
+    assert_silenced_in_maintenance(&[], MaintenanceScope::Node).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_engine_silences_changes_in_tag_maintenance() {
+    assert_silenced_in_maintenance(&["prod", "edge"], |_| {
+        MaintenanceScope::Tag("edge".to_string())
+    })
+    .await;
+}
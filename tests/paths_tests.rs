@@ -0,0 +1,68 @@
+use net_monitor::paths::{AppPaths, PathOverrides};
+use std::path::PathBuf;
+
+#[test]
+fn test_defaults_share_data_directory() {
+    let paths = AppPaths::resolve(PathOverrides::default()).unwrap();
+
+    assert_eq!(paths.database, paths.data_dir.join("network_monitor.db"));
+    assert_eq!(paths.credentials, paths.data_dir.join("credentials.enc"));
+    assert_eq!(
+        paths.keyring_metadata,
+        paths.data_dir.join("credentials.json")
+    );
+    assert_eq!(paths.log_file, paths.data_dir.join("net-monitor.log"));
+    assert!(paths.config.ends_with("config.json"));
+}
+
+#[test]
+fn test_data_dir_override_moves_everything() {
+    let paths = AppPaths::resolve(PathOverrides {
+        data_dir: Some(PathBuf::from("/srv/site-a")),
+        ..Default::default()
+    })
+    .unwrap();
+
+    assert_eq!(paths.data_dir, PathBuf::from("/srv/site-a"));
+    assert_eq!(
+        paths.database,
+        PathBuf::from("/srv/site-a/network_monitor.db")
+    );
+    assert_eq!(paths.config, PathBuf::from("/srv/site-a/config.json"));
+    assert_eq!(
+        paths.credentials,
+        PathBuf::from("/srv/site-a/credentials.enc")
+    );
+    assert_eq!(paths.log_file, PathBuf::from("/srv/site-a/net-monitor.log"));
+}
+
+#[test]
+fn test_file_overrides_take_precedence_over_data_dir() {
+    let paths = AppPaths::resolve(PathOverrides {
+        data_dir: Some(PathBuf::from("/srv/site-a")),
+        database: Some(PathBuf::from("/mnt/volume/monitor.db")),
+        config: Some(PathBuf::from("/etc/net-monitor.json")),
+    })
+    .unwrap();
+
+    assert_eq!(paths.database, PathBuf::from("/mnt/volume/monitor.db"));
+    assert_eq!(paths.config, PathBuf::from("/etc/net-monitor.json"));
+    assert_eq!(
+        paths.credentials,
+        PathBuf::from("/srv/site-a/credentials.enc")
+    );
+}
+
+#[test]
+fn test_database_override_keeps_default_data_dir() {
+    let defaults = AppPaths::resolve(PathOverrides::default()).unwrap();
+    let paths = AppPaths::resolve(PathOverrides {
+        database: Some(PathBuf::from("/tmp/sandbox.db")),
+        ..Default::default()
+    })
+    .unwrap();
+
+    assert_eq!(paths.database, PathBuf::from("/tmp/sandbox.db"));
+    assert_eq!(paths.data_dir, defaults.data_dir);
+    assert_eq!(paths.config, defaults.config);
+}
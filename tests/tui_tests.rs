@@ -64,13 +64,15 @@ fn test_database_integration() {
     let database = Database::new(&db_path).expect("Failed to create database");
 
     // Add a test node
-    use net_monitor::models::{MonitorDetail, Node, NodeStatus};
+    use net_monitor::models::{HookCommands, MonitorDetail, Node, NodeStatus};
     let node = Node {
         id: None,
         name: "Test Node".to_string(),
         detail: MonitorDetail::Http {
             url: "https://example.com".to_string(),
             expected_status: 200,
+            timeout: 30,
+            options: Default::default(),
         },
         status: NodeStatus::Offline,
         last_check: None,
@@ -80,6 +82,12 @@ fn test_database_integration() {
         consecutive_failures: 0,
         max_check_attempts: 3,
         retry_interval: 15,
+        cert_expires_at: None,
+        ping_stats: None,
+        hooks: HookCommands::default(),
+        parents: Vec::new(),
+        group: None,
+        tags: Vec::new(),
     };
 
     let node_id = database.add_node(&node).expect("Failed to add node");